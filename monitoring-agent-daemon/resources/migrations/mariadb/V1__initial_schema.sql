@@ -0,0 +1,90 @@
+-- Initial schema for MariaDB and MySQL.
+-- The migration runner skips CREATE INDEX statements for indexes that already exist, as MySQL has no CREATE INDEX IF NOT EXISTS.
+CREATE TABLE IF NOT EXISTS monitor_status (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    status VARCHAR(10) NOT NULL,
+    log_time DATETIME(3) NOT NULL,
+    message TEXT
+);
+CREATE INDEX idx_monitor_status_server_name_log_time ON monitor_status (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS loadavg (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    loadavg1min DOUBLE,
+    loadavg5min DOUBLE,
+    loadavg15min DOUBLE,
+    num_processes INT UNSIGNED,
+    num_running_processes INT UNSIGNED,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_loadavg_server_name_log_time ON loadavg (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS meminfo (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    freemem BIGINT UNSIGNED,
+    mem_percent_used DOUBLE,
+    freeswap BIGINT UNSIGNED,
+    swap_percent_used DOUBLE,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_meminfo_server_name_log_time ON meminfo (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS diskinfo (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    mount_point VARCHAR(4096) NOT NULL,
+    freespace BIGINT UNSIGNED,
+    space_percent_used DOUBLE,
+    freeinodes BIGINT UNSIGNED,
+    inodes_percent_used DOUBLE,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_diskinfo_server_name_log_time ON diskinfo (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS cpustat (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    cpu_name VARCHAR(32),
+    user_percent DOUBLE,
+    system_percent DOUBLE,
+    iowait_percent DOUBLE,
+    steal_percent DOUBLE,
+    total_percent DOUBLE,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_cpustat_server_name_log_time ON cpustat (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS netdev (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    interface VARCHAR(255) NOT NULL,
+    rx_bytes_per_second DOUBLE,
+    tx_bytes_per_second DOUBLE,
+    rx_errors BIGINT UNSIGNED,
+    tx_errors BIGINT UNSIGNED,
+    rx_drop BIGINT UNSIGNED,
+    tx_drop BIGINT UNSIGNED,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_netdev_server_name_log_time ON netdev (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS statm (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    app_name VARCHAR(255),
+    log_time DATETIME(3) NOT NULL,
+    pid INT UNSIGNED NOT NULL,
+    total INT UNSIGNED,
+    resident INT UNSIGNED,
+    share INT UNSIGNED,
+    trs INT UNSIGNED,
+    drs INT UNSIGNED,
+    lrs INT UNSIGNED,
+    dt INT UNSIGNED,
+    pagesize INT UNSIGNED
+);
+CREATE INDEX idx_statm_server_name_log_time ON statm (server_name, log_time);
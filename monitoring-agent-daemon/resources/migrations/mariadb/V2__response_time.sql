@@ -0,0 +1,12 @@
+-- Response times of the HTTP and TCP monitors in milliseconds.
+CREATE TABLE IF NOT EXISTS response_time (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    connect_time DOUBLE,
+    first_byte_time DOUBLE,
+    total_time DOUBLE NOT NULL,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_response_time_server_name_log_time ON response_time (server_name, log_time);
+CREATE INDEX idx_response_time_monitor_name_log_time ON response_time (server_name, monitor_name, log_time);
@@ -0,0 +1,16 @@
+-- Metrics reported by checks, like the perfdata of Nagios plugins.
+CREATE TABLE IF NOT EXISTS metrics (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    value DOUBLE NOT NULL,
+    unit VARCHAR(32),
+    warn VARCHAR(64),
+    crit VARCHAR(64),
+    min_value DOUBLE,
+    max_value DOUBLE,
+    log_time DATETIME(3) NOT NULL
+);
+CREATE INDEX idx_metrics_server_name_log_time ON metrics (server_name, log_time);
+CREATE INDEX idx_metrics_monitor_name_log_time ON metrics (server_name, monitor_name, label, log_time);
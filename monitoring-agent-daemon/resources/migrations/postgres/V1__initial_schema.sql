@@ -0,0 +1,96 @@
+-- Initial schema for Postgres.
+CREATE SEQUENCE IF NOT EXISTS seq_monitor_status;
+CREATE TABLE IF NOT EXISTS monitor_status (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    status VARCHAR(10) NOT NULL,
+    log_time TIMESTAMP NOT NULL,
+    message TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_monitor_status_server_name_log_time ON monitor_status (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_loadavg;
+CREATE TABLE IF NOT EXISTS loadavg (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    loadavg1min NUMERIC,
+    loadavg5min NUMERIC,
+    loadavg15min NUMERIC,
+    num_processes BIGINT,
+    num_running_processes BIGINT,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_loadavg_server_name_log_time ON loadavg (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_meminfo;
+CREATE TABLE IF NOT EXISTS meminfo (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    freemem BIGINT,
+    mem_percent_used NUMERIC,
+    freeswap BIGINT,
+    swap_percent_used NUMERIC,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_meminfo_server_name_log_time ON meminfo (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_diskinfo;
+CREATE TABLE IF NOT EXISTS diskinfo (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    mount_point VARCHAR(4096) NOT NULL,
+    freespace BIGINT,
+    space_percent_used NUMERIC,
+    freeinodes BIGINT,
+    inodes_percent_used NUMERIC,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_diskinfo_server_name_log_time ON diskinfo (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_cpustat;
+CREATE TABLE IF NOT EXISTS cpustat (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    cpu_name VARCHAR(32),
+    user_percent NUMERIC,
+    system_percent NUMERIC,
+    iowait_percent NUMERIC,
+    steal_percent NUMERIC,
+    total_percent NUMERIC,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_cpustat_server_name_log_time ON cpustat (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_netdev;
+CREATE TABLE IF NOT EXISTS netdev (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    interface VARCHAR(255) NOT NULL,
+    rx_bytes_per_second NUMERIC,
+    tx_bytes_per_second NUMERIC,
+    rx_errors BIGINT,
+    tx_errors BIGINT,
+    rx_drop BIGINT,
+    tx_drop BIGINT,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_netdev_server_name_log_time ON netdev (server_name, log_time);
+
+CREATE SEQUENCE IF NOT EXISTS seq_statm;
+CREATE TABLE IF NOT EXISTS statm (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    app_name VARCHAR(255),
+    log_time TIMESTAMP NOT NULL,
+    pid BIGINT NOT NULL,
+    total BIGINT,
+    resident BIGINT,
+    share BIGINT,
+    trs BIGINT,
+    drs BIGINT,
+    lrs BIGINT,
+    dt BIGINT,
+    pagesize BIGINT
+);
+CREATE INDEX IF NOT EXISTS idx_statm_server_name_log_time ON statm (server_name, log_time);
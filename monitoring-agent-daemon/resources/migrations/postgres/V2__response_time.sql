@@ -0,0 +1,13 @@
+-- Response times of the HTTP and TCP monitors in milliseconds.
+CREATE SEQUENCE IF NOT EXISTS seq_response_time;
+CREATE TABLE IF NOT EXISTS response_time (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    connect_time NUMERIC,
+    first_byte_time NUMERIC,
+    total_time NUMERIC NOT NULL,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_response_time_server_name_log_time ON response_time (server_name, log_time);
+CREATE INDEX IF NOT EXISTS idx_response_time_monitor_name_log_time ON response_time (server_name, monitor_name, log_time);
@@ -0,0 +1,17 @@
+-- Metrics reported by checks, like the perfdata of Nagios plugins.
+CREATE SEQUENCE IF NOT EXISTS seq_metrics;
+CREATE TABLE IF NOT EXISTS metrics (
+    id BIGINT PRIMARY KEY,
+    server_name VARCHAR(255) NOT NULL,
+    monitor_name VARCHAR(255) NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    value NUMERIC NOT NULL,
+    unit VARCHAR(32),
+    warn VARCHAR(64),
+    crit VARCHAR(64),
+    min_value NUMERIC,
+    max_value NUMERIC,
+    log_time TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_metrics_server_name_log_time ON metrics (server_name, log_time);
+CREATE INDEX IF NOT EXISTS idx_metrics_monitor_name_log_time ON metrics (server_name, monitor_name, label, log_time);
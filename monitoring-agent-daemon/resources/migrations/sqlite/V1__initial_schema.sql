@@ -0,0 +1,89 @@
+-- Initial schema for Sqlite. Log times are stored as utc text, YYYY-MM-DD HH:MM:SS.SSS.
+CREATE TABLE IF NOT EXISTS monitor_status (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    monitor_name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    log_time TEXT NOT NULL,
+    message TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_monitor_status_server_name_log_time ON monitor_status (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS loadavg (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    loadavg1min REAL,
+    loadavg5min REAL,
+    loadavg15min REAL,
+    num_processes INTEGER,
+    num_running_processes INTEGER,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_loadavg_server_name_log_time ON loadavg (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS meminfo (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    freemem INTEGER,
+    mem_percent_used REAL,
+    freeswap INTEGER,
+    swap_percent_used REAL,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_meminfo_server_name_log_time ON meminfo (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS diskinfo (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    mount_point TEXT NOT NULL,
+    freespace INTEGER,
+    space_percent_used REAL,
+    freeinodes INTEGER,
+    inodes_percent_used REAL,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_diskinfo_server_name_log_time ON diskinfo (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS cpustat (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    cpu_name TEXT,
+    user_percent REAL,
+    system_percent REAL,
+    iowait_percent REAL,
+    steal_percent REAL,
+    total_percent REAL,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_cpustat_server_name_log_time ON cpustat (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS netdev (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    interface TEXT NOT NULL,
+    rx_bytes_per_second REAL,
+    tx_bytes_per_second REAL,
+    rx_errors INTEGER,
+    tx_errors INTEGER,
+    rx_drop INTEGER,
+    tx_drop INTEGER,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_netdev_server_name_log_time ON netdev (server_name, log_time);
+
+CREATE TABLE IF NOT EXISTS statm (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    app_name TEXT,
+    log_time TEXT NOT NULL,
+    pid INTEGER NOT NULL,
+    total INTEGER,
+    resident INTEGER,
+    share INTEGER,
+    trs INTEGER,
+    drs INTEGER,
+    lrs INTEGER,
+    dt INTEGER,
+    pagesize INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_statm_server_name_log_time ON statm (server_name, log_time);
@@ -0,0 +1,12 @@
+-- Response times of the HTTP and TCP monitors in milliseconds.
+CREATE TABLE IF NOT EXISTS response_time (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    monitor_name TEXT NOT NULL,
+    connect_time REAL,
+    first_byte_time REAL,
+    total_time REAL NOT NULL,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_response_time_server_name_log_time ON response_time (server_name, log_time);
+CREATE INDEX IF NOT EXISTS idx_response_time_monitor_name_log_time ON response_time (server_name, monitor_name, log_time);
@@ -0,0 +1,16 @@
+-- Metrics reported by checks, like the perfdata of Nagios plugins.
+CREATE TABLE IF NOT EXISTS metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    server_name TEXT NOT NULL,
+    monitor_name TEXT NOT NULL,
+    label TEXT NOT NULL,
+    value REAL NOT NULL,
+    unit TEXT,
+    warn TEXT,
+    crit TEXT,
+    min_value REAL,
+    max_value REAL,
+    log_time TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_metrics_server_name_log_time ON metrics (server_name, log_time);
+CREATE INDEX IF NOT EXISTS idx_metrics_monitor_name_log_time ON metrics (server_name, monitor_name, label, log_time);
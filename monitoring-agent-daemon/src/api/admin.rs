@@ -0,0 +1,32 @@
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+
+use crate::api::common::{check_admin_access, set_cors_headers};
+use crate::api::response::MonitorChangesResponse;
+use crate::api::StateApi;
+
+/**
+ * Reload the configuration file.
+ * 
+ * New monitors are scheduled, removed monitors are unscheduled and changed monitors are rescheduled.
+ * If the configuration is invalid, the running configuration is kept.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * 
+ * Returns the monitors that were changed or an error.
+ */
+#[post("/admin/reload")]
+pub async fn reload_configuration(state: web::Data<StateApi>, req: HttpRequest) -> impl Responder {
+    if let Some(response) = check_admin_access(&req, &state.server_config) {
+        return response;
+    }
+    let changes = state.scheduling_service.lock().await.reload_from_file(&state.config_path).await;
+    match changes {
+        Ok(changes) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(MonitorChangesResponse::from_monitor_changes(&changes))
+        }
+        Err(err) => HttpResponse::BadRequest().body(format!("Error reloading configuration: {err:?}")),
+    }
+}
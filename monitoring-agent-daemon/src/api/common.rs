@@ -1,4 +1,4 @@
-use actix_web::HttpResponseBuilder;
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
 
 use crate::common::configuration::ServerConfig;
 
@@ -14,4 +14,66 @@ pub fn set_cors_headers(response_builder: &mut HttpResponseBuilder, server_confi
     if let Some(header) = &server_config.access_control_allow_methods { response_builder.append_header(("Access-Control-Allow-Methods", header.to_string())); }
     if let Some(header) = server_config.access_control_allow_credentials { response_builder.append_header(("Access-Control-Allow-Credentials", header.to_string())); }
     if let Some(header) = server_config.access_control_max_age { response_builder.append_header(("Access-Control-Allow-Credentials", header)); }
-}
\ No newline at end of file
+}
+
+/**
+ * Check that the request is allowed to use the admin web services.
+ * The admin web services are only enabled if an admin token is configured, and the request must
+ * provide it as a bearer token in the Authorization header.
+ * 
+ * `req`: The request.
+ * `server_config`: The server configuration.
+ * 
+ * Returns the error response if access is denied, otherwise None.
+ */
+pub fn check_admin_access(req: &HttpRequest, server_config: &ServerConfig) -> Option<HttpResponse> {
+    let Some(admin_token) = &server_config.admin_token else {
+        return Some(HttpResponse::Forbidden().body("Admin API not enabled"));
+    };
+    let token = req.headers().get("Authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "));
+    match token {
+        Some(token) if token.len() == admin_token.len() && openssl::memcmp::eq(token.as_bytes(), admin_token.as_bytes()) => None,
+        _ => Some(HttpResponse::Unauthorized().body("Invalid admin token")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{http::StatusCode, test::TestRequest};
+
+    use crate::common::configuration::MonitoringConfig;
+
+    use super::*;
+
+    fn get_server_config(admin_token: Option<String>) -> ServerConfig {
+        let mut server_config = MonitoringConfig::new("resources/test/configuration_import_test/test_simple_server.json").unwrap().server;
+        server_config.admin_token = admin_token;
+        server_config
+    }
+
+    #[test]
+    fn test_check_admin_access_not_enabled() {
+        let req = TestRequest::default().insert_header(("Authorization", "Bearer secret")).to_http_request();
+        let response = check_admin_access(&req, &get_server_config(None));
+        assert_eq!(response.unwrap().status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_check_admin_access_valid_token() {
+        let req = TestRequest::default().insert_header(("Authorization", "Bearer secret")).to_http_request();
+        let response = check_admin_access(&req, &get_server_config(Some("secret".to_string())));
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_check_admin_access_invalid_token() {
+        let req = TestRequest::default().insert_header(("Authorization", "Bearer wrong")).to_http_request();
+        let response = check_admin_access(&req, &get_server_config(Some("secret".to_string())));
+        assert_eq!(response.unwrap().status(), StatusCode::UNAUTHORIZED);
+        let req = TestRequest::default().to_http_request();
+        let response = check_admin_access(&req, &get_server_config(Some("secret".to_string())));
+        assert_eq!(response.unwrap().status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+
+use crate::api::common::set_cors_headers;
+use crate::api::{HistoricalParams, StateApi};
+use crate::api::response::{DiskinfoHistoricalResponse, DiskinfoResponse};
+
+/**
+ * Get current disk use of the mounted disks.
+ * 
+ * `state`: The state object.
+ * 
+ * Returns the current disk use or an error.
+ */
+#[get("/disk/current")]
+pub async fn get_current_diskinfo(state: web::Data<StateApi>) -> impl Responder {
+    let diskinfo = state.monitoring_service.get_current_diskinfo();
+    match diskinfo {
+        Ok(diskinfo) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(DiskinfoResponse::from_disk_usages(&diskinfo))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
+
+/**
+ * Get historical disk use.
+ *
+ * `state`: The state object.
+ *
+ * Returns the disk use per mount point.
+ */
+#[get("/disk/historical")]
+pub async fn get_historical_diskinfo(
+    state: web::Data<StateApi>,
+    req: HttpRequest,
+) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    /*
+     * Parse the query string.
+     */
+    let params = match web::Query::<HistoricalParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}"))
+        }
+    };
+    /*
+     * Get the historical disk use.
+     */
+    let diskinfo = db_service.get_historical_diskinfo(params.0).await;
+    /*
+     * Return the response.
+     */
+    match diskinfo {
+        Ok(diskinfo) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(DiskinfoHistoricalResponse::from_diskinfo_historical(&diskinfo))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
@@ -55,7 +55,7 @@ pub async fn get_historical_loadavg(
     /*
      * Get the historical load average.
      */
-    let loadavg = db_service.get_historical_loadavg(params.0);
+    let loadavg = db_service.get_historical_loadavg(params.0).await;
     /*
      * Return the response.
      */
@@ -53,7 +53,7 @@ pub async fn get_historical_meminfo(
     /*
      * Get the historical meminfo.
      */
-    let meminfo = db_service.get_historical_meminfo(params.0);
+    let meminfo = db_service.get_historical_meminfo(params.0).await;
     /*
      * Return the response.
      */
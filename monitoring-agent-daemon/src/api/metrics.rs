@@ -0,0 +1,348 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use monitoring_agent_lib::proc::{ProcStat, ProcsLoadavg, ProcsMeminfo};
+
+use crate::api::common::set_cors_headers;
+use crate::api::StateApi;
+use crate::common::{MonitorStatus, Status};
+use crate::services::TrackedProcessStatm;
+
+/**
+ * Content type of the Prometheus text exposition format.
+ */
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/**
+ * Get the metrics in the Prometheus text exposition format.
+ *
+ * `state`: The state object.
+ *
+ * Returns the metrics, or not found if the metrics are disabled in the server configuration.
+ */
+#[get("/metrics")]
+pub async fn get_metrics(state: web::Data<StateApi>) -> impl Responder {
+    if !state.server_config.metrics_enabled {
+        return HttpResponse::NotFound().body("Metrics not enabled");
+    }
+    let mut metrics = Metrics::new(&state.server_config.name);
+    metrics.add_monitor_statuses(&state.monitoring_service.get_all_monitorstatuses());
+    if let Ok(meminfo) = state.monitoring_service.get_current_meminfo() {
+        metrics.add_meminfo(&meminfo);
+    }
+    if let Ok(loadavg) = state.monitoring_service.get_current_loadavg() {
+        metrics.add_loadavg(&loadavg);
+    }
+    if let Ok(stat) = state.monitoring_service.get_stat() {
+        metrics.add_stat(&stat);
+    }
+    let monitors = match state.running_config.read() {
+        Ok(running_config) => running_config.monitors.clone(),
+        Err(err) => return HttpResponse::InternalServerError().body(format!("Error reading the running configuration: {err:?}")),
+    };
+    metrics.add_process_statm(&state.monitoring_service.get_tracked_process_statm(&monitors));
+    let mut response_builder = HttpResponse::Ok();
+    set_cors_headers(&mut response_builder, &state.server_config);
+    response_builder.content_type(METRICS_CONTENT_TYPE).body(metrics.render())
+}
+
+/**
+ * A metric with the samples of all label combinations.
+ *
+ * `name`: The name of the metric.
+ * `help`: The help text.
+ * `metric_type`: The type of the metric. gauge or counter.
+ * `samples`: The labels and the value of the samples.
+ */
+struct MetricFamily {
+    /// The name of the metric.
+    name: String,
+    /// The help text.
+    help: String,
+    /// The type of the metric.
+    metric_type: String,
+    /// The labels and the value of the samples.
+    samples: Vec<(Vec<(String, String)>, f64)>,
+}
+
+/**
+ * Collects metrics and renders them in the Prometheus text exposition format.
+ * All samples get a server label with the server name.
+ */
+struct Metrics {
+    /// The name of the server.
+    server_name: String,
+    /// The metrics in the order they were first added.
+    families: Vec<MetricFamily>,
+}
+
+impl Metrics {
+
+    /**
+     * Create a new `Metrics`.
+     *
+     * `server_name`: The name of the server.
+     *
+     * Returns a new `Metrics`.
+     */
+    fn new(server_name: &str) -> Metrics {
+        Metrics {
+            server_name: server_name.to_string(),
+            families: Vec::new(),
+        }
+    }
+
+    /**
+     * Add a sample. The sample is grouped with the earlier samples of the metric.
+     *
+     * `name`: The name of the metric.
+     * `help`: The help text.
+     * `metric_type`: The type of the metric.
+     * `labels`: The labels of the sample in addition to the server label.
+     * `value`: The value of the sample.
+     */
+    fn add(&mut self, name: &str, help: &str, metric_type: &str, labels: &[(&str, &str)], value: f64) {
+        let mut sample_labels = vec![("server".to_string(), self.server_name.clone())];
+        sample_labels.extend(labels.iter().map(|(key, value)| ((*key).to_string(), (*value).to_string())));
+        if let Some(family) = self.families.iter_mut().find(|family| family.name == name) {
+            family.samples.push((sample_labels, value));
+        } else {
+            self.families.push(MetricFamily {
+                name: name.to_string(),
+                help: help.to_string(),
+                metric_type: metric_type.to_string(),
+                samples: vec![(sample_labels, value)],
+            });
+        }
+    }
+
+    /**
+     * Add the status of the monitors.
+     *
+     * `monitor_statuses`: The monitor statuses.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn add_monitor_statuses(&mut self, monitor_statuses: &[MonitorStatus]) {
+        for monitor_status in monitor_statuses {
+            let labels = [("name", monitor_status.name.as_str())];
+            let status = match monitor_status.status {
+                Status::Ok => 0.0,
+                Status::Warn { .. } => 1.0,
+                Status::Error { .. } => 2.0,
+                Status::Unknown => 3.0,
+            };
+            self.add("monitoring_agent_monitor_status", "Status of the monitor. 0 is ok, 1 is warn, 2 is error and 3 is unknown.", "gauge", &labels, status);
+            if let Some(last_successful_time) = monitor_status.last_successful_time {
+                self.add("monitoring_agent_monitor_last_success_timestamp_seconds", "Time of the last successful check of the monitor.", "gauge", &labels, last_successful_time.timestamp() as f64);
+            }
+            if let Some(last_error_time) = monitor_status.last_error_time {
+                self.add("monitoring_agent_monitor_last_error_timestamp_seconds", "Time of the last failed check of the monitor.", "gauge", &labels, last_error_time.timestamp() as f64);
+            }
+        }
+    }
+
+    /**
+     * Add the memory information. The values in /proc/meminfo are in kB.
+     *
+     * `meminfo`: The memory information.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn add_meminfo(&mut self, meminfo: &ProcsMeminfo) {
+        let values = [
+            ("monitoring_agent_memory_total_bytes", "Total memory.", meminfo.memtotal),
+            ("monitoring_agent_memory_free_bytes", "Free memory.", meminfo.memfree),
+            ("monitoring_agent_memory_available_bytes", "Available memory.", meminfo.memavailable),
+            ("monitoring_agent_swap_total_bytes", "Total swap.", meminfo.swaptotal),
+            ("monitoring_agent_swap_free_bytes", "Free swap.", meminfo.swapfree),
+        ];
+        for (name, help, value) in values {
+            if let Some(value) = value {
+                self.add(name, help, "gauge", &[], (value * 1024) as f64);
+            }
+        }
+    }
+
+    /**
+     * Add the load average.
+     *
+     * `loadavg`: The load average.
+     */
+    fn add_loadavg(&mut self, loadavg: &ProcsLoadavg) {
+        let values = [
+            ("monitoring_agent_load1", "Load average for the last minute.", loadavg.loadavg1min.map(f64::from)),
+            ("monitoring_agent_load5", "Load average for the last 5 minutes.", loadavg.loadavg5min.map(f64::from)),
+            ("monitoring_agent_load15", "Load average for the last 15 minutes.", loadavg.loadavg15min.map(f64::from)),
+            ("monitoring_agent_processes_running", "Number of currently running processes.", loadavg.current_running_processes.map(f64::from)),
+            ("monitoring_agent_processes", "Total number of processes.", loadavg.total_number_of_processes.map(f64::from)),
+        ];
+        for (name, help, value) in values {
+            if let Some(value) = value {
+                self.add(name, help, "gauge", &[], value);
+            }
+        }
+    }
+
+    /**
+     * Add the cpu statistics.
+     *
+     * `stat`: The cpu statistics.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn add_stat(&mut self, stat: &ProcStat) {
+        for cpu in stat.cpus.iter().flatten() {
+            let Some(cpu_name) = cpu.name.as_deref() else { continue };
+            let modes = [
+                ("user", cpu.user),
+                ("nice", cpu.nice),
+                ("system", cpu.system),
+                ("idle", cpu.idle),
+                ("iowait", cpu.iowait),
+                ("irq", cpu.irq),
+                ("softirq", cpu.softirq),
+                ("steal", cpu.steal),
+            ];
+            for (mode, value) in modes {
+                if let Some(value) = value {
+                    self.add("monitoring_agent_cpu_jiffies_total", "Time spent by the cpu in each mode in jiffies. cpu is the total of all cpus.", "counter", &[("cpu", cpu_name), ("mode", mode)], value as f64);
+                }
+            }
+        }
+        let values = [
+            ("monitoring_agent_interrupts_total", "Number of interrupts serviced since boot.", "counter", stat.intr),
+            ("monitoring_agent_context_switches_total", "Number of context switches since boot.", "counter", stat.ctxt),
+            ("monitoring_agent_boot_time_seconds", "Time at which the system booted.", "gauge", stat.btime),
+            ("monitoring_agent_forks_total", "Number of processes and threads created since boot.", "counter", stat.processes),
+            ("monitoring_agent_procs_running", "Number of processes running on cpus.", "gauge", stat.procs_running),
+            ("monitoring_agent_procs_blocked", "Number of processes blocked waiting for I/O.", "gauge", stat.procs_blocked),
+        ];
+        for (name, help, metric_type, value) in values {
+            if let Some(value) = value {
+                self.add(name, help, metric_type, &[], value as f64);
+            }
+        }
+    }
+
+    /**
+     * Add the memory use of the processes tracked by the process monitors.
+     *
+     * `tracked_statm`: The memory use of the tracked processes.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn add_process_statm(&mut self, tracked_statm: &[TrackedProcessStatm]) {
+        for tracked in tracked_statm {
+            let Some(pagesize) = tracked.statm.pagesize else { continue };
+            let pid = tracked.pid.to_string();
+            let labels = [("monitor", tracked.monitor.as_str()), ("rule", tracked.rule.as_str()), ("name", tracked.name.as_str()), ("pid", pid.as_str())];
+            let values = [
+                ("monitoring_agent_process_virtual_memory_bytes", "Virtual memory size of the process.", tracked.statm.size),
+                ("monitoring_agent_process_resident_memory_bytes", "Resident memory size of the process.", tracked.statm.resident),
+                ("monitoring_agent_process_shared_memory_bytes", "Shared memory size of the process.", tracked.statm.share),
+            ];
+            for (name, help, pages) in values {
+                if let Some(pages) = pages {
+                    self.add(name, help, "gauge", &labels, (u64::from(pages) * u64::from(pagesize)) as f64);
+                }
+            }
+        }
+    }
+
+    /**
+     * Render the metrics in the Prometheus text exposition format.
+     *
+     * Returns the metrics.
+     */
+    fn render(&self) -> String {
+        let mut output = String::new();
+        for family in &self.families {
+            output.push_str(&format!("# HELP {} {}\n", family.name, family.help));
+            output.push_str(&format!("# TYPE {} {}\n", family.name, family.metric_type));
+            for (labels, value) in &family.samples {
+                let labels = labels.iter().map(|(key, value)| format!("{key}=\"{}\"", Metrics::escape_label_value(value))).collect::<Vec<String>>().join(",");
+                output.push_str(&format!("{}{{{labels}}} {value}\n", family.name));
+            }
+        }
+        output
+    }
+
+    /**
+     * Escape backslash, double quote and line feed in a label value.
+     *
+     * `value`: The label value.
+     *
+     * Returns the escaped value.
+     */
+    fn escape_label_value(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use monitoring_agent_lib::proc::{ProcCpuStat, ProcsStatm};
+
+    use super::*;
+
+    #[test]
+    fn test_monitor_statuses() {
+        let mut ok_status = MonitorStatus::new("ok \"monitor\"", &None, Status::Ok);
+        ok_status.last_successful_time = Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap());
+        let error_status = MonitorStatus::new("error", &None, Status::Error { message: "Error".to_string() });
+        let mut metrics = Metrics::new("server");
+        metrics.add_monitor_statuses(&[ok_status, error_status]);
+        assert_eq!(metrics.render(), "# HELP monitoring_agent_monitor_status Status of the monitor. 0 is ok, 1 is warn, 2 is error and 3 is unknown.\n\
+            # TYPE monitoring_agent_monitor_status gauge\n\
+            monitoring_agent_monitor_status{server=\"server\",name=\"ok \\\"monitor\\\"\"} 0\n\
+            monitoring_agent_monitor_status{server=\"server\",name=\"error\"} 2\n\
+            # HELP monitoring_agent_monitor_last_success_timestamp_seconds Time of the last successful check of the monitor.\n\
+            # TYPE monitoring_agent_monitor_last_success_timestamp_seconds gauge\n\
+            monitoring_agent_monitor_last_success_timestamp_seconds{server=\"server\",name=\"ok \\\"monitor\\\"\"} 1700000000\n");
+    }
+
+    #[test]
+    fn test_meminfo_and_loadavg() {
+        let mut metrics = Metrics::new("server");
+        metrics.add_meminfo(&ProcsMeminfo::new(Some(2), Some(1), None, None, None));
+        metrics.add_loadavg(&ProcsLoadavg::new(Some(0.5), None, None, Some(1), None));
+        let output = metrics.render();
+        assert!(output.contains("monitoring_agent_memory_total_bytes{server=\"server\"} 2048\n"));
+        assert!(output.contains("monitoring_agent_memory_free_bytes{server=\"server\"} 1024\n"));
+        assert!(!output.contains("monitoring_agent_memory_available_bytes"));
+        assert!(output.contains("monitoring_agent_load1{server=\"server\"} 0.5\n"));
+        assert!(output.contains("monitoring_agent_processes_running{server=\"server\"} 1\n"));
+    }
+
+    #[test]
+    fn test_stat() {
+        let mut metrics = Metrics::new("server");
+        metrics.add_stat(&ProcStat::new(Some(vec![
+            ProcCpuStat::new(Some("cpu"), Some(10), None, None, None, None, None, None, None),
+            ProcCpuStat::new(Some("cpu0"), Some(5), None, None, None, None, None, None, None),
+        ]), Some(100), None, None, None, Some(2), None));
+        let output = metrics.render();
+        assert_eq!(output.matches("# TYPE monitoring_agent_cpu_jiffies_total counter").count(), 1);
+        assert!(output.contains("monitoring_agent_cpu_jiffies_total{server=\"server\",cpu=\"cpu\",mode=\"user\"} 10\n"));
+        assert!(output.contains("monitoring_agent_cpu_jiffies_total{server=\"server\",cpu=\"cpu0\",mode=\"user\"} 5\n"));
+        assert!(output.contains("monitoring_agent_interrupts_total{server=\"server\"} 100\n"));
+        assert!(output.contains("monitoring_agent_procs_running{server=\"server\"} 2\n"));
+    }
+
+    #[test]
+    fn test_process_statm() {
+        let mut metrics = Metrics::new("server");
+        metrics.add_process_statm(&[TrackedProcessStatm {
+            monitor: "process".to_string(),
+            rule: "applicationName=app".to_string(),
+            name: "app".to_string(),
+            pid: 10,
+            statm: ProcsStatm::new(&Some(3), &Some(2), &Some(1), &None, &None, &None, &None, &4096),
+        }]);
+        let output = metrics.render();
+        assert!(output.contains("monitoring_agent_process_virtual_memory_bytes{server=\"server\",monitor=\"process\",rule=\"applicationName=app\",name=\"app\",pid=\"10\"} 12288\n"));
+        assert!(output.contains("monitoring_agent_process_resident_memory_bytes{server=\"server\",monitor=\"process\",rule=\"applicationName=app\",name=\"app\",pid=\"10\"} 8192\n"));
+        assert!(output.contains("monitoring_agent_process_shared_memory_bytes{server=\"server\",monitor=\"process\",rule=\"applicationName=app\",name=\"app\",pid=\"10\"} 4096\n"));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(Metrics::escape_label_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+}
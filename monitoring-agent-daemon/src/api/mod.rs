@@ -12,6 +12,10 @@
  * `common`: The common API.
  * `stat`: The stat API.
  * `ping`: The ping API.
+ * `admin`: The admin API. Requires the admin token.
+ * `disk`: The disk use API.
+ * `network`: The network throughput API.
+ * `metrics`: The Prometheus metrics API.
  */
 mod meminfo;
 mod state;
@@ -24,15 +28,23 @@ mod common;
 mod stat;
 mod ping;
 mod request;
+mod admin;
+mod disk;
+mod network;
+mod metrics;
 
 pub use crate::api::meminfo::{get_current_meminfo, get_historical_meminfo};
 pub use crate::api::cpuinfo::get_current_cpuinfo;
 pub use crate::api::loadavg::{get_current_loadavg, get_historical_loadavg};
 pub use crate::api::process::{get_processes, get_process, get_threads, get_current_statm, get_historical_statm};
-pub use crate::api::monitor::get_monitor_status;
-pub use crate::api::stat::get_stat;
+pub use crate::api::monitor::{get_monitor_status, get_monitor_history, get_monitor_uptime, get_historical_response_time, add_monitor, update_monitor, delete_monitor};
+pub use crate::api::stat::{get_stat, get_historical_cpustat};
 pub use crate::api::ping::get_ping;
-pub use crate::api::request::HistoricalParams;
+pub use crate::api::request::{HistoricalParams, MonitorHistoryParams, PersistParams, ProcessParams, UptimeParams};
+pub use crate::api::admin::reload_configuration;
+pub use crate::api::disk::{get_current_diskinfo, get_historical_diskinfo};
+pub use crate::api::network::{get_current_network, get_historical_network};
+pub use crate::api::metrics::get_metrics;
 
 #[allow(clippy::module_name_repetitions)]
 pub use crate::api::state::StateApi;
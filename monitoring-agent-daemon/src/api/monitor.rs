@@ -1,8 +1,11 @@
-use actix_web::{get, web, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, HttpResponseBuilder, Responder};
 
-use crate::api::common::set_cors_headers;
-use crate::api::StateApi;
-use crate::api::response::MonitorResponse;
+use crate::api::common::{check_admin_access, set_cors_headers};
+use crate::api::{HistoricalParams, MonitorHistoryParams, PersistParams, StateApi, UptimeParams};
+use crate::api::response::{MonitorChangesResponse, MonitorHistoryResponse, MonitorResponse, MonitorUptimeResponse, ResponseTimeHistoricalResponse};
+use crate::common::configuration::MonitoringConfig;
+use crate::common::Monitor;
+use crate::services::MonitorChanges;
 
 #[get("/monitors/status")]
 pub async fn get_monitor_status(state: web::Data<StateApi>) -> impl Responder {
@@ -12,4 +15,261 @@ pub async fn get_monitor_status(state: web::Data<StateApi>) -> impl Responder {
     response_builder.json(MonitorResponse::from_monitor_status_messages(&monitor_statuses))  
             
           
-}
\ No newline at end of file
+}
+
+/**
+ * Get the stored status history of a monitor, newest first.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * 
+ * Returns a page of the status history or an error.
+ */
+#[get("/monitors/{name}/history")]
+pub async fn get_monitor_history(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    /*
+     * Parse and validate the query string.
+     */
+    let params = match web::Query::<MonitorHistoryParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    let params = match params.0.validate() {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(err.message),
+    };
+    let (page, page_size) = (params.page, params.page_size);
+    match db_service.get_monitor_status_history(&name, params).await {
+        Ok(statuses) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(MonitorHistoryResponse::from_monitor_status_elements(&name, page, page_size, &statuses))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
+
+/**
+ * Get the availability and number of outages of a monitor in a window. The window is the
+ * last 30 days if not set.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * 
+ * Returns the uptime of the monitor or an error.
+ */
+#[get("/monitors/{name}/uptime")]
+pub async fn get_monitor_uptime(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    let params = match web::Query::<UptimeParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    if params.from_datetime >= params.to_datetime {
+        return HttpResponse::BadRequest().body("fromDateTime must be before toDateTime");
+    }
+    match db_service.get_monitor_uptime(&name, &params).await {
+        Ok(uptime) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(MonitorUptimeResponse::from_monitor_uptime_element(&name, &uptime))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
+
+/**
+ * Get the historical response times of a HTTP or TCP monitor.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * 
+ * Returns the historical response times or an error.
+ */
+#[get("/monitors/{name}/responsetime/historical")]
+pub async fn get_historical_response_time(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    let params = match web::Query::<HistoricalParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    match db_service.get_historical_response_time(&name, params.0).await {
+        Ok(response_time) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(ResponseTimeHistoricalResponse::from_response_time_historical(&response_time))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
+
+/**
+ * Add a monitor and schedule it.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * `body`: The monitor configuration.
+ * 
+ * Returns the monitors that were changed or an error.
+ */
+#[post("/monitors/{name}")]
+pub async fn add_monitor(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>, body: web::Json<serde_json::Value>) -> impl Responder {
+    if let Some(response) = check_admin_access(&req, &state.server_config) {
+        return response;
+    }
+    let params = match web::Query::<PersistParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    let monitor = match parse_monitor(&name, &body) {
+        Ok(monitor) => monitor,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let mut scheduling_service = state.scheduling_service.lock().await;
+    if scheduling_service.get_monitor(&name).is_some() {
+        return HttpResponse::Conflict().body(format!("Monitor already exists: {name}"));
+    }
+    match scheduling_service.add_monitor(&monitor).await {
+        Ok(changes) => get_changes_response(&state, HttpResponse::Created(), &changes, params.persist, &name, Some(&body)),
+        Err(err) => HttpResponse::BadRequest().body(format!("Error adding monitor: {err:?}")),
+    }
+}
+
+/**
+ * Replace a monitor and reschedule it.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * `body`: The monitor configuration.
+ * 
+ * Returns the monitors that were changed or an error.
+ */
+#[put("/monitors/{name}")]
+pub async fn update_monitor(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>, body: web::Json<serde_json::Value>) -> impl Responder {
+    if let Some(response) = check_admin_access(&req, &state.server_config) {
+        return response;
+    }
+    let params = match web::Query::<PersistParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    let monitor = match parse_monitor(&name, &body) {
+        Ok(monitor) => monitor,
+        Err(err) => return HttpResponse::BadRequest().body(err),
+    };
+    let mut scheduling_service = state.scheduling_service.lock().await;
+    if scheduling_service.get_monitor(&name).is_none() {
+        return HttpResponse::NotFound().body(format!("Monitor not found: {name}"));
+    }
+    match scheduling_service.update_monitor(&monitor).await {
+        Ok(changes) => get_changes_response(&state, HttpResponse::Ok(), &changes, params.persist, &name, Some(&body)),
+        Err(err) => HttpResponse::BadRequest().body(format!("Error updating monitor: {err:?}")),
+    }
+}
+
+/**
+ * Remove a monitor and unschedule it.
+ * 
+ * `state`: The state object.
+ * `req`: The request.
+ * `name`: The name of the monitor.
+ * 
+ * Returns the monitors that were changed or an error.
+ */
+#[delete("/monitors/{name}")]
+pub async fn delete_monitor(state: web::Data<StateApi>, req: HttpRequest, name: web::Path<String>) -> impl Responder {
+    if let Some(response) = check_admin_access(&req, &state.server_config) {
+        return response;
+    }
+    let params = match web::Query::<PersistParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}")),
+    };
+    let mut scheduling_service = state.scheduling_service.lock().await;
+    if scheduling_service.get_monitor(&name).is_none() {
+        return HttpResponse::NotFound().body(format!("Monitor not found: {name}"));
+    }
+    match scheduling_service.remove_monitor(&name).await {
+        Ok(changes) => get_changes_response(&state, HttpResponse::Ok(), &changes, params.persist, &name, None),
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error removing monitor: {err:?}")),
+    }
+}
+
+/**
+ * Parse and validate the monitor configuration.
+ * 
+ * `name`: The name of the monitor in the path.
+ * `body`: The monitor configuration.
+ * 
+ * Returns the monitor or an error message.
+ */
+fn parse_monitor(name: &str, body: &serde_json::Value) -> Result<Monitor, String> {
+    let monitor: Monitor = serde_json::from_value(body.clone()).map_err(|err| format!("Invalid monitor: {err}"))?;
+    if monitor.name != name {
+        return Err(format!("Monitor name {} does not match the path {name}", monitor.name));
+    }
+    if monitor.name.is_empty() {
+        return Err("Monitor name can not be empty".to_string());
+    }
+    Ok(monitor)
+}
+
+/**
+ * Write the monitor to the configuration file if requested and create the response.
+ * 
+ * `state`: The state object.
+ * `response_builder`: The response builder for a successful change.
+ * `changes`: The monitors that were changed.
+ * `persist`: Write the monitor to the configuration file.
+ * `name`: The name of the monitor.
+ * `monitor`: The monitor configuration, or None if the monitor was removed.
+ * 
+ * Returns the response.
+ */
+fn get_changes_response(state: &StateApi, mut response_builder: HttpResponseBuilder, changes: &MonitorChanges, persist: bool, name: &str, monitor: Option<&serde_json::Value>) -> HttpResponse {
+    if persist {
+        if let Err(err) = MonitoringConfig::write_monitor(&state.config_path, name, monitor) {
+            return HttpResponse::InternalServerError().body(format!("Monitor changed, but the configuration file was not written: {err:?}"));
+        }
+    }
+    set_cors_headers(&mut response_builder, &state.server_config);
+    response_builder.json(MonitorChangesResponse::from_monitor_changes(changes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_monitor() {
+        let body = serde_json::json!({
+            "name": "test",
+            "schedule": "*/10 * * * * *",
+            "details": { "type": "tcp", "host": "localhost", "port": 80 }
+        });
+        assert_eq!(parse_monitor("test", &body).unwrap().name, "test");
+        assert!(parse_monitor("other", &body).is_err());
+        assert!(parse_monitor("test", &serde_json::json!({ "name": "test" })).is_err());
+    }
+}
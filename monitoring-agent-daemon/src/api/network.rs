@@ -0,0 +1,69 @@
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+
+use crate::api::common::set_cors_headers;
+use crate::api::{HistoricalParams, StateApi};
+use crate::api::response::{NetworkHistoricalResponse, NetworkResponse};
+
+/**
+ * Get current network throughput of the interfaces. The throughput is measured over one second.
+ * 
+ * `state`: The state object.
+ * 
+ * Returns the current network throughput or an error.
+ */
+#[get("/network/current")]
+pub async fn get_current_network(state: web::Data<StateApi>) -> impl Responder {
+    let network = state.monitoring_service.get_current_network().await;
+    match network {
+        Ok(network) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(NetworkResponse::from_netdev_rates(&network))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
+
+/**
+ * Get historical network throughput stored by the network monitor.
+ *
+ * `state`: The state object.
+ *
+ * Returns the network throughput per interface.
+ */
+#[get("/network/historical")]
+pub async fn get_historical_network(
+    state: web::Data<StateApi>,
+    req: HttpRequest,
+) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    /*
+     * Parse the query string.
+     */
+    let params = match web::Query::<HistoricalParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}"))
+        }
+    };
+    /*
+     * Get the historical network throughput.
+     */
+    let network = db_service.get_historical_network(params.0).await;
+    /*
+     * Return the response.
+     */
+    match network {
+        Ok(network) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(NetworkHistoricalResponse::from_network_historical(&network))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 
-use crate::api::{common::set_cors_headers, response::{ProcessMeminfoHistoricalResponse, StatmResponse}, HistoricalParams, StateApi};
+use crate::api::{common::set_cors_headers, response::{ProcessMeminfoHistoricalResponse, StatmResponse}, HistoricalParams, ProcessParams, StateApi};
 
 use super::response::ProcessResponse;
 
@@ -25,24 +25,42 @@ pub async fn get_processes(state: web::Data<StateApi>) -> impl Responder {
 }
 
 /**
- * Get process.
+ * Get process. The cpu, I/O and file descriptor use is included with the usage parameter, it is
+ * measured over one second.
  * 
  * `state`: The state object.
+ * `path`: The path object.
+ * `req`: The request.
  * 
  * Returns the specified process.
  */
 #[get("/processes/{pid}")]
-pub async fn get_process(state: web::Data<StateApi>, path: web::Path<u32>) -> impl Responder {
+pub async fn get_process(state: web::Data<StateApi>, path: web::Path<u32>, req: HttpRequest) -> impl Responder {
     let pid: u32 = path.into_inner();
-    let procs = state.monitoring_service.get_process(pid);    
-    match procs {
-        Ok(procs) => { 
-            let mut response_builder = HttpResponse::Ok();
-            set_cors_headers(&mut response_builder, &state.server_config);            
-            response_builder.json(ProcessResponse::from_process(&procs)) 
-        },
-        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
-    }
+    /*
+     * Parse the query string.
+     */
+    let params = match web::Query::<ProcessParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}"))
+        }
+    };
+    let procs = match state.monitoring_service.get_process(pid) {
+        Ok(procs) => procs,
+        Err(err) => return HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    };
+    let response = if params.usage {
+        match state.monitoring_service.get_process_usage(pid).await {
+            Ok(usage) => ProcessResponse::from_process_usage(&procs, &usage),
+            Err(err) => return HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+        }
+    } else {
+        ProcessResponse::from_process(&procs)
+    };
+    let mut response_builder = HttpResponse::Ok();
+    set_cors_headers(&mut response_builder, &state.server_config);
+    response_builder.json(response)
 }
 
 /**
@@ -117,7 +135,7 @@ pub async fn get_historical_statm(state: web::Data<StateApi>, path: web::Path<u3
     /*
      * Get the historical meminfo.
      */
-    let meminfo = db_service.get_process_memory_use(pid, params.0);
+    let meminfo = db_service.get_process_memory_use(pid, params.0).await;
     /*
      * Return the response.
      */
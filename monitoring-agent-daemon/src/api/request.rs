@@ -1,6 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
+use crate::common::ApplicationError;
+
+/**
+ * The maximum number of statuses returned in one page of the monitor history.
+ */
+const MAX_PAGE_SIZE: u32 = 1000;
+
 /**
  * The historical parameters. Used to represent the historical parameters.
  * 
@@ -21,6 +28,114 @@ pub struct HistoricalParams {
     pub split: u16, 
 }
 
+/**
+ * The monitor history parameters. Used to filter and page the status history of a monitor.
+ * 
+ * `from_datetime`: The from date time.
+ * `to_datetime`: The to date time.
+ * `status`: Only return statuses of this type. One of OK, WARN, ERROR or UNKNOWN.
+ * `page`: The page. The first page is 0.
+ * `page_size`: The number of statuses in a page.
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub struct MonitorHistoryParams {
+    /// The from date time.
+    #[serde(rename = "fromDateTime", default = "default_from_datetime")]
+    pub from_datetime: DateTime<Utc>,
+    /// The to date time.
+    #[serde(rename = "toDateTime", default = "Utc::now")]
+    pub to_datetime: DateTime<Utc>,
+    /// Only return statuses of this type.
+    #[serde(rename = "status", default)]
+    pub status: Option<String>,
+    /// The page. The first page is 0.
+    #[serde(rename = "page", default)]
+    pub page: u32,
+    /// The number of statuses in a page.
+    #[serde(rename = "pageSize", default = "default_page_size")]
+    pub page_size: u32,
+}
+
+impl MonitorHistoryParams {
+    /**
+     * Validate the parameters. The status is converted to the database representation.
+     * 
+     * Returns the validated parameters.
+     * 
+     * Errors:
+     * - If the status is not one of OK, WARN, ERROR or UNKNOWN.
+     * - If the page size is 0 or larger than the maximum page size.
+     */
+    pub fn validate(mut self) -> Result<MonitorHistoryParams, ApplicationError> {
+        if let Some(status) = &self.status {
+            let status = status.to_uppercase();
+            if !["OK", "WARN", "ERROR", "UNKNOWN"].contains(&status.as_str()) {
+                return Err(ApplicationError::new(&format!("Invalid status: {status}")));
+            }
+            self.status = Some(status);
+        }
+        if self.page_size == 0 || self.page_size > MAX_PAGE_SIZE {
+            return Err(ApplicationError::new(&format!("Page size must be between 1 and {MAX_PAGE_SIZE}")));
+        }
+        Ok(self)
+    }
+
+    /**
+     * Get the number of rows to query. One more row than the page size is queried, so the
+     * caller can tell if there are more pages.
+     */
+    pub fn get_limit(&self) -> u32 {
+        self.page_size + 1
+    }
+
+    /**
+     * Get the number of rows to skip.
+     */
+    pub fn get_offset(&self) -> u64 {
+        u64::from(self.page) * u64::from(self.page_size)
+    }
+}
+
+/**
+ * The uptime parameters. Used to select the window the availability of a monitor is computed for.
+ * 
+ * `from_datetime`: The from date time.
+ * `to_datetime`: The to date time.
+ */
+#[derive(Debug, Clone, Deserialize)]
+pub struct UptimeParams {
+    /// The from date time.
+    #[serde(rename = "fromDateTime", default = "default_uptime_from_datetime")]
+    pub from_datetime: DateTime<Utc>,
+    /// The to date time.
+    #[serde(rename = "toDateTime", default = "Utc::now")]
+    pub to_datetime: DateTime<Utc>,
+}
+
+/**
+ * The persist parameters. Used by the endpoints that change monitors.
+ * 
+ * `persist`: Write the changed monitor to the configuration file.
+ */
+#[derive(Debug, Deserialize)]
+pub struct PersistParams {
+    /// Write the changed monitor to the configuration file.
+    #[serde(rename = "persist", default)]
+    pub persist: bool,
+}
+
+/**
+ * The process parameters.
+ * 
+ * `usage`: Include the cpu, I/O and file descriptor use, measured over one second.
+ */
+#[derive(Debug, Deserialize)]
+pub struct ProcessParams {
+    /// Include the cpu, I/O and file descriptor use.
+    #[serde(rename = "usage", default)]
+    pub usage: bool,
+}
+
 /**
  * The default from date time.
  */
@@ -34,3 +149,48 @@ fn default_from_datetime() -> DateTime<Utc> {
 fn default_split() -> u16 {
     1
 }
+
+/**
+ * The default page size.
+ */
+fn default_page_size() -> u32 {
+    100
+}
+
+/**
+ * The default from date time of the uptime window.
+ */
+fn default_uptime_from_datetime() -> DateTime<Utc> {
+    Utc::now() - chrono::Duration::days(30)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor_history_params_defaults() {
+        let params = serde_json::from_str::<MonitorHistoryParams>("{}").unwrap().validate().unwrap();
+        assert_eq!(params.status, None);
+        assert_eq!(params.page, 0);
+        assert_eq!(params.page_size, 100);
+        assert_eq!(params.get_limit(), 101);
+        assert_eq!(params.get_offset(), 0);
+    }
+
+    #[test]
+    fn test_monitor_history_params_validate() {
+        let params = serde_json::from_str::<MonitorHistoryParams>(r#"{"status":"error","page":2,"pageSize":10}"#).unwrap().validate().unwrap();
+        assert_eq!(params.status, Some("ERROR".to_string()));
+        assert_eq!(params.get_offset(), 20);
+        assert!(serde_json::from_str::<MonitorHistoryParams>(r#"{"status":"broken"}"#).unwrap().validate().is_err());
+        assert!(serde_json::from_str::<MonitorHistoryParams>(r#"{"pageSize":0}"#).unwrap().validate().is_err());
+        assert!(serde_json::from_str::<MonitorHistoryParams>(r#"{"pageSize":1001}"#).unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn test_process_params() {
+        assert!(!serde_json::from_str::<ProcessParams>("{}").unwrap().usage);
+        assert!(serde_json::from_str::<ProcessParams>(r#"{"usage":true}"#).unwrap().usage);
+    }
+}
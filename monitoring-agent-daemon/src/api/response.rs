@@ -1,8 +1,9 @@
 use chrono::{DateTime, TimeZone, Utc };
-use monitoring_agent_lib::proc::{process::ProcessState, ProcStat, ProcsCpuinfo, ProcsLoadavg, ProcsMeminfo, ProcsProcess, ProcsStatm};
+use monitoring_agent_lib::proc::{process::ProcessState, ProcStat, ProcsCpuinfo, ProcsDiskUsage, ProcsLoadavg, ProcsMeminfo, ProcsNetDevRate, ProcsProcess, ProcsProcessFd, ProcsStatm};
 use serde::{Deserialize, Serialize};
 
-use crate::common::{historical::MeminfoElement, LoadavgElement, MonitorStatus, ProcessMemoryElement, Status};
+use crate::common::{historical::MeminfoElement, CpustatElement, DiskinfoElement, LoadavgElement, NetworkElement, MonitorStatus, MonitorStatusElement, MonitorUptimeElement, ProcessMemoryElement, ResponseTimeElement, Status};
+use crate::services::{MonitorChanges, ProcessUsage};
 
 /**
  * The `MeminfoResponse` struct represents the response of the meminfo endpoint.
@@ -67,6 +68,136 @@ impl MeminfoResponse {
     }
 }
 
+/**
+ * The `DiskinfoResponse` struct represents the disk use of a mount point in the disk endpoint.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct DiskinfoResponse {
+    /// The mount point.
+    #[serde(rename = "mountPoint")]
+    pub mount_point: String,
+    /// The total size in bytes.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    /// The free bytes available to unprivileged users.
+    #[serde(rename = "availableBytes")]
+    pub available_bytes: u64,
+    /// The percentage of the space used.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "spacePercentUsed")]
+    pub space_percent_used: Option<f64>,
+    /// The total number of inodes.
+    #[serde(rename = "totalInodes")]
+    pub total_inodes: u64,
+    /// The number of free inodes.
+    #[serde(rename = "freeInodes")]
+    pub free_inodes: u64,
+    /// The percentage of the inodes used.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "inodesPercentUsed")]
+    pub inodes_percent_used: Option<f64>,
+}
+
+impl DiskinfoResponse {
+    /**
+     * Create a new `DiskinfoResponse` from a `ProcsDiskUsage`.
+     *
+     * `disk_usage`: The `ProcsDiskUsage` object.
+     * 
+     * Returns a new `DiskinfoResponse`.
+     */
+    pub fn from_disk_usage(disk_usage: &ProcsDiskUsage) -> DiskinfoResponse {
+        DiskinfoResponse {
+            mount_point: disk_usage.mount_point.clone(),
+            total_bytes: disk_usage.total_bytes,
+            available_bytes: disk_usage.available_bytes,
+            space_percent_used: disk_usage.get_percent_space_used(),
+            total_inodes: disk_usage.total_inodes,
+            free_inodes: disk_usage.free_inodes,
+            inodes_percent_used: disk_usage.get_percent_inodes_used(),
+        }
+    }
+
+    /**
+     * Create a list of `DiskinfoResponse` from a list of `ProcsDiskUsage`.
+     *
+     * `disk_usage`: The `ProcsDiskUsage` objects.
+     * 
+     * Returns a list of `DiskinfoResponse`.
+     */
+    pub fn from_disk_usages(disk_usage: &[ProcsDiskUsage]) -> Vec<DiskinfoResponse> {
+        disk_usage.iter().map(DiskinfoResponse::from_disk_usage).collect()
+    }
+}
+
+/**
+ * The `NetworkResponse` struct represents the throughput of an interface in the network endpoint.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct NetworkResponse {
+    /// The name of the interface.
+    #[serde(rename = "interface")]
+    pub interface: String,
+    /// The bytes received per second.
+    #[serde(rename = "rxBytesPerSecond")]
+    pub rx_bytes_per_second: f64,
+    /// The bytes transmitted per second.
+    #[serde(rename = "txBytesPerSecond")]
+    pub tx_bytes_per_second: f64,
+    /// The receive errors between the samples.
+    #[serde(rename = "rxErrors")]
+    pub rx_errors: u64,
+    /// The transmit errors between the samples.
+    #[serde(rename = "txErrors")]
+    pub tx_errors: u64,
+    /// The received packets dropped between the samples.
+    #[serde(rename = "rxDrop")]
+    pub rx_drop: u64,
+    /// The transmitted packets dropped between the samples.
+    #[serde(rename = "txDrop")]
+    pub tx_drop: u64,
+    /// The link speed in Mbit/s.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "speed")]
+    pub speed: Option<u64>,
+    /// The percentage of the link speed used.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "percentSaturation")]
+    pub percent_saturation: Option<f64>,
+}
+
+impl NetworkResponse {
+    /**
+     * Create a new `NetworkResponse` from a `ProcsNetDevRate`.
+     *
+     * `rate`: The `ProcsNetDevRate` object.
+     * 
+     * Returns a new `NetworkResponse`.
+     */
+    pub fn from_netdev_rate(rate: &ProcsNetDevRate) -> NetworkResponse {
+        NetworkResponse {
+            interface: rate.interface.clone(),
+            rx_bytes_per_second: rate.rx_bytes_per_second,
+            tx_bytes_per_second: rate.tx_bytes_per_second,
+            rx_errors: rate.rx_errors,
+            tx_errors: rate.tx_errors,
+            rx_drop: rate.rx_drop,
+            tx_drop: rate.tx_drop,
+            speed: rate.speed,
+            percent_saturation: rate.get_percent_saturation(),
+        }
+    }
+
+    /**
+     * Create a list of `NetworkResponse` from a list of `ProcsNetDevRate`.
+     *
+     * `rates`: The `ProcsNetDevRate` objects.
+     * 
+     * Returns a list of `NetworkResponse`.
+     */
+    pub fn from_netdev_rates(rates: &[ProcsNetDevRate]) -> Vec<NetworkResponse> {
+        rates.iter().map(NetworkResponse::from_netdev_rate).collect()
+    }
+}
+
 /**
  * The `CpuinfoResponse` struct represents the response of the cpu endpoint.
  */
@@ -242,7 +373,10 @@ pub struct ProcessResponse {
     pub gids: Option<Vec<String>>,
     /// Whether the process is monitored.
     #[serde(rename = "monitored")]          
-    pub monitored: bool
+    pub monitored: bool,
+    /// The cpu, I/O and file descriptor use of the process.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "usage")]
+    pub usage: Option<ProcessUsageResponse>
 }
 
 impl ProcessResponse {
@@ -284,7 +418,8 @@ impl ProcessResponse {
             groups, 
             uids, 
             gids, 
-            monitored
+            monitored,
+            usage: None,
         }
     }
 
@@ -311,6 +446,22 @@ impl ProcessResponse {
         )
     }
     
+    /**
+     * Create a new `ProcessResponse` from a `ProcsProcess` and the use of the process.
+     * 
+     * `proc`: The `ProcsProcess` object.
+     * `usage`: The cpu, I/O and file descriptor use of the process.
+     * 
+     * Returns a new `ProcessResponse`.
+     * 
+     */
+    pub fn from_process_usage(proc: &ProcsProcess, usage: &ProcessUsage) -> ProcessResponse {
+        ProcessResponse {
+            usage: Some(ProcessUsageResponse::from_process_usage(usage)),
+            ..ProcessResponse::from_process(proc)
+        }
+    }
+
    /** 
     * Create a new `ProcessResponse` from a `ProcsProcess`.
     * 
@@ -325,6 +476,84 @@ impl ProcessResponse {
 
 }
 
+/**
+ * The `ProcessUsageResponse` struct represents the cpu, I/O and file descriptor use of a process.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ProcessUsageResponse {
+    /// Time scheduled in user mode in clock ticks.
+    #[serde(rename = "utime")]
+    pub utime: u64,
+    /// Time scheduled in kernel mode in clock ticks.
+    #[serde(rename = "stime")]
+    pub stime: u64,
+    /// The total cpu time in seconds.
+    #[serde(rename = "cpuSeconds")]
+    pub cpu_seconds: f64,
+    /// The cpu usage in percent of one core.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "cpuPercent")]
+    pub cpu_percent: Option<f64>,
+    /// Time the process started after system boot in clock ticks.
+    #[serde(rename = "startTime")]
+    pub start_time: u64,
+    /// The nice value.
+    #[serde(rename = "nice")]
+    pub nice: i64,
+    /// Resident set size in pages.
+    #[serde(rename = "rss")]
+    pub rss: u64,
+    /// Bytes fetched from the storage layer.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "readBytes")]
+    pub read_bytes: Option<u64>,
+    /// Bytes sent to the storage layer.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "writeBytes")]
+    pub write_bytes: Option<u64>,
+    /// Bytes read from storage per second.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "readBytesPerSecond")]
+    pub read_bytes_per_second: Option<f64>,
+    /// Bytes written to storage per second.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "writeBytesPerSecond")]
+    pub write_bytes_per_second: Option<f64>,
+    /// The number of open file descriptors.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "openFiles")]
+    pub open_files: Option<u64>,
+    /// The soft limit of open files.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "openFilesLimit")]
+    pub open_files_limit: Option<u64>,
+    /// The percentage of the open files limit in use.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "percentOpenFiles")]
+    pub percent_open_files: Option<f64>,
+}
+
+impl ProcessUsageResponse {
+    /**
+     * Create a new `ProcessUsageResponse` from a `ProcessUsage`.
+     * 
+     * `usage`: The `ProcessUsage` object.
+     * 
+     * Returns a new `ProcessUsageResponse`.
+     */
+    pub fn from_process_usage(usage: &ProcessUsage) -> ProcessUsageResponse {
+        ProcessUsageResponse {
+            utime: usage.stat.utime,
+            stime: usage.stat.stime,
+            cpu_seconds: usage.stat.get_cpu_seconds(),
+            cpu_percent: usage.cpu_percent,
+            start_time: usage.stat.start_time,
+            nice: usage.stat.nice,
+            rss: usage.stat.rss,
+            read_bytes: usage.io.as_ref().map(|io| io.read_bytes),
+            write_bytes: usage.io.as_ref().map(|io| io.write_bytes),
+            read_bytes_per_second: usage.io_rate.as_ref().map(|io_rate| io_rate.read_bytes_per_second),
+            write_bytes_per_second: usage.io_rate.as_ref().map(|io_rate| io_rate.write_bytes_per_second),
+            open_files: usage.fd.as_ref().map(|fd| fd.open),
+            open_files_limit: usage.fd.as_ref().and_then(|fd| fd.soft_limit),
+            percent_open_files: usage.fd.as_ref().and_then(ProcsProcessFd::get_percent_usage),
+        }
+    }
+}
+
 /**
  * The `ProcessStateResponse` enum represents the response of the process state.
  */
@@ -398,6 +627,12 @@ pub struct MonitorResponse {
     /// The last time the monitor encountered an error.
     #[serde(skip_serializing_if = "Option::is_none", rename = "lastErrorTime")]
     last_error_time: Option<DateTime<Utc>>,
+    /// The results of the monitor changed before a threshold was reached.
+    #[serde(rename = "flapping")]
+    flapping: bool,
+    /// Details of the last check.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "details")]
+    details: Option<String>,
 }
 
 impl MonitorResponse {
@@ -409,8 +644,11 @@ impl MonitorResponse {
      * `last_successful_time`: The last time the monitor was successful.
      * `last_error`: The last error message.
      * `last_error_time`: The last time the monitor encountered an error.
+     * `flapping`: The results of the monitor changed before a threshold was reached.
+     * `details`: Details of the last check.
      * 
      */
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         description: Option<String>,
@@ -418,6 +656,8 @@ impl MonitorResponse {
         last_successful_time: Option<DateTime<Utc>>,
         last_error: Option<String>,
         last_error_time: Option<DateTime<Utc>>,
+        flapping: bool,
+        details: Option<String>,
     ) -> MonitorResponse {
         MonitorResponse {
             name,
@@ -426,6 +666,8 @@ impl MonitorResponse {
             last_successful_time,
             last_error,
             last_error_time,
+            flapping,
+            details,
         }
     }
 
@@ -445,6 +687,8 @@ impl MonitorResponse {
             monitor_status.last_successful_time,
             monitor_status.last_error.clone(),
             monitor_status.last_error_time,
+            monitor_status.flapping,
+            monitor_status.details.clone(),
         )
     }
 
@@ -491,6 +735,23 @@ impl MonitorStatusResponse {
             Status::Warn { message: _ } => MonitorStatusResponse::Warn,
         }
     }
+
+    /**
+     * Create a new `MonitorStatusResponse` from the status stored in the database.
+     * 
+     * `status`: The stored status. One of OK, WARN, ERROR or UNKNOWN.
+     * 
+     * Returns a new `MonitorStatusResponse`.
+     * 
+     */
+    pub fn from_db_status(status: &str) -> MonitorStatusResponse {
+        match status {
+            "OK" => MonitorStatusResponse::Ok,
+            "WARN" => MonitorStatusResponse::Warn,
+            "ERROR" => MonitorStatusResponse::Error,
+            _ => MonitorStatusResponse::Unknown,
+        }
+    }
 }
 
 /**
@@ -829,6 +1090,227 @@ impl MeminfoHistoricalResponse {
     }
 }
 
+/**
+ * The `DiskinfoHistoricalResponse` struct represents the response of the disk historical endpoint.
+ * The disk historical endpoint is used to get the historical disk use per mount point.
+ * 
+ * The disk historical endpoint contains the following columns per mount point:
+ * * The percentage of the space used.
+ * * The percentage of the inodes used.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskinfoHistoricalResponse {  
+    /// The historical disk use per mount point.
+    #[serde(rename = "mounts")]
+    pub mounts: Vec<DiskinfoMountHistoricalResponse>,
+}
+
+/**
+ * The historical disk use of a mount point.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskinfoMountHistoricalResponse {  
+    /// The mount point.
+    #[serde(rename = "mountPoint")]
+    pub mount_point: String,
+    /// The percentage of the space used.
+    #[serde(rename = "spacePercentUsed")]
+    pub space_percent_used: Vec<HistoryElement<Option<f64>>>,
+    /// The percentage of the inodes used.
+    #[serde(rename = "inodesPercentUsed")]
+    pub inodes_percent_used: Vec<HistoryElement<Option<f64>>>,
+}
+
+impl DiskinfoHistoricalResponse {
+    /**
+     * Create a new `DiskinfoHistoricalResponse`. The elements are grouped per mount point in the order they are found.
+     * 
+     * `diskinfo`: The disk use elements.
+     * 
+     * Returns a new `DiskinfoHistoricalResponse`.
+     * 
+     */
+    pub fn from_diskinfo_historical(diskinfo: &[DiskinfoElement]) -> DiskinfoHistoricalResponse {
+        let mut mounts: Vec<DiskinfoMountHistoricalResponse> = Vec::new();
+        for element in diskinfo {
+            let index = if let Some(index) = mounts.iter().position(|mount| mount.mount_point == element.mount_point) {
+                index
+            } else {
+                mounts.push(DiskinfoMountHistoricalResponse {
+                    mount_point: element.mount_point.clone(),
+                    space_percent_used: Vec::new(),
+                    inodes_percent_used: Vec::new(),
+                });
+                mounts.len() - 1
+            };
+            mounts[index].space_percent_used.push(HistoryElement {
+                timestamp: element.timestamp,
+                value: element.space_percent_used,
+            });
+            mounts[index].inodes_percent_used.push(HistoryElement {
+                timestamp: element.timestamp,
+                value: element.inodes_percent_used,
+            });
+        }
+        DiskinfoHistoricalResponse { mounts }
+    }
+}
+
+/**
+ * The `CpustatHistoricalResponse` struct represents the response of the stat historical endpoint.
+ * The stat historical endpoint is used to get the historical cpu utilisation per cpu.
+ * 
+ * The stat historical endpoint contains the following columns per cpu:
+ * * The percentage of time in user mode.
+ * * The percentage of time in system mode.
+ * * The percentage of time waiting for I/O.
+ * * The percentage of time stolen by other virtual machines.
+ * * The percentage of time not idle or waiting for I/O.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpustatHistoricalResponse {  
+    /// The historical cpu utilisation per cpu.
+    #[serde(rename = "cpus")]
+    pub cpus: Vec<CpustatCpuHistoricalResponse>,
+}
+
+/**
+ * The historical cpu utilisation of a cpu.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpustatCpuHistoricalResponse {  
+    /// The name of the cpu.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The percentage of time in user mode.
+    #[serde(rename = "user")]
+    pub user: Vec<HistoryElement<Option<f64>>>,
+    /// The percentage of time in system mode.
+    #[serde(rename = "system")]
+    pub system: Vec<HistoryElement<Option<f64>>>,
+    /// The percentage of time waiting for I/O.
+    #[serde(rename = "iowait")]
+    pub iowait: Vec<HistoryElement<Option<f64>>>,
+    /// The percentage of time stolen by other virtual machines.
+    #[serde(rename = "steal")]
+    pub steal: Vec<HistoryElement<Option<f64>>>,
+    /// The percentage of time not idle or waiting for I/O.
+    #[serde(rename = "total")]
+    pub total: Vec<HistoryElement<Option<f64>>>,
+}
+
+impl CpustatHistoricalResponse {
+    /**
+     * Create a new `CpustatHistoricalResponse`. The elements are grouped per cpu in the order they are found.
+     * 
+     * `cpustat`: The cpu utilisation elements.
+     * 
+     * Returns a new `CpustatHistoricalResponse`.
+     * 
+     */
+    pub fn from_cpustat_historical(cpustat: &[CpustatElement]) -> CpustatHistoricalResponse {
+        let mut cpus: Vec<CpustatCpuHistoricalResponse> = Vec::new();
+        for element in cpustat {
+            let index = if let Some(index) = cpus.iter().position(|cpu| cpu.name == element.cpu_name) {
+                index
+            } else {
+                cpus.push(CpustatCpuHistoricalResponse {
+                    name: element.cpu_name.clone(),
+                    user: Vec::new(),
+                    system: Vec::new(),
+                    iowait: Vec::new(),
+                    steal: Vec::new(),
+                    total: Vec::new(),
+                });
+                cpus.len() - 1
+            };
+            let cpu = &mut cpus[index];
+            cpu.user.push(HistoryElement { timestamp: element.timestamp, value: element.user });
+            cpu.system.push(HistoryElement { timestamp: element.timestamp, value: element.system });
+            cpu.iowait.push(HistoryElement { timestamp: element.timestamp, value: element.iowait });
+            cpu.steal.push(HistoryElement { timestamp: element.timestamp, value: element.steal });
+            cpu.total.push(HistoryElement { timestamp: element.timestamp, value: element.total });
+        }
+        CpustatHistoricalResponse { cpus }
+    }
+}
+
+/**
+ * The `NetworkHistoricalResponse` struct represents the response of the network historical endpoint.
+ * The network historical endpoint is used to get the historical throughput per interface.
+ * 
+ * The network historical endpoint contains the following columns per interface:
+ * * The bytes received per second.
+ * * The bytes transmitted per second.
+ * * The receive and transmit errors.
+ * * The received and transmitted packets dropped.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkHistoricalResponse {  
+    /// The historical throughput per interface.
+    #[serde(rename = "interfaces")]
+    pub interfaces: Vec<NetworkInterfaceHistoricalResponse>,
+}
+
+/**
+ * The historical throughput of an interface.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInterfaceHistoricalResponse {  
+    /// The name of the interface.
+    #[serde(rename = "interface")]
+    pub interface: String,
+    /// The bytes received per second.
+    #[serde(rename = "rxBytesPerSecond")]
+    pub rx_bytes_per_second: Vec<HistoryElement<Option<f64>>>,
+    /// The bytes transmitted per second.
+    #[serde(rename = "txBytesPerSecond")]
+    pub tx_bytes_per_second: Vec<HistoryElement<Option<f64>>>,
+    /// The receive and transmit errors.
+    #[serde(rename = "errors")]
+    pub errors: Vec<HistoryElement<Option<u64>>>,
+    /// The received and transmitted packets dropped.
+    #[serde(rename = "drops")]
+    pub drops: Vec<HistoryElement<Option<u64>>>,
+}
+
+impl NetworkHistoricalResponse {
+    /**
+     * Create a new `NetworkHistoricalResponse`. The elements are grouped per interface in the order they are found.
+     * 
+     * `network`: The network elements.
+     * 
+     * Returns a new `NetworkHistoricalResponse`.
+     * 
+     */
+    pub fn from_network_historical(network: &[NetworkElement]) -> NetworkHistoricalResponse {
+        let mut interfaces: Vec<NetworkInterfaceHistoricalResponse> = Vec::new();
+        for element in network {
+            let index = if let Some(index) = interfaces.iter().position(|interface| interface.interface == element.interface) {
+                index
+            } else {
+                interfaces.push(NetworkInterfaceHistoricalResponse {
+                    interface: element.interface.clone(),
+                    rx_bytes_per_second: Vec::new(),
+                    tx_bytes_per_second: Vec::new(),
+                    errors: Vec::new(),
+                    drops: Vec::new(),
+                });
+                interfaces.len() - 1
+            };
+            let interface = &mut interfaces[index];
+            interface.rx_bytes_per_second.push(HistoryElement { timestamp: element.timestamp, value: element.rx_bytes_per_second });
+            interface.tx_bytes_per_second.push(HistoryElement { timestamp: element.timestamp, value: element.tx_bytes_per_second });
+            interface.errors.push(HistoryElement { timestamp: element.timestamp, value: element.errors });
+            interface.drops.push(HistoryElement { timestamp: element.timestamp, value: element.drops });
+        }
+        NetworkHistoricalResponse { interfaces }
+    }
+}
+
 /**
  * The `ProcessMeminfoHistoricalResponse` struct represents the response of the process meminfo historical endpoint.
  * The process meminfo historical endpoint is used to get the historical memory information of a process.
@@ -964,6 +1446,226 @@ impl ProcessMemoryHistoryElement {
     }
 }
 
+/**
+ * The `MonitorChangesResponse` struct represents the response of the reload and monitor change endpoints.
+ * 
+ * `added`: Names of monitors that were added.
+ * `removed`: Names of monitors that were removed.
+ * `changed`: Names of monitors that were rescheduled.
+ * `errors`: Errors removing the previous jobs.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorChangesResponse {
+    /// Names of monitors that were added.
+    #[serde(rename = "added")]
+    pub added: Vec<String>,
+    /// Names of monitors that were removed.
+    #[serde(rename = "removed")]
+    pub removed: Vec<String>,
+    /// Names of monitors that were rescheduled.
+    #[serde(rename = "changed")]
+    pub changed: Vec<String>,
+    /// Errors removing the previous jobs.
+    #[serde(skip_serializing_if = "Vec::is_empty", rename = "errors", default)]
+    pub errors: Vec<String>,
+}
+
+impl MonitorChangesResponse {
+    /**
+     * Create a new `MonitorChangesResponse` from the monitor changes.
+     * 
+     * `changes`: The monitor changes.
+     * 
+     * Returns a new `MonitorChangesResponse`.
+     */
+    pub fn from_monitor_changes(changes: &MonitorChanges) -> MonitorChangesResponse {
+        MonitorChangesResponse {
+            added: changes.added.clone(),
+            removed: changes.removed.clone(),
+            changed: changes.changed.clone(),
+            errors: changes.errors.clone(),
+        }
+    }
+}
+
+/**
+ * The `MonitorHistoryResponse` struct represents the response of the monitor history endpoint.
+ * 
+ * `monitor_name`: The name of the monitor.
+ * `page`: The page.
+ * `page_size`: The number of statuses in a page.
+ * `has_more`: If there are more pages.
+ * `statuses`: The statuses, newest first.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorHistoryResponse {
+    /// The name of the monitor.
+    #[serde(rename = "monitorName")]
+    pub monitor_name: String,
+    /// The page.
+    #[serde(rename = "page")]
+    pub page: u32,
+    /// The number of statuses in a page.
+    #[serde(rename = "pageSize")]
+    pub page_size: u32,
+    /// If there are more pages.
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+    /// The statuses, newest first.
+    #[serde(rename = "statuses")]
+    pub statuses: Vec<MonitorHistoryElement>,
+}
+
+/**
+ * A stored status of a monitor.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorHistoryElement {
+    /// The time the status was stored.
+    #[serde(rename = "timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// The status.
+    #[serde(rename = "status")]
+    pub status: MonitorStatusResponse,
+    /// The error message.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "message")]
+    pub message: Option<String>,
+}
+
+impl MonitorHistoryResponse {
+    /**
+     * Create a new `MonitorHistoryResponse` from the stored statuses. The statuses are queried with
+     * one more element than the page size, and the extra element tells if there are more pages.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `page`: The page.
+     * `page_size`: The number of statuses in a page.
+     * `monitor_status_elements`: The stored statuses.
+     * 
+     * Returns a new `MonitorHistoryResponse`.
+     */
+    pub fn from_monitor_status_elements(monitor_name: &str, page: u32, page_size: u32, monitor_status_elements: &[MonitorStatusElement]) -> MonitorHistoryResponse {
+        let page_length = usize::try_from(page_size).unwrap_or(usize::MAX);
+        MonitorHistoryResponse {
+            monitor_name: monitor_name.to_string(),
+            page,
+            page_size,
+            has_more: monitor_status_elements.len() > page_length,
+            statuses: monitor_status_elements.iter().take(page_length).map(|element| MonitorHistoryElement {
+                timestamp: element.timestamp,
+                status: MonitorStatusResponse::from_db_status(&element.status),
+                message: element.message.clone(),
+            }).collect(),
+        }
+    }
+}
+
+/**
+ * The `MonitorUptimeResponse` struct represents the response of the monitor uptime endpoint.
+ * 
+ * `monitor_name`: The name of the monitor.
+ * `from_datetime`: The start of the window.
+ * `to_datetime`: The end of the window.
+ * `availability_percent`: The percentage of the monitored time the monitor was available. Not set if there are no statuses.
+ * `outages`: The number of times the monitor went into error.
+ * `monitored_seconds`: The number of seconds with a known status.
+ * `downtime_seconds`: The number of seconds in error.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorUptimeResponse {
+    /// The name of the monitor.
+    #[serde(rename = "monitorName")]
+    pub monitor_name: String,
+    /// The start of the window.
+    #[serde(rename = "fromDateTime")]
+    pub from_datetime: DateTime<Utc>,
+    /// The end of the window.
+    #[serde(rename = "toDateTime")]
+    pub to_datetime: DateTime<Utc>,
+    /// The percentage of the monitored time the monitor was available.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "availabilityPercent")]
+    pub availability_percent: Option<f64>,
+    /// The number of times the monitor went into error.
+    #[serde(rename = "outages")]
+    pub outages: u32,
+    /// The number of seconds with a known status.
+    #[serde(rename = "monitoredSeconds")]
+    pub monitored_seconds: i64,
+    /// The number of seconds in error.
+    #[serde(rename = "downtimeSeconds")]
+    pub downtime_seconds: i64,
+}
+
+impl MonitorUptimeResponse {
+    /**
+     * Create a new `MonitorUptimeResponse`.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `monitor_uptime_element`: The uptime of the monitor.
+     * 
+     * Returns a new `MonitorUptimeResponse`.
+     */
+    pub fn from_monitor_uptime_element(monitor_name: &str, monitor_uptime_element: &MonitorUptimeElement) -> MonitorUptimeResponse {
+        MonitorUptimeResponse {
+            monitor_name: monitor_name.to_string(),
+            from_datetime: monitor_uptime_element.from_datetime,
+            to_datetime: monitor_uptime_element.to_datetime,
+            availability_percent: monitor_uptime_element.availability_percent,
+            outages: monitor_uptime_element.outages,
+            monitored_seconds: monitor_uptime_element.monitored_seconds,
+            downtime_seconds: monitor_uptime_element.downtime_seconds,
+        }
+    }
+}
+
+/**
+ * The `ResponseTimeHistoricalResponse` struct represents the response of the monitor response time historical endpoint.
+ * 
+ * The response time historical endpoint contains the following columns in milliseconds:
+ * * The connect time, including the TLS handshake for https.
+ * * The time to first byte. Only http monitors.
+ * * The total time.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseTimeHistoricalResponse {
+    /// The connect time.
+    #[serde(rename = "connectTime")]
+    pub connect_time: Vec<HistoryElement<f64>>,
+    /// The time to first byte.
+    #[serde(rename = "firstByteTime")]
+    pub first_byte_time: Vec<HistoryElement<f64>>,
+    /// The total time.
+    #[serde(rename = "totalTime")]
+    pub total_time: Vec<HistoryElement<f64>>,
+}
+
+impl ResponseTimeHistoricalResponse {
+    /**
+     * Create a new `ResponseTimeHistoricalResponse`. Timings not measured in a period are left out.
+     * 
+     * `response_time`: The historical response times.
+     * 
+     * Returns a new `ResponseTimeHistoricalResponse`.
+     */
+    pub fn from_response_time_historical(response_time: &[ResponseTimeElement]) -> ResponseTimeHistoricalResponse {
+        let get_values = |get_value: fn(&ResponseTimeElement) -> Option<f64>| {
+            response_time.iter().filter_map(|element| get_value(element).map(|value| HistoryElement {
+                timestamp: element.timestamp,
+                value,
+            })).collect()
+        };
+        ResponseTimeHistoricalResponse {
+            connect_time: get_values(|element| element.connect_time),
+            first_byte_time: get_values(|element| element.first_byte_time),
+            total_time: get_values(|element| element.total_time),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryElement<T> {
     #[serde(rename = "timestamp")]
@@ -977,7 +1679,7 @@ pub struct HistoryElement<T> {
 mod test {
     use std::vec;
 
-    use monitoring_agent_lib::proc::ProcCpuStat;
+    use monitoring_agent_lib::proc::{ProcCpuStat, ProcsProcessIo, ProcsProcessStat};
 
     use super::*;
 
@@ -1008,6 +1710,79 @@ mod test {
         assert_eq!(meminfo_response.swap_free, Some(100));
     }
 
+    #[test]
+    fn test_diskinfo_response_from_disk_usages() {
+        let disk_usage = vec![ProcsDiskUsage::new("/", 1000, 250, 250, 100, 50)];
+        let diskinfo_response = DiskinfoResponse::from_disk_usages(&disk_usage);
+        assert_eq!(diskinfo_response.len(), 1);
+        assert_eq!(diskinfo_response[0].mount_point, "/");
+        assert_eq!(diskinfo_response[0].total_bytes, 1000);
+        assert_eq!(diskinfo_response[0].available_bytes, 250);
+        assert_eq!(diskinfo_response[0].space_percent_used, Some(75.0));
+        assert_eq!(diskinfo_response[0].inodes_percent_used, Some(50.0));
+    }
+
+    #[test]
+    fn test_diskinfo_historical_response() {
+        let diskinfo = vec![
+            DiskinfoElement::new(Utc::now(), "/", Some(10.0), Some(20.0)),
+            DiskinfoElement::new(Utc::now(), "/var", Some(30.0), None),
+            DiskinfoElement::new(Utc::now(), "/", Some(11.0), Some(21.0)),
+        ];
+        let response = DiskinfoHistoricalResponse::from_diskinfo_historical(&diskinfo);
+        assert_eq!(response.mounts.len(), 2);
+        assert_eq!(response.mounts[0].mount_point, "/");
+        assert_eq!(response.mounts[0].space_percent_used.len(), 2);
+        assert_eq!(response.mounts[0].space_percent_used[1].value, Some(11.0));
+        assert_eq!(response.mounts[1].mount_point, "/var");
+        assert_eq!(response.mounts[1].inodes_percent_used[0].value, None);
+    }
+
+    #[test]
+    fn test_cpustat_historical_response() {
+        let cpustat = vec![
+            CpustatElement::new(Utc::now(), "cpu", Some(10.0), Some(5.0), Some(1.0), Some(0.0), Some(16.0)),
+            CpustatElement::new(Utc::now(), "cpu0", Some(20.0), Some(5.0), Some(1.0), Some(0.0), Some(26.0)),
+            CpustatElement::new(Utc::now(), "cpu", Some(12.0), Some(5.0), Some(1.0), None, Some(18.0)),
+        ];
+        let response = CpustatHistoricalResponse::from_cpustat_historical(&cpustat);
+        assert_eq!(response.cpus.len(), 2);
+        assert_eq!(response.cpus[0].name, "cpu");
+        assert_eq!(response.cpus[0].total.len(), 2);
+        assert_eq!(response.cpus[0].total[1].value, Some(18.0));
+        assert_eq!(response.cpus[0].steal[1].value, None);
+        assert_eq!(response.cpus[1].name, "cpu0");
+        assert_eq!(response.cpus[1].user[0].value, Some(20.0));
+    }
+
+    #[test]
+    fn test_network_response_from_netdev_rates() {
+        let rates = vec![ProcsNetDevRate::new("eth0", 6_250_000.0, 1000.0, 1, 2, 3, 4, Some(100))];
+        let response = NetworkResponse::from_netdev_rates(&rates);
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0].interface, "eth0");
+        assert_eq!(response[0].rx_bytes_per_second, 6_250_000.0);
+        assert_eq!(response[0].tx_errors, 2);
+        assert_eq!(response[0].tx_drop, 4);
+        assert_eq!(response[0].percent_saturation, Some(50.0));
+    }
+
+    #[test]
+    fn test_network_historical_response() {
+        let network = vec![
+            NetworkElement::new(Utc::now(), "eth0", Some(100.0), Some(200.0), Some(0), Some(1)),
+            NetworkElement::new(Utc::now(), "eth1", Some(300.0), None, None, Some(0)),
+            NetworkElement::new(Utc::now(), "eth0", Some(110.0), Some(210.0), Some(2), Some(0)),
+        ];
+        let response = NetworkHistoricalResponse::from_network_historical(&network);
+        assert_eq!(response.interfaces.len(), 2);
+        assert_eq!(response.interfaces[0].interface, "eth0");
+        assert_eq!(response.interfaces[0].rx_bytes_per_second.len(), 2);
+        assert_eq!(response.interfaces[0].errors[1].value, Some(2));
+        assert_eq!(response.interfaces[1].interface, "eth1");
+        assert_eq!(response.interfaces[1].tx_bytes_per_second[0].value, None);
+    }
+
     #[test]
     fn test_cpuinfo_response_new() {
         let cpuinfo_response = CpuinfoResponse::new(Some(1), Some("AuthenticAMD".to_string()), Some("cpuFamily".to_string()), Some("model".to_string()), Some("modelName".to_string()), Some(2), Some(3.0));
@@ -1101,6 +1876,44 @@ mod test {
         assert_eq!(process_response.state, Some(ProcessStateResponse::Running));
         assert_eq!(process_response.threads, Some(3));
         assert_eq!(process_response.groups, Some(vec!["group1".to_string(), "group2".to_string()]));
+        assert!(process_response.usage.is_none());
+    }
+
+    #[test]
+    fn test_process_response_from_process_usage() {
+        let procs_process = ProcsProcess {
+            pid: Some(1),
+            parent_pid: None,
+            name: Some("name".to_string()),
+            umask: None,
+            state: None,
+            threads: None,
+            groups: None,
+            uid: None,
+            gid: None,
+        };
+        let usage = ProcessUsage {
+            stat: ProcsProcessStat::new(1, 300, 100, 500, -5, 1000, 100),
+            cpu_percent: Some(12.5),
+            io: Some(ProcsProcessIo::new(0, 0, 4096, 8192)),
+            io_rate: None,
+            fd: Some(ProcsProcessFd::new(25, Some(100), Some(1000))),
+        };
+        let process_response = ProcessResponse::from_process_usage(&procs_process, &usage);
+        assert_eq!(process_response.pid, Some(1));
+        let usage_response = process_response.usage.unwrap();
+        assert_eq!(usage_response.cpu_seconds, 4.0);
+        assert_eq!(usage_response.cpu_percent, Some(12.5));
+        assert_eq!(usage_response.nice, -5);
+        assert_eq!(usage_response.read_bytes, Some(4096));
+        assert_eq!(usage_response.write_bytes, Some(8192));
+        assert_eq!(usage_response.read_bytes_per_second, None);
+        assert_eq!(usage_response.open_files, Some(25));
+        assert_eq!(usage_response.open_files_limit, Some(100));
+        assert_eq!(usage_response.percent_open_files, Some(25.0));
+        let json = serde_json::to_string(&usage_response).unwrap();
+        assert!(json.contains("\"openFiles\":25"));
+        assert!(!json.contains("readBytesPerSecond"));
     }
 
     #[test]
@@ -1151,23 +1964,31 @@ mod test {
             last_successful_time: Some(Utc::now()),
             last_error: Some("error".to_string()),
             last_error_time: Some(Utc::now()),
+            failure_threshold: 1,
+            success_threshold: 1,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            flapping: false,
+            details: Some("Timings: login 35 ms".to_string()),
         };
         let monitor_response = MonitorResponse::from_monitor_status_message(&monitor_status);
         assert_eq!(monitor_response.name, "name".to_string());
         assert_eq!(monitor_response.status, MonitorStatusResponse::Ok);
-        assert_eq!(monitor_response.last_successful_time.is_some(), true);
+        assert!(monitor_response.last_successful_time.is_some());
         assert_eq!(monitor_response.last_error, Some("error".to_string()));
-        assert_eq!(monitor_response.last_error_time.is_some(), true);
+        assert!(monitor_response.last_error_time.is_some());
+        assert_eq!(monitor_response.details, Some("Timings: login 35 ms".to_string()));
     }
 
     #[test]
     fn test_new_moniorresponse() {
-        let monitor_response = MonitorResponse::new("name".to_string(), None, MonitorStatusResponse::Ok, Some(Utc::now()), Some("error".to_string()), Some(Utc::now()));
+        let monitor_response = MonitorResponse::new("name".to_string(), None, MonitorStatusResponse::Ok, Some(Utc::now()), Some("error".to_string()), Some(Utc::now()), true, None);
         assert_eq!(monitor_response.name, "name".to_string());
         assert_eq!(monitor_response.status, MonitorStatusResponse::Ok);
-        assert_eq!(monitor_response.last_successful_time.is_some(), true);
+        assert!(monitor_response.last_successful_time.is_some());
         assert_eq!(monitor_response.last_error, Some("error".to_string()));
-        assert_eq!(monitor_response.last_error_time.is_some(), true);        
+        assert!(monitor_response.last_error_time.is_some());        
+        assert!(monitor_response.flapping);
     }
 
     #[test]
@@ -1179,13 +2000,19 @@ mod test {
             last_successful_time: Some(Utc::now()),
             last_error: Some("error".to_string()),
             last_error_time: Some(Utc::now()),
+            failure_threshold: 1,
+            success_threshold: 1,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            flapping: false,
+            details: None,
         }];
         let monitor_response = MonitorResponse::from_monitor_status_messages(&monitor_status);
         assert_eq!(monitor_response[0].name, "name".to_string());
         assert_eq!(monitor_response[0].status, MonitorStatusResponse::Ok);
-        assert_eq!(monitor_response[0].last_successful_time.is_some(), true);
+        assert!(monitor_response[0].last_successful_time.is_some());
         assert_eq!(monitor_response[0].last_error, Some("error".to_string()));
-        assert_eq!(monitor_response[0].last_error_time.is_some(), true);        
+        assert!(monitor_response[0].last_error_time.is_some());        
     }
 
     #[test]
@@ -1283,7 +2110,7 @@ mod test {
             procs_blocked: Some(6),
         };
         let stat_response = StatResponse::from_stat(&procs_stat);
-        assert_eq!(stat_response.cpus.clone().is_some(), true);
+        assert!(stat_response.cpus.clone().is_some());
         assert_eq!(stat_response.cpus.clone().unwrap()[0].name, Some("cpu".to_string()));
         assert_eq!(stat_response.cpus.clone().unwrap()[0].user, Some(1));
         assert_eq!(stat_response.cpus.clone().unwrap()[0].system, Some(2));
@@ -1300,5 +2127,39 @@ mod test {
         assert_eq!(stat_response.procs_running, Some(5));
         assert_eq!(stat_response.procs_blocked, Some(6));
     }
+
+    #[test]
+    fn test_monitor_changes_response_from_monitor_changes() {
+        let changes = MonitorChanges {
+            added: vec!["added".to_string()],
+            removed: vec!["removed".to_string()],
+            changed: vec!["changed".to_string()],
+            errors: Vec::new(),
+        };
+        let monitor_changes_response = MonitorChangesResponse::from_monitor_changes(&changes);
+        assert_eq!(monitor_changes_response.added, vec!["added".to_string()]);
+        assert_eq!(monitor_changes_response.removed, vec!["removed".to_string()]);
+        assert_eq!(monitor_changes_response.changed, vec!["changed".to_string()]);
+    }
+
+    #[test]
+    fn test_monitor_history_response_from_monitor_status_elements() {
+        let elements = vec![
+            MonitorStatusElement::new(Utc::now(), "ERROR", Some("Error".to_string())),
+            MonitorStatusElement::new(Utc::now(), "OK", None),
+            MonitorStatusElement::new(Utc::now(), "WARN", Some("Warn".to_string())),
+        ];
+        let response = MonitorHistoryResponse::from_monitor_status_elements("test", 1, 2, &elements);
+        assert_eq!(response.monitor_name, "test");
+        assert_eq!(response.page, 1);
+        assert!(response.has_more);
+        assert_eq!(response.statuses.len(), 2);
+        assert_eq!(response.statuses[0].status, MonitorStatusResponse::Error);
+        assert_eq!(response.statuses[0].message, Some("Error".to_string()));
+        assert_eq!(response.statuses[1].status, MonitorStatusResponse::Ok);
+        let response = MonitorHistoryResponse::from_monitor_status_elements("test", 0, 3, &elements);
+        assert!(!response.has_more);
+        assert_eq!(response.statuses.len(), 3);
+    }
         
 }
\ No newline at end of file
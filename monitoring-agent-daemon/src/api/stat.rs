@@ -1,6 +1,7 @@
-use actix_web::{get, web, HttpResponse, Responder};
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 
-use crate::api::response::StatResponse;
+use crate::api::response::{CpustatHistoricalResponse, StatResponse};
+use crate::api::HistoricalParams;
 
 use super::{common::set_cors_headers, StateApi};
 
@@ -22,4 +23,48 @@ pub async fn get_stat(state: web::Data<StateApi>) -> impl Responder {
         },
         Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
     }
-}
\ No newline at end of file
+}
+
+/**
+ * Get historical cpu utilisation stored by the cpu monitor.
+ *
+ * `state`: The state object.
+ *
+ * Returns the cpu utilisation per cpu.
+ */
+#[get("/stat/historical")]
+pub async fn get_historical_cpustat(
+    state: web::Data<StateApi>,
+    req: HttpRequest,
+) -> impl Responder {
+    /*
+     * If the database service is not found, return a 404. 
+     */
+    let Some(db_service) = state.database_service.as_ref() else {
+        return HttpResponse::NotFound().body("Database service not found");
+    };
+    /*
+     * Parse the query string.
+     */
+    let params = match web::Query::<HistoricalParams>::from_query(req.query_string()) {
+        Ok(params) => params,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("Error parsing query string: {err:?}"))
+        }
+    };
+    /*
+     * Get the historical cpu utilisation.
+     */
+    let cpustat = db_service.get_historical_cpustat(params.0).await;
+    /*
+     * Return the response.
+     */
+    match cpustat {
+        Ok(cpustat) => {
+            let mut response_builder = HttpResponse::Ok();
+            set_cors_headers(&mut response_builder, &state.server_config);
+            response_builder.json(CpustatHistoricalResponse::from_cpustat_historical(&cpustat))
+        }
+        Err(err) => HttpResponse::InternalServerError().body(format!("Error occured: {err:?}")),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{common::{configuration::ServerConfig, DatabaseServiceType}, services::MonitoringService};
+use crate::{common::{configuration::ServerConfig, DatabaseServiceType, MonitoringConfigType, SchedulingServiceType}, services::MonitoringService};
 
 /**
  * State object for the API modules.
@@ -11,6 +11,12 @@ pub struct StateApi {
     pub database_service: DatabaseServiceType,    
     /// Server configuration object.
     pub server_config: ServerConfig,
+    /// Scheduling service object.
+    pub scheduling_service: SchedulingServiceType,
+    /// The running monitoring configuration. Read without locking the scheduling service.
+    pub running_config: MonitoringConfigType,
+    /// Path to the configuration file.
+    pub config_path: String,
 }
 
 impl StateApi {
@@ -20,15 +26,21 @@ impl StateApi {
      * @param `monitoring_service` `MonitoringService` The monitoring service object.
      * @param `database_service` `Arc<DbService>` The database service object.
      * @param `server_config` `ServerConfig` The server configuration object.
+     * @param `scheduling_service` `SchedulingServiceType` The scheduling service object.
+     * @param `running_config` `MonitoringConfigType` The running monitoring configuration.
+     * @param `config_path` `&str` The path to the configuration file.
      * 
      * @return `StateApi`
      * 
      */
-    pub fn new(monitoring_service: MonitoringService, database_service: DatabaseServiceType, server_config: ServerConfig) -> StateApi {
+    pub fn new(monitoring_service: MonitoringService, database_service: DatabaseServiceType, server_config: ServerConfig, scheduling_service: SchedulingServiceType, running_config: MonitoringConfigType, config_path: &str) -> StateApi {
         StateApi {
             monitoring_service,
             database_service,
             server_config,
+            scheduling_service,
+            running_config,
+            config_path: config_path.to_string(),
         }
     }
 }
@@ -38,6 +38,10 @@ pub struct ApplicationArguments {
     /// pid file. Only used when daemonizing the application.
     #[arg(short = 'p', long, default_value = "/var/run/monitoring-agent-daemon.pid")]
     pub pidfile: String,
+
+    /// Apply the database migrations and exit. Will not migrate only by default.
+    #[arg(long = "migrate-only", default_value = "false")]
+    pub migrate_only: bool,
 }
 
 #[cfg(test)]
@@ -47,23 +51,32 @@ mod test {
 
     #[test]
     fn test_application_arguments() {
-        let args = ApplicationArguments::try_parse_from(&["monitoring-agent-daemon", "-c", "/etc/monitoring-agent-daemon/config.json", "-d", "-t", "-p", "/var/run/monitoring-agent-daemon.pid"]).unwrap();
+        let args = ApplicationArguments::try_parse_from(["monitoring-agent-daemon", "-c", "/etc/monitoring-agent-daemon/config.json", "-d", "-t", "-p", "/var/run/monitoring-agent-daemon.pid"]).unwrap();
         assert_eq!(args.config, "/etc/monitoring-agent-daemon/config.json");
         assert_eq!(args.logfile, "/var/log/monitoring-agent-daemon/monitoring-agent.log");
-        assert_eq!(args.daemon, true);
-        assert_eq!(args.test, true);
+        assert!(args.daemon);
+        assert!(args.test);
         assert_eq!(args.pidfile, "/var/run/monitoring-agent-daemon.pid");
+        assert!(!args.migrate_only);
+    }
+
+    #[test]
+    fn test_application_migrate_only_argument() {
+        let args = ApplicationArguments::try_parse_from(["monitoring-agent-daemon", "--migrate-only"]).unwrap();
+        assert!(args.migrate_only);
+        assert!(!args.daemon);
     }
 
 
     #[test]
     fn test_application_default_arguments() {
-        let args = ApplicationArguments::parse_from(&["monitoring-agent-daemon"]);
+        let args = ApplicationArguments::parse_from(["monitoring-agent-daemon"]);
         assert_eq!(args.config, "/etc/monitoring-agent-daemon/config.json");
         assert_eq!(args.logfile, "/var/log/monitoring-agent-daemon/monitoring-agent.log");
-        assert_eq!(args.daemon, false);
-        assert_eq!(args.test, false);
+        assert!(!args.daemon);
+        assert!(!args.test);
         assert_eq!(args.pidfile, "/var/run/monitoring-agent-daemon.pid");
+        assert!(!args.migrate_only);
     }
 
 }
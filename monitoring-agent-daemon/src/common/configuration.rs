@@ -2,6 +2,7 @@ use log::debug;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 
 use crate::common::ApplicationError;
 
@@ -12,9 +13,13 @@ use crate::common::ApplicationError;
  *
  * `Tcp`: Monitor a TCP connection.
  * `Http`: Monitor an HTTP connection.
+ * `HttpScenario`: Monitor an ordered list of HTTP requests sharing cookies and variables.
  * `Sql`: Monitor a SQL connection.
  * `Command`: Monitor a command.
  * `LoadAvg`: Monitor the load average of the system. Can only be one.
+ * `Disk`: Monitor the disk space and inode use of mounted filesystems.
+ * `Cpu`: Monitor the cpu utilisation computed between two runs of the monitor.
+ * `Network`: Monitor the throughput, errors and drops of network interfaces computed between two runs of the monitor.
  *
  */
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -24,7 +29,13 @@ pub enum MonitorType {
         host: String,
         port: u16,
         #[serde(skip_serializing_if = "Option::is_none", rename = "retry", default = "default_none")]
-        retry: Option<u16>
+        retry: Option<u16>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnLatencyMs", default = "default_none")]
+        warn_latency_ms: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorLatencyMs", default = "default_none")]
+        error_latency_ms: Option<u64>,
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
     },
     Http {
         url: String,
@@ -46,7 +57,32 @@ pub enum MonitorType {
         #[serde(skip_serializing, rename = "identityPassword")]
         identity_password: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none", rename = "retry", default = "default_none")]
-        retry: Option<u16>
+        retry: Option<u16>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnLatencyMs", default = "default_none")]
+        warn_latency_ms: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorLatencyMs", default = "default_none")]
+        error_latency_ms: Option<u64>,
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "assertions", default = "default_none")]
+        assertions: Option<HttpAssertions>,
+    },
+    HttpScenario {
+        /// The requests, run in order.
+        #[serde(rename = "steps")]
+        steps: Vec<HttpScenarioStep>,
+        #[serde(default = "default_as_true", rename = "useBuiltinRootCerts")]
+        use_builtin_root_certs: bool,
+        #[serde(default = "default_as_false", rename = "acceptInvalidCerts")]
+        accept_invalid_certs: bool,
+        #[serde(default = "default_as_false", rename = "tlsInfo")]
+        tls_info: bool,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "rootCertificate")]
+        root_certificate: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "identity")]
+        identity: Option<String>,
+        #[serde(skip_serializing, rename = "identityPassword")]
+        identity_password: Option<String>,
     },
     Command {
         command: String,
@@ -54,6 +90,39 @@ pub enum MonitorType {
         args: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         expected: Option<String>,
+        /// How the result of the command is interpreted.
+        #[serde(rename = "mode", default = "default_command_mode")]
+        mode: CommandMode,
+        /// Store the perfdata of Nagios plugins in the metrics table.
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
+        /// Accepted exit codes. If not given then only 0 is accepted.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "expectedExitCodes", default = "default_none")]
+        expected_exit_codes: Option<Vec<i32>>,
+        /// Regular expression the standard output must match.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "stdoutRegex", default = "default_none")]
+        stdout_regex: Option<String>,
+        /// Regular expression the standard error must match.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "stderrRegex", default = "default_none")]
+        stderr_regex: Option<String>,
+        /// Environment variables added to the environment of the daemon.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "environment", default = "default_none")]
+        environment: Option<HashMap<String, String>>,
+        /// Directory the command runs in.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "workingDirectory", default = "default_none")]
+        working_directory: Option<String>,
+        /// Written to the standard input of the command.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "stdin", default = "default_none")]
+        stdin: Option<String>,
+        /// User name or id to run the command as. Requires the daemon to run as root.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "user", default = "default_none")]
+        user: Option<String>,
+        /// Group name or id to run the command as. Requires the daemon to run as root.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "group", default = "default_none")]
+        group: Option<String>,
+        /// Maximum number of bytes read from the standard output and standard error. The rest is discarded.
+        #[serde(rename = "maxOutputSize", default = "default_max_output_size")]
+        max_output_size: usize,
     },
     LoadAvg {
         #[serde(skip_serializing_if = "Option::is_none", rename = "threshold1min")]
@@ -104,25 +173,99 @@ pub enum MonitorType {
         /// Regexp on name.
         #[serde(rename = "regexp")] 
         regexp: Option<String>,     
+        /// Match rules with the number of instances expected.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "rules", default = "default_none")]
+        rules: Option<Vec<ProcessMatchRule>>,
         /// The maximum memory before warn.
         #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdMemWarn")]
         threshold_mem_warn: Option<u64>,                 
         /// The maximum memory before error.
         #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdMemError")]
         threshold_mem_error: Option<u64>,        
+        /// The maximum cpu usage in percent of one core before warn.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdCpuWarn", default = "default_none")]
+        threshold_cpu_warn: Option<f64>,
+        /// The maximum cpu usage in percent of one core before error.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdCpuError", default = "default_none")]
+        threshold_cpu_error: Option<f64>,
+        /// The maximum bytes read and written per second before warn.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdIoWarn", default = "default_none")]
+        threshold_io_warn: Option<u64>,
+        /// The maximum bytes read and written per second before error.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdIoError", default = "default_none")]
+        threshold_io_error: Option<u64>,
+        /// The maximum percentage of the open files limit in use before warn.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdFdWarn", default = "default_none")]
+        threshold_fd_warn: Option<f64>,
+        /// The maximum percentage of the open files limit in use before error.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "thresholdFdError", default = "default_none")]
+        threshold_fd_error: Option<f64>,
         /// Store vales in database        
         #[serde(rename = "storeValues", default = "default_as_false")]
         store_values: bool,         
     },
     Certificate {
-        /// The certificate to monitor.
-        #[serde(rename = "certificates")]
-        certificates: Vec<String>,
+        /// The certificate files, directories or glob patterns to monitor.
+        #[serde(rename = "certificates", default)]
+        certificates: Vec<CertificateSource>,
+        /// Remote endpoints presenting the certificates to monitor.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "endpoints", default = "default_none")]
+        endpoints: Option<Vec<CertificateEndpoint>>,
         #[serde(rename = "thresholdDaysWarn", default = "default_threshold_days_warn")]
         threshold_days_warn: u32,
         #[serde(rename = "thresholdDaysError", default = "default_threshold_days_error")]
         threshold_days_error: u32,
-    }
+    },
+    Disk {
+        /// Mount points to monitor. If not given then the mounted disks are discovered from /proc/mounts.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "mounts")]
+        mounts: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorPercentageSpaceUsed")]
+        error_percentage_used_space: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnPercentageSpaceUsed")]
+        warn_percentage_used_space: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorPercentageInodesUsed")]
+        error_percentage_used_inodes: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnPercentageInodesUsed")]
+        warn_percentage_used_inodes: Option<f64>,
+        /// Store vales in database
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
+    },
+    Cpu {
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorPercentageTotal")]
+        error_percentage_total: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnPercentageTotal")]
+        warn_percentage_total: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorPercentageIowait")]
+        error_percentage_iowait: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnPercentageIowait")]
+        warn_percentage_iowait: Option<f64>,
+        /// Check the thresholds for each core instead of the total of all cores.
+        #[serde(rename = "perCore", default = "default_as_false")]
+        per_core: bool,
+        /// Store vales in database
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
+    },
+    Network {
+        /// Interfaces to monitor. If not given then all interfaces except loopback are monitored.
+        #[serde(skip_serializing_if = "Option::is_none", rename = "interfaces")]
+        interfaces: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "errorPercentageSaturation")]
+        error_percentage_saturation: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "warnPercentageSaturation")]
+        warn_percentage_saturation: Option<f64>,
+        /// Change to error when the receive or transmit errors increase.
+        #[serde(rename = "errorOnErrors", default = "default_as_false")]
+        error_on_errors: bool,
+        /// Change to warn when the dropped packets increase.
+        #[serde(rename = "warnOnDrops", default = "default_as_false")]
+        warn_on_drops: bool,
+        /// Store vales in database
+        #[serde(rename = "storeValues", default = "default_as_false")]
+        store_values: bool,
+    },
 }
 
 /**
@@ -134,6 +277,18 @@ pub enum ThresholdLevel {
     Error,
 }
 
+/**
+ * How the result of a command is interpreted.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandMode {
+    /// Exit code 0 and the expected output, if given, is Ok. Anything else is an error.
+    Standard,
+    /// Nagios plugin. Exit code 0, 1, 2 and 3 is Ok, Warn, Error and Unknown.
+    Nagios,
+}
+
 /**
  * HTTP methods.
  */
@@ -148,6 +303,218 @@ pub enum HttpMethod {
     Head,
 }
 
+/**
+ * Assertions on the response of a http monitor. Each assertion that fails is named in the status message.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+pub struct HttpAssertions {
+    /// The expected status codes. If not set the status code must be successful.
+    #[serde(rename = "statusCodes", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub status_codes: Option<Vec<u16>>,
+    /// Text the body must contain.
+    #[serde(rename = "bodyContains", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub body_contains: Option<String>,
+    /// Regular expression the body must match.
+    #[serde(rename = "bodyRegex", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub body_regex: Option<String>,
+    /// JSONPath expression evaluated on the body. Supports $, .name, ['name'] and [index].
+    #[serde(rename = "jsonPath", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub json_path: Option<String>,
+    /// The expected value at the JSONPath expression. If not set the value must exist.
+    #[serde(rename = "jsonPathValue", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub json_path_value: Option<serde_json::Value>,
+    /// Headers the response must have.
+    #[serde(rename = "requiredHeaders", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub required_headers: Option<Vec<String>>,
+    /// The maximum body size in bytes.
+    #[serde(rename = "maxBodySize", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub max_body_size: Option<u64>,
+}
+
+/**
+ * A rule matching the processes of a process monitor. All the criteria given must match.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ProcessMatchRule {
+    /// The name of the rule used in the status message. Defaults to a description of the criteria.
+    #[serde(rename = "name", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub name: Option<String>,
+    /// The exact application name.
+    #[serde(rename = "applicationName", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub application_name: Option<String>,
+    /// Regular expression on the application name.
+    #[serde(rename = "regexp", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub regexp: Option<String>,
+    /// The process id.
+    #[serde(rename = "pid", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub pid: Option<u32>,
+    /// Regular expression on the full command line, with the arguments separated by spaces.
+    #[serde(rename = "cmdlineRegex", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub cmdline_regex: Option<String>,
+    /// The name of the effective user.
+    #[serde(rename = "user", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub user: Option<String>,
+    /// The parent process id.
+    #[serde(rename = "parentPid", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub parent_pid: Option<u32>,
+    /// The application name of the parent process.
+    #[serde(rename = "parentName", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub parent_name: Option<String>,
+    /// File containing the process id, like /run/nginx.pid.
+    #[serde(rename = "pidfile", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub pidfile: Option<String>,
+    /// The minimum number of matching processes.
+    #[serde(rename = "minInstances", default = "default_min_instances")]
+    pub min_instances: u32,
+    /// The maximum number of matching processes.
+    #[serde(rename = "maxInstances", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub max_instances: Option<u32>,
+    /// Warn when the process ids matching the rule change between two runs.
+    #[serde(rename = "warnOnRestart", default = "default_as_true")]
+    pub warn_on_restart: bool,
+}
+
+/**
+ * A request in a http scenario. The variables extracted by earlier steps are used as ${name} in
+ * the url, header values and body.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HttpScenarioStep {
+    /// The name of the step used in the status message.
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The url of the request.
+    #[serde(rename = "url")]
+    pub url: String,
+    /// The method of the request.
+    #[serde(rename = "method")]
+    pub method: HttpMethod,
+    /// The body of the request.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "body", default = "default_none")]
+    pub body: Option<String>,
+    /// The headers of the request.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "headers", default = "default_none")]
+    pub headers: Option<HashMap<String, String>>,
+    /// Follow redirects. The cookies set by the redirect responses are kept.
+    #[serde(rename = "followRedirects", default = "default_as_true")]
+    pub follow_redirects: bool,
+    /// The assertions on the response.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "assertions", default = "default_none")]
+    pub assertions: Option<HttpAssertions>,
+    /// The variables extracted from the response.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "extract", default = "default_none")]
+    pub extract: Option<HashMap<String, HttpExtraction>>,
+}
+
+/**
+ * Extraction of a value from a http response into a variable.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HttpExtraction {
+    /// JSONPath expression evaluated on the body.
+    JsonPath(String),
+    /// Regular expression on the body. The first capture group is used, or the match if there are no groups.
+    Regex(String),
+    /// Name of a response header.
+    Header(String),
+}
+
+/**
+ * A certificate file, directory or glob pattern. Either only the path or the path with options.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CertificateSource {
+    /// Path to a file or directory, or a glob pattern.
+    Path(String),
+    /// Path with options.
+    File(CertificateFile),
+}
+
+impl CertificateSource {
+    /**
+     * Get the certificate file. A path is returned without options.
+     *
+     * Returns: The certificate file.
+     */
+    pub fn get_file(&self) -> CertificateFile {
+        match self {
+            CertificateSource::Path(path) => CertificateFile {
+                path: path.clone(),
+                password: None,
+                private_key: None,
+                private_key_password: None,
+                ca_file: None,
+            },
+            CertificateSource::File(file) => file.clone(),
+        }
+    }
+}
+
+/**
+ * A certificate file, directory or glob pattern with options.
+ *
+ * `path`: Path to a file or directory, or a glob pattern.
+ * `password`: The password of PKCS#12 files.
+ * `private_key`: Pem file with the private key of the certificate.
+ * `private_key_password`: The password of the private key.
+ * `ca_file`: Pem file with the CA certificates the chain is verified against.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CertificateFile {
+    /// Path to a file or directory, or a glob pattern.
+    #[serde(rename = "path")]
+    pub path: String,
+    /// The password of PKCS#12 files.
+    #[serde(skip_serializing, rename = "password", default = "default_none")]
+    pub password: Option<String>,
+    /// Pem file with the private key of the certificate.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "privateKey", default = "default_none")]
+    pub private_key: Option<String>,
+    /// The password of the private key.
+    #[serde(skip_serializing, rename = "privateKeyPassword", default = "default_none")]
+    pub private_key_password: Option<String>,
+    /// Pem file with the CA certificates the chain is verified against.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "caFile", default = "default_none")]
+    pub ca_file: Option<String>,
+}
+
+/**
+ * A remote endpoint presenting a certificate chain.
+ *
+ * `address`: The endpoint as host:port.
+ * `server_name`: The server name used for SNI and the hostname check. Defaults to the host.
+ * `starttls`: The protocol used to upgrade the connection to TLS.
+ * `ca_file`: Pem file with CA certificates trusted in addition to the system trust store.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CertificateEndpoint {
+    /// The endpoint as host:port.
+    #[serde(rename = "address")]
+    pub address: String,
+    /// The server name used for SNI and the hostname check.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "serverName", default = "default_none")]
+    pub server_name: Option<String>,
+    /// The protocol used to upgrade the connection to TLS.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "starttls", default = "default_none")]
+    pub starttls: Option<StartTls>,
+    /// Pem file with CA certificates trusted in addition to the system trust store.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "caFile", default = "default_none")]
+    pub ca_file: Option<String>,
+}
+
+/**
+ * Protocols upgraded to TLS after connecting.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StartTls {
+    Smtp,
+    Imap,
+    Postgres,
+}
+
 /**
  * Monitor struct.
  *
@@ -175,6 +542,15 @@ pub struct Monitor {
     /// The database store configuration.
     #[serde(rename = "store", default = "default_database_store_level")]
     pub store: DatabaseStoreLevel,
+    /// The timeout of a check in seconds. A check running longer is cancelled.
+    #[serde(rename = "timeout", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub timeout: Option<u64>,
+    /// Number of consecutive errors before the status is set to error.
+    #[serde(rename = "failureThreshold", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub failure_threshold: Option<u32>,
+    /// Number of consecutive successes before an error status is cleared.
+    #[serde(rename = "successThreshold", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub success_threshold: Option<u32>,
 }
 
 /**
@@ -262,12 +638,86 @@ impl MonitoringConfig {
      * result: The result of getting the monitor configuration.
      */
     fn get_monitor_config(data: &str) -> Result<MonitoringConfig, ApplicationError> {
-        match serde_json::from_str(data) {
-            Ok(monitor_config) => Ok(monitor_config),
-            Err(err) => Err(ApplicationError::new(
+        let monitor_config: MonitoringConfig = match serde_json::from_str(data) {
+            Ok(monitor_config) => monitor_config,
+            Err(err) => return Err(ApplicationError::new(
                 format!("Could not parse config file: Line {}", err.line()).as_str(),
             )),
+        };
+        if let Some(notification_config) = &monitor_config.notification_config {
+            notification_config.validate()?;
         }
+        Ok(monitor_config)
+    }
+
+    /**
+     * Write a monitor to the configuration file.
+     * 
+     * The monitor with the given name is replaced, or added if it does not exist. If no monitor is given,
+     * the monitor with the given name is removed. The rest of the file is kept as is, so secrets that are
+     * not serialized are not lost. The file is replaced atomically.
+     * 
+     * `path`: The path to the configuration file.
+     * `name`: The name of the monitor.
+     * `monitor`: The monitor as JSON, or None to remove the monitor.
+     * 
+     * result: The result of writing the configuration file.
+     */
+    pub fn write_monitor(path: &str, name: &str, monitor: Option<&serde_json::Value>) -> Result<(), ApplicationError> {
+        let monitor_data = MonitoringConfig::get_monitor_data(path)?;
+        let mut config: serde_json::Value = serde_json::from_str(monitor_data.as_str()).map_err(|err| ApplicationError::new(
+            format!("Could not parse config file: Line {}", err.line()).as_str(),
+        ))?;
+        let Some(monitors) = config.get_mut("monitors").and_then(serde_json::Value::as_array_mut) else {
+            return Err(ApplicationError::new("Could not find monitors in config file"));
+        };
+        let position = monitors.iter().position(|current| current.get("name").and_then(serde_json::Value::as_str) == Some(name));
+        match (position, monitor) {
+            (Some(position), Some(monitor)) => monitors[position] = monitor.clone(),
+            (None, Some(monitor)) => monitors.push(monitor.clone()),
+            (Some(position), None) => {
+                monitors.remove(position);
+            }
+            (None, None) => {}
+        }
+        let data = serde_json::to_string_pretty(&config).map_err(|err| ApplicationError::new(
+            format!("Could not serialize config file: {err}").as_str(),
+        ))?;
+        MonitoringConfig::write_atomic(path, data.as_str())
+    }
+
+    /**
+     * Write data to a file atomically. The data is written to a temporary file in the same directory,
+     * which then replaces the file. The permissions of the original file are kept.
+     * 
+     * path: The path to the file.
+     * data: The data to write.
+     * 
+     * result: The result of writing the file.
+     */
+    fn write_atomic(path: &str, data: &str) -> Result<(), ApplicationError> {
+        let temporary_path = format!("{path}.tmp");
+        MonitoringConfig::write_and_replace(path, temporary_path.as_str(), data).map_err(|err| {
+            let _ = fs::remove_file(&temporary_path);
+            ApplicationError::new(format!("Could not write config file {path}, error: {err}").as_str())
+        })
+    }
+
+    /**
+     * Write data to the temporary file and replace the file with it.
+     * 
+     * path: The path to the file.
+     * temporary_path: The path to the temporary file.
+     * data: The data to write.
+     * 
+     * result: The result of writing the file.
+     */
+    fn write_and_replace(path: &str, temporary_path: &str, data: &str) -> std::io::Result<()> {
+        let mut file = fs::File::create(temporary_path)?;
+        file.set_permissions(fs::metadata(path)?.permissions())?;
+        file.write_all(data.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(temporary_path, path)
     }
 }
 
@@ -309,7 +759,13 @@ pub struct ServerConfig {
     #[serde(rename = "tlsConfig", skip_serializing_if = "Option::is_none", default = "default_none")]
     pub tls_config: Option<TlsConfig>,
     #[serde(rename = "workers", default = "default_server_workers")]
-    pub workers: usize
+    pub workers: usize,
+    /// Bearer token required by the admin web services. The admin web services are disabled if not set.
+    #[serde(skip_serializing, rename = "adminToken", default = "default_none")]
+    pub admin_token: Option<String>,
+    /// Expose the Prometheus metrics web service.
+    #[serde(rename = "metricsEnabled", default = "default_as_true")]
+    pub metrics_enabled: bool,
 }
 
 /**
@@ -332,7 +788,8 @@ pub struct TlsConfig {
 pub enum DatabaseType {
     Postgres,
     Mysql,
-    Maria
+    Maria,
+    Sqlite
 }
 
 /**
@@ -347,17 +804,23 @@ pub struct DatabaseConfig {
     #[serde(rename = "host", default = "default_server_ip")]
     pub host: String,
     /// The database name
-    #[serde(rename = "database")]
+    #[serde(rename = "database", default)]
     pub db_name: String,
     /// The user.
-    #[serde(rename = "user")]
+    #[serde(rename = "user", default)]
     pub user: String,
     /// The password.
-    #[serde(skip_serializing, rename = "password")]
+    #[serde(skip_serializing, rename = "password", default)]
     pub password: String,
     /// The port.
-    #[serde(rename = "port")]
+    #[serde(rename = "port", default)]
     pub port: u16,
+    /// The database file. Only used by Sqlite.
+    #[serde(rename = "path", default = "default_none")]
+    pub path: Option<String>,
+    /// The spool storing writes while the database is unavailable.
+    #[serde(rename = "spool", default = "default_spool")]
+    pub spool: SpoolConfig,
     /// The minimum connections in pool.
     #[serde(rename = "minConnections")]
     pub min_connections: u32,
@@ -369,32 +832,149 @@ pub struct DatabaseConfig {
     pub max_lifetime: u32,        
 }
 
+/**
+ * Spool configuration. Writes failing while the database is unavailable are appended to the spool
+ * file and replayed when the database is available again.
+ */
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SpoolConfig {
+    /// Store failed writes in the spool.
+    #[serde(rename = "enabled", default = "default_as_true")]
+    pub enabled: bool,
+    /// The spool file. One json record per line.
+    #[serde(rename = "path", default = "default_spool_path")]
+    pub path: String,
+    /// The maximum size of the spool file in bytes. Writes are dropped when the spool is full.
+    #[serde(rename = "maxSize", default = "default_spool_max_size")]
+    pub max_size: u64,
+    /// The schedule for replaying the spool.
+    #[serde(rename = "replaySchedule", default = "default_spool_replay_schedule")]
+    pub replay_schedule: String,
+}
+
 /**
  * Notification configuration.
  */
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct NotificationConfig {
-    /// The url of the smtp server.
-    #[serde(rename = "url")]
-    pub url: String,
+    /// The url of the smtp server. Kept for configurations written before notifiers were added.
+    #[serde(rename = "url", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub url: Option<String>,
     /// The recipients.
-    #[serde(rename = "recipients")]
-    pub recipients: Vec<String>,
+    #[serde(rename = "recipients", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub recipients: Option<Vec<String>>,
     /// From.
-    #[serde(rename = "from")]
-    pub from: String,
+    #[serde(rename = "from", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub from: Option<String>,
     /// Reply to email.
-    #[serde(rename = "replyTo")]
-    pub reply_to: String,  
+    #[serde(rename = "replyTo", skip_serializing_if = "Option::is_none", default = "default_none")]
+    pub reply_to: Option<String>,
+    /// The notifiers to send the notifications with.
+    #[serde(rename = "notifiers", default)]
+    pub notifiers: Vec<NotifierConfig>,
     /// Notification check interval.  
     #[serde(rename = "schedule", default = "default_notify_schedule")]
     pub schedule: String,
     /// Resend errors after minutes
     #[serde(rename = "resendAfter", default = "default_resend_after")]
     pub resend_after: i64,
+    /// The minimum severity to notify. Warn also notifies warnings.
+    #[serde(rename = "minimumSeverity", default = "default_minimum_severity")]
+    pub minimum_severity: ThresholdLevel,
 
 }
 
+impl NotificationConfig {
+    /**
+     * Validate the notification configuration. The email fields directly on the notification configuration
+     * must be given together, or not at all.
+     * 
+     * result: Ok if the configuration is valid.
+     * 
+     * throws: `ApplicationError`: If only some of the email fields are given. The message names the missing fields.
+     */
+    pub fn validate(&self) -> Result<(), ApplicationError> {
+        let fields = [
+            ("url", self.url.is_some()),
+            ("recipients", self.recipients.is_some()),
+            ("from", self.from.is_some()),
+            ("replyTo", self.reply_to.is_some()),
+        ];
+        let missing: Vec<&str> = fields.iter().filter(|(_, is_set)| !is_set).map(|(name, _)| *name).collect();
+        if missing.is_empty() || missing.len() == fields.len() {
+            return Ok(());
+        }
+        Err(ApplicationError::new(
+            format!("Notification email configuration is missing {}", missing.join(", ")).as_str(),
+        ))
+    }
+
+    /**
+     * Get all the configured notifiers. The email fields directly on the notification configuration
+     * are added as an email notifier.
+     * 
+     * result: The notifier configurations.
+     */
+    pub fn get_notifiers(&self) -> Vec<NotifierConfig> {
+        let mut notifiers = Vec::new();
+        if let (Some(url), Some(recipients), Some(from), Some(reply_to)) = (&self.url, &self.recipients, &self.from, &self.reply_to) {
+            notifiers.push(NotifierConfig::Email {
+                url: url.clone(),
+                recipients: recipients.clone(),
+                from: from.clone(),
+                reply_to: reply_to.clone(),
+            });
+        }
+        notifiers.extend(self.notifiers.iter().cloned());
+        notifiers
+    }
+}
+
+/**
+ * Notifier configuration.
+ * 
+ * Email: Send the notifications as email through an smtp server.
+ * Webhook: Post the notifications as json to an url.
+ */
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NotifierConfig {
+    Email {
+        /// The url of the smtp server.
+        #[serde(rename = "url")]
+        url: String,
+        /// The recipients.
+        #[serde(rename = "recipients")]
+        recipients: Vec<String>,
+        /// From.
+        #[serde(rename = "from")]
+        from: String,
+        /// Reply to email.
+        #[serde(rename = "replyTo")]
+        reply_to: String,
+    },
+    Webhook {
+        /// The urls to post the notifications to.
+        #[serde(rename = "urls")]
+        urls: Vec<String>,
+        /// Headers added to the request.
+        #[serde(rename = "headers", skip_serializing_if = "Option::is_none", default = "default_none")]
+        headers: Option<HashMap<String, String>>,
+        /// Secret used to sign the payload with HMAC-SHA256. The signature is sent in the X-Signature-256 header.
+        #[serde(rename = "secret", skip_serializing_if = "Option::is_none", default = "default_none")]
+        secret: Option<String>,
+        /// Number of retries when a request fails.
+        #[serde(rename = "retries", default = "default_webhook_retries")]
+        retries: u32,
+        /// Seconds to wait before the first retry. Doubled for every following retry.
+        #[serde(rename = "retryDelay", default = "default_webhook_retry_delay")]
+        retry_delay: u64,
+        /// The request timeout in seconds.
+        #[serde(rename = "timeout", default = "default_webhook_timeout")]
+        timeout: u64,
+    },
+}
+
 /**
  * Default server configuration.
  * 
@@ -412,7 +992,9 @@ fn default_server() -> ServerConfig {
         access_control_allow_credentials: default_none(),
         access_control_max_age: default_none(),
         tls_config: default_none(),
-        workers: default_server_workers()
+        workers: default_server_workers(),
+        admin_token: default_none(),
+        metrics_enabled: default_as_true(),
     }
 }
 
@@ -420,6 +1002,18 @@ fn default_threshold_level() -> ThresholdLevel {
     ThresholdLevel::Error
 }
 
+fn default_command_mode() -> CommandMode {
+    CommandMode::Standard
+}
+
+pub(crate) fn default_max_output_size() -> usize {
+    65536
+}
+
+fn default_min_instances() -> u32 {
+    1
+}
+
 /**
  * Default server workers.
  * 
@@ -489,6 +1083,43 @@ fn default_max_lifetime() -> u32 {
     300
 }
 
+/**
+ * Default spool configuration.
+ */
+fn default_spool() -> SpoolConfig {
+    debug!("Using default spool");
+    SpoolConfig {
+        enabled: default_as_true(),
+        path: default_spool_path(),
+        max_size: default_spool_max_size(),
+        replay_schedule: default_spool_replay_schedule(),
+    }
+}
+
+/**
+ * Default spool file.
+ */
+fn default_spool_path() -> String {
+    debug!("Using default spool path");
+    "/var/lib/monitoring-agent-daemon/spool.jsonl".to_string()
+}
+
+/**
+ * Default maximum spool size. 10 MiB.
+ */
+fn default_spool_max_size() -> u64 {
+    debug!("Using default spool max size");
+    10 * 1024 * 1024
+}
+
+/**
+ * Replay the spool every minute.
+ */
+fn default_spool_replay_schedule() -> String {
+    debug!("Using default spool replay schedule");
+    "30 * * * * *".to_string()
+}
+
 /** 
  * Default tokio stack size.
  */
@@ -520,6 +1151,38 @@ fn default_resend_after() -> i64 {
     120
 }
 
+/**
+ * Default minimum severity to notify.
+ */
+fn default_minimum_severity() -> ThresholdLevel {
+    debug!("Using default minimum severity");
+    ThresholdLevel::Error
+}
+
+/**
+ * Default number of webhook retries.
+ */
+fn default_webhook_retries() -> u32 {
+    debug!("Using default webhook retries");
+    3
+}
+
+/**
+ * Default seconds before the first webhook retry.
+ */
+fn default_webhook_retry_delay() -> u64 {
+    debug!("Using default webhook retry delay");
+    1
+}
+
+/**
+ * Default webhook request timeout in seconds.
+ */
+fn default_webhook_timeout() -> u64 {
+    debug!("Using default webhook timeout");
+    10
+}
+
 /**
  * Default threshold days warn.
  */
@@ -556,7 +1219,10 @@ mod tests {
             MonitorType::Tcp {
                 host: "192.168.1.1".to_string(),
                 port: 8080,
-                retry: None
+                retry: None,
+                warn_latency_ms: None,
+                error_latency_ms: None,
+                store_values: false,
             }
         );
         assert_eq!(&8080, &monitoring.server.clone().port);
@@ -587,7 +1253,11 @@ mod tests {
                 root_certificate: None,
                 identity: None,
                 identity_password: None,
-                retry: None
+                retry: None,
+                warn_latency_ms: None,
+                error_latency_ms: None,
+                store_values: false,
+                assertions: None,
             }
         );
         assert_eq!(&65000, &monitoring.server.clone().port);
@@ -595,6 +1265,102 @@ mod tests {
         Ok(())
     }
 
+    /**
+     * Test for a http monitor with latency thresholds and assertions.
+     */
+    #[test]
+    fn test_simple_http_assertions_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_http_assertions.json")?;
+        let MonitorType::Http { warn_latency_ms, error_latency_ms, store_values, assertions, .. } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected http monitor");
+        };
+        assert_eq!(warn_latency_ms, Some(500));
+        assert_eq!(error_latency_ms, Some(2000));
+        assert!(store_values);
+        assert_eq!(
+            assertions,
+            Some(HttpAssertions {
+                status_codes: Some(vec![200, 204]),
+                body_contains: Some("status".to_string()),
+                body_regex: Some("\"version\":\\s*\"\\d+\"".to_string()),
+                json_path: Some("$.status".to_string()),
+                json_path_value: Some(serde_json::Value::String("UP".to_string())),
+                required_headers: Some(vec!["Content-Type".to_string()]),
+                max_body_size: Some(65536),
+            })
+        );
+        Ok(())
+    }
+
+    /**
+     * Test for a certificate monitor with certificate files and remote endpoints.
+     */
+    #[test]
+    fn test_simple_certificate_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_certificate.json")?;
+        assert_eq!(2, monitoring.monitors.len());
+        let MonitorType::Certificate { certificates, endpoints, threshold_days_warn, threshold_days_error } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected certificate monitor");
+        };
+        assert_eq!(certificates.len(), 4);
+        assert_eq!(certificates[0], CertificateSource::Path("/etc/ssl/certs/server.pem".to_string()));
+        assert_eq!(certificates[1].get_file().path, "/etc/letsencrypt/live/*/fullchain.pem");
+        assert_eq!(certificates[1].get_file().private_key, None);
+        assert_eq!(
+            certificates[2].get_file(),
+            CertificateFile {
+                path: "/etc/ssl/private/server.p12".to_string(),
+                password: Some("secret".to_string()),
+                private_key: None,
+                private_key_password: None,
+                ca_file: Some("/etc/ssl/certs/internal-ca.pem".to_string()),
+            }
+        );
+        assert_eq!(certificates[3].get_file().private_key, Some("/etc/ssl/private/server.key".to_string()));
+        assert_eq!(certificates[3].get_file().private_key_password, Some("keysecret".to_string()));
+        assert_eq!(threshold_days_warn, 30);
+        assert_eq!(threshold_days_error, 7);
+        let endpoints = endpoints.unwrap();
+        assert_eq!(endpoints.len(), 3);
+        assert_eq!(endpoints[0], CertificateEndpoint { address: "www.example.com:443".to_string(), server_name: None, starttls: None, ca_file: None });
+        assert_eq!(endpoints[1].server_name, Some("smtp.example.com".to_string()));
+        assert_eq!(endpoints[1].starttls, Some(StartTls::Smtp));
+        assert_eq!(endpoints[2].starttls, Some(StartTls::Postgres));
+        assert_eq!(endpoints[2].ca_file, Some("/etc/ssl/certs/internal-ca.pem".to_string()));
+        let MonitorType::Certificate { certificates, endpoints, .. } = monitoring.monitors[1].details.clone() else {
+            panic!("Expected certificate monitor");
+        };
+        assert!(certificates.is_empty());
+        assert_eq!(endpoints.unwrap()[0].starttls, Some(StartTls::Imap));
+        Ok(())
+    }
+
+    /**
+     * Test for a http scenario monitor.
+     */
+    #[test]
+    fn test_simple_http_scenario_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_http_scenario.json")?;
+        let MonitorType::HttpScenario { steps, use_builtin_root_certs, accept_invalid_certs, .. } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected http scenario monitor");
+        };
+        assert!(use_builtin_root_certs);
+        assert!(accept_invalid_certs);
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].method, HttpMethod::Post);
+        assert!(steps[0].follow_redirects);
+        assert_eq!(steps[0].extract.as_ref().unwrap().get("token"), Some(&HttpExtraction::JsonPath("$.token".to_string())));
+        assert_eq!(steps[0].extract.as_ref().unwrap().get("csrf"), Some(&HttpExtraction::Header("X-Csrf".to_string())));
+        assert!(!steps[1].follow_redirects);
+        assert_eq!(steps[1].headers.as_ref().unwrap().get("Authorization"), Some(&"Bearer ${token}".to_string()));
+        assert_eq!(steps[1].assertions.as_ref().unwrap().json_path, Some("$.status".to_string()));
+        assert_eq!(steps[1].extract.as_ref().unwrap().get("version"), Some(&HttpExtraction::Regex("\"version\":\\s*\"(\\d+)\"".to_string())));
+        Ok(())
+    }
+
     /**
      * Test for a simple command monitor.
      */
@@ -610,12 +1376,61 @@ mod tests {
             MonitorType::Command {
                 command: "ls".to_string(),
                 args: Some(vec!["-l".to_string()]),
-                expected: Some("expected".to_string())
+                expected: Some("expected".to_string()),
+                mode: CommandMode::Standard,
+                store_values: false,
+                expected_exit_codes: None,
+                stdout_regex: None,
+                stderr_regex: None,
+                environment: None,
+                working_directory: None,
+                stdin: None,
+                user: None,
+                group: None,
+                max_output_size: 65536,
             }
         );
         Ok(())
     }
 
+    /**
+     * Test for a command monitor with output matching and execution options.
+     */
+    #[test]
+    fn test_simple_command_options_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_command_options.json")?;
+        let MonitorType::Command { expected_exit_codes, stdout_regex, stderr_regex, environment, working_directory, stdin, user, group, max_output_size, .. } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected command monitor");
+        };
+        assert_eq!(expected_exit_codes, Some(vec![0, 1]));
+        assert_eq!(stdout_regex, Some("^backup finished".to_string()));
+        assert_eq!(stderr_regex, Some("^$".to_string()));
+        assert_eq!(environment, Some(HashMap::from([("BACKUP_TARGET".to_string(), "/mnt/backup".to_string())])));
+        assert_eq!(working_directory, Some("/var/backups".to_string()));
+        assert_eq!(stdin, Some("yes\n".to_string()));
+        assert_eq!(user, Some("backup".to_string()));
+        assert_eq!(group, Some("backup".to_string()));
+        assert_eq!(max_output_size, 1024);
+        Ok(())
+    }
+
+    /**
+     * Test for a command monitor running a Nagios plugin.
+     */
+    #[test]
+    fn test_simple_command_nagios_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_command_nagios.json")?;
+        let MonitorType::Command { command, mode, store_values, .. } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected command monitor");
+        };
+        assert_eq!(command, "/usr/lib/nagios/plugins/check_disk");
+        assert_eq!(mode, CommandMode::Nagios);
+        assert!(store_values);
+        Ok(())
+    }
+
     /**
      * Test for a simple mariadb monitor.
      */
@@ -639,6 +1454,8 @@ mod tests {
                     min_connections: 1,
                     max_connections: 10,
                     max_lifetime: 300,
+                    path: None,
+                    spool: default_spool(),
                 }),
                 max_query_time: Some(100),
             }
@@ -669,6 +1486,8 @@ mod tests {
                     min_connections: 1,
                     max_connections: 10,
                     max_lifetime: 300,
+                    path: None,
+                    spool: default_spool(),
                 }),
                 max_query_time: Some(100),
             }
@@ -726,6 +1545,75 @@ mod tests {
     }    
 
 
+    /**
+     * Test for a simple disk monitor.
+     */
+    #[test]
+    fn test_simple_disk_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_disk.json")?;
+        assert_eq!(1, monitoring.monitors.len());
+        let monitor = monitoring.monitors[0].details.clone();
+        assert_eq!(
+            monitor,
+            MonitorType::Disk {
+                mounts: Some(vec!["/".to_string(), "/var".to_string()]),
+                error_percentage_used_space: Some(90.0),
+                warn_percentage_used_space: Some(80.0),
+                error_percentage_used_inodes: Some(95.0),
+                warn_percentage_used_inodes: Some(85.0),
+                store_values: true,
+            }
+        );
+        Ok(())
+    }
+
+    /**
+     * Test for a simple cpu monitor.
+     */
+    #[test]
+    fn test_simple_cpu_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_cpu.json")?;
+        assert_eq!(1, monitoring.monitors.len());
+        let monitor = monitoring.monitors[0].details.clone();
+        assert_eq!(
+            monitor,
+            MonitorType::Cpu {
+                error_percentage_total: Some(95.0),
+                warn_percentage_total: Some(85.0),
+                error_percentage_iowait: Some(50.0),
+                warn_percentage_iowait: None,
+                per_core: true,
+                store_values: true,
+            }
+        );
+        Ok(())
+    }
+
+    /**
+     * Test for a simple network monitor.
+     */
+    #[test]
+    fn test_simple_network_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_network.json")?;
+        assert_eq!(1, monitoring.monitors.len());
+        let monitor = monitoring.monitors[0].details.clone();
+        assert_eq!(
+            monitor,
+            MonitorType::Network {
+                interfaces: Some(vec!["eth0".to_string()]),
+                error_percentage_saturation: Some(90.0),
+                warn_percentage_saturation: Some(70.0),
+                error_on_errors: true,
+                warn_on_drops: false,
+                store_values: true,
+            }
+        );
+        Ok(())
+    }
+
     /**
      * Test for a simple systemctl monitor.
      */
@@ -761,8 +1649,15 @@ mod tests {
                 application_names: Some(vec!["app1".to_string(), "app2".to_string()]),
                 pids: None,
                 regexp: None,
+                rules: None,
                 threshold_mem_error: Some(100),
                 threshold_mem_warn: Some(100),                
+                threshold_cpu_warn: None,
+                threshold_cpu_error: None,
+                threshold_io_warn: None,
+                threshold_io_error: None,
+                threshold_fd_warn: None,
+                threshold_fd_error: None,
                 store_values: true,
                 
             }
@@ -770,6 +1665,74 @@ mod tests {
         Ok(())
     }    
 
+    /**
+     * Test for a process monitor with match rules.
+     */
+    #[test]
+    fn test_simple_process_rules_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_process_rules.json")?;
+        let MonitorType::Process { application_names, rules, threshold_cpu_warn, threshold_cpu_error, threshold_io_warn, threshold_io_error, threshold_fd_warn, threshold_fd_error, store_values, .. } = monitoring.monitors[0].details.clone() else {
+            panic!("Expected process monitor");
+        };
+        assert_eq!(application_names, None);
+        assert!(!store_values);
+        assert_eq!(threshold_cpu_warn, Some(80.0));
+        assert_eq!(threshold_cpu_error, Some(95.0));
+        assert_eq!(threshold_io_warn, Some(10_485_760));
+        assert_eq!(threshold_io_error, None);
+        assert_eq!(threshold_fd_warn, Some(80.0));
+        assert_eq!(threshold_fd_error, None);
+        assert_eq!(
+            rules,
+            Some(vec![
+                ProcessMatchRule {
+                    name: Some("nginx".to_string()),
+                    application_name: Some("nginx".to_string()),
+                    regexp: None,
+                    pid: None,
+                    cmdline_regex: None,
+                    user: None,
+                    parent_pid: None,
+                    parent_name: None,
+                    pidfile: None,
+                    min_instances: 2,
+                    max_instances: Some(9),
+                    warn_on_restart: true,
+                },
+                ProcessMatchRule {
+                    name: Some("nginx master".to_string()),
+                    application_name: None,
+                    regexp: None,
+                    pid: None,
+                    cmdline_regex: None,
+                    user: None,
+                    parent_pid: Some(1),
+                    parent_name: None,
+                    pidfile: Some("/run/nginx.pid".to_string()),
+                    min_instances: 1,
+                    max_instances: None,
+                    warn_on_restart: true,
+                },
+                ProcessMatchRule {
+                    name: None,
+                    application_name: None,
+                    regexp: Some("^php-fpm".to_string()),
+                    pid: None,
+                    cmdline_regex: Some("--pool www".to_string()),
+                    user: Some("www-data".to_string()),
+                    parent_pid: None,
+                    parent_name: Some("php-fpm".to_string()),
+                    pidfile: None,
+                    min_instances: 1,
+                    max_instances: None,
+                    warn_on_restart: false,
+                },
+            ])
+        );
+        Ok(())
+    }
+
     /**
      * Test for a simple server.
      */
@@ -784,6 +1747,110 @@ mod tests {
         assert_eq!("*".to_string(), monitoring.server.clone().access_control_allow_origin.unwrap());
         assert_eq!("GET, POST, PUT, DELETE, OPTIONS".to_string(), monitoring.server.clone().access_control_allow_methods.unwrap());
         assert_eq!("Content-Type, Authorization, Content-Length, X-Requested-With".to_string(), monitoring.server.clone().access_control_allow_headers.unwrap());
+        assert_eq!(Some("secret".to_string()), monitoring.server.clone().admin_token);
+        Ok(())
+    }
+
+    /**
+     * Test for a simple sqlite database configuration.
+     */
+    #[test]
+    fn test_simple_database_sqlite_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_database_sqlite.json")?;
+        let database_config = monitoring.database.unwrap();
+        assert_eq!(DatabaseType::Sqlite, database_config.dbtype);
+        assert_eq!(Some("/var/lib/monitoring-agent-daemon/monitoring.db".to_string()), database_config.path);
+        assert_eq!(1, database_config.min_connections);
+        assert_eq!(4, database_config.max_connections);
+        assert_eq!(default_spool(), database_config.spool);
+        Ok(())
+    }
+
+    /**
+     * Test for a database configuration with the spool configured.
+     */
+    #[test]
+    fn test_simple_database_spool_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_database_spool.json")?;
+        let spool = monitoring.database.unwrap().spool;
+        assert!(spool.enabled);
+        assert_eq!("/tmp/monitoring-agent/spool.jsonl", spool.path);
+        assert_eq!(1_048_576, spool.max_size);
+        assert_eq!("0 * * * * *", spool.replay_schedule);
+        Ok(())
+    }
+
+    /**
+     * Test for a simple notification configuration.
+     */
+    #[test]
+    fn test_simple_notification_file() -> Result<(), ApplicationError> {
+        let monitoring: MonitoringConfig =
+            MonitoringConfig::new("resources/test/configuration_import_test/test_simple_notification.json")?;
+        let notification_config = monitoring.notification_config.unwrap();
+        assert_eq!(60, notification_config.resend_after);
+        assert_eq!("0 */5 * * * *", notification_config.schedule);
+        assert_eq!(ThresholdLevel::Warn, notification_config.minimum_severity);
+        assert_eq!(notification_config.get_notifiers(), vec![
+            NotifierConfig::Email {
+                url: "smtp://localhost:25".to_string(),
+                recipients: vec!["test@test.com".to_string()],
+                from: "from@test.com".to_string(),
+                reply_to: "reply@test.com".to_string(),
+            },
+            NotifierConfig::Webhook {
+                urls: vec!["http://localhost:8080/hook".to_string()],
+                headers: Some(HashMap::from([("Authorization".to_string(), "Bearer token".to_string())])),
+                secret: Some("secret".to_string()),
+                retries: 5,
+                retry_delay: 1,
+                timeout: 10,
+            },
+        ]);
+        Ok(())
+    }
+
+    /**
+     * Test that a partially specified notification email is rejected.
+     */
+    #[test]
+    fn test_partial_notification_email() {
+        let data = r#"{ "monitors": [], "notificationConfig": { "url": "smtp://localhost:25", "from": "from@test.com" } }"#;
+        let err = MonitoringConfig::get_monitor_config(data).unwrap_err();
+        assert_eq!(err.message, "Notification email configuration is missing recipients, replyTo");
+        let data = r#"{ "monitors": [], "notificationConfig": { "notifiers": [] } }"#;
+        assert!(MonitoringConfig::get_monitor_config(data).is_ok());
+    }
+
+    /**
+     * Test writing monitors to the configuration file.
+     */
+    #[test]
+    fn test_write_monitor() -> Result<(), ApplicationError> {
+        let path = std::env::temp_dir().join("monitoring-agent-test-write-monitor.json");
+        let path = path.to_str().unwrap();
+        fs::copy("resources/test/configuration_import_test/test_simple_http_tlsfields.json", path).unwrap();
+        let original = MonitoringConfig::new(path)?;
+        let name = original.monitors[0].name.clone();
+        let monitor = serde_json::json!({
+            "name": "added",
+            "schedule": "*/10 * * * * *",
+            "details": { "type": "tcp", "host": "localhost", "port": 80 }
+        });
+        MonitoringConfig::write_monitor(path, "added", Some(&monitor))?;
+        let monitoring = MonitoringConfig::new(path)?;
+        assert_eq!(2, monitoring.monitors.len());
+        assert_eq!(original.monitors[0], monitoring.monitors[0]);
+        assert_eq!("added", monitoring.monitors[1].name);
+
+        MonitoringConfig::write_monitor(path, &name, None)?;
+        let monitoring = MonitoringConfig::new(path)?;
+        assert_eq!(1, monitoring.monitors.len());
+        assert_eq!("added", monitoring.monitors[0].name);
+        assert_eq!(original.server, monitoring.server);
+        fs::remove_file(path).unwrap();
         Ok(())
     }                   
 
@@ -135,4 +135,384 @@ impl ProcessMemoryElement {
             dt,
         }
     }
-}
\ No newline at end of file
+}
+
+/**
+ * The disk use element. Used to represent the disk use of a mount point.
+ * 
+ * `timestamp`: The timestamp.
+ * `mount_point`: The mount point.
+ * `space_percent_used`: The percentage of the space used.
+ * `inodes_percent_used`: The percentage of the inodes used.
+ */
+#[derive(Debug, Clone)]
+pub struct DiskinfoElement {
+    /// The timestamp.    
+    pub timestamp: DateTime<Utc>,
+    /// The mount point.
+    pub mount_point: String,
+    /// The percentage of the space used.
+    pub space_percent_used: Option<f64>,
+    /// The percentage of the inodes used.
+    pub inodes_percent_used: Option<f64>,
+}
+
+impl DiskinfoElement {
+    /**
+     * Create a new disk use element.
+     * 
+     * `timestamp`: The timestamp.
+     * `mount_point`: The mount point.
+     * `space_percent_used`: The percentage of the space used.
+     * `inodes_percent_used`: The percentage of the inodes used.
+     * 
+     * Returns the disk use element.
+     */
+    pub fn new(timestamp: DateTime<Utc>, mount_point: &str, space_percent_used: Option<f64>, inodes_percent_used: Option<f64>) -> DiskinfoElement {
+        DiskinfoElement {
+            timestamp,
+            mount_point: mount_point.to_string(),
+            space_percent_used,
+            inodes_percent_used,
+        }
+    }
+}
+
+/**
+ * The cpu utilisation element. Used to represent the cpu utilisation of a cpu.
+ * 
+ * `timestamp`: The timestamp.
+ * `cpu_name`: The name of the cpu. cpu is the total of all cpus.
+ * `user`: The percentage of time spent in user mode.
+ * `system`: The percentage of time spent in system mode.
+ * `iowait`: The percentage of time spent waiting for I/O.
+ * `steal`: The percentage of time stolen by other virtual machines.
+ * `total`: The percentage of time not spent idle or waiting for I/O.
+ */
+#[derive(Debug, Clone)]
+pub struct CpustatElement {
+    /// The timestamp.    
+    pub timestamp: DateTime<Utc>,
+    /// The name of the cpu.
+    pub cpu_name: String,
+    /// The percentage of time spent in user mode.
+    pub user: Option<f64>,
+    /// The percentage of time spent in system mode.
+    pub system: Option<f64>,
+    /// The percentage of time spent waiting for I/O.
+    pub iowait: Option<f64>,
+    /// The percentage of time stolen by other virtual machines.
+    pub steal: Option<f64>,
+    /// The percentage of time not spent idle or waiting for I/O.
+    pub total: Option<f64>,
+}
+
+impl CpustatElement {
+    /**
+     * Create a new cpu utilisation element.
+     * 
+     * `timestamp`: The timestamp.
+     * `cpu_name`: The name of the cpu.
+     * `user`: The percentage of time spent in user mode.
+     * `system`: The percentage of time spent in system mode.
+     * `iowait`: The percentage of time spent waiting for I/O.
+     * `steal`: The percentage of time stolen by other virtual machines.
+     * `total`: The percentage of time not spent idle or waiting for I/O.
+     * 
+     * Returns the cpu utilisation element.
+     */
+    pub fn new(timestamp: DateTime<Utc>, cpu_name: &str, user: Option<f64>, system: Option<f64>, iowait: Option<f64>, steal: Option<f64>, total: Option<f64>) -> CpustatElement {
+        CpustatElement {
+            timestamp,
+            cpu_name: cpu_name.to_string(),
+            user,
+            system,
+            iowait,
+            steal,
+            total,
+        }
+    }
+}
+
+/**
+ * The network element. Used to represent the throughput of a network interface.
+ * 
+ * `timestamp`: The timestamp.
+ * `interface`: The name of the interface.
+ * `rx_bytes_per_second`: The bytes received per second.
+ * `tx_bytes_per_second`: The bytes transmitted per second.
+ * `errors`: The receive and transmit errors.
+ * `drops`: The received and transmitted packets dropped.
+ */
+#[derive(Debug, Clone)]
+pub struct NetworkElement {
+    /// The timestamp.    
+    pub timestamp: DateTime<Utc>,
+    /// The name of the interface.
+    pub interface: String,
+    /// The bytes received per second.
+    pub rx_bytes_per_second: Option<f64>,
+    /// The bytes transmitted per second.
+    pub tx_bytes_per_second: Option<f64>,
+    /// The receive and transmit errors.
+    pub errors: Option<u64>,
+    /// The received and transmitted packets dropped.
+    pub drops: Option<u64>,
+}
+
+impl NetworkElement {
+    /**
+     * Create a new network element.
+     * 
+     * `timestamp`: The timestamp.
+     * `interface`: The name of the interface.
+     * `rx_bytes_per_second`: The bytes received per second.
+     * `tx_bytes_per_second`: The bytes transmitted per second.
+     * `errors`: The receive and transmit errors.
+     * `drops`: The received and transmitted packets dropped.
+     * 
+     * Returns the network element.
+     */
+    pub fn new(timestamp: DateTime<Utc>, interface: &str, rx_bytes_per_second: Option<f64>, tx_bytes_per_second: Option<f64>, errors: Option<u64>, drops: Option<u64>) -> NetworkElement {
+        NetworkElement {
+            timestamp,
+            interface: interface.to_string(),
+            rx_bytes_per_second,
+            tx_bytes_per_second,
+            errors,
+            drops,
+        }
+    }
+}
+
+/**
+ * The response time element. Used to represent the average response time of a monitor in milliseconds.
+ * 
+ * `timestamp`: The timestamp.
+ * `connect_time`: The time to open the connection, including the TLS handshake for https.
+ * `first_byte_time`: The time until the response headers were received.
+ * `total_time`: The total time of the check.
+ */
+#[derive(Debug, Clone)]
+pub struct ResponseTimeElement {
+    /// The timestamp.
+    pub timestamp: DateTime<Utc>,
+    /// The time to open the connection, including the TLS handshake for https.
+    pub connect_time: Option<f64>,
+    /// The time until the response headers were received.
+    pub first_byte_time: Option<f64>,
+    /// The total time of the check.
+    pub total_time: Option<f64>,
+}
+
+impl ResponseTimeElement {
+    /**
+     * Create a new response time element.
+     * 
+     * `timestamp`: The timestamp.
+     * `connect_time`: The time to open the connection, including the TLS handshake for https.
+     * `first_byte_time`: The time until the response headers were received.
+     * `total_time`: The total time of the check.
+     * 
+     * Returns the response time element.
+     */
+    pub fn new(timestamp: DateTime<Utc>, connect_time: Option<f64>, first_byte_time: Option<f64>, total_time: Option<f64>) -> ResponseTimeElement {
+        ResponseTimeElement {
+            timestamp,
+            connect_time,
+            first_byte_time,
+            total_time,
+        }
+    }
+}
+
+/**
+ * The monitor status element. Used to represent a stored status of a monitor.
+ * 
+ * `timestamp`: The timestamp.
+ * `status`: The status. One of OK, WARN, ERROR or UNKNOWN.
+ * `message`: The error message.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorStatusElement {
+    /// The timestamp.
+    pub timestamp: DateTime<Utc>,
+    /// The status. One of OK, WARN, ERROR or UNKNOWN.
+    pub status: String,
+    /// The error message.
+    pub message: Option<String>,
+}
+
+impl MonitorStatusElement {
+    /**
+     * Create a new monitor status element.
+     * 
+     * `timestamp`: The timestamp.
+     * `status`: The status.
+     * `message`: The error message.
+     * 
+     * Returns the monitor status element.
+     */
+    pub fn new(timestamp: DateTime<Utc>, status: &str, message: Option<String>) -> MonitorStatusElement {
+        MonitorStatusElement {
+            timestamp,
+            status: status.to_string(),
+            message,
+        }
+    }
+}
+
+/**
+ * The uptime of a monitor in a window. A status lasts until the next stored status or the end of the
+ * window. OK and WARN count as available, ERROR as unavailable and UNKNOWN is not counted. An UNKNOWN
+ * between two errors does not end the outage.
+ * 
+ * `from_datetime`: The start of the window.
+ * `to_datetime`: The end of the window.
+ * `availability_percent`: The percentage of the monitored time the monitor was available.
+ * `outages`: The number of times the monitor went into error.
+ * `monitored_seconds`: The number of seconds with a known status.
+ * `downtime_seconds`: The number of seconds in error.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorUptimeElement {
+    /// The start of the window.
+    pub from_datetime: DateTime<Utc>,
+    /// The end of the window.
+    pub to_datetime: DateTime<Utc>,
+    /// The percentage of the monitored time the monitor was available.
+    pub availability_percent: Option<f64>,
+    /// The number of times the monitor went into error.
+    pub outages: u32,
+    /// The number of seconds with a known status.
+    pub monitored_seconds: i64,
+    /// The number of seconds in error.
+    pub downtime_seconds: i64,
+}
+
+impl MonitorUptimeElement {
+    /**
+     * Compute the uptime from the status changes in the window.
+     * 
+     * `from_datetime`: The start of the window.
+     * `to_datetime`: The end of the window. Limited to the current time.
+     * `initial_status`: The last status stored before the window.
+     * `changes`: The status changes in the window ordered by time.
+     * 
+     * Returns the uptime element.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_status_changes(from_datetime: DateTime<Utc>, to_datetime: DateTime<Utc>, initial_status: Option<&str>, changes: &[MonitorStatusElement]) -> MonitorUptimeElement {
+        let to_datetime = to_datetime.min(Utc::now());
+        let mut segments: Vec<(DateTime<Utc>, &str)> = Vec::new();
+        if let Some(initial_status) = initial_status {
+            segments.push((from_datetime, initial_status));
+        }
+        segments.extend(changes.iter()
+            .filter(|change| change.timestamp >= from_datetime && change.timestamp <= to_datetime)
+            .map(|change| (change.timestamp, change.status.as_str())));
+        let mut uptime = chrono::Duration::zero();
+        let mut downtime = chrono::Duration::zero();
+        let mut outages: u32 = 0;
+        let mut previous_status: Option<&str> = None;
+        for (index, (start, status)) in segments.iter().enumerate() {
+            let end = segments.get(index + 1).map_or(to_datetime, |(next, _)| *next);
+            let duration = (end - *start).max(chrono::Duration::zero());
+            match *status {
+                "ERROR" => {
+                    downtime += duration;
+                    if previous_status != Some("ERROR") {
+                        outages += 1;
+                    }
+                }
+                "OK" | "WARN" => uptime += duration,
+                _ => continue,
+            }
+            previous_status = Some(status);
+        }
+        let monitored = uptime + downtime;
+        let availability_percent = if monitored > chrono::Duration::zero() {
+            Some(uptime.num_milliseconds() as f64 / monitored.num_milliseconds() as f64 * 100.0)
+        } else {
+            None
+        };
+        MonitorUptimeElement {
+            from_datetime,
+            to_datetime,
+            availability_percent,
+            outages,
+            monitored_seconds: monitored.num_seconds(),
+            downtime_seconds: downtime.num_seconds(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn get_time(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_uptime_without_statuses() {
+        let uptime = MonitorUptimeElement::from_status_changes(get_time(0), get_time(10), None, &[]);
+        assert_eq!(uptime.availability_percent, None);
+        assert_eq!(uptime.outages, 0);
+        assert_eq!(uptime.monitored_seconds, 0);
+    }
+
+    #[test]
+    fn test_uptime_with_outages() {
+        let changes = vec![
+            MonitorStatusElement::new(get_time(2), "ERROR", Some("Error".to_string())),
+            MonitorStatusElement::new(get_time(3), "OK", None),
+            MonitorStatusElement::new(get_time(5), "ERROR", Some("Error".to_string())),
+            MonitorStatusElement::new(get_time(6), "WARN", None),
+        ];
+        let uptime = MonitorUptimeElement::from_status_changes(get_time(0), get_time(10), Some("OK"), &changes);
+        assert_eq!(uptime.outages, 2);
+        assert_eq!(uptime.monitored_seconds, 10 * 3600);
+        assert_eq!(uptime.downtime_seconds, 2 * 3600);
+        assert_eq!(uptime.availability_percent, Some(80.0));
+    }
+
+    #[test]
+    fn test_uptime_outage_continues_into_window() {
+        let changes = vec![
+            MonitorStatusElement::new(get_time(0), "ERROR", None),
+            MonitorStatusElement::new(get_time(1), "UNKNOWN", None),
+            MonitorStatusElement::new(get_time(2), "OK", None),
+        ];
+        let uptime = MonitorUptimeElement::from_status_changes(get_time(0), get_time(4), Some("ERROR"), &changes);
+        assert_eq!(uptime.outages, 1);
+        assert_eq!(uptime.monitored_seconds, 3 * 3600);
+        assert_eq!(uptime.downtime_seconds, 3600);
+    }
+
+    #[test]
+    fn test_uptime_unknown_does_not_end_outage() {
+        let changes = vec![
+            MonitorStatusElement::new(get_time(1), "ERROR", None),
+            MonitorStatusElement::new(get_time(2), "UNKNOWN", None),
+            MonitorStatusElement::new(get_time(3), "ERROR", None),
+            MonitorStatusElement::new(get_time(4), "OK", None),
+        ];
+        let uptime = MonitorUptimeElement::from_status_changes(get_time(0), get_time(5), Some("OK"), &changes);
+        assert_eq!(uptime.outages, 1);
+        assert_eq!(uptime.downtime_seconds, 2 * 3600);
+        assert_eq!(uptime.monitored_seconds, 4 * 3600);
+    }
+
+    #[test]
+    fn test_uptime_window_ends_now() {
+        let from_datetime = Utc::now() - chrono::Duration::hours(1);
+        let uptime = MonitorUptimeElement::from_status_changes(from_datetime, from_datetime + chrono::Duration::hours(10), Some("OK"), &[]);
+        assert!(uptime.to_datetime <= Utc::now());
+        assert!(uptime.monitored_seconds >= 3600 && uptime.monitored_seconds < 3700);
+        assert_eq!(uptime.availability_percent, Some(100.0));
+    }
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * A metric reported by a check, like the perfdata of a Nagios plugin.
+ *
+ * `label`: The label of the metric.
+ * `value`: The value.
+ * `unit`: The unit of measurement, like s, %, B or c.
+ * `warn`: The warning range.
+ * `crit`: The critical range.
+ * `min`: The minimum possible value.
+ * `max`: The maximum possible value.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    /// The label of the metric.
+    pub label: String,
+    /// The value.
+    pub value: f64,
+    /// The unit of measurement.
+    pub unit: Option<String>,
+    /// The warning range.
+    pub warn: Option<String>,
+    /// The critical range.
+    pub crit: Option<String>,
+    /// The minimum possible value.
+    pub min: Option<f64>,
+    /// The maximum possible value.
+    pub max: Option<f64>,
+}
+
+impl Metric {
+    /**
+     * Parse Nagios plugin perfdata, like `time=0.012s;1;2;0 'free space'=45%;20;10`.
+     * Metrics with an undetermined value (U) or that can not be parsed are skipped.
+     *
+     * `perfdata`: The perfdata.
+     *
+     * Returns: The metrics.
+     */
+    pub fn parse_perfdata(perfdata: &str) -> Vec<Metric> {
+        let mut metrics = Vec::new();
+        let mut chars = perfdata.chars().peekable();
+        loop {
+            while chars.next_if(|char| char.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+            /*
+             * Labels are quoted with single quotes if they contain spaces or equal signs. A quote in a
+             * quoted label is written as two quotes.
+             */
+            let mut label = String::new();
+            if chars.next_if_eq(&'\'').is_some() {
+                while let Some(char) = chars.next() {
+                    if char == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                    label.push(char);
+                }
+            } else {
+                while let Some(char) = chars.next_if(|char| *char != '=' && !char.is_whitespace()) {
+                    label.push(char);
+                }
+            }
+            let has_value = chars.next_if_eq(&'=').is_some();
+            let mut value = String::new();
+            while let Some(char) = chars.next_if(|char| !char.is_whitespace()) {
+                value.push(char);
+            }
+            if has_value && !label.is_empty() {
+                if let Some(metric) = Metric::parse_value(&label, &value) {
+                    metrics.push(metric);
+                }
+            }
+        }
+        metrics
+    }
+
+    /**
+     * Parse the value of a metric, like `0.012s;1;2;0;10`.
+     *
+     * `label`: The label of the metric.
+     * `value`: The value with the unit and optional ranges, minimum and maximum.
+     *
+     * Returns: The metric, or None if the value is undetermined or can not be parsed.
+     */
+    fn parse_value(label: &str, value: &str) -> Option<Metric> {
+        let mut fields = value.split(';');
+        let value_with_unit = fields.next()?;
+        let unit_start = value_with_unit
+            .find(|char: char| !(char.is_ascii_digit() || matches!(char, '.' | ',' | '-' | '+')))
+            .unwrap_or(value_with_unit.len());
+        let number = value_with_unit[..unit_start].replace(',', ".").parse::<f64>().ok()?;
+        let unit = &value_with_unit[unit_start..];
+        let mut get_field = || fields.next().map(str::trim).filter(|field| !field.is_empty()).map(str::to_string);
+        let warn = get_field();
+        let crit = get_field();
+        let min = get_field().and_then(|min| min.replace(',', ".").parse::<f64>().ok());
+        let max = get_field().and_then(|max| max.replace(',', ".").parse::<f64>().ok());
+        Some(Metric {
+            label: label.to_string(),
+            value: number,
+            unit: if unit.is_empty() { None } else { Some(unit.to_string()) },
+            warn,
+            crit,
+            min,
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_perfdata() {
+        let metrics = Metric::parse_perfdata("time=0.012s;1.000;2.000;0.000 size=512B;;;0 'free space'=45%;20:;10:;0;100 load1=0,5");
+        assert_eq!(metrics.len(), 4);
+        assert_eq!(
+            metrics[0],
+            Metric {
+                label: "time".to_string(),
+                value: 0.012,
+                unit: Some("s".to_string()),
+                warn: Some("1.000".to_string()),
+                crit: Some("2.000".to_string()),
+                min: Some(0.0),
+                max: None,
+            }
+        );
+        assert_eq!(metrics[1].unit, Some("B".to_string()));
+        assert_eq!(metrics[1].warn, None);
+        assert_eq!(metrics[1].crit, None);
+        assert_eq!(metrics[1].min, Some(0.0));
+        assert_eq!(metrics[2].label, "free space");
+        assert_eq!(metrics[2].warn, Some("20:".to_string()));
+        assert_eq!(metrics[2].max, Some(100.0));
+        assert_eq!(metrics[3].value, 0.5);
+        assert_eq!(metrics[3].unit, None);
+    }
+
+    #[test]
+    fn test_parse_perfdata_skips_invalid() {
+        let metrics = Metric::parse_perfdata("  users=U;5;10 'it''s'=3c garbage rta=-1.5ms =4 ");
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].label, "it's");
+        assert_eq!(metrics[0].unit, Some("c".to_string()));
+        assert_eq!(metrics[1].value, -1.5);
+        assert!(Metric::parse_perfdata("").is_empty());
+    }
+}
@@ -6,17 +6,23 @@
  * `configuration`: The configuration. Used to represent the configuration of the monitoring agent daemon.
  * `args`: The application arguments. Used to represent the arguments passed to the application.
  * `historical`: The historical data. Used to represent the historical data of the monitoring agent daemon.
+ * `responsetime`: The response time. Used to represent the timings of the HTTP and TCP checks.
+ * `metric`: The metric. Used to represent the perfdata reported by checks.
  */
 mod applicationerror;
 mod monitorstatus;
 pub mod configuration;
 pub mod args;
 pub mod historical;
+mod responsetime;
+mod metric;
 pub mod types;
 
 pub use crate::common::applicationerror::ApplicationError;
 pub use crate::common::monitorstatus::{MonitorStatus, Status};
 pub use crate::common::configuration::{Monitor, MonitorType, HttpMethod, DatabaseConfig};
 pub use crate::common::args::ApplicationArguments;
-pub use crate::common::historical::{LoadavgElement, MeminfoElement, ProcessMemoryElement};
-pub use crate::common::types::{MonitorStatusType, DatabaseServiceType};
\ No newline at end of file
+pub use crate::common::historical::{CpustatElement, DiskinfoElement, LoadavgElement, MeminfoElement, MonitorStatusElement, MonitorUptimeElement, NetworkElement, ProcessMemoryElement, ResponseTimeElement};
+pub use crate::common::responsetime::ResponseTime;
+pub use crate::common::metric::Metric;
+pub use crate::common::types::{MonitorStatusType, DatabaseServiceType, SchedulingServiceType, MonitoringConfigType};
\ No newline at end of file
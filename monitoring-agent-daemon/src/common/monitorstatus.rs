@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 /**
  * `MonitorStatus` struct
@@ -10,6 +11,12 @@ use chrono::{DateTime, Utc};
  * - `last_successful_time`: The last time the monitor was successful
  * - `last_error`: The last error message
  * - `last_error_time`: The last time the monitor encountered an error
+ * - `failure_threshold`: Number of consecutive errors before the status is set to error
+ * - `success_threshold`: Number of consecutive successes before an error status is cleared
+ * - `consecutive_failures`: Number of consecutive errors
+ * - `consecutive_successes`: Number of consecutive successes
+ * - `flapping`: The results changed before a threshold was reached
+ * - `details`: Details of the last check
  *
  */
 #[derive(Debug, Clone, PartialEq)]
@@ -26,6 +33,18 @@ pub struct MonitorStatus {
     pub last_error: Option<String>,
     /// The last time the monitor encountered an error.
     pub last_error_time: Option<DateTime<Utc>>,
+    /// Number of consecutive errors before the status is set to error.
+    pub failure_threshold: u32,
+    /// Number of consecutive successes before an error status is cleared.
+    pub success_threshold: u32,
+    /// Number of consecutive errors.
+    pub consecutive_failures: u32,
+    /// Number of consecutive successes.
+    pub consecutive_successes: u32,
+    /// The results changed before a threshold was reached.
+    pub flapping: bool,
+    /// Details of the last check.
+    pub details: Option<String>,
 }
 
 impl MonitorStatus {
@@ -45,13 +64,35 @@ impl MonitorStatus {
             last_successful_time: None,
             last_error: None,
             last_error_time: None,
+            failure_threshold: 1,
+            success_threshold: 1,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            flapping: false,
+            details: None,
         }
     }
 
+    /**
+     * Set the thresholds used to debounce the status.
+     *
+     * `failure_threshold`: Number of consecutive errors before the status is set to error. Minimum 1.
+     * `success_threshold`: Number of consecutive successes before an error status is cleared. Minimum 1.
+     *
+     */
+    pub fn set_thresholds(&mut self, failure_threshold: u32, success_threshold: u32) {
+        self.failure_threshold = failure_threshold.max(1);
+        self.success_threshold = success_threshold.max(1);
+    }
+
     /**
      * Set the status of the monitor.
      *
-     * `status`: The new status.
+     * The status is only changed to error after `failure_threshold` consecutive errors,
+     * and only cleared after `success_threshold` consecutive successes. Warnings count as successes.
+     * The monitor is flapping if the results change before a threshold is reached.
+     *
+     * `status`: The result of the last check.
      *
      */
     pub fn set_status(&mut self, status: &Status) {
@@ -65,7 +106,44 @@ impl MonitorStatus {
             }
             Status::Unknown => {}
         }
-        self.status = status.clone();
+        let is_error = matches!(self.status, Status::Error { .. });
+        match status {
+            Status::Error { .. } => {
+                /*
+                 * A recovery was interrupted before the success threshold was reached.
+                 */
+                if is_error && self.consecutive_successes > 0 {
+                    self.flapping = true;
+                }
+                self.consecutive_failures += 1;
+                self.consecutive_successes = 0;
+                if is_error || self.consecutive_failures >= self.failure_threshold {
+                    self.status = status.clone();
+                }
+                if self.consecutive_failures >= self.failure_threshold {
+                    self.flapping = false;
+                }
+            }
+            Status::Ok | Status::Warn { .. } => {
+                /*
+                 * A failure was interrupted before the failure threshold was reached.
+                 */
+                if !is_error && self.consecutive_failures > 0 {
+                    self.flapping = true;
+                }
+                self.consecutive_successes += 1;
+                self.consecutive_failures = 0;
+                if !is_error || self.consecutive_successes >= self.success_threshold {
+                    self.status = status.clone();
+                }
+                if self.consecutive_successes >= self.success_threshold {
+                    self.flapping = false;
+                }
+            }
+            Status::Unknown => {
+                self.status = status.clone();
+            }
+        }
     }
 }
 
@@ -79,7 +157,7 @@ impl MonitorStatus {
  *
  */
 #[warn(dead_code)]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Status {
     /// The monitor is working correctly.
     Ok,
@@ -95,7 +173,7 @@ pub enum Status {
 impl Status {
     /**
      * Get the maximum status from a list of statuses.
-     * Ok < Error (Unknown is ignored)
+     * Ok < Warn < Error (Unknown is ignored)
      *
      * `statuses`: The list of statuses.
      *
@@ -103,9 +181,11 @@ impl Status {
     pub fn get_max_status(statuses: Vec<Status>) -> Status {
         let mut max_status = Status::Ok;
         for status in statuses {
-            if let Status::Error { .. } = status {
-                max_status = status;
-            }                
+            match status {
+                Status::Error { .. } => max_status = status,
+                Status::Warn { .. } if !matches!(max_status, Status::Error { .. }) => max_status = status,
+                _ => {}
+            }
         }
         max_status
     }
@@ -150,6 +230,66 @@ mod test {
         assert!(monitorstatus.last_error_time.is_some());
     }
 
+    #[test]
+    fn test_monitorstatus_set_status_failure_threshold() {
+        let mut monitorstatus = MonitorStatus::new("test_monitor", &None, Status::Unknown);
+        monitorstatus.set_thresholds(3, 1);
+        monitorstatus.set_status(&Status::Ok);
+        assert_eq!(monitorstatus.status, Status::Ok);
+        let error = Status::Error { message: "test error".to_string() };
+        monitorstatus.set_status(&error);
+        monitorstatus.set_status(&error);
+        assert_eq!(monitorstatus.status, Status::Ok);
+        assert_eq!(monitorstatus.consecutive_failures, 2);
+        assert_eq!(monitorstatus.last_error, Some("test error".to_string()));
+        monitorstatus.set_status(&error);
+        assert_eq!(monitorstatus.status, error);
+        assert!(!monitorstatus.flapping);
+    }
+
+    #[test]
+    fn test_monitorstatus_set_status_success_threshold() {
+        let mut monitorstatus = MonitorStatus::new("test_monitor", &None, Status::Unknown);
+        monitorstatus.set_thresholds(1, 2);
+        let error = Status::Error { message: "test error".to_string() };
+        monitorstatus.set_status(&error);
+        assert_eq!(monitorstatus.status, error);
+        monitorstatus.set_status(&Status::Ok);
+        assert_eq!(monitorstatus.status, error);
+        monitorstatus.set_status(&Status::Ok);
+        assert_eq!(monitorstatus.status, Status::Ok);
+        assert!(!monitorstatus.flapping);
+    }
+
+    #[test]
+    fn test_monitorstatus_flapping() {
+        let mut monitorstatus = MonitorStatus::new("test_monitor", &None, Status::Unknown);
+        monitorstatus.set_thresholds(2, 2);
+        let error = Status::Error { message: "test error".to_string() };
+        monitorstatus.set_status(&Status::Ok);
+        monitorstatus.set_status(&error);
+        monitorstatus.set_status(&Status::Ok);
+        assert_eq!(monitorstatus.status, Status::Ok);
+        assert!(monitorstatus.flapping);
+        monitorstatus.set_status(&Status::Ok);
+        assert!(!monitorstatus.flapping);
+        monitorstatus.set_status(&error);
+        monitorstatus.set_status(&error);
+        assert_eq!(monitorstatus.status, error);
+        monitorstatus.set_status(&Status::Ok);
+        monitorstatus.set_status(&error);
+        assert_eq!(monitorstatus.status, error);
+        assert!(monitorstatus.flapping);
+    }
+
+    #[test]
+    fn test_monitorstatus_set_thresholds_minimum() {
+        let mut monitorstatus = MonitorStatus::new("test_monitor", &None, Status::Unknown);
+        monitorstatus.set_thresholds(0, 0);
+        assert_eq!(monitorstatus.failure_threshold, 1);
+        assert_eq!(monitorstatus.success_threshold, 1);
+    }
+
     #[test]
     fn test_status_get_max_status() {
         let statuses = vec![Status::Ok, Status::Error { message: "test error".to_string() }];
@@ -159,6 +299,14 @@ mod test {
         let statuses = vec![Status::Ok, Status::Unknown];
         let max_status = Status::get_max_status(statuses);
         assert_eq!(max_status, Status::Ok);
+
+        let statuses = vec![Status::Warn { message: "test warn".to_string() }, Status::Ok];
+        let max_status = Status::get_max_status(statuses);
+        assert_eq!(max_status, Status::Warn { message: "test warn".to_string() });
+
+        let statuses = vec![Status::Error { message: "test error".to_string() }, Status::Warn { message: "test warn".to_string() }];
+        let max_status = Status::get_max_status(statuses);
+        assert_eq!(max_status, Status::Error { message: "test error".to_string() });
     }
 
 }
\ No newline at end of file
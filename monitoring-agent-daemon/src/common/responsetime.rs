@@ -0,0 +1,101 @@
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::Status;
+
+/**
+ * The response time of a check in milliseconds. Used by the HTTP and TCP monitors.
+ *
+ * `connect_time`: The time to open the connection, including the TLS handshake for https.
+ * `first_byte_time`: The time until the response headers were received. Not set for TCP.
+ * `total_time`: The total time of the check.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseTime {
+    /// The time to open the connection, including the TLS handshake for https.
+    #[serde(rename = "connectTime")]
+    pub connect_time: Option<f64>,
+    /// The time until the response headers were received.
+    #[serde(rename = "firstByteTime")]
+    pub first_byte_time: Option<f64>,
+    /// The total time of the check.
+    #[serde(rename = "totalTime")]
+    pub total_time: f64,
+}
+
+impl ResponseTime {
+    /**
+     * Create a new response time.
+     *
+     * `connect_time`: The time to open the connection, including the TLS handshake for https.
+     * `first_byte_time`: The time until the response headers were received.
+     * `total_time`: The total time of the check.
+     *
+     * Returns the response time.
+     */
+    pub fn new(connect_time: Option<f64>, first_byte_time: Option<f64>, total_time: f64) -> ResponseTime {
+        ResponseTime {
+            connect_time,
+            first_byte_time,
+            total_time,
+        }
+    }
+
+    /**
+     * Get the time elapsed since the start in milliseconds.
+     *
+     * `start`: The start of the measurement.
+     *
+     * Returns the elapsed time in milliseconds.
+     */
+    pub fn get_elapsed_ms(start: &Instant) -> f64 {
+        start.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /**
+     * Check the total time against the latency thresholds. Only successful checks are changed,
+     * an Ok status changes to Warn or Error and a Warn status changes to Error.
+     *
+     * `status`: The status of the check.
+     * `warn_latency_ms`: The total time in milliseconds before warning.
+     * `error_latency_ms`: The total time in milliseconds before error.
+     *
+     * Returns the status of the check.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    pub fn check_latency(&self, status: Status, warn_latency_ms: Option<u64>, error_latency_ms: Option<u64>) -> Status {
+        if !matches!(status, Status::Ok | Status::Warn { .. }) {
+            return status;
+        }
+        if let Some(error_latency_ms) = error_latency_ms {
+            if self.total_time > error_latency_ms as f64 {
+                return Status::Error { message: format!("Response time {:0.0} ms is more than {error_latency_ms} ms", self.total_time) };
+            }
+        }
+        if let Some(warn_latency_ms) = warn_latency_ms {
+            if status == Status::Ok && self.total_time > warn_latency_ms as f64 {
+                return Status::Warn { message: format!("Response time {:0.0} ms is more than {warn_latency_ms} ms", self.total_time) };
+            }
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_latency() {
+        let response_time = ResponseTime::new(Some(10.0), None, 150.0);
+        assert_eq!(response_time.check_latency(Status::Ok, None, None), Status::Ok);
+        assert_eq!(response_time.check_latency(Status::Ok, Some(200), Some(300)), Status::Ok);
+        assert_eq!(response_time.check_latency(Status::Ok, Some(100), Some(300)), Status::Warn { message: "Response time 150 ms is more than 100 ms".to_string() });
+        assert_eq!(response_time.check_latency(Status::Ok, Some(50), Some(100)), Status::Error { message: "Response time 150 ms is more than 100 ms".to_string() });
+        let retried = Status::Warn { message: "Success after retries 1".to_string() };
+        assert_eq!(response_time.check_latency(retried.clone(), Some(100), None), retried);
+        let failed = Status::Error { message: "Error".to_string() };
+        assert_eq!(response_time.check_latency(failed.clone(), Some(50), Some(100)), failed);
+    }
+}
@@ -1,6 +1,8 @@
-use std::{collections::HashMap, sync::{Arc, Mutex}};
+use std::{collections::HashMap, sync::{Arc, Mutex, RwLock}};
 
-use crate::services::DbService;
+use crate::services::{DbService, SchedulingService};
+
+use super::configuration::MonitoringConfig;
 
 use super::MonitorStatus;
 
@@ -12,3 +14,12 @@ pub type MonitorStatusType = Arc<Mutex<HashMap<String, MonitorStatus>>>;
  * The database service.
  */
 pub type DatabaseServiceType = Arc<Option<DbService>>;
+/**
+ * The scheduling service. Shared between the signal handler and the web services.
+ */
+pub type SchedulingServiceType = Arc<tokio::sync::Mutex<SchedulingService>>;
+/**
+ * The running monitoring configuration. Replaced by the scheduling service when the configuration is reloaded, and
+ * read without waiting for the scheduling service.
+ */
+pub type MonitoringConfigType = Arc<RwLock<Arc<MonitoringConfig>>>;
@@ -9,7 +9,7 @@ use std::sync::Arc;
 
 use clap::Parser;
 use common::configuration::{DatabaseConfig, MonitoringConfig, ServerConfig};
-use common::{ApplicationError, DatabaseServiceType};
+use common::{ApplicationError, DatabaseServiceType, SchedulingServiceType};
 use daemonize::Daemonize;
 use log::{debug, error, info};
 use actix_web::{web, App, HttpServer};
@@ -17,6 +17,7 @@ use openssl::pkey::{PKey, Private};
 use openssl::ssl::{SslAcceptor, SslMethod};
 use services::SchedulingService;
 use tokio::runtime::Builder;
+use tokio::signal::unix::{signal, SignalKind};
 use tracing_subscriber::{filter, prelude::*};
 
 use crate::common::ApplicationArguments;
@@ -38,13 +39,18 @@ fn main() -> Result<(), std::io::Error> {
     /*
      * Parse command line arguments.
      */
-    let args: Arc<ApplicationArguments> = Arc::new(ApplicationArguments::parse());
+    let mut args = ApplicationArguments::parse();
+    /*
+     * Use the absolute configuration path. The daemon changes working directory, and the path is used again on reload.
+     */
+    args.config = std::fs::canonicalize(&args.config).map_or(args.config.clone(), |path| path.to_string_lossy().to_string());
+    let args: Arc<ApplicationArguments> = Arc::new(args);
     /*
      * Initialize logging.
      */
     setup_logging(args.logfile.as_str(), args.stdout_errorlevel.as_str(), args.file_errorlevel.as_str()).map_err(|err| {
         error!("Error setting up logging: {:?}", err);
-        std::io::Error::new(std::io::ErrorKind::Other, format!("Error setting up logging: {err:?}"))
+        std::io::Error::other(format!("Error setting up logging: {err:?}"))
     })?;
     /*
      * Load configuration.
@@ -56,7 +62,7 @@ fn main() -> Result<(), std::io::Error> {
         }
         Err(err) => {
             error!("Error loading configuration: {:?}", err);
-            Err(std::io::Error::new(std::io::ErrorKind::Other, "Error loading configuration"))
+            Err(std::io::Error::other("Error loading configuration"))
         }
     }?;
 
@@ -73,7 +79,7 @@ fn main() -> Result<(), std::io::Error> {
         }
         Err(err) => {
             error!("Error creating runtime: {:?}", err);
-            Err(std::io::Error::new(std::io::ErrorKind::Other, "Error creating runtime"))
+            Err(std::io::Error::other("Error creating runtime"))
         }        
     }
  }
@@ -82,6 +88,12 @@ fn main() -> Result<(), std::io::Error> {
  * Initialize the application.
  */
 async fn initialize(args: Arc<ApplicationArguments>, monitoring_config: Arc<MonitoringConfig>) -> Result<(), std::io::Error> {    
+    /*
+     * Apply the database migrations and exit.
+     */
+    if args.migrate_only {
+        return migrate_database(&monitoring_config).await;
+    }
     /*
      * Start the application.
      */
@@ -115,21 +127,21 @@ async fn start_application(monitoring_config: Arc<MonitoringConfig>, args: Arc<A
     /*
      * Start the scheduling service.
      */
-    init_scheduling(&monitoring_config, args.clone(), &monitoring_service, &database_service);
+    let scheduling_service = init_scheduling(&monitoring_config, args.clone(), &monitoring_service, &database_service).await;
     /*
      * If this is a test, return.
      */
     if args.test {
         return Ok(());
     }
-    
     /*
-     * Initialize the HTTP server.
+     * Reload the configuration on SIGHUP.
      */
+    init_reload_signal(&args.config, &scheduling_service)?;
     /*
      * Initialize the HTTP server.
      */
-    init_http_server(monitoring_config, monitoring_service, database_service).await
+    init_http_server(monitoring_config, monitoring_service, database_service, scheduling_service, &args.config).await
 }
 
 /**
@@ -143,7 +155,7 @@ async fn start_application(monitoring_config: Arc<MonitoringConfig>, args: Arc<A
 async fn init_database(monitoring_config: Arc<MonitoringConfig>) -> DatabaseServiceType {
     let database_config = monitoring_config.database.clone();
     let database_service: DatabaseServiceType = if let Some(database_config) = database_config {
-        Arc::new(initialize_database(&database_config, &monitoring_config.server).await)
+        initialize_database(&database_config, &monitoring_config.server).await
     } else {
         info!("No database configuration found!");
         Arc::new(None)
@@ -159,21 +171,53 @@ async fn init_database(monitoring_config: Arc<MonitoringConfig>) -> DatabaseServ
  * `monitoring_service`: The monitoring service.
  * `database_service`: The database service.
  * 
+ * Returns the scheduling service.
+ * 
  */
-fn init_scheduling(monitoring_config: &Arc<MonitoringConfig>, args: Arc<ApplicationArguments>, monitoring_service: &MonitoringService, database_service: &DatabaseServiceType) {
+async fn init_scheduling(monitoring_config: &Arc<MonitoringConfig>, args: Arc<ApplicationArguments>, monitoring_service: &MonitoringService, database_service: &DatabaseServiceType) -> SchedulingServiceType {
     let monitor_statuses = monitoring_service.get_status();
     let server_name = monitoring_config.server.name.clone();
-    let mut scheduling_service = SchedulingService::new(&server_name, monitoring_config, &monitor_statuses, database_service);
+    let scheduling_service = Arc::new(tokio::sync::Mutex::new(SchedulingService::new(&server_name, monitoring_config, &monitor_statuses, database_service)));
+    match scheduling_service.lock().await.start(args.test).await {
+        Ok(()) => {
+            info!("Scheduling service started!");
+        }
+        Err(err) => {
+            error!("Error starting scheduling service: {err:?}");
+        }
+    };
+    scheduling_service
+}
+
+/**
+ * Reload the configuration when the process receives SIGHUP.
+ * 
+ * `config_path`: The path to the configuration file.
+ * `scheduling_service`: The scheduling service.
+ * 
+ * Returns the result of registering the signal handler.
+ */
+fn init_reload_signal(config_path: &str, scheduling_service: &SchedulingServiceType) -> Result<(), std::io::Error> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    let config_path = config_path.to_string();
+    let scheduling_service = scheduling_service.clone();
     tokio::spawn(async move {
-        match scheduling_service.start(args.test).await {
-            Ok(()) => {
-                info!("Scheduling service started!");
-            }
-            Err(err) => {
-                error!("Error starting scheduling service: {err:?}");
+        while hangup.recv().await.is_some() {
+            info!("Received SIGHUP, reloading configuration from {config_path}");
+            match scheduling_service.lock().await.reload_from_file(&config_path).await {
+                Ok(changes) => {
+                    info!("Configuration reloaded! Added: {:?}, removed: {:?}, changed: {:?}", changes.added, changes.removed, changes.changed);
+                    if !changes.errors.is_empty() {
+                        error!("Errors removing previous jobs: {:?}", changes.errors);
+                    }
+                }
+                Err(err) => {
+                    error!("Error reloading configuration, keeping the running configuration: {err:?}");
+                }
             }
-        };
+        }
     });
+    Ok(())
 }
 
 /**
@@ -182,22 +226,30 @@ fn init_scheduling(monitoring_config: &Arc<MonitoringConfig>, args: Arc<Applicat
  * `monitoring_config`: The monitoring configuration.
  * `monitoring_service`: The monitoring service.
  * `database_service`: The database service.
+ * `scheduling_service`: The scheduling service.
+ * `config_path`: The path to the configuration file.
  * 
  * Returns the result of initializing the HTTP server.
  */
-async fn init_http_server(monitoring_config: Arc<MonitoringConfig>, monitoring_service: MonitoringService, database_service: DatabaseServiceType) -> Result<(), std::io::Error> {
+async fn init_http_server(monitoring_config: Arc<MonitoringConfig>, monitoring_service: MonitoringService, database_service: DatabaseServiceType, scheduling_service: SchedulingServiceType, config_path: &str) -> Result<(), std::io::Error> {
     /*
      * Start the HTTP server.
      */
     let ip = monitoring_config.server.ip.clone();
     let port = monitoring_config.server.port;
     let cloned_monitoring_config = monitoring_config.clone();
+    let config_path = config_path.to_string();
+    let running_config = scheduling_service.lock().await.get_running_config();
     info!("Starting HTTP server on {}:{}", ip, port);
     let http_server = HttpServer::new(move || {
         App::new()
-            .app_data(web::Data::new(StateApi::new(monitoring_service.clone(), database_service.clone(), cloned_monitoring_config.server.clone())))
+            .app_data(web::Data::new(StateApi::new(monitoring_service.clone(), database_service.clone(), cloned_monitoring_config.server.clone(), scheduling_service.clone(), running_config.clone(), &config_path)))
             .service(api::get_current_meminfo)   
             .service(api::get_historical_meminfo)
+            .service(api::get_current_diskinfo)
+            .service(api::get_historical_diskinfo)
+            .service(api::get_current_network)
+            .service(api::get_historical_network)
             .service(api::get_current_cpuinfo)   
             .service(api::get_current_loadavg)  
             .service(api::get_historical_loadavg) 
@@ -206,17 +258,26 @@ async fn init_http_server(monitoring_config: Arc<MonitoringConfig>, monitoring_s
             .service(api::get_process)
             .service(api::get_threads)
             .service(api::get_monitor_status)
+            .service(api::get_monitor_history)
+            .service(api::get_monitor_uptime)
+            .service(api::get_historical_response_time)
             .service(api::get_current_statm)
             .service(api::get_stat)
+            .service(api::get_historical_cpustat)
             .service(api::get_ping)
+            .service(api::reload_configuration)
+            .service(api::add_monitor)
+            .service(api::update_monitor)
+            .service(api::delete_monitor)
+            .service(api::get_metrics)
     });
     let http_server = http_server.workers(monitoring_config.server.workers);
     let http_server = match monitoring_config.server.tls_config.clone() {
         Some(tls_config) => {
-            let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls()).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, format!("Error creating SSL acceptor: {err:?}")))?;
-            let private_key = load_private_key(&tls_config.identity, tls_config.identity_password).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, format!("Error loading private key: {err:?}")))?;
-            builder.set_certificate_chain_file(tls_config.certificate).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, format!("Error setting certificate chain file: {err:?}")))?;        
-            builder.set_private_key(&private_key).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, format!("Error setting private key: {err:?}")))?;
+            let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls()).map_err(|err| std::io::Error::other(format!("Error creating SSL acceptor: {err:?}")))?;
+            let private_key = load_private_key(&tls_config.identity, tls_config.identity_password).map_err(|err| std::io::Error::other(format!("Error loading private key: {err:?}")))?;
+            builder.set_certificate_chain_file(tls_config.certificate).map_err(|err| std::io::Error::other(format!("Error setting certificate chain file: {err:?}")))?;        
+            builder.set_private_key(&private_key).map_err(|err| std::io::Error::other(format!("Error setting private key: {err:?}")))?;
             http_server.bind_openssl((ip, port), builder)?
         }
         None => {
@@ -250,22 +311,62 @@ fn load_private_key(private_key_path: &str, private_key_password: Option<String>
  }
 
 /**
- * Initialize the database service.
+ * Initialize the database service. If the database is unavailable, the service is returned
+ * unconnected and a background task keeps trying to connect. Writes are spooled until then.
  * 
  * `database_config`: The database configuration.
+ * `server_config`: The server configuration.
  * 
  * Returns the database service.
  * 
  */
-async fn initialize_database(database_config: &DatabaseConfig, server_config: &ServerConfig) -> Option<DbService> {
-    match DbService::new(database_config, &server_config.name).await {
-        Ok(database_service) => {
-            info!("Database service initialized!");
-            Some(database_service)
+async fn initialize_database(database_config: &DatabaseConfig, server_config: &ServerConfig) -> DatabaseServiceType {
+    let database_service = Arc::new(Some(DbService::new_unconnected(database_config, &server_config.name)));
+    if let Some(service) = database_service.as_ref() {
+        match service.try_connect().await {
+            Ok(()) => {
+                info!("Database service initialized!");
+            }
+            Err(err) => {
+                error!("Error initializing database service, retrying in the background: {:?}", err);
+                let reconnect_service = database_service.clone();
+                tokio::spawn(async move {
+                    if let Some(service) = reconnect_service.as_ref() {
+                        service.reconnect().await;
+                        info!("Database service initialized!");
+                    }
+                });
+            }
+        }
+    }
+    database_service
+}
+
+/**
+ * Apply the database migrations.
+ * 
+ * `monitoring_config`: The monitoring configuration.
+ * 
+ * Returns Ok if the migrations were applied.
+ * 
+ */
+async fn migrate_database(monitoring_config: &MonitoringConfig) -> Result<(), std::io::Error> {
+    let Some(database_config) = &monitoring_config.database else {
+        error!("No database configuration found!");
+        return Err(std::io::Error::other("No database configuration found"));
+    };
+    let database_service = DbService::connect(database_config, &monitoring_config.server.name).await.map_err(|err| {
+        error!("Error initializing database service: {:?}", err);
+        std::io::Error::other(format!("Error initializing database service: {err:?}"))
+    })?;
+    match database_service.migrate().await {
+        Ok(version) => {
+            info!("Database migrated to version {version}!");
+            Ok(())
         }
         Err(err) => {
-            error!("Error initializing database service: {:?}", err);
-            None
+            error!("Error migrating database: {:?}", err);
+            Err(std::io::Error::other(format!("Error migrating database: {err:?}")))
         }
     }
 }
@@ -415,6 +516,7 @@ mod test {
             stdout_errorlevel: "info".to_string(),
             pidfile: String::new(),
             logfile: "/tmp/monitoring-agent.log".to_string(),
+            migrate_only: false,
         });
         let monitoring_config = Arc::new(MonitoringConfig::new(&args.config).unwrap());
         start_application(monitoring_config, args).await?;
@@ -431,12 +533,19 @@ mod test {
             stdout_errorlevel: "info".to_string(),            
             pidfile: String::new(),
             logfile: "/tmp/monitoring-agent.log".to_string(),
+            migrate_only: false,
         });
         let monitoring_config = Arc::new(MonitoringConfig::new(&args.config).unwrap());
         start_application(monitoring_config, args).await?;
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_migrate_without_database() {
+        let monitoring_config = MonitoringConfig::new("./resources/test/test_full_configuration.json").unwrap();
+        assert!(migrate_database(&monitoring_config).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_normal_application() {
         let args = Arc::new(ApplicationArguments {
@@ -447,6 +556,7 @@ mod test {
             stdout_errorlevel: "info".to_string(),
             pidfile: String::new(),
             logfile: "/tmp/monitoring-agent.log".to_string(),
+            migrate_only: false,
         });
         let monitoring_config = Arc::new(MonitoringConfig::new(&args.config).unwrap());
         let result = start_application(monitoring_config, args).await;
@@ -463,6 +573,7 @@ mod test {
             stdout_errorlevel: "info".to_string(),
             pidfile: "/tmp/monitoring-agent.pid".to_string(),
             logfile: "/tmp/monitoring-agent.log".to_string(),
+            migrate_only: false,
         });
         let monitoring_config = Arc::new(MonitoringConfig::new(&args.config).unwrap());
         let result = start_daemon_application(monitoring_config, args).await;
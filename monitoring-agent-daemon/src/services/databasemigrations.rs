@@ -0,0 +1,220 @@
+/**
+ * A versioned database migration. The migrations are embedded in the daemon and applied in version order.
+ *
+ * `version`: The version of the schema after the migration is applied.
+ * `description`: The description of the migration.
+ * `sql`: The sql statements of the migration separated by semicolon.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// The schema version after the migration is applied.
+    pub version: i32,
+    /// The description of the migration.
+    pub description: &'static str,
+    /// The sql statements of the migration.
+    pub sql: &'static str,
+}
+
+/**
+ * The migrations for `MariaDB` and `MySQL`.
+ */
+pub const MARIADB_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        sql: include_str!("../../resources/migrations/mariadb/V1__initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "Response time",
+        sql: include_str!("../../resources/migrations/mariadb/V2__response_time.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Metrics",
+        sql: include_str!("../../resources/migrations/mariadb/V3__metrics.sql"),
+    },
+];
+
+/**
+ * The migrations for `Postgres`.
+ */
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        sql: include_str!("../../resources/migrations/postgres/V1__initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "Response time",
+        sql: include_str!("../../resources/migrations/postgres/V2__response_time.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Metrics",
+        sql: include_str!("../../resources/migrations/postgres/V3__metrics.sql"),
+    },
+];
+
+/**
+ * The migrations for `Sqlite`.
+ */
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        sql: include_str!("../../resources/migrations/sqlite/V1__initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "Response time",
+        sql: include_str!("../../resources/migrations/sqlite/V2__response_time.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "Metrics",
+        sql: include_str!("../../resources/migrations/sqlite/V3__metrics.sql"),
+    },
+];
+
+/**
+ * Name of the lock preventing several agents from migrating the same database at the same time.
+ */
+pub const MIGRATION_LOCK_NAME: &str = "monitoring_agent_migration";
+
+/**
+ * Key of the `Postgres` advisory lock preventing several agents from migrating the same database at the same time.
+ */
+pub const MIGRATION_LOCK_KEY: i64 = 0x006d_6f6e_6974_6f72;
+
+impl Migration {
+    /**
+     * Get the migrations not yet applied to the database.
+     *
+     * `migrations`: All migrations of the dialect.
+     * `current_version`: The schema version stored in the database.
+     *
+     * Returns: The migrations with a higher version than the current version.
+     */
+    pub fn get_pending(migrations: &'static [Migration], current_version: i32) -> Vec<&'static Migration> {
+        migrations.iter().filter(|migration| migration.version > current_version).collect()
+    }
+
+    /**
+     * Get the statements of the migration. Comment lines are removed and the statements are split on semicolon.
+     *
+     * Returns: The statements of the migration.
+     */
+    pub fn get_statements(&self) -> Vec<String> {
+        self.sql
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("--"))
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .split(';')
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /**
+     * Get the index created by a `CREATE INDEX <index> ON <table>` statement.
+     *
+     * `statement`: The statement.
+     *
+     * Returns: The table and the index, or None if the statement does not create an index.
+     */
+    pub fn get_created_index(statement: &str) -> Option<(String, String)> {
+        let words: Vec<&str> = statement.split_whitespace().collect();
+        match words.as_slice() {
+            [create, index_keyword, index, on, table, ..] if create.eq_ignore_ascii_case("CREATE") && index_keyword.eq_ignore_ascii_case("INDEX") && on.eq_ignore_ascii_case("ON") => {
+                Some(((*table).to_string(), (*index).to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_increasing() {
+        for migrations in [MARIADB_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            assert!(!migrations.is_empty());
+            assert!(migrations.windows(2).all(|pair| pair[0].version < pair[1].version));
+            assert!(migrations[0].version > 0);
+        }
+    }
+
+    #[test]
+    fn test_get_pending() {
+        assert_eq!(Migration::get_pending(POSTGRES_MIGRATIONS, 0).len(), POSTGRES_MIGRATIONS.len());
+        assert!(Migration::get_pending(POSTGRES_MIGRATIONS, POSTGRES_MIGRATIONS[POSTGRES_MIGRATIONS.len() - 1].version).is_empty());
+    }
+
+    #[test]
+    fn test_get_statements() {
+        let migration = Migration {
+            version: 1,
+            description: "Test",
+            sql: "-- Comment; with semicolon\nCREATE TABLE a (id INT);\n\nCREATE INDEX idx_a ON a (id);\n",
+        };
+        assert_eq!(migration.get_statements(), vec!["CREATE TABLE a (id INT)", "CREATE INDEX idx_a ON a (id)"]);
+    }
+
+    #[test]
+    fn test_get_created_index() {
+        assert_eq!(Migration::get_created_index("CREATE INDEX idx_a ON a (id)"), Some(("a".to_string(), "idx_a".to_string())));
+        assert_eq!(Migration::get_created_index("CREATE INDEX IF NOT EXISTS idx_a ON a (id)"), None);
+        assert_eq!(Migration::get_created_index("CREATE TABLE IF NOT EXISTS a (id INT)"), None);
+    }
+
+    #[test]
+    fn test_indexes_are_separate_statements() {
+        for migrations in [MARIADB_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            for migration in migrations {
+                for statement in migration.get_statements() {
+                    assert!(!statement.starts_with("CREATE TABLE") || !statement.contains("INDEX"), "{statement}");
+                }
+            }
+        }
+        for migration in MARIADB_MIGRATIONS {
+            for statement in migration.get_statements().iter().filter(|statement| statement.starts_with("CREATE INDEX")) {
+                assert!(Migration::get_created_index(statement).is_some(), "{statement}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_initial_schema_has_indexes() {
+        for migrations in [MARIADB_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            let statements = migrations[0].get_statements().join("\n");
+            for table in ["monitor_status", "loadavg", "meminfo", "diskinfo", "cpustat", "netdev", "statm"] {
+                assert!(statements.contains(&format!("CREATE TABLE IF NOT EXISTS {table} (")));
+                assert!(statements.contains(&format!("idx_{table}_server_name_log_time")));
+            }
+        }
+    }
+
+    #[test]
+    fn test_response_time_schema() {
+        for migrations in [MARIADB_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            let statements = migrations[1].get_statements().join("\n");
+            assert!(statements.contains("CREATE TABLE IF NOT EXISTS response_time ("));
+            assert!(statements.contains("idx_response_time_server_name_log_time"));
+        }
+    }
+
+    #[test]
+    fn test_metrics_schema() {
+        for migrations in [MARIADB_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            let statements = migrations[2].get_statements().join("\n");
+            assert!(statements.contains("CREATE TABLE IF NOT EXISTS metrics ("));
+            assert!(statements.contains("idx_metrics_monitor_name_log_time"));
+        }
+    }
+}
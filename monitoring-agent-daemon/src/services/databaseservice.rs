@@ -1,6 +1,9 @@
-use chrono::NaiveDateTime;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::{info, warn};
 use monitoring_agent_lib::proc::ProcsStatm;
-use monitoring_agent_lib::proc::{ProcsLoadavg, ProcsMeminfo};
+use monitoring_agent_lib::proc::{ProcCpuUsage, ProcsDiskUsage, ProcsLoadavg, ProcsMeminfo, ProcsNetDevRate};
 use r2d2::Pool;
 use r2d2_mysql::mysql::params;
 use r2d2_mysql::mysql::prelude::Queryable;
@@ -8,58 +11,221 @@ use r2d2_mysql::mysql::OptsBuilder;
 use r2d2_mysql::mysql::Row;
 use r2d2_mysql::mysql::TxOpts;
 use r2d2_mysql::MySqlConnectionManager;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{OptionalExtension, TransactionBehavior};
 use bb8_postgres::tokio_postgres::tls::NoTls;
+use bb8_postgres::tokio_postgres::types::FromSql;
 use bb8_postgres::tokio_postgres::Config;
 use bb8_postgres::PostgresConnectionManager;
 use rust_decimal::Decimal;
+use tokio::sync::OnceCell;
 
-use crate::api::HistoricalParams;
+use crate::api::{HistoricalParams, MonitorHistoryParams, UptimeParams};
 use crate::common::configuration::DatabaseConfig;
 use crate::common::configuration::DatabaseType;
-use crate::common::{LoadavgElement, MeminfoElement, ProcessMemoryElement, Status};
+use crate::common::{CpustatElement, DiskinfoElement, LoadavgElement, MeminfoElement, Metric, MonitorStatusElement, MonitorUptimeElement, NetworkElement, ProcessMemoryElement, ResponseTime, ResponseTimeElement, Status};
 use crate::common::ApplicationError;
+use crate::services::databasespool::{DbSpool, ReplayOutcome, SpoolRecord};
+use crate::services::databasemigrations::{Migration, MARIADB_MIGRATIONS, MIGRATION_LOCK_KEY, MIGRATION_LOCK_NAME, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS};
 
 /**
  * Database Service.
  * 
- * This enum represents the database service. It is used to interact with the database.
+ * This struct represents the database service. It is used to interact with the database.
+ * The connection is established once, either when the service is created or later by the
+ * reconnect loop. Writes that fail are appended to the spool if one is configured, and
+ * replayed when the database is reachable again.
  * 
+ * `database_config`: The database configuration.
+ * `server_name`: The server name.
+ * `backend`: The connected database, set when the connection succeeds.
+ * `spool`: The spool for failed writes if enabled.
  */
 #[derive(Debug)]
-pub enum DbService {
-    MariaDb(MariaDbService),
-    PostgresDb(PostgresDbService),
+pub struct DbService {
+    /// The database configuration.
+    database_config: DatabaseConfig,
+    /// Server name
+    server_name: String,
+    /// The connected database.
+    backend: OnceCell<DbBackend>,
+    /// The spool for failed writes.
+    spool: Option<DbSpool>,
 }
 
+/**
+ * The delay before the first reconnect attempt. The delay is doubled after each failed attempt.
+ */
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(5);
+
+/**
+ * The maximum delay between reconnect attempts.
+ */
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(300);
+
 impl DbService {
 
     /**
-     * Create a new database service.
-     * 
+     * Create a new database service without applying migrations. Used for databases that are
+     * monitored, but not used for storing the monitoring data, so the spool is not used.
+     *
      * `database_config`: The database configuration.
      * `server_name`: The server name.
-     * 
+     *
      * Returns: A new database service.
-     * 
+     *
      * Errors:
      * - If there is an error creating the database service.
-     * 
+     *
      */
-    pub async fn new(database_config: &DatabaseConfig, server_name: &str) -> Result<DbService, ApplicationError> {
-        match &database_config.dbtype {
-            DatabaseType::Maria => Ok(DbService::MariaDb(MariaDbService::new(database_config, server_name)?)),
-            DatabaseType::Mysql => Ok(DbService::MariaDb(MariaDbService::new(database_config, server_name)?)),
-            DatabaseType::Postgres => Ok(DbService::PostgresDb(PostgresDbService::new(database_config, server_name).await?)),
+    pub async fn connect(database_config: &DatabaseConfig, server_name: &str) -> Result<DbService, ApplicationError> {
+        let backend = DbBackend::connect(database_config, server_name).await?;
+        Ok(DbService {
+            database_config: database_config.clone(),
+            server_name: server_name.to_string(),
+            backend: OnceCell::new_with(Some(backend)),
+            spool: None,
+        })
+    }
+
+    /**
+     * Create a new database service that is not yet connected. Writes are spooled until
+     * `try_connect` or `reconnect` succeeds.
+     *
+     * `database_config`: The database configuration.
+     * `server_name`: The server name.
+     *
+     * Returns: A new database service.
+     */
+    pub fn new_unconnected(database_config: &DatabaseConfig, server_name: &str) -> DbService {
+        DbService {
+            database_config: database_config.clone(),
+            server_name: server_name.to_string(),
+            backend: OnceCell::new(),
+            spool: DbSpool::new(&database_config.spool),
+        }
+    }
+
+    /**
+     * Connect to the database and apply the schema migrations not yet applied. Does nothing if
+     * the service is already connected.
+     *
+     * Errors:
+     * - If there is an error connecting to the database.
+     * - If there is an error applying the migrations.
+     */
+    pub async fn try_connect(&self) -> Result<(), ApplicationError> {
+        self.backend.get_or_try_init(|| async {
+            let backend = DbBackend::connect(&self.database_config, &self.server_name).await?;
+            let version = backend.migrate().await?;
+            info!("Connected to database with schema version {version}");
+            Ok::<DbBackend, ApplicationError>(backend)
+        }).await?;
+        Ok(())
+    }
+
+    /**
+     * Try to connect until the connection succeeds. The delay between the attempts is doubled
+     * after each failure, up to five minutes.
+     */
+    pub async fn reconnect(&self) {
+        let mut delay = RECONNECT_MIN_DELAY;
+        while let Err(err) = self.try_connect().await {
+            warn!("Could not connect to database, retrying in {} seconds: {}", delay.as_secs(), err.message);
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+        }
+    }
+
+    /**
+     * Get the connected database.
+     *
+     * Returns: The connected database.
+     *
+     * Errors:
+     * - If the service is not connected.
+     */
+    fn get_backend(&self) -> Result<&DbBackend, ApplicationError> {
+        self.backend.get().ok_or_else(|| ApplicationError::new("Database not connected"))
+    }
+
+    /**
+     * Apply the schema migrations not yet applied. The applied version is stored in the `schema_version` table.
+     *
+     * Returns: The schema version after the migrations are applied.
+     *
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error applying a migration.
+     *
+     */
+    pub async fn migrate(&self) -> Result<i32, ApplicationError> {
+        self.get_backend()?.migrate().await
+    }
+
+    /**
+     * Store a record in the database. If the database is unavailable and the spool is enabled,
+     * the record is appended to the spool instead. A write failing while the database is available
+     * is retried once, as it may have failed on a deadlock or lock timeout. If the retry fails the
+     * error is returned, as the database would reject the record when it is replayed too.
+     *
+     * `record`: The record to store.
+     *
+     * Errors:
+     * - If the record could not be stored and could not be spooled.
+     * - If the database rejected the record.
+     */
+    async fn store(&self, record: SpoolRecord) -> Result<(), ApplicationError> {
+        let result = match self.get_backend() {
+            Ok(backend) => match backend.store_record(&record).await {
+                Err(err) if backend.is_available().await => {
+                    warn!("Retrying database write: {}", err.message);
+                    return backend.store_record(&record).await;
+                }
+                result => result,
+            },
+            Err(err) => Err(err),
+        };
+        match (result, &self.spool) {
+            (Ok(()), _) => Ok(()),
+            (Err(err), Some(spool)) => {
+                warn!("Spooling database write: {}", err.message);
+                spool.append(&record).await
+            },
+            (Err(err), None) => Err(err),
         }
     }
 
+    /**
+     * Replay the spooled records in the order they were written. Does nothing if the service
+     * is not connected or the spool is disabled. A record failing while the database is available
+     * is rejected, and dropped from the spool when it has been rejected too many times.
+     *
+     * Returns: The number of records replayed.
+     *
+     * Errors:
+     * - If the spool could not be read or rewritten.
+     */
+    pub async fn replay_spool(&self) -> Result<usize, ApplicationError> {
+        let (Some(backend), Some(spool)) = (self.backend.get(), &self.spool) else {
+            return Ok(0);
+        };
+        spool.replay(|record| async move {
+            match backend.store_record(&record).await {
+                Ok(()) => ReplayOutcome::Stored,
+                Err(err) if backend.is_available().await => ReplayOutcome::Rejected(err),
+                Err(err) => ReplayOutcome::Unavailable(err),
+            }
+        }).await
+    }
+
     /**
      * Insert a monitor status into the database.
      * 
      * `name`: The name of the monitor.
      * `status`: The status of the monitor.
      * 
-     * Returns: Ok if the status was inserted successfully.
+     * Returns: Ok if the status was inserted or spooled successfully.
      * 
      * Errors:
      * - If there is an error inserting the status.
@@ -68,10 +234,7 @@ impl DbService {
      * 
      */
     pub async fn insert_monitor_status(&self, name: &str, status: &Status) -> Result<(), ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.insert_monitor_status(name, status),
-            DbService::PostgresDb(service) => service.insert_monitor_status(name, status).await,
-        }
+        self.store(SpoolRecord::MonitorStatus { log_time: Utc::now(), name: name.to_string(), status: status.clone() }).await
     }
 
     /**
@@ -79,7 +242,7 @@ impl DbService {
      * 
      * `loadavg`: The load average to store.
      * 
-     * Returns: Ok if the load average was stored successfully.
+     * Returns: Ok if the load average was stored or spooled successfully.
      * 
      * Errors:
      * - If there is an error storing the load average.
@@ -87,10 +250,7 @@ impl DbService {
      * 
      */
     pub async fn store_loadavg(&self, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.store_loadavg(loadavg),
-            DbService::PostgresDb(service) => service.store_loadavg(loadavg).await,
-        }
+        self.store(SpoolRecord::Loadavg { log_time: Utc::now(), loadavg: loadavg.clone() }).await
     }
 
     /**
@@ -98,7 +258,7 @@ impl DbService {
      * 
      * `meminfo`: The meminfo to store.
      * 
-     * Returns: Ok if the meminfo was stored successfully.
+     * Returns: Ok if the meminfo was stored or spooled successfully.
      * 
      * Errors:
      * - If there is an error storing the meminfo.
@@ -106,10 +266,101 @@ impl DbService {
      * 
      */
     pub async fn store_meminfo(&self, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.store_meminfo(meminfo),
-            DbService::PostgresDb(service) => service.store_meminfo(meminfo).await,
-        }
+        self.store(SpoolRecord::Meminfo { log_time: Utc::now(), meminfo: meminfo.clone() }).await
+    }
+
+    /**
+     * Store the disk use in the database.
+     * 
+     * `disk_usage`: The disk use of the mount points to store.
+     * 
+     * Returns: Ok if the disk use was stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the disk use.
+     * - If there is an error starting a transaction.
+     * 
+     */
+    pub async fn store_diskinfo(&self, disk_usage: &[ProcsDiskUsage]) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::Diskinfo { log_time: Utc::now(), disk_usage: disk_usage.to_vec() }).await
+    }
+
+    /**
+     * Store the cpu utilisation in the database.
+     * 
+     * `cpu_usage`: The cpu utilisation of the cpus to store.
+     * 
+     * Returns: Ok if the cpu utilisation was stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the cpu utilisation.
+     * - If there is an error starting a transaction.
+     * 
+     */
+    pub async fn store_cpustat(&self, cpu_usage: &[ProcCpuUsage]) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::Cpustat { log_time: Utc::now(), cpu_usage: cpu_usage.to_vec() }).await
+    }
+
+    /**
+     * Store the network throughput in the database.
+     * 
+     * `rates`: The throughput of the interfaces to store.
+     * 
+     * Returns: Ok if the network throughput was stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the network throughput.
+     * - If there is an error starting a transaction.
+     * 
+     */
+    pub async fn store_network(&self, rates: &[ProcsNetDevRate]) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::Network { log_time: Utc::now(), rates: rates.to_vec() }).await
+    }
+
+    /**
+     * Store the statm values in the database.
+     * 
+     * `app_name`: The application name.
+     * `pid`: The process id.
+     * `statm`: The statm values.
+     * 
+     * Returns: Ok if the statm values were stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the statm values.
+     */
+    pub async fn store_statm_values(&self, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::Statm { log_time: Utc::now(), app_name: app_name.to_string(), pid: *pid, statm: statm.clone() }).await
+    }
+
+    /**
+     * Store the response time of a monitor in the database.
+     * 
+     * `name`: The name of the monitor.
+     * `response_time`: The response time.
+     * 
+     * Returns: Ok if the response time was stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the response time.
+     */
+    pub async fn store_response_time(&self, name: &str, response_time: &ResponseTime) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::ResponseTime { log_time: Utc::now(), name: name.to_string(), response_time: response_time.clone() }).await
+    }
+
+    /**
+     * Store the metrics reported by a monitor in the database.
+     * 
+     * `name`: The name of the monitor.
+     * `metrics`: The metrics.
+     * 
+     * Returns: Ok if the metrics were stored or spooled successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the metrics.
+     */
+    pub async fn store_metrics(&self, name: &str, metrics: &[Metric]) -> Result<(), ApplicationError> {
+        self.store(SpoolRecord::Metrics { log_time: Utc::now(), name: name.to_string(), metrics: metrics.to_vec() }).await
     }
 
     /**
@@ -144,6 +395,34 @@ impl DbService {
         }
     }
 
+    /**
+     * Get the database representation of the log time. The log time is stored in UTC.
+     *
+     * `log_time`: The time the values were measured.
+     *
+     * Returns: The database representation.
+     *
+     */
+    fn get_log_time_db_repr(log_time: &DateTime<Utc>) -> String {
+        log_time.format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+    }
+
+    /**
+     * Parse the log time returned by the monitor status queries.
+     *
+     * `log_time`: The log time formatted as YYYY-MM-DD HH:MM:SS with optional fractional seconds.
+     *
+     * Returns: The log time.
+     *
+     * Errors:
+     * - If the log time could not be parsed.
+     */
+    fn get_log_time_from_db_repr(log_time: &str) -> Result<DateTime<Utc>, ApplicationError> {
+        NaiveDateTime::parse_from_str(log_time, "%Y-%m-%d %H:%M:%S%.f")
+            .map(|log_time| log_time.and_utc())
+            .map_err(|err| ApplicationError::new(&err.to_string()))
+    }
+
     /**
      * Query long running queries.
      * 
@@ -152,65 +431,79 @@ impl DbService {
      * Returns: The long running queries.
      * 
      * Errors:
+     * - If the service is not connected.
      * - If there is an error querying the long running queries.
      * - If there is an error starting a transaction.
      * - If there is an error committing the transaction.
      * 
      */
     pub async fn query_long_running_queries(&self, max_query_time: u32) -> Result<Vec<String>, ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.query_long_running_queries(max_query_time),
-            DbService::PostgresDb(service) => service.query_long_running_queries(max_query_time).await,
-        }
+        self.get_backend()?.query_long_running_queries(max_query_time).await
     }
 
     /**
-     * Store the statm values in the database.
+     * Get the historical load average.
      * 
-     * `app_name`: The application name.
-     * `pid`: The process id.
-     * `statm`: The statm values.
+     * Returns: The historical load average.
      * 
-     * Returns: Ok if the statm values were stored successfully.
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error getting the historical load average.
+     */
+    pub async fn get_historical_loadavg(&self, historical_params: HistoricalParams) -> Result<Vec<LoadavgElement>, ApplicationError> {
+        self.get_backend()?.get_historical_loadavg(historical_params).await
+    }
+
+    /**
+     * Get the historical memory use.
+     * 
+     * Returns: The historical memory use.
      * 
      * Errors:
-     * - If there is an error storing the statm values.
+     * - If the service is not connected.
+     * - If there is an error getting the historical memory use.
      */
-    pub async fn store_statm_values(&self, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.store_statm_values(app_name, pid, statm),
-            DbService::PostgresDb(service) => service.store_statm_values(app_name, pid, statm).await,
-        }
+    pub async fn get_historical_meminfo(&self, historical_params: HistoricalParams) -> Result<Vec<MeminfoElement>, ApplicationError> {
+        self.get_backend()?.get_historical_meminfo(historical_params).await
     }
 
     /**
-     * Get the historical load average.
+     * Get the historical disk use.
      * 
-     * Returns: The historical load average.
+     * Returns: The historical disk use.
      * 
      * Errors:
-     * - If there is an error getting the historical load average.
+     * - If the service is not connected.
+     * - If there is an error getting the historical disk use.
      */
-    pub fn get_historical_loadavg(&self, historical_params: HistoricalParams) -> Result<Vec<LoadavgElement>, ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.get_historical_loadavg(historical_params),
-            DbService::PostgresDb(_) => Err(ApplicationError::new("Not implemented")),
-        }
+    pub async fn get_historical_diskinfo(&self, historical_params: HistoricalParams) -> Result<Vec<DiskinfoElement>, ApplicationError> {
+        self.get_backend()?.get_historical_diskinfo(historical_params).await
     }
 
     /**
-     * Get the historical memory use.
+     * Get the historical cpu utilisation.
      * 
-     * Returns: The historical memory use.
+     * Returns: The historical cpu utilisation.
      * 
      * Errors:
-     * - If there is an error getting the historical memory use.
+     * - If the service is not connected.
+     * - If there is an error getting the historical cpu utilisation.
      */
-    pub fn get_historical_meminfo(&self, historical_params: HistoricalParams) -> Result<Vec<MeminfoElement>, ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.get_historical_meminfo(historical_params),
-            DbService::PostgresDb(_) => Err(ApplicationError::new("Not implemented")),
-        }
+    pub async fn get_historical_cpustat(&self, historical_params: HistoricalParams) -> Result<Vec<CpustatElement>, ApplicationError> {
+        self.get_backend()?.get_historical_cpustat(historical_params).await
+    }
+
+    /**
+     * Get the historical network throughput.
+     * 
+     * Returns: The historical network throughput.
+     * 
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error getting the historical network throughput.
+     */
+    pub async fn get_historical_network(&self, historical_params: HistoricalParams) -> Result<Vec<NetworkElement>, ApplicationError> {
+        self.get_backend()?.get_historical_network(historical_params).await
     }
 
     /**
@@ -220,12 +513,60 @@ impl DbService {
      * `historical_params`: The historical parameters.
      * 
      */
-    pub fn get_process_memory_use(&self, pid: u32, historical_params: HistoricalParams) -> Result<Vec<ProcessMemoryElement>, ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.get_process_memory_use(&pid, historical_params),
-            DbService::PostgresDb(_) => Err(ApplicationError::new("Not implemented")),
-        }
-    }   
+    pub async fn get_process_memory_use(&self, pid: u32, historical_params: HistoricalParams) -> Result<Vec<ProcessMemoryElement>, ApplicationError> {
+        self.get_backend()?.get_process_memory_use(pid, historical_params).await
+    }
+
+    /**
+     * Get the historical response time of a monitor.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The historical response time.
+     * 
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error getting the historical response time.
+     */
+    pub async fn get_historical_response_time(&self, monitor_name: &str, historical_params: HistoricalParams) -> Result<Vec<ResponseTimeElement>, ApplicationError> {
+        self.get_backend()?.get_historical_response_time(monitor_name, historical_params).await
+    }
+
+    /**
+     * Get the stored statuses of a monitor, newest first.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `history_params`: The time window, status filter and page.
+     * 
+     * Returns: The statuses. One more status than the page size is returned if there are more pages.
+     * 
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error getting the statuses.
+     */
+    pub async fn get_monitor_status_history(&self, monitor_name: &str, history_params: MonitorHistoryParams) -> Result<Vec<MonitorStatusElement>, ApplicationError> {
+        self.get_backend()?.get_monitor_status_history(monitor_name, history_params).await
+    }
+
+    /**
+     * Get the uptime of a monitor. The window ends now if the to date time is in the future.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `uptime_params`: The time window.
+     * 
+     * Returns: The uptime of the monitor in the window.
+     * 
+     * Errors:
+     * - If the service is not connected.
+     * - If there is an error getting the status changes.
+     */
+    pub async fn get_monitor_uptime(&self, monitor_name: &str, uptime_params: &UptimeParams) -> Result<MonitorUptimeElement, ApplicationError> {
+        let from_datetime = uptime_params.from_datetime;
+        let to_datetime = uptime_params.to_datetime.min(Utc::now());
+        let (initial_status, changes) = self.get_backend()?.get_monitor_status_changes(monitor_name, from_datetime, to_datetime).await?;
+        Ok(MonitorUptimeElement::from_status_changes(from_datetime, to_datetime, initial_status.as_deref(), &changes))
+    }
 
     /**
      * Delete old data.
@@ -235,41 +576,326 @@ impl DbService {
      * Returns: Ok if the data was deleted successfully.
      * 
      * Errors:
+     * - If the service is not connected.
      * - If there is an error deleting the data.
      */
-    pub fn delete_old_data(&self, max_time_stored_db: u32) -> Result<(), ApplicationError> {
-        match self {
-            DbService::MariaDb(service) => service.delete_old_data(max_time_stored_db),
-            DbService::PostgresDb(_) => Err(ApplicationError::new("Not implemented")),
-        }
+    pub async fn delete_old_data(&self, max_time_stored_db: u32) -> Result<(), ApplicationError> {
+        self.get_backend()?.delete_old_data(max_time_stored_db).await
     }
 }
 
 /**
- * `MariaDB` Service.
+ * Database backend.
  * 
- * This struct represents a `MariaDB` service. It is used to interact with the `MariaDB` database.
+ * This enum represents a connected database. It dispatches the calls to the service of the database type.
  * 
  */
 #[derive(Debug)]
-pub struct MariaDbService {
-    /// The database connection pool.
-    pool: Pool<MySqlConnectionManager>,
-    /// Server name
-    server_name: String
+pub enum DbBackend {
+    MariaDb(MariaDbService),
+    PostgresDb(PostgresDbService),
+    Sqlite(SqliteDbService),
 }
 
-impl MariaDbService {
+impl DbBackend {
+
     /**
-     * Create a new `MariaDB` service.
-     * 
+     * Connect to the database.
+     *
      * `database_config`: The database configuration.
-     * 
-     * Returns: A new `MariaDB` service.
-     * 
+     * `server_name`: The server name.
+     *
+     * Returns: The connected database.
+     *
      * Errors:
-     * - If there is an error creating the pool.
-     */
+     * - If there is an error creating the database service.
+     *
+     */
+    pub async fn connect(database_config: &DatabaseConfig, server_name: &str) -> Result<DbBackend, ApplicationError> {
+        match &database_config.dbtype {
+            DatabaseType::Maria => Ok(DbBackend::MariaDb(MariaDbService::new(database_config, server_name)?)),
+            DatabaseType::Mysql => Ok(DbBackend::MariaDb(MariaDbService::new(database_config, server_name)?)),
+            DatabaseType::Postgres => Ok(DbBackend::PostgresDb(PostgresDbService::new(database_config, server_name).await?)),
+            DatabaseType::Sqlite => Ok(DbBackend::Sqlite(SqliteDbService::new(database_config, server_name)?)),
+        }
+    }
+
+    /**
+     * Apply the schema migrations not yet applied.
+     *
+     * Returns: The schema version after the migrations are applied.
+     *
+     * Errors:
+     * - If there is an error applying a migration.
+     *
+     */
+    pub async fn migrate(&self) -> Result<i32, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.migrate(),
+            DbBackend::PostgresDb(service) => service.migrate().await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(SqliteDbService::migrate).await,
+        }
+    }
+
+    /**
+     * Check if the database answers a query.
+     *
+     * Returns: True if the database is available.
+     */
+    pub async fn is_available(&self) -> bool {
+        let result = match self {
+            DbBackend::MariaDb(service) => service.ping(),
+            DbBackend::PostgresDb(service) => service.ping().await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(SqliteDbService::ping).await,
+        };
+        result.is_ok()
+    }
+
+    /**
+     * Store a record in the database.
+     *
+     * `record`: The record to store.
+     *
+     * Errors:
+     * - If there is an error storing the record.
+     *
+     */
+    pub async fn store_record(&self, record: &SpoolRecord) -> Result<(), ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.store_record(record),
+            DbBackend::PostgresDb(service) => service.store_record(record).await,
+            DbBackend::Sqlite(service) => {
+                let record = record.clone();
+                service.spawn_blocking(move |service| service.store_record(&record)).await
+            },
+        }
+    }
+
+    /**
+     * Query long running queries.
+     * 
+     * `max_query_time`: The maximum query time.
+     * 
+     * Returns: The long running queries.
+     * 
+     * Errors:
+     * - If there is an error querying the long running queries.
+     * 
+     */
+    pub async fn query_long_running_queries(&self, max_query_time: u32) -> Result<Vec<String>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.query_long_running_queries(max_query_time),
+            DbBackend::PostgresDb(service) => service.query_long_running_queries(max_query_time).await,
+            DbBackend::Sqlite(service) => service.query_long_running_queries(max_query_time),
+        }
+    }
+
+    /**
+     * Get the historical load average.
+     * 
+     * Returns: The historical load average.
+     * 
+     * Errors:
+     * - If there is an error getting the historical load average.
+     */
+    pub async fn get_historical_loadavg(&self, historical_params: HistoricalParams) -> Result<Vec<LoadavgElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_loadavg(historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_loadavg(historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_historical_loadavg(historical_params)).await,
+        }
+    }
+
+    /**
+     * Get the historical memory use.
+     * 
+     * Returns: The historical memory use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical memory use.
+     */
+    pub async fn get_historical_meminfo(&self, historical_params: HistoricalParams) -> Result<Vec<MeminfoElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_meminfo(historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_meminfo(historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_historical_meminfo(historical_params)).await,
+        }
+    }
+
+    /**
+     * Get the historical disk use.
+     * 
+     * Returns: The historical disk use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical disk use.
+     */
+    pub async fn get_historical_diskinfo(&self, historical_params: HistoricalParams) -> Result<Vec<DiskinfoElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_diskinfo(historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_diskinfo(historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_historical_diskinfo(historical_params)).await,
+        }
+    }
+
+    /**
+     * Get the historical cpu utilisation.
+     * 
+     * Returns: The historical cpu utilisation.
+     * 
+     * Errors:
+     * - If there is an error getting the historical cpu utilisation.
+     */
+    pub async fn get_historical_cpustat(&self, historical_params: HistoricalParams) -> Result<Vec<CpustatElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_cpustat(historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_cpustat(historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_historical_cpustat(historical_params)).await,
+        }
+    }
+
+    /**
+     * Get the historical network throughput.
+     * 
+     * Returns: The historical network throughput.
+     * 
+     * Errors:
+     * - If there is an error getting the historical network throughput.
+     */
+    pub async fn get_historical_network(&self, historical_params: HistoricalParams) -> Result<Vec<NetworkElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_network(historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_network(historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_historical_network(historical_params)).await,
+        }
+    }
+
+    /**
+     * Get the process memory use.
+     * 
+     * `pid`: The process id.
+     * `historical_params`: The historical parameters.
+     * 
+     */
+    pub async fn get_process_memory_use(&self, pid: u32, historical_params: HistoricalParams) -> Result<Vec<ProcessMemoryElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_process_memory_use(&pid, historical_params),
+            DbBackend::PostgresDb(service) => service.get_process_memory_use(&pid, historical_params).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.get_process_memory_use(&pid, historical_params)).await,
+        }
+    }   
+
+    /**
+     * Get the stored statuses of a monitor, newest first.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `history_params`: The time window, status filter and page.
+     * 
+     * Returns: The statuses.
+     * 
+     * Errors:
+     * - If there is an error getting the statuses.
+     */
+    pub async fn get_monitor_status_history(&self, monitor_name: &str, history_params: MonitorHistoryParams) -> Result<Vec<MonitorStatusElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_monitor_status_history(monitor_name, &history_params),
+            DbBackend::PostgresDb(service) => service.get_monitor_status_history(monitor_name, &history_params).await,
+            DbBackend::Sqlite(service) => {
+                let monitor_name = monitor_name.to_string();
+                service.spawn_blocking(move |service| service.get_monitor_status_history(&monitor_name, &history_params)).await
+            },
+        }
+    }
+
+    /**
+     * Get the historical response time of a monitor.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The historical response time.
+     * 
+     * Errors:
+     * - If there is an error getting the historical response time.
+     */
+    pub async fn get_historical_response_time(&self, monitor_name: &str, historical_params: HistoricalParams) -> Result<Vec<ResponseTimeElement>, ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_historical_response_time(monitor_name, &historical_params),
+            DbBackend::PostgresDb(service) => service.get_historical_response_time(monitor_name, &historical_params).await,
+            DbBackend::Sqlite(service) => {
+                let monitor_name = monitor_name.to_string();
+                service.spawn_blocking(move |service| service.get_historical_response_time(&monitor_name, &historical_params)).await
+            },
+        }
+    }
+
+    /**
+     * Get the status changes of a monitor in a window, and the last status stored before the window.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `from_datetime`: The start of the window.
+     * `to_datetime`: The end of the window.
+     * 
+     * Returns: The last status before the window and the status changes ordered by time.
+     * 
+     * Errors:
+     * - If there is an error getting the status changes.
+     */
+    pub async fn get_monitor_status_changes(&self, monitor_name: &str, from_datetime: DateTime<Utc>, to_datetime: DateTime<Utc>) -> Result<(Option<String>, Vec<MonitorStatusElement>), ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.get_monitor_status_changes(monitor_name, &from_datetime, &to_datetime),
+            DbBackend::PostgresDb(service) => service.get_monitor_status_changes(monitor_name, &from_datetime, &to_datetime).await,
+            DbBackend::Sqlite(service) => {
+                let monitor_name = monitor_name.to_string();
+                service.spawn_blocking(move |service| service.get_monitor_status_changes(&monitor_name, &from_datetime, &to_datetime)).await
+            },
+        }
+    }
+
+    /**
+     * Delete old data.
+     * 
+     * `max_time_stored_db`: The maximum time to store data in the database.
+     * 
+     * Returns: Ok if the data was deleted successfully.
+     * 
+     * Errors:
+     * - If there is an error deleting the data.
+     */
+    pub async fn delete_old_data(&self, max_time_stored_db: u32) -> Result<(), ApplicationError> {
+        match self {
+            DbBackend::MariaDb(service) => service.delete_old_data(max_time_stored_db),
+            DbBackend::PostgresDb(service) => service.delete_old_data(max_time_stored_db).await,
+            DbBackend::Sqlite(service) => service.spawn_blocking(move |service| service.delete_old_data(max_time_stored_db)).await,
+        }
+    }
+}
+
+/**
+ * `MariaDB` Service.
+ * 
+ * This struct represents a `MariaDB` service. It is used to interact with the `MariaDB` database.
+ * 
+ */
+#[derive(Debug)]
+pub struct MariaDbService {
+    /// The database connection pool.
+    pool: Pool<MySqlConnectionManager>,
+    /// Server name
+    server_name: String
+}
+
+impl MariaDbService {
+    /**
+     * Create a new `MariaDB` service.
+     * 
+     * `database_config`: The database configuration.
+     * 
+     * Returns: A new `MariaDB` service.
+     * 
+     * Errors:
+     * - If there is an error creating the pool.
+     */
     #[tracing::instrument(level = "debug")]
     pub fn new(database_config: &DatabaseConfig, server_name: &str) -> Result<MariaDbService, ApplicationError> {
 
@@ -297,9 +923,125 @@ impl MariaDbService {
         })
     }
 
+    /**
+     * Apply the schema migrations not yet applied. A named lock prevents several agents from migrating
+     * the same database at the same time.
+     *
+     * Returns: The schema version after the migrations are applied.
+     *
+     * Errors:
+     * - If the migration lock could not be acquired.
+     * - If there is an error applying a migration.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn migrate(&self) -> Result<i32, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let locked: Option<Option<i32>> = conn.exec_first("SELECT GET_LOCK(:name, 60)", params! { "name" => MIGRATION_LOCK_NAME })
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        if locked != Some(Some(1)) {
+            return Err(ApplicationError::new("Could not acquire the migration lock"));
+        }
+        let result = MariaDbService::apply_migrations(&mut conn);
+        conn.exec_drop("SELECT RELEASE_LOCK(:name)", params! { "name" => MIGRATION_LOCK_NAME })
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        result
+    }
+
+    /**
+     * Apply the migrations with a higher version than the version in the `schema_version` table.
+     * `MariaDB` commits ddl statements implicitly, so the statements of the migrations are created
+     * idempotent and the version is stored when all statements of a migration are applied. `CREATE INDEX`
+     * statements are skipped when the index already exists, as `MySQL` has no `CREATE INDEX IF NOT EXISTS`.
+     *
+     * `conn`: The connection holding the migration lock.
+     *
+     * Returns: The schema version after the migrations are applied.
+     *
+     * Errors:
+     * - If there is an error applying a migration.
+     */
+    fn apply_migrations(conn: &mut r2d2::PooledConnection<MySqlConnectionManager>) -> Result<i32, ApplicationError> {
+        conn.query_drop("CREATE TABLE IF NOT EXISTS schema_version (version INT NOT NULL PRIMARY KEY, description VARCHAR(255) NOT NULL, applied_time DATETIME(3) NOT NULL)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut version: i32 = conn.query_first("SELECT CAST(COALESCE(MAX(version), 0) AS SIGNED) FROM schema_version")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?
+            .unwrap_or_default();
+        for migration in Migration::get_pending(MARIADB_MIGRATIONS, version) {
+            info!("Applying database migration {}: {}", migration.version, migration.description);
+            for statement in migration.get_statements() {
+                if let Some((table, index)) = Migration::get_created_index(&statement) {
+                    if MariaDbService::index_exists(conn, &table, &index)? {
+                        continue;
+                    }
+                }
+                conn.query_drop(statement).map_err(|err| ApplicationError::new(&format!("Error applying migration {}: {err}", migration.version)))?;
+            }
+            conn.exec_drop("INSERT INTO schema_version (version, description, applied_time) VALUES (:version, :description, now(3))", params! {
+                "version" => migration.version,
+                "description" => migration.description,
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+            version = migration.version;
+        }
+        Ok(version)
+    }
+
+    /**
+     * Run a trivial query to check that the database answers.
+     *
+     * Errors:
+     * - If the database does not answer.
+     */
+    pub fn ping(&self) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.query_drop("SELECT 1").map_err(|err| ApplicationError::new(&err.to_string()))
+    }
+
+    /**
+     * Check if an index exists in the current database.
+     *
+     * `conn`: The connection.
+     * `table`: The table of the index.
+     * `index`: The name of the index.
+     *
+     * Returns: True if the index exists.
+     *
+     * Errors:
+     * - If there is an error reading the information schema.
+     */
+    fn index_exists(conn: &mut r2d2::PooledConnection<MySqlConnectionManager>, table: &str, index: &str) -> Result<bool, ApplicationError> {
+        let count: Option<i64> = conn.exec_first(
+            "SELECT COUNT(*) FROM information_schema.statistics WHERE table_schema = DATABASE() AND table_name = :table AND index_name = :index",
+            params! { "table" => table, "index" => index },
+        ).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(count.unwrap_or_default() > 0)
+    }
+
+    /**
+     * Store a record in the database.
+     *
+     * `record`: The record to store.
+     *
+     * Errors:
+     * - If there is an error storing the record.
+     */
+    pub fn store_record(&self, record: &SpoolRecord) -> Result<(), ApplicationError> {
+        match record {
+            SpoolRecord::MonitorStatus { log_time, name, status } => self.insert_monitor_status(log_time, name, status),
+            SpoolRecord::Loadavg { log_time, loadavg } => self.store_loadavg(log_time, loadavg),
+            SpoolRecord::Meminfo { log_time, meminfo } => self.store_meminfo(log_time, meminfo),
+            SpoolRecord::Diskinfo { log_time, disk_usage } => self.store_diskinfo(log_time, disk_usage),
+            SpoolRecord::Cpustat { log_time, cpu_usage } => self.store_cpustat(log_time, cpu_usage),
+            SpoolRecord::Network { log_time, rates } => self.store_network(log_time, rates),
+            SpoolRecord::Statm { log_time, app_name, pid, statm } => self.store_statm_values(log_time, app_name, pid, statm),
+            SpoolRecord::ResponseTime { log_time, name, response_time } => self.store_response_time(log_time, name, response_time),
+            SpoolRecord::Metrics { log_time, name, metrics } => self.store_metrics(log_time, name, metrics),
+        }
+    }
+
     /**
      * Insert a monitor status into the database.
      * 
+     * `log_time`: The time the values were measured.
      * `name`: The name of the monitor.
      * `status`: The status of the monitor.
      * `message`: The message associated with the status.
@@ -313,11 +1055,12 @@ impl MariaDbService {
      * 
      */
     #[tracing::instrument(level = "debug")]    
-    pub fn insert_monitor_status(&self, name: &str, status: &Status) -> Result<(), ApplicationError> {
+    pub fn insert_monitor_status(&self, log_time: &DateTime<Utc>, name: &str, status: &Status) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
         let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("INSERT INTO monitor_status (server_name, monitor_name, status, log_time, message) VALUES (:server_name,:name, :status, now(3), :message)", params! {
+        tx.exec_drop("INSERT INTO monitor_status (server_name, monitor_name, status, log_time, message) VALUES (:server_name,:name, :status, :log_time, :message)", params! {
             "server_name" => self.server_name.to_string(),
+            "log_time" => DbService::get_log_time_db_repr(log_time),
             "name" => &name,
             "status" => DbService::get_status_db_repr(status),
             "message" => DbService::get_message(status),
@@ -329,6 +1072,7 @@ impl MariaDbService {
     /**
      * Store the load average in the database.
      * 
+     * `log_time`: The time the values were measured.
      * `loadavg`: The load average to store.
      * 
      * Returns: Ok if the load average was stored successfully.
@@ -338,11 +1082,12 @@ impl MariaDbService {
      * - If there is an error starting a transaction.
      */
     #[tracing::instrument(level = "debug")]
-    pub fn store_loadavg(&self, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
+    pub fn store_loadavg(&self, log_time: &DateTime<Utc>, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
         let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("INSERT INTO loadavg (server_name, loadavg1min, loadavg5min, loadavg15min, num_processes, num_running_processes, log_time) VALUES (:server_name, :loadavg1min, :loadavg5min, :loadavg15min, :num_processes, :num_running_processes, now(3))", params! {
+        tx.exec_drop("INSERT INTO loadavg (server_name, loadavg1min, loadavg5min, loadavg15min, num_processes, num_running_processes, log_time) VALUES (:server_name, :loadavg1min, :loadavg5min, :loadavg15min, :num_processes, :num_running_processes, :log_time)", params! {
             "server_name" => self.server_name.to_string(),
+            "log_time" => DbService::get_log_time_db_repr(log_time),
             "loadavg1min" => loadavg.loadavg1min,
             "loadavg5min" => loadavg.loadavg5min,
             "loadavg15min" => loadavg.loadavg15min,
@@ -355,6 +1100,7 @@ impl MariaDbService {
     /**
      * Store the meminfo in the database.
      * 
+     * `log_time`: The time the values were measured.
      * `meminfo`: The meminfo to store.
      * 
      * Returns: Ok if the meminfo was stored successfully.
@@ -364,11 +1110,12 @@ impl MariaDbService {
      * - If there is an error starting a transaction.
      */
     #[tracing::instrument(level = "debug")]
-    pub fn store_meminfo(&self, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
+    pub fn store_meminfo(&self, log_time: &DateTime<Utc>, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
         let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("INSERT INTO meminfo (server_name, freemem, mem_percent_used, freeswap, swap_percent_used, log_time) VALUES (:server_name, :freemem, :mem_percent_used, :freeswap, :swap_percent_used, now(3))", params! {
+        tx.exec_drop("INSERT INTO meminfo (server_name, freemem, mem_percent_used, freeswap, swap_percent_used, log_time) VALUES (:server_name, :freemem, :mem_percent_used, :freeswap, :swap_percent_used, :log_time)", params! {
             "server_name" => self.server_name.to_string(),
+            "log_time" => DbService::get_log_time_db_repr(log_time),
             "freemem" => meminfo.memfree,
             "mem_percent_used" => ProcsMeminfo::get_percent_used(meminfo.memfree, meminfo.memtotal),
             "freeswap" => meminfo.swapfree,
@@ -379,53 +1126,151 @@ impl MariaDbService {
     }
 
     /**
-     * Query long running queries.
+     * Store the disk use in the database.
      * 
-     * `max_query_time`: The maximum query time.
+     * `log_time`: The time the values were measured.
+     * `disk_usage`: The disk use of the mount points to store.
      * 
-     * Returns: The long running queries.
+     * Returns: Ok if the disk use was stored successfully.
      * 
      * Errors:
-     * - If there is an error querying the long running queries.
+     * - If there is an error storing the disk use.
      * - If there is an error starting a transaction.
-     * - If there is an error committing the transaction.
-     * 
      */
     #[tracing::instrument(level = "debug")]
-    pub fn query_long_running_queries(&self, max_query_time: u32) -> Result<Vec<String>, ApplicationError> {
+    pub fn store_diskinfo(&self, log_time: &DateTime<Utc>, disk_usage: &[ProcsDiskUsage]) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
         let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        let params = params! {
-            "max_query_time" => max_query_time,
-        };
-        let result = tx.exec_map("SELECT * FROM INFORMATION_SCHEMA.PROCESSLIST WHERE COMMAND != 'Sleep' AND TIME > :max_query_time", params, |row: Row| {
-            let id: u32 = row.get(0).unwrap_or(0);
-            let info: String = row.get(7).unwrap_or("unknown".to_string());
-            format!("id: {id}, info: {info}")
-        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
-        Ok(result)
+        for usage in disk_usage {
+            tx.exec_drop("INSERT INTO diskinfo (server_name, mount_point, freespace, space_percent_used, freeinodes, inodes_percent_used, log_time) VALUES (:server_name, :mount_point, :freespace, :space_percent_used, :freeinodes, :inodes_percent_used, :log_time)", params! {
+                "server_name" => self.server_name.to_string(),
+                "log_time" => DbService::get_log_time_db_repr(log_time),
+                "mount_point" => usage.mount_point.to_string(),
+                "freespace" => usage.available_bytes,
+                "space_percent_used" => usage.get_percent_space_used(),
+                "freeinodes" => usage.free_inodes,
+                "inodes_percent_used" => usage.get_percent_inodes_used(),
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(())
     }
 
     /**
-     * Store the statm values in the database.
+     * Store the cpu utilisation in the database.
      * 
-     * `app_name`: The application name.
-     * `pid`: The process id.
-     * `statm`: The statm values.
+     * `log_time`: The time the values were measured.
+     * `cpu_usage`: The cpu utilisation of the cpus to store.
      * 
-     * Returns: Ok if the statm values were stored successfully.
+     * Returns: Ok if the cpu utilisation was stored successfully.
      * 
      * Errors:
-     * - If there is an error storing the statm values.
+     * - If there is an error storing the cpu utilisation.
+     * - If there is an error starting a transaction.
      */
     #[tracing::instrument(level = "debug")]
-    fn store_statm_values(&self, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
+    pub fn store_cpustat(&self, log_time: &DateTime<Utc>, cpu_usage: &[ProcCpuUsage]) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
         let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("INSERT INTO statm (server_name, app_name, log_time, pid, total, resident, share, trs, drs, lrs, dt, pagesize) VALUES 
-                    (:server_name, :app_name, now(3), :pid, :total, :resident, :share, :trs, :drs, :lrs, :dt, :pagesize)", params! {
-            "server_name" => self.server_name.to_string(),
+        for usage in cpu_usage {
+            tx.exec_drop("INSERT INTO cpustat (server_name, cpu_name, user_percent, system_percent, iowait_percent, steal_percent, total_percent, log_time) VALUES (:server_name, :cpu_name, :user_percent, :system_percent, :iowait_percent, :steal_percent, :total_percent, :log_time)", params! {
+                "server_name" => self.server_name.to_string(),
+                "log_time" => DbService::get_log_time_db_repr(log_time),
+                "cpu_name" => usage.name.clone(),
+                "user_percent" => usage.user,
+                "system_percent" => usage.system,
+                "iowait_percent" => usage.iowait,
+                "steal_percent" => usage.steal,
+                "total_percent" => usage.total,
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(())
+    }
+
+    /**
+     * Store the network throughput in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `rates`: The throughput of the interfaces to store.
+     * 
+     * Returns: Ok if the network throughput was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the network throughput.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_network(&self, log_time: &DateTime<Utc>, rates: &[ProcsNetDevRate]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for rate in rates {
+            tx.exec_drop("INSERT INTO netdev (server_name, interface, rx_bytes_per_second, tx_bytes_per_second, rx_errors, tx_errors, rx_drop, tx_drop, log_time) VALUES (:server_name, :interface, :rx_bytes_per_second, :tx_bytes_per_second, :rx_errors, :tx_errors, :rx_drop, :tx_drop, :log_time)", params! {
+                "server_name" => self.server_name.to_string(),
+                "log_time" => DbService::get_log_time_db_repr(log_time),
+                "interface" => rate.interface.to_string(),
+                "rx_bytes_per_second" => rate.rx_bytes_per_second,
+                "tx_bytes_per_second" => rate.tx_bytes_per_second,
+                "rx_errors" => rate.rx_errors,
+                "tx_errors" => rate.tx_errors,
+                "rx_drop" => rate.rx_drop,
+                "tx_drop" => rate.tx_drop,
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(())
+    }
+
+    /**
+     * Query long running queries.
+     * 
+     * `max_query_time`: The maximum query time.
+     * 
+     * Returns: The long running queries.
+     * 
+     * Errors:
+     * - If there is an error querying the long running queries.
+     * - If there is an error starting a transaction.
+     * - If there is an error committing the transaction.
+     * 
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn query_long_running_queries(&self, max_query_time: u32) -> Result<Vec<String>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let params = params! {
+            "max_query_time" => max_query_time,
+        };
+        let result = tx.exec_map("SELECT * FROM INFORMATION_SCHEMA.PROCESSLIST WHERE COMMAND != 'Sleep' AND TIME > :max_query_time", params, |row: Row| {
+            let id: u32 = row.get(0).unwrap_or(0);
+            let info: String = row.get(7).unwrap_or("unknown".to_string());
+            format!("id: {id}, info: {info}")
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(result)
+    }
+
+    /**
+     * Store the statm values in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `app_name`: The application name.
+     * `pid`: The process id.
+     * `statm`: The statm values.
+     * 
+     * Returns: Ok if the statm values were stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the statm values.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_statm_values(&self, log_time: &DateTime<Utc>, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("INSERT INTO statm (server_name, app_name, log_time, pid, total, resident, share, trs, drs, lrs, dt, pagesize) VALUES 
+                    (:server_name, :app_name, :log_time, :pid, :total, :resident, :share, :trs, :drs, :lrs, :dt, :pagesize)", params! {
+            "server_name" => self.server_name.to_string(),
+            "log_time" => DbService::get_log_time_db_repr(log_time),
             "app_name" => app_name,
             "pid" => pid,
             "total" => statm.size,
@@ -441,6 +1286,68 @@ impl MariaDbService {
         Ok(())
     }
 
+    /**
+     * Store the response time of a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `response_time`: The response time.
+     * 
+     * Returns: Ok if the response time was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the response time.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_response_time(&self, log_time: &DateTime<Utc>, name: &str, response_time: &ResponseTime) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("INSERT INTO response_time (server_name, monitor_name, connect_time, first_byte_time, total_time, log_time) VALUES (:server_name, :monitor_name, :connect_time, :first_byte_time, :total_time, :log_time)", params! {
+            "server_name" => self.server_name.to_string(),
+            "log_time" => DbService::get_log_time_db_repr(log_time),
+            "monitor_name" => name,
+            "connect_time" => response_time.connect_time,
+            "first_byte_time" => response_time.first_byte_time,
+            "total_time" => response_time.total_time,
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the metrics reported by a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `metrics`: The metrics.
+     * 
+     * Returns: Ok if the metrics were stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the metrics.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_metrics(&self, log_time: &DateTime<Utc>, name: &str, metrics: &[Metric]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for metric in metrics {
+            tx.exec_drop("INSERT INTO metrics (server_name, monitor_name, label, value, unit, warn, crit, min_value, max_value, log_time) VALUES (:server_name, :monitor_name, :label, :value, :unit, :warn, :crit, :min_value, :max_value, :log_time)", params! {
+                "server_name" => self.server_name.to_string(),
+                "log_time" => DbService::get_log_time_db_repr(log_time),
+                "monitor_name" => name,
+                "label" => &metric.label,
+                "value" => metric.value,
+                "unit" => &metric.unit,
+                "warn" => &metric.warn,
+                "crit" => &metric.crit,
+                "min_value" => metric.min,
+                "max_value" => metric.max,
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
     /**
      * Get the historical load average.
      * 
@@ -529,6 +1436,140 @@ impl MariaDbService {
         Ok(freemem_elements)   
     }
 
+    /**
+     * Get the historical disk use.
+     * 
+     * Returns: The historical disk use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical disk use.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_diskinfo(&self, historical_params: HistoricalParams) -> Result<Vec<DiskinfoElement>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        /*
+         * Init parameters 
+         */
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "from_datetime" => historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "to_datetime" => historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(), 
+            "split" => historical_params.split * 60,           
+        };
+        /*
+         * Execute query
+         */
+        let query_result: Vec<(String, String, Option<f64>, Option<f64>)> = tx
+            .exec_map("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), mount_point, avg(space_percent_used), avg(inodes_percent_used) FROM diskinfo WHERE server_name = :server_name and log_time>=:from_datetime and log_time<=:to_datetime GROUP BY mount_point, UNIX_TIMESTAMP(log_time) DIV :split ORDER BY mount_point, min(log_time)", params, |(log_time, mount_point, space_percent_used, inodes_percent_used) : (String, String, Option<f64>, Option<f64>)| {
+                (log_time, mount_point, space_percent_used, inodes_percent_used)
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;                   
+        /*
+         * Map result
+         */
+        let mut diskinfo_elements: Vec<DiskinfoElement> = Vec::new();
+        for (log_time, mount_point, space_percent_used, inodes_percent_used) in query_result {
+            let log_time = NaiveDateTime::parse_from_str(&log_time, "%Y-%m-%d %H:%M:%S");
+            let timestamp = match log_time {
+                Ok(log_time) => log_time.and_utc(),
+                Err(err) => Err(ApplicationError::new(err.to_string().as_str()))?,
+            };
+            diskinfo_elements.push(DiskinfoElement::new(timestamp, &mount_point, space_percent_used, inodes_percent_used));
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(diskinfo_elements)   
+    }
+
+    /**
+     * Get the historical cpu utilisation.
+     * 
+     * Returns: The historical cpu utilisation.
+     * 
+     * Errors:
+     * - If there is an error getting the historical cpu utilisation.
+     */
+    #[tracing::instrument(level = "debug")]
+    #[allow(clippy::type_complexity)]
+    pub fn get_historical_cpustat(&self, historical_params: HistoricalParams) -> Result<Vec<CpustatElement>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        /*
+         * Init parameters 
+         */
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "from_datetime" => historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "to_datetime" => historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(), 
+            "split" => historical_params.split * 60,           
+        };
+        /*
+         * Execute query
+         */
+        let query_result: Vec<(String, String, Option<f64>, Option<f64>, Option<f64>, Option<f64>, Option<f64>)> = tx
+            .exec_map("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), cpu_name, avg(user_percent), avg(system_percent), avg(iowait_percent), avg(steal_percent), avg(total_percent) FROM cpustat WHERE server_name = :server_name and log_time>=:from_datetime and log_time<=:to_datetime GROUP BY cpu_name, UNIX_TIMESTAMP(log_time) DIV :split ORDER BY cpu_name, min(log_time)", params, |(log_time, cpu_name, user, system, iowait, steal, total) : (String, String, Option<f64>, Option<f64>, Option<f64>, Option<f64>, Option<f64>)| {
+                (log_time, cpu_name, user, system, iowait, steal, total)
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;                   
+        /*
+         * Map result
+         */
+        let mut cpustat_elements: Vec<CpustatElement> = Vec::new();
+        for (log_time, cpu_name, user, system, iowait, steal, total) in query_result {
+            let log_time = NaiveDateTime::parse_from_str(&log_time, "%Y-%m-%d %H:%M:%S");
+            let timestamp = match log_time {
+                Ok(log_time) => log_time.and_utc(),
+                Err(err) => Err(ApplicationError::new(err.to_string().as_str()))?,
+            };
+            cpustat_elements.push(CpustatElement::new(timestamp, &cpu_name, user, system, iowait, steal, total));
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(cpustat_elements)   
+    }
+
+    /**
+     * Get the historical network throughput.
+     * 
+     * Returns: The historical network throughput.
+     * 
+     * Errors:
+     * - If there is an error getting the historical network throughput.
+     */
+    #[tracing::instrument(level = "debug")]
+    #[allow(clippy::type_complexity)]
+    pub fn get_historical_network(&self, historical_params: HistoricalParams) -> Result<Vec<NetworkElement>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut tx = conn.start_transaction(TxOpts::default()).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        /*
+         * Init parameters 
+         */
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "from_datetime" => historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "to_datetime" => historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(), 
+            "split" => historical_params.split * 60,           
+        };
+        /*
+         * Execute query
+         */
+        let query_result: Vec<(String, String, Option<f64>, Option<f64>, Option<u64>, Option<u64>)> = tx
+            .exec_map("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), interface, avg(rx_bytes_per_second), avg(tx_bytes_per_second), CAST(sum(rx_errors + tx_errors) AS UNSIGNED), CAST(sum(rx_drop + tx_drop) AS UNSIGNED) FROM netdev WHERE server_name = :server_name and log_time>=:from_datetime and log_time<=:to_datetime GROUP BY interface, UNIX_TIMESTAMP(log_time) DIV :split ORDER BY interface, min(log_time)", params, |(log_time, interface, rx_bytes_per_second, tx_bytes_per_second, errors, drops) : (String, String, Option<f64>, Option<f64>, Option<u64>, Option<u64>)| {
+                (log_time, interface, rx_bytes_per_second, tx_bytes_per_second, errors, drops)
+            }).map_err(|err| ApplicationError::new(&err.to_string()))?;                   
+        /*
+         * Map result
+         */
+        let mut network_elements: Vec<NetworkElement> = Vec::new();
+        for (log_time, interface, rx_bytes_per_second, tx_bytes_per_second, errors, drops) in query_result {
+            let log_time = NaiveDateTime::parse_from_str(&log_time, "%Y-%m-%d %H:%M:%S");
+            let timestamp = match log_time {
+                Ok(log_time) => log_time.and_utc(),
+                Err(err) => Err(ApplicationError::new(err.to_string().as_str()))?,
+            };
+            network_elements.push(NetworkElement::new(timestamp, &interface, rx_bytes_per_second, tx_bytes_per_second, errors, drops));
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;       
+        Ok(network_elements)   
+    }
+
     /**
      * Get the process memory use.
      * 
@@ -581,6 +1622,109 @@ impl MariaDbService {
         Ok(elements)   
     }
 
+    /**
+     * Get the historical response time of a monitor.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The average response time in each split.
+     * 
+     * Errors:
+     * - If there is an error getting the historical response time.
+     */
+    #[allow(clippy::type_complexity)]
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_response_time(&self, monitor_name: &str, historical_params: &HistoricalParams) -> Result<Vec<ResponseTimeElement>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "monitor_name" => monitor_name,
+            "from_datetime" => historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "to_datetime" => historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "split" => historical_params.split * 60,
+        };
+        let query_result: Vec<(String, Option<f64>, Option<f64>, Option<f64>)> = conn
+            .exec("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), avg(connect_time), avg(first_byte_time), avg(total_time) FROM response_time WHERE server_name = :server_name and monitor_name = :monitor_name and log_time>=:from_datetime and log_time<=:to_datetime GROUP BY UNIX_TIMESTAMP(log_time) DIV :split ORDER BY min(log_time)", params)
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<ResponseTimeElement> = Vec::new();
+        for (log_time, connect_time, first_byte_time, total_time) in query_result {
+            let timestamp = NaiveDateTime::parse_from_str(&log_time, "%Y-%m-%d %H:%M:%S")
+                .map(|log_time| log_time.and_utc())
+                .map_err(|err| ApplicationError::new(&err.to_string()))?;
+            elements.push(ResponseTimeElement::new(timestamp, connect_time, first_byte_time, total_time));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the stored statuses of a monitor, newest first.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `history_params`: The time window, status filter and page.
+     * 
+     * Returns: The statuses.
+     * 
+     * Errors:
+     * - If there is an error getting the statuses.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_monitor_status_history(&self, monitor_name: &str, history_params: &MonitorHistoryParams) -> Result<Vec<MonitorStatusElement>, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "monitor_name" => monitor_name,
+            "from_datetime" => DbService::get_log_time_db_repr(&history_params.from_datetime),
+            "to_datetime" => DbService::get_log_time_db_repr(&history_params.to_datetime),
+            "status" => history_params.status.clone(),
+            "limit" => history_params.get_limit(),
+            "offset" => history_params.get_offset(),
+        };
+        let query_result: Vec<(String, String, Option<String>)> = conn
+            .exec("SELECT DATE_FORMAT(log_time, '%Y-%m-%d %H:%i:%s.%f'), status, message FROM monitor_status WHERE server_name = :server_name AND monitor_name = :monitor_name AND log_time >= :from_datetime AND log_time <= :to_datetime AND (:status IS NULL OR status = :status) ORDER BY log_time DESC LIMIT :limit OFFSET :offset", params)
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        query_result.into_iter()
+            .map(|(log_time, status, message)| Ok(MonitorStatusElement::new(DbService::get_log_time_from_db_repr(&log_time)?, &status, message)))
+            .collect()
+    }
+
+    /**
+     * Get the status changes of a monitor in a window, and the last status stored before the window.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `from_datetime`: The start of the window.
+     * `to_datetime`: The end of the window.
+     * 
+     * Returns: The last status before the window and the status changes ordered by time.
+     * 
+     * Errors:
+     * - If there is an error getting the status changes.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_monitor_status_changes(&self, monitor_name: &str, from_datetime: &DateTime<Utc>, to_datetime: &DateTime<Utc>) -> Result<(Option<String>, Vec<MonitorStatusElement>), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let params = params! {
+            "server_name" => self.server_name.to_string(),
+            "monitor_name" => monitor_name,
+            "from_datetime" => DbService::get_log_time_db_repr(from_datetime),
+            "to_datetime" => DbService::get_log_time_db_repr(to_datetime),
+        };
+        let initial_status: Option<String> = conn
+            .exec_first("SELECT status FROM monitor_status WHERE server_name = :server_name AND monitor_name = :monitor_name AND log_time < :from_datetime ORDER BY log_time DESC LIMIT 1", params! {
+                "server_name" => self.server_name.to_string(),
+                "monitor_name" => monitor_name,
+                "from_datetime" => DbService::get_log_time_db_repr(from_datetime),
+            })
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let query_result: Vec<(String, String, Option<String>)> = conn
+            .exec("SELECT DATE_FORMAT(log_time, '%Y-%m-%d %H:%i:%s.%f'), status, message FROM (SELECT log_time, status, message, LAG(status) OVER (ORDER BY log_time) AS previous_status FROM monitor_status WHERE server_name = :server_name AND monitor_name = :monitor_name AND log_time >= :from_datetime AND log_time <= :to_datetime) changes WHERE previous_status IS NULL OR previous_status <> status ORDER BY log_time", params)
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let changes = query_result.into_iter()
+            .map(|(log_time, status, message)| Ok(MonitorStatusElement::new(DbService::get_log_time_from_db_repr(&log_time)?, &status, message)))
+            .collect::<Result<Vec<MonitorStatusElement>, ApplicationError>>()?;
+        Ok((initial_status, changes))
+    }
+
     /**
      * Delete old data.
      * 
@@ -598,10 +1742,15 @@ impl MariaDbService {
             "max_time_stored_db" => max_time_stored_db,
             "server_name" => self.server_name.to_string(),
         };
-        tx.exec_drop("DELETE FROM monitor_status WHERE log_time < now() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("DELETE FROM loadavg WHERE log_time < now() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("DELETE FROM meminfo WHERE log_time < now() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.exec_drop("DELETE FROM statm WHERE log_time < now() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM monitor_status WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM loadavg WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM meminfo WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM diskinfo WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM cpustat WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM netdev WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM statm WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM response_time WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.exec_drop("DELETE FROM metrics WHERE log_time < UTC_TIMESTAMP() - INTERVAL :max_time_stored_db HOUR AND server_name = :server_name", &params).map_err(|err| ApplicationError::new(&err.to_string()))?;
         tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
         Ok(())
     }
@@ -663,29 +1812,96 @@ impl PostgresDbService {
     }
 
     /**
-     * Insert a monitor status into the database.
-     * 
-     * `name`: The name of the monitor.
-     * `status`: The status of the monitor.
-     * `message`: The message associated with the status.
-     * 
-     * Returns: Ok if the status was inserted successfully.
-     * 
+     * Apply the schema migrations not yet applied. The migrations are applied in one transaction holding
+     * an advisory lock, so several agents can not migrate the same database at the same time.
+     *
+     * Returns: The schema version after the migrations are applied.
+     *
      * Errors:
-     * - If there is an error inserting the status.
+     * - If there is an error applying a migration.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn migrate(&self) -> Result<i32, ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK_KEY]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.batch_execute("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, description VARCHAR(255) NOT NULL, applied_time TIMESTAMP NOT NULL)")
+            .await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let row = tx.query_one("SELECT COALESCE(MAX(version), 0) FROM schema_version", &[]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut version: i32 = PostgresDbService::get_column(&row, 0)?;
+        for migration in Migration::get_pending(POSTGRES_MIGRATIONS, version) {
+            info!("Applying database migration {}: {}", migration.version, migration.description);
+            tx.batch_execute(migration.sql).await.map_err(|err| ApplicationError::new(&format!("Error applying migration {}: {err}", migration.version)))?;
+            tx.execute("INSERT INTO schema_version (version, description, applied_time) VALUES ($1, $2, now())", &[
+                &migration.version,
+                &migration.description,
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            version = migration.version;
+        }
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(version)
+    }
+
+    /**
+     * Run a trivial query to check that the database answers.
+     *
+     * Errors:
+     * - If the database does not answer.
+     */
+    pub async fn ping(&self) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.simple_query("SELECT 1").await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store a record in the database.
+     *
+     * `record`: The record to store.
+     *
+     * Errors:
+     * - If there is an error storing the record.
+     */
+    pub async fn store_record(&self, record: &SpoolRecord) -> Result<(), ApplicationError> {
+        match record {
+            SpoolRecord::MonitorStatus { log_time, name, status } => self.insert_monitor_status(log_time, name, status).await,
+            SpoolRecord::Loadavg { log_time, loadavg } => self.store_loadavg(log_time, loadavg).await,
+            SpoolRecord::Meminfo { log_time, meminfo } => self.store_meminfo(log_time, meminfo).await,
+            SpoolRecord::Diskinfo { log_time, disk_usage } => self.store_diskinfo(log_time, disk_usage).await,
+            SpoolRecord::Cpustat { log_time, cpu_usage } => self.store_cpustat(log_time, cpu_usage).await,
+            SpoolRecord::Network { log_time, rates } => self.store_network(log_time, rates).await,
+            SpoolRecord::Statm { log_time, app_name, pid, statm } => self.store_statm_values(log_time, app_name, pid, statm).await,
+            SpoolRecord::ResponseTime { log_time, name, response_time } => self.store_response_time(log_time, name, response_time).await,
+            SpoolRecord::Metrics { log_time, name, metrics } => self.store_metrics(log_time, name, metrics).await,
+        }
+    }
+
+    /**
+     * Insert a monitor status into the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `status`: The status of the monitor.
+     * `message`: The message associated with the status.
+     * 
+     * Returns: Ok if the status was inserted successfully.
+     * 
+     * Errors:
+     * - If there is an error inserting the status.
      * - If there is an error starting a transaction.
      * - If there is an error committing the transaction.
      * 
      */
     #[tracing::instrument(level = "debug")]
-    pub async fn insert_monitor_status(&self, name: &str, status: &Status) -> Result<(), ApplicationError> {
+    pub async fn insert_monitor_status(&self, log_time: &DateTime<Utc>, name: &str, status: &Status) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.execute("INSERT INTO monitor_status (id, server_name, monitor_name, status, log_time, message) VALUES (nextval('seq_monitor_status'), $1, $2, $3, now(), $4)", &[
+        tx.execute("INSERT INTO monitor_status (id, server_name, monitor_name, status, log_time, message) VALUES (nextval('seq_monitor_status'), $1, $2, $3, $5::text::timestamp, $4)", &[
             &self.server_name,
             &name,
             &DbService::get_status_db_repr(status),
             &DbService::get_message(status),
+            &DbService::get_log_time_db_repr(log_time),
         ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         Ok(())
@@ -694,6 +1910,7 @@ impl PostgresDbService {
     /**
      * Store the load average in the database.
      * 
+     * `log_time`: The time the values were measured.
      * `loadavg`: The load average to store.
      * 
      * Returns: Ok if the load average was stored successfully.
@@ -703,16 +1920,17 @@ impl PostgresDbService {
      * - If there is an error starting a transaction.
      */
     #[tracing::instrument(level = "debug")]
-    pub async fn store_loadavg(&self, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
+    pub async fn store_loadavg(&self, log_time: &DateTime<Utc>, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.execute("INSERT INTO loadavg (id, server_name, loadavg1min, loadavg5min, loadavg15min, num_processes, num_running_processes, log_time) VALUES (nextval('seq_loadavg'), $1, $2, $3, $4, $5, $6, now())", &[
+        tx.execute("INSERT INTO loadavg (id, server_name, loadavg1min, loadavg5min, loadavg15min, num_processes, num_running_processes, log_time) VALUES (nextval('seq_loadavg'), $1, $2, $3, $4, $5, $6, $7::text::timestamp)", &[
             &self.server_name,
             &loadavg.loadavg1min.map(|f|Decimal::try_from(f).ok()),
             &loadavg.loadavg5min.map(|f|Decimal::try_from(f).ok()),
             &loadavg.loadavg15min.map(|f|Decimal::try_from(f).ok()),
             &loadavg.total_number_of_processes.map(i64::from),
             &loadavg.current_running_processes.map(i64::from),
+            &DbService::get_log_time_db_repr(log_time),
         ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         Ok(())
@@ -721,6 +1939,7 @@ impl PostgresDbService {
     /**
      * Store the meminfo in the database.
      * 
+     * `log_time`: The time the values were measured.
      * `meminfo`: The meminfo to store.
      * 
      * Returns: Ok if the meminfo was stored successfully.
@@ -730,20 +1949,117 @@ impl PostgresDbService {
      * - If there is an error starting a transaction.
      */
     #[tracing::instrument(level = "debug")]
-    pub async fn store_meminfo(&self, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
+    pub async fn store_meminfo(&self, log_time: &DateTime<Utc>, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
-        tx.execute("INSERT INTO meminfo (id, server_name, freemem, mem_percent_used, freeswap, swap_percent_used, log_time) VALUES (nextval('seq_meminfo'), $1, $2, $3, $4, $5, now())", &[
+        tx.execute("INSERT INTO meminfo (id, server_name, freemem, mem_percent_used, freeswap, swap_percent_used, log_time) VALUES (nextval('seq_meminfo'), $1, $2, $3, $4, $5, $6::text::timestamp)", &[
             &self.server_name,
-            &meminfo.memfree.map(|x| i32::try_from(x).ok()),
+            &meminfo.memfree.map(|x| i64::try_from(x).ok()),
             &ProcsMeminfo::get_percent_used(meminfo.memfree, meminfo.memtotal).map(|f|Decimal::try_from(f).ok()),
-            &meminfo.swapfree.map(|x| i32::try_from(x).ok()),
+            &meminfo.swapfree.map(|x| i64::try_from(x).ok()),
             &ProcsMeminfo::get_percent_used(meminfo.swapfree, meminfo.swaptotal).map(|f|Decimal::try_from(f).ok()),
+            &DbService::get_log_time_db_repr(log_time),
         ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         Ok(())
     }
 
+    /**
+     * Store the disk use in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `disk_usage`: The disk use of the mount points to store.
+     * 
+     * Returns: Ok if the disk use was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the disk use.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn store_diskinfo(&self, log_time: &DateTime<Utc>, disk_usage: &[ProcsDiskUsage]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for usage in disk_usage {
+            tx.execute("INSERT INTO diskinfo (id, server_name, mount_point, freespace, space_percent_used, freeinodes, inodes_percent_used, log_time) VALUES (nextval('seq_diskinfo'), $1, $2, $3, $4, $5, $6, $7::text::timestamp)", &[
+                &self.server_name,
+                &usage.mount_point,
+                &i64::try_from(usage.available_bytes).ok(),
+                &usage.get_percent_space_used().map(|f|Decimal::try_from(f).ok()),
+                &i64::try_from(usage.free_inodes).ok(),
+                &usage.get_percent_inodes_used().map(|f|Decimal::try_from(f).ok()),
+                &DbService::get_log_time_db_repr(log_time),
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the cpu utilisation in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `cpu_usage`: The cpu utilisation of the cpus to store.
+     * 
+     * Returns: Ok if the cpu utilisation was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the cpu utilisation.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn store_cpustat(&self, log_time: &DateTime<Utc>, cpu_usage: &[ProcCpuUsage]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for usage in cpu_usage {
+            tx.execute("INSERT INTO cpustat (id, server_name, cpu_name, user_percent, system_percent, iowait_percent, steal_percent, total_percent, log_time) VALUES (nextval('seq_cpustat'), $1, $2, $3, $4, $5, $6, $7, $8::text::timestamp)", &[
+                &self.server_name,
+                &usage.name,
+                &Decimal::try_from(usage.user).ok(),
+                &Decimal::try_from(usage.system).ok(),
+                &Decimal::try_from(usage.iowait).ok(),
+                &Decimal::try_from(usage.steal).ok(),
+                &Decimal::try_from(usage.total).ok(),
+                &DbService::get_log_time_db_repr(log_time),
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the network throughput in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `rates`: The throughput of the interfaces to store.
+     * 
+     * Returns: Ok if the network throughput was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the network throughput.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn store_network(&self, log_time: &DateTime<Utc>, rates: &[ProcsNetDevRate]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for rate in rates {
+            tx.execute("INSERT INTO netdev (id, server_name, interface, rx_bytes_per_second, tx_bytes_per_second, rx_errors, tx_errors, rx_drop, tx_drop, log_time) VALUES (nextval('seq_netdev'), $1, $2, $3, $4, $5, $6, $7, $8, $9::text::timestamp)", &[
+                &self.server_name,
+                &rate.interface,
+                &Decimal::try_from(rate.rx_bytes_per_second).ok(),
+                &Decimal::try_from(rate.tx_bytes_per_second).ok(),
+                &i64::try_from(rate.rx_errors).ok(),
+                &i64::try_from(rate.tx_errors).ok(),
+                &i64::try_from(rate.rx_drop).ok(),
+                &i64::try_from(rate.tx_drop).ok(),
+                &DbService::get_log_time_db_repr(log_time),
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
     /**
      * Query long running queries.
      * 
@@ -783,26 +2099,1640 @@ impl PostgresDbService {
     }
 
     #[tracing::instrument(level = "debug")]
-    async fn store_statm_values(&self, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
+    async fn store_statm_values(&self, log_time: &DateTime<Utc>, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
         let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         tx.execute("INSERT INTO statm (id, server_name, app_name, log_time, pid, total, resident, share, trs, drs, lrs, dt, pagesize) VALUES 
-                    (nextval('seq_statm'), $1, $2, now(), $3, $4, $5, $6, $7, $8, $9, $10)", &[
+                    (nextval('seq_statm'), $1, $2, $12::text::timestamp, $3, $4, $5, $6, $7, $8, $9, $10, $11)", &[
             &self.server_name,
             &app_name,
-            &pid,
-            &statm.size,
-            &statm.resident,
-            &statm.share,
-            &statm.trs,
-            &statm.drs,
-            &statm.lrs,
-            &statm.dt,
-            &statm.pagesize,
+            &i64::from(*pid),
+            &statm.size.map(i64::from),
+            &statm.resident.map(i64::from),
+            &statm.share.map(i64::from),
+            &statm.trs.map(i64::from),
+            &statm.drs.map(i64::from),
+            &statm.lrs.map(i64::from),
+            &statm.dt.map(i64::from),
+            &statm.pagesize.map(i64::from),
+            &DbService::get_log_time_db_repr(log_time),
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+
+    }
 
+    /**
+     * Store the response time of a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `response_time`: The response time.
+     * 
+     * Returns: Ok if the response time was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the response time.
+     */
+    #[tracing::instrument(level = "debug")]
+    async fn store_response_time(&self, log_time: &DateTime<Utc>, name: &str, response_time: &ResponseTime) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO response_time (id, server_name, monitor_name, connect_time, first_byte_time, total_time, log_time) VALUES (nextval('seq_response_time'), $1, $2, $3, $4, $5, $6::text::timestamp)", &[
+            &self.server_name,
+            &name,
+            &response_time.connect_time.and_then(|time| Decimal::try_from(time).ok()),
+            &response_time.first_byte_time.and_then(|time| Decimal::try_from(time).ok()),
+            &Decimal::try_from(response_time.total_time).map_err(|err| ApplicationError::new(&err.to_string()))?,
+            &DbService::get_log_time_db_repr(log_time),
         ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the metrics reported by a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `metrics`: The metrics.
+     * 
+     * Returns: Ok if the metrics were stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the metrics.
+     */
+    #[tracing::instrument(level = "debug")]
+    async fn store_metrics(&self, log_time: &DateTime<Utc>, name: &str, metrics: &[Metric]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for metric in metrics {
+            tx.execute("INSERT INTO metrics (id, server_name, monitor_name, label, value, unit, warn, crit, min_value, max_value, log_time) VALUES (nextval('seq_metrics'), $1, $2, $3, $4, $5, $6, $7, $8, $9, $10::text::timestamp)", &[
+                &self.server_name,
+                &name,
+                &metric.label,
+                &Decimal::try_from(metric.value).map_err(|err| ApplicationError::new(&err.to_string()))?,
+                &metric.unit,
+                &metric.warn,
+                &metric.crit,
+                &metric.min.and_then(|min| Decimal::try_from(min).ok()),
+                &metric.max.and_then(|max| Decimal::try_from(max).ok()),
+                &DbService::get_log_time_db_repr(log_time),
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
         tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
         Ok(())
+    }
+
+    /**
+     * Get the query parameters used by the historical queries.
+     * 
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The from date time, the to date time and the split in seconds.
+     */
+    fn get_historical_query_params(historical_params: &HistoricalParams) -> (String, String, f64) {
+        (
+            historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            f64::from(historical_params.split) * 60.0,
+        )
+    }
+
+    /**
+     * Get the log time in the first column of a historical query row.
+     * 
+     * `row`: The row.
+     * 
+     * Returns: The log time.
+     * 
+     * Errors:
+     * - If the column is missing or not a valid date time.
+     */
+    fn get_log_time(row: &bb8_postgres::tokio_postgres::Row) -> Result<DateTime<Utc>, ApplicationError> {
+        let log_time: String = PostgresDbService::get_column(row, 0)?;
+        NaiveDateTime::parse_from_str(&log_time, "%Y-%m-%d %H:%M:%S")
+            .map(|log_time| log_time.and_utc())
+            .map_err(|err| ApplicationError::new(err.to_string().as_str()))
+    }
+
+    /**
+     * Get a column value from a row.
+     * 
+     * `row`: The row.
+     * `index`: The column index.
+     * 
+     * Returns: The column value.
+     * 
+     * Errors:
+     * - If the column is missing or has another type.
+     */
+    fn get_column<'a, T: FromSql<'a>>(row: &'a bb8_postgres::tokio_postgres::Row, index: usize) -> Result<T, ApplicationError> {
+        row.try_get(index).map_err(|err| ApplicationError::new(&err.to_string()))
+    }
+
+    /**
+     * Get the historical load average.
+     * 
+     * Returns: The historical load average.
+     * 
+     * Errors:
+     * - If there is an error getting the historical load average.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_loadavg(&self, historical_params: HistoricalParams) -> Result<Vec<LoadavgElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), avg(loadavg1min)::float8, avg(loadavg5min)::float8, avg(loadavg15min)::float8 FROM loadavg WHERE server_name = $1 and log_time>=$2::text::timestamp and log_time<=$3::text::timestamp GROUP BY floor(extract(epoch from log_time) / $4::float8) ORDER BY min(log_time)", &[
+            &self.server_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut loadavg_elements: Vec<LoadavgElement> = Vec::new();
+        for row in rows {
+            loadavg_elements.push(LoadavgElement::new(
+                PostgresDbService::get_log_time(&row)?,
+                PostgresDbService::get_column(&row, 1)?,
+                PostgresDbService::get_column(&row, 2)?,
+                PostgresDbService::get_column(&row, 3)?));
+        }
+        Ok(loadavg_elements)
+    }
+
+    /**
+     * Get the historical memory use.
+     * 
+     * Returns: The historical memory use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical memory use.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_meminfo(&self, historical_params: HistoricalParams) -> Result<Vec<MeminfoElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), round(avg(freemem))::int8 FROM meminfo WHERE server_name = $1 and log_time>=$2::text::timestamp and log_time<=$3::text::timestamp GROUP BY floor(extract(epoch from log_time) / $4::float8) ORDER BY min(log_time)", &[
+            &self.server_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut freemem_elements: Vec<MeminfoElement> = Vec::new();
+        for row in rows {
+            let freemem: i64 = PostgresDbService::get_column(&row, 1)?;
+            freemem_elements.push(MeminfoElement::new(PostgresDbService::get_log_time(&row)?, u64::try_from(freemem).unwrap_or_default()));
+        }
+        Ok(freemem_elements)
+    }
+
+    /**
+     * Get the historical disk use.
+     * 
+     * Returns: The historical disk use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical disk use.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_diskinfo(&self, historical_params: HistoricalParams) -> Result<Vec<DiskinfoElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), mount_point, avg(space_percent_used)::float8, avg(inodes_percent_used)::float8 FROM diskinfo WHERE server_name = $1 and log_time>=$2::text::timestamp and log_time<=$3::text::timestamp GROUP BY mount_point, floor(extract(epoch from log_time) / $4::float8) ORDER BY mount_point, min(log_time)", &[
+            &self.server_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut diskinfo_elements: Vec<DiskinfoElement> = Vec::new();
+        for row in rows {
+            let mount_point: String = PostgresDbService::get_column(&row, 1)?;
+            diskinfo_elements.push(DiskinfoElement::new(
+                PostgresDbService::get_log_time(&row)?,
+                &mount_point,
+                PostgresDbService::get_column(&row, 2)?,
+                PostgresDbService::get_column(&row, 3)?));
+        }
+        Ok(diskinfo_elements)
+    }
+
+    /**
+     * Get the historical cpu utilisation.
+     * 
+     * Returns: The historical cpu utilisation.
+     * 
+     * Errors:
+     * - If there is an error getting the historical cpu utilisation.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_cpustat(&self, historical_params: HistoricalParams) -> Result<Vec<CpustatElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), cpu_name, avg(user_percent)::float8, avg(system_percent)::float8, avg(iowait_percent)::float8, avg(steal_percent)::float8, avg(total_percent)::float8 FROM cpustat WHERE server_name = $1 and log_time>=$2::text::timestamp and log_time<=$3::text::timestamp GROUP BY cpu_name, floor(extract(epoch from log_time) / $4::float8) ORDER BY cpu_name, min(log_time)", &[
+            &self.server_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut cpustat_elements: Vec<CpustatElement> = Vec::new();
+        for row in rows {
+            let cpu_name: String = PostgresDbService::get_column(&row, 1)?;
+            cpustat_elements.push(CpustatElement::new(
+                PostgresDbService::get_log_time(&row)?,
+                &cpu_name,
+                PostgresDbService::get_column(&row, 2)?,
+                PostgresDbService::get_column(&row, 3)?,
+                PostgresDbService::get_column(&row, 4)?,
+                PostgresDbService::get_column(&row, 5)?,
+                PostgresDbService::get_column(&row, 6)?));
+        }
+        Ok(cpustat_elements)
+    }
+
+    /**
+     * Get the historical network throughput.
+     * 
+     * Returns: The historical network throughput.
+     * 
+     * Errors:
+     * - If there is an error getting the historical network throughput.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_network(&self, historical_params: HistoricalParams) -> Result<Vec<NetworkElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), interface, avg(rx_bytes_per_second)::float8, avg(tx_bytes_per_second)::float8, sum(rx_errors + tx_errors)::int8, sum(rx_drop + tx_drop)::int8 FROM netdev WHERE server_name = $1 and log_time>=$2::text::timestamp and log_time<=$3::text::timestamp GROUP BY interface, floor(extract(epoch from log_time) / $4::float8) ORDER BY interface, min(log_time)", &[
+            &self.server_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut network_elements: Vec<NetworkElement> = Vec::new();
+        for row in rows {
+            let interface: String = PostgresDbService::get_column(&row, 1)?;
+            let errors: Option<i64> = PostgresDbService::get_column(&row, 4)?;
+            let drops: Option<i64> = PostgresDbService::get_column(&row, 5)?;
+            network_elements.push(NetworkElement::new(
+                PostgresDbService::get_log_time(&row)?,
+                &interface,
+                PostgresDbService::get_column(&row, 2)?,
+                PostgresDbService::get_column(&row, 3)?,
+                errors.and_then(|errors| u64::try_from(errors).ok()),
+                drops.and_then(|drops| u64::try_from(drops).ok())));
+        }
+        Ok(network_elements)
+    }
+
+    /**
+     * Get the process memory use.
+     * 
+     * `pid`: The process id.
+     * `historical_params`: The historical parameters.
+     * 
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_process_memory_use(&self, pid: &u32, historical_params: HistoricalParams) -> Result<Vec<ProcessMemoryElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(&historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), round(avg(resident))::int8, round(avg(share))::int8, round(avg(trs))::int8, round(avg(drs))::int8, round(avg(lrs))::int8, round(avg(dt))::int8, round(avg(pagesize))::int8 FROM statm WHERE server_name = $1 and pid = $2 and log_time>=$3::text::timestamp and log_time<=$4::text::timestamp GROUP BY floor(extract(epoch from log_time) / $5::float8) ORDER BY min(log_time)", &[
+            &self.server_name,
+            &i64::from(*pid),
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<ProcessMemoryElement> = Vec::new();
+        for row in rows {
+            let mut values: Vec<Option<u64>> = Vec::new();
+            for index in 1..=7 {
+                let value: Option<i64> = PostgresDbService::get_column(&row, index)?;
+                values.push(value.and_then(|value| u64::try_from(value).ok()));
+            }
+            let pagesize = values[6].unwrap_or(4096);
+            elements.push(ProcessMemoryElement::new(PostgresDbService::get_log_time(&row)?,
+                values[0].map(|f| f * pagesize),
+                values[1].map(|f| f * pagesize),
+                values[2].map(|f| f * pagesize),
+                values[3].map(|f| f * pagesize),
+                values[4].map(|f| f * pagesize),
+                values[5].map(|f| f * pagesize)));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the historical response time of a monitor.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The average response time in each split.
+     * 
+     * Errors:
+     * - If there is an error getting the historical response time.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_historical_response_time(&self, monitor_name: &str, historical_params: &HistoricalParams) -> Result<Vec<ResponseTimeElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = PostgresDbService::get_historical_query_params(historical_params);
+        let rows = conn.query("SELECT to_char(min(log_time),'YYYY-MM-DD HH24:MI:SS'), avg(connect_time)::float8, avg(first_byte_time)::float8, avg(total_time)::float8 FROM response_time WHERE server_name = $1 and monitor_name = $2 and log_time>=$3::text::timestamp and log_time<=$4::text::timestamp GROUP BY floor(extract(epoch from log_time) / $5::float8) ORDER BY min(log_time)", &[
+            &self.server_name,
+            &monitor_name,
+            &from_datetime,
+            &to_datetime,
+            &split,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<ResponseTimeElement> = Vec::new();
+        for row in rows {
+            elements.push(ResponseTimeElement::new(
+                PostgresDbService::get_log_time(&row)?,
+                PostgresDbService::get_column(&row, 1)?,
+                PostgresDbService::get_column(&row, 2)?,
+                PostgresDbService::get_column(&row, 3)?));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the stored statuses of a monitor, newest first.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `history_params`: The time window, status filter and page.
+     * 
+     * Returns: The statuses.
+     * 
+     * Errors:
+     * - If there is an error getting the statuses.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_monitor_status_history(&self, monitor_name: &str, history_params: &MonitorHistoryParams) -> Result<Vec<MonitorStatusElement>, ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = conn.query("SELECT to_char(log_time, 'YYYY-MM-DD HH24:MI:SS.MS'), status, message FROM monitor_status WHERE server_name = $1 AND monitor_name = $2 AND log_time >= $3::text::timestamp AND log_time <= $4::text::timestamp AND ($5::text IS NULL OR status = $5) ORDER BY log_time DESC LIMIT $6 OFFSET $7", &[
+            &self.server_name,
+            &monitor_name,
+            &DbService::get_log_time_db_repr(&history_params.from_datetime),
+            &DbService::get_log_time_db_repr(&history_params.to_datetime),
+            &history_params.status,
+            &i64::from(history_params.get_limit()),
+            &i64::try_from(history_params.get_offset()).unwrap_or(i64::MAX),
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        rows.iter().map(PostgresDbService::get_monitor_status_element).collect()
+    }
+
+    /**
+     * Get the status changes of a monitor in a window, and the last status stored before the window.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `from_datetime`: The start of the window.
+     * `to_datetime`: The end of the window.
+     * 
+     * Returns: The last status before the window and the status changes ordered by time.
+     * 
+     * Errors:
+     * - If there is an error getting the status changes.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub async fn get_monitor_status_changes(&self, monitor_name: &str, from_datetime: &DateTime<Utc>, to_datetime: &DateTime<Utc>) -> Result<(Option<String>, Vec<MonitorStatusElement>), ApplicationError> {
+        let conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let from_datetime = DbService::get_log_time_db_repr(from_datetime);
+        let to_datetime = DbService::get_log_time_db_repr(to_datetime);
+        let initial_row = conn.query_opt("SELECT status FROM monitor_status WHERE server_name = $1 AND monitor_name = $2 AND log_time < $3::text::timestamp ORDER BY log_time DESC LIMIT 1", &[
+            &self.server_name,
+            &monitor_name,
+            &from_datetime,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let initial_status: Option<String> = match initial_row {
+            Some(row) => Some(PostgresDbService::get_column(&row, 0)?),
+            None => None,
+        };
+        let rows = conn.query("SELECT to_char(log_time, 'YYYY-MM-DD HH24:MI:SS.MS'), status, message FROM (SELECT log_time, status, message, LAG(status) OVER (ORDER BY log_time) AS previous_status FROM monitor_status WHERE server_name = $1 AND monitor_name = $2 AND log_time >= $3::text::timestamp AND log_time <= $4::text::timestamp) changes WHERE previous_status IS NULL OR previous_status <> status ORDER BY log_time", &[
+            &self.server_name,
+            &monitor_name,
+            &from_datetime,
+            &to_datetime,
+        ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let changes = rows.iter().map(PostgresDbService::get_monitor_status_element).collect::<Result<Vec<MonitorStatusElement>, ApplicationError>>()?;
+        Ok((initial_status, changes))
+    }
 
+    /**
+     * Map a row with the log time, status and message to a monitor status element.
+     * 
+     * `row`: The row.
+     * 
+     * Returns: The monitor status element.
+     * 
+     * Errors:
+     * - If a column could not be read.
+     */
+    fn get_monitor_status_element(row: &bb8_postgres::tokio_postgres::Row) -> Result<MonitorStatusElement, ApplicationError> {
+        let log_time: String = PostgresDbService::get_column(row, 0)?;
+        let status: String = PostgresDbService::get_column(row, 1)?;
+        Ok(MonitorStatusElement::new(DbService::get_log_time_from_db_repr(&log_time)?, &status, PostgresDbService::get_column(row, 2)?))
+    }
+
+    /**
+     * Delete old data.
+     * 
+     * `max_time_stored_db`: The maximum time to store data in the database in hours.
+     * 
+     * Returns: Ok if the data was deleted successfully.
+     * 
+     * Errors:
+     * - If there is an error deleting the data.
+     */
+    #[tracing::instrument(level = "debug")]
+    async fn delete_old_data(&self, max_time_stored_db: u32) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let max_time_stored_db = f64::from(max_time_stored_db);
+        for table in ["monitor_status", "loadavg", "meminfo", "diskinfo", "cpustat", "netdev", "statm", "response_time", "metrics"] {
+            tx.execute(&format!("DELETE FROM {table} WHERE log_time < (now() at time zone 'utc') - interval '1 hour' * $1 AND server_name = $2"), &[
+                &max_time_stored_db,
+                &self.server_name,
+            ]).await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().await.map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+}
+
+/**
+ * `Sqlite` Service.
+ * 
+ * This struct represents a `Sqlite` service. It is used to store the monitoring data in a local database file.
+ * The calls are blocking, so the `DbService` runs them on the blocking thread pool.
+ * 
+ * `pool`: The database connection pool.
+ * `server_name`: The server name.
+ */
+#[derive(Debug, Clone)]
+pub struct SqliteDbService {
+    /// The database connection pool.
+    pool: Pool<SqliteConnectionManager>,
+    /// Server name
+    server_name: String
+}
+
+impl SqliteDbService {
+
+    /**
+     * Create a new `Sqlite` service. The database file is created if it does not exist.
+     * 
+     * `database_config`: The database configuration.
+     * `server_name`: The server name.
+     * 
+     * Returns: A new `Sqlite` service.
+     * 
+     * Errors:
+     * - If the database configuration has no path.
+     * - If there is an error creating the pool.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn new(database_config: &DatabaseConfig, server_name: &str) -> Result<SqliteDbService, ApplicationError> {
+        let Some(path) = &database_config.path else {
+            return Err(ApplicationError::new("Sqlite database requires a path"));
+        };
+        /*
+         * Write ahead logging lets the api read while the monitors write. The busy timeout makes the
+         * connections wait for each other instead of failing.
+         */
+        let manager = SqliteConnectionManager::file(path).with_init(|conn| {
+            conn.busy_timeout(std::time::Duration::from_secs(5))?;
+            conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+        });
+        let pool = r2d2::Pool::builder()
+            .max_size(database_config.max_connections)
+            .max_lifetime(Some(std::time::Duration::from_secs(u64::from(database_config.max_lifetime))))
+            .min_idle(Some(database_config.min_connections))
+            .build(manager)
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(SqliteDbService {
+            pool,
+            server_name: server_name.to_string()
+        })
+    }
+
+    /**
+     * Run a function on the blocking thread pool so the tokio workers are not stalled by the database.
+     * 
+     * `function`: The function to run with the service.
+     * 
+     * Returns: The result of the function.
+     * 
+     * Errors:
+     * - If the function fails.
+     * - If the blocking task could not be completed.
+     */
+    pub async fn spawn_blocking<T, F>(&self, function: F) -> Result<T, ApplicationError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SqliteDbService) -> Result<T, ApplicationError> + Send + 'static,
+    {
+        let service = self.clone();
+        tokio::task::spawn_blocking(move || function(&service))
+            .await
+            .map_err(|err| ApplicationError::new(&format!("Error running sqlite task: {err:?}")))?
+    }
+
+    /**
+     * Apply the schema migrations not yet applied. The migrations are applied in one immediate
+     * transaction, so several agents can not migrate the same database file at the same time.
+     * 
+     * Returns: The schema version after the migrations are applied.
+     * 
+     * Errors:
+     * - If there is an error applying a migration.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn migrate(&self) -> Result<i32, ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        tx.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, description TEXT NOT NULL, applied_time TEXT NOT NULL)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut version: i32 = tx.query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for migration in Migration::get_pending(SQLITE_MIGRATIONS, version) {
+            info!("Applying database migration {}: {}", migration.version, migration.description);
+            tx.execute_batch(migration.sql).map_err(|err| ApplicationError::new(&format!("Error applying migration {}: {err}", migration.version)))?;
+            tx.execute("INSERT INTO schema_version (version, description, applied_time) VALUES (?1, ?2, strftime('%Y-%m-%d %H:%M:%f', 'now'))", rusqlite::params![
+                migration.version,
+                migration.description,
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+            version = migration.version;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(version)
+    }
+
+    /**
+     * Run a trivial query to check that the database answers.
+     *
+     * Errors:
+     * - If the database does not answer.
+     */
+    pub fn ping(&self) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0)).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store a record in the database.
+     *
+     * `record`: The record to store.
+     *
+     * Errors:
+     * - If there is an error storing the record.
+     */
+    pub fn store_record(&self, record: &SpoolRecord) -> Result<(), ApplicationError> {
+        match record {
+            SpoolRecord::MonitorStatus { log_time, name, status } => self.insert_monitor_status(log_time, name, status),
+            SpoolRecord::Loadavg { log_time, loadavg } => self.store_loadavg(log_time, loadavg),
+            SpoolRecord::Meminfo { log_time, meminfo } => self.store_meminfo(log_time, meminfo),
+            SpoolRecord::Diskinfo { log_time, disk_usage } => self.store_diskinfo(log_time, disk_usage),
+            SpoolRecord::Cpustat { log_time, cpu_usage } => self.store_cpustat(log_time, cpu_usage),
+            SpoolRecord::Network { log_time, rates } => self.store_network(log_time, rates),
+            SpoolRecord::Statm { log_time, app_name, pid, statm } => self.store_statm_values(log_time, app_name, pid, statm),
+            SpoolRecord::ResponseTime { log_time, name, response_time } => self.store_response_time(log_time, name, response_time),
+            SpoolRecord::Metrics { log_time, name, metrics } => self.store_metrics(log_time, name, metrics),
+        }
+    }
+
+    /**
+     * Insert a monitor status into the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `status`: The status of the monitor.
+     * 
+     * Returns: Ok if the status was inserted successfully.
+     * 
+     * Errors:
+     * - If there is an error inserting the status.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn insert_monitor_status(&self, log_time: &DateTime<Utc>, name: &str, status: &Status) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO monitor_status (server_name, monitor_name, status, log_time, message) VALUES (?1, ?2, ?3, ?5, ?4)", rusqlite::params![
+            self.server_name,
+            name,
+            DbService::get_status_db_repr(status),
+            DbService::get_message(status),
+            DbService::get_log_time_db_repr(log_time),
+        ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the load average in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `loadavg`: The load average to store.
+     * 
+     * Returns: Ok if the load average was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the load average.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_loadavg(&self, log_time: &DateTime<Utc>, loadavg: &ProcsLoadavg) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO loadavg (server_name, loadavg1min, loadavg5min, loadavg15min, num_processes, num_running_processes, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", rusqlite::params![
+            self.server_name,
+            loadavg.loadavg1min.map(f64::from),
+            loadavg.loadavg5min.map(f64::from),
+            loadavg.loadavg15min.map(f64::from),
+            loadavg.total_number_of_processes,
+            loadavg.current_running_processes,
+            DbService::get_log_time_db_repr(log_time),
+        ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the meminfo in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `meminfo`: The meminfo to store.
+     * 
+     * Returns: Ok if the meminfo was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the meminfo.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_meminfo(&self, log_time: &DateTime<Utc>, meminfo: &ProcsMeminfo) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO meminfo (server_name, freemem, mem_percent_used, freeswap, swap_percent_used, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", rusqlite::params![
+            self.server_name,
+            meminfo.memfree.and_then(|x| i64::try_from(x).ok()),
+            ProcsMeminfo::get_percent_used(meminfo.memfree, meminfo.memtotal),
+            meminfo.swapfree.and_then(|x| i64::try_from(x).ok()),
+            ProcsMeminfo::get_percent_used(meminfo.swapfree, meminfo.swaptotal),
+            DbService::get_log_time_db_repr(log_time),
+        ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the disk use in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `disk_usage`: The disk use of the mount points to store.
+     * 
+     * Returns: Ok if the disk use was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the disk use.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_diskinfo(&self, log_time: &DateTime<Utc>, disk_usage: &[ProcsDiskUsage]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for usage in disk_usage {
+            tx.execute("INSERT INTO diskinfo (server_name, mount_point, freespace, space_percent_used, freeinodes, inodes_percent_used, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)", rusqlite::params![
+                self.server_name,
+                usage.mount_point,
+                i64::try_from(usage.available_bytes).ok(),
+                usage.get_percent_space_used(),
+                i64::try_from(usage.free_inodes).ok(),
+                usage.get_percent_inodes_used(),
+                DbService::get_log_time_db_repr(log_time),
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the cpu utilisation in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `cpu_usage`: The cpu utilisation of the cpus to store.
+     * 
+     * Returns: Ok if the cpu utilisation was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the cpu utilisation.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_cpustat(&self, log_time: &DateTime<Utc>, cpu_usage: &[ProcCpuUsage]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for usage in cpu_usage {
+            tx.execute("INSERT INTO cpustat (server_name, cpu_name, user_percent, system_percent, iowait_percent, steal_percent, total_percent, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)", rusqlite::params![
+                self.server_name,
+                usage.name,
+                usage.user,
+                usage.system,
+                usage.iowait,
+                usage.steal,
+                usage.total,
+                DbService::get_log_time_db_repr(log_time),
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the network throughput in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `rates`: The throughput of the interfaces to store.
+     * 
+     * Returns: Ok if the network throughput was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the network throughput.
+     * - If there is an error starting a transaction.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn store_network(&self, log_time: &DateTime<Utc>, rates: &[ProcsNetDevRate]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for rate in rates {
+            tx.execute("INSERT INTO netdev (server_name, interface, rx_bytes_per_second, tx_bytes_per_second, rx_errors, tx_errors, rx_drop, tx_drop, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", rusqlite::params![
+                self.server_name,
+                rate.interface,
+                rate.rx_bytes_per_second,
+                rate.tx_bytes_per_second,
+                i64::try_from(rate.rx_errors).ok(),
+                i64::try_from(rate.tx_errors).ok(),
+                i64::try_from(rate.rx_drop).ok(),
+                i64::try_from(rate.tx_drop).ok(),
+                DbService::get_log_time_db_repr(log_time),
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Query long running queries. `Sqlite` runs inside the calling process and has no server
+     * processes to inspect, so there are never long running queries to report.
+     * 
+     * `max_query_time`: The maximum query time.
+     * 
+     * Returns: An empty list.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn query_long_running_queries(&self, _max_query_time: u32) -> Result<Vec<String>, ApplicationError> {
+        Ok(Vec::new())
+    }
+
+    /**
+     * Store the statm values in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `app_name`: The application name.
+     * `pid`: The process id.
+     * `statm`: The statm values.
+     * 
+     * Returns: Ok if the statm values were stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the statm values.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_statm_values(&self, log_time: &DateTime<Utc>, app_name: &str, pid: &u32, statm: &ProcsStatm) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO statm (server_name, app_name, log_time, pid, total, resident, share, trs, drs, lrs, dt, pagesize) VALUES 
+                    (?1, ?2, ?12, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)", rusqlite::params![
+            self.server_name,
+            app_name,
+            pid,
+            statm.size,
+            statm.resident,
+            statm.share,
+            statm.trs,
+            statm.drs,
+            statm.lrs,
+            statm.dt,
+            statm.pagesize,
+            DbService::get_log_time_db_repr(log_time),
+        ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the response time of a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `response_time`: The response time.
+     * 
+     * Returns: Ok if the response time was stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the response time.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_response_time(&self, log_time: &DateTime<Utc>, name: &str, response_time: &ResponseTime) -> Result<(), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        conn.execute("INSERT INTO response_time (server_name, monitor_name, connect_time, first_byte_time, total_time, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", rusqlite::params![
+            self.server_name,
+            name,
+            response_time.connect_time,
+            response_time.first_byte_time,
+            response_time.total_time,
+            DbService::get_log_time_db_repr(log_time),
+        ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Store the metrics reported by a monitor in the database.
+     * 
+     * `log_time`: The time the values were measured.
+     * `name`: The name of the monitor.
+     * `metrics`: The metrics.
+     * 
+     * Returns: Ok if the metrics were stored successfully.
+     * 
+     * Errors:
+     * - If there is an error storing the metrics.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn store_metrics(&self, log_time: &DateTime<Utc>, name: &str, metrics: &[Metric]) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        for metric in metrics {
+            tx.execute("INSERT INTO metrics (server_name, monitor_name, label, value, unit, warn, crit, min_value, max_value, log_time) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)", rusqlite::params![
+                self.server_name,
+                name,
+                metric.label,
+                metric.value,
+                metric.unit,
+                metric.warn,
+                metric.crit,
+                metric.min,
+                metric.max,
+                DbService::get_log_time_db_repr(log_time),
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+
+    /**
+     * Get the query parameters used by the historical queries.
+     * 
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The from date time, the to date time and the split in seconds.
+     */
+    fn get_historical_query_params(historical_params: &HistoricalParams) -> (String, String, i64) {
+        (
+            historical_params.from_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            historical_params.to_datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+            i64::from(historical_params.split) * 60,
+        )
+    }
+
+    /**
+     * Parse the log time returned by the historical queries.
+     * 
+     * `log_time`: The log time formatted as YYYY-MM-DD HH:MM:SS.
+     * 
+     * Returns: The log time.
+     * 
+     * Errors:
+     * - If the log time could not be parsed.
+     */
+    fn get_log_time(log_time: &str) -> Result<DateTime<Utc>, ApplicationError> {
+        NaiveDateTime::parse_from_str(log_time, "%Y-%m-%d %H:%M:%S")
+            .map(|log_time| log_time.and_utc())
+            .map_err(|err| ApplicationError::new(&err.to_string()))
+    }
+
+    /**
+     * Get the historical load average.
+     * 
+     * Returns: The historical load average.
+     * 
+     * Errors:
+     * - If there is an error getting the historical load average.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_loadavg(&self, historical_params: HistoricalParams) -> Result<Vec<LoadavgElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), avg(loadavg1min), avg(loadavg5min), avg(loadavg15min) FROM loadavg WHERE server_name = ?1 and log_time >= ?2 and log_time <= ?3 GROUP BY CAST(strftime('%s', log_time) AS INTEGER) / ?4 ORDER BY min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, Option<f64>>(1)?, row.get::<usize, Option<f64>>(2)?, row.get::<usize, Option<f64>>(3)?))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut loadavg_elements: Vec<LoadavgElement> = Vec::new();
+        for row in rows {
+            let (log_time, loadavg1min, loadavg5min, loadavg15min) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            loadavg_elements.push(LoadavgElement::new(SqliteDbService::get_log_time(&log_time)?, loadavg1min.unwrap_or_default(), loadavg5min.unwrap_or_default(), loadavg15min.unwrap_or_default()));
+        }
+        Ok(loadavg_elements)
+    }
+
+    /**
+     * Get the historical memory use.
+     * 
+     * Returns: The historical memory use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical memory use.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_meminfo(&self, historical_params: HistoricalParams) -> Result<Vec<MeminfoElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), CAST(round(avg(freemem)) AS INTEGER) FROM meminfo WHERE server_name = ?1 and log_time >= ?2 and log_time <= ?3 GROUP BY CAST(strftime('%s', log_time) AS INTEGER) / ?4 ORDER BY min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, Option<i64>>(1)?))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut freemem_elements: Vec<MeminfoElement> = Vec::new();
+        for row in rows {
+            let (log_time, freemem) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            freemem_elements.push(MeminfoElement::new(SqliteDbService::get_log_time(&log_time)?, freemem.and_then(|freemem| u64::try_from(freemem).ok()).unwrap_or_default()));
+        }
+        Ok(freemem_elements)
+    }
+
+    /**
+     * Get the historical disk use.
+     * 
+     * Returns: The historical disk use.
+     * 
+     * Errors:
+     * - If there is an error getting the historical disk use.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_diskinfo(&self, historical_params: HistoricalParams) -> Result<Vec<DiskinfoElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), mount_point, avg(space_percent_used), avg(inodes_percent_used) FROM diskinfo WHERE server_name = ?1 and log_time >= ?2 and log_time <= ?3 GROUP BY mount_point, CAST(strftime('%s', log_time) AS INTEGER) / ?4 ORDER BY mount_point, min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?, row.get::<usize, Option<f64>>(2)?, row.get::<usize, Option<f64>>(3)?))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut diskinfo_elements: Vec<DiskinfoElement> = Vec::new();
+        for row in rows {
+            let (log_time, mount_point, space_percent_used, inodes_percent_used) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            diskinfo_elements.push(DiskinfoElement::new(SqliteDbService::get_log_time(&log_time)?, &mount_point, space_percent_used, inodes_percent_used));
+        }
+        Ok(diskinfo_elements)
+    }
+
+    /**
+     * Get the historical cpu utilisation.
+     * 
+     * Returns: The historical cpu utilisation.
+     * 
+     * Errors:
+     * - If there is an error getting the historical cpu utilisation.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_cpustat(&self, historical_params: HistoricalParams) -> Result<Vec<CpustatElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), cpu_name, avg(user_percent), avg(system_percent), avg(iowait_percent), avg(steal_percent), avg(total_percent) FROM cpustat WHERE server_name = ?1 and log_time >= ?2 and log_time <= ?3 GROUP BY cpu_name, CAST(strftime('%s', log_time) AS INTEGER) / ?4 ORDER BY cpu_name, min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, Option<String>>(1)?, [row.get::<usize, Option<f64>>(2)?, row.get::<usize, Option<f64>>(3)?, row.get::<usize, Option<f64>>(4)?, row.get::<usize, Option<f64>>(5)?, row.get::<usize, Option<f64>>(6)?]))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut cpustat_elements: Vec<CpustatElement> = Vec::new();
+        for row in rows {
+            let (log_time, cpu_name, [user, system, iowait, steal, total]) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            cpustat_elements.push(CpustatElement::new(SqliteDbService::get_log_time(&log_time)?, &cpu_name.unwrap_or_default(), user, system, iowait, steal, total));
+        }
+        Ok(cpustat_elements)
+    }
+
+    /**
+     * Get the historical network throughput.
+     * 
+     * Returns: The historical network throughput.
+     * 
+     * Errors:
+     * - If there is an error getting the historical network throughput.
+     */
+    #[tracing::instrument(level = "debug")]
+    #[allow(clippy::type_complexity)]
+    pub fn get_historical_network(&self, historical_params: HistoricalParams) -> Result<Vec<NetworkElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), interface, avg(rx_bytes_per_second), avg(tx_bytes_per_second), sum(rx_errors + tx_errors), sum(rx_drop + tx_drop) FROM netdev WHERE server_name = ?1 and log_time >= ?2 and log_time <= ?3 GROUP BY interface, CAST(strftime('%s', log_time) AS INTEGER) / ?4 ORDER BY interface, min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, String>(1)?, row.get::<usize, Option<f64>>(2)?, row.get::<usize, Option<f64>>(3)?, row.get::<usize, Option<i64>>(4)?, row.get::<usize, Option<i64>>(5)?))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut network_elements: Vec<NetworkElement> = Vec::new();
+        for row in rows {
+            let (log_time, interface, rx_bytes_per_second, tx_bytes_per_second, errors, drops) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            network_elements.push(NetworkElement::new(SqliteDbService::get_log_time(&log_time)?, &interface, rx_bytes_per_second, tx_bytes_per_second,
+                errors.and_then(|errors| u64::try_from(errors).ok()),
+                drops.and_then(|drops| u64::try_from(drops).ok())));
+        }
+        Ok(network_elements)
+    }
+
+    /**
+     * Get the process memory use.
+     * 
+     * `pid`: The process id.
+     * `historical_params`: The historical parameters.
+     * 
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_process_memory_use(&self, pid: &u32, historical_params: HistoricalParams) -> Result<Vec<ProcessMemoryElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(&historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), CAST(round(avg(resident)) AS INTEGER), CAST(round(avg(share)) AS INTEGER), CAST(round(avg(trs)) AS INTEGER), CAST(round(avg(drs)) AS INTEGER), CAST(round(avg(lrs)) AS INTEGER), CAST(round(avg(dt)) AS INTEGER), CAST(round(avg(pagesize)) AS INTEGER) FROM statm WHERE server_name = ?1 and pid = ?2 and log_time >= ?3 and log_time <= ?4 GROUP BY CAST(strftime('%s', log_time) AS INTEGER) / ?5 ORDER BY min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, pid, from_datetime, to_datetime, split], |row| {
+            let mut values: Vec<Option<u64>> = Vec::new();
+            for index in 1..=7 {
+                let value: Option<i64> = row.get(index)?;
+                values.push(value.and_then(|value| u64::try_from(value).ok()));
+            }
+            Ok((row.get::<usize, String>(0)?, values))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<ProcessMemoryElement> = Vec::new();
+        for row in rows {
+            let (log_time, values) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            let pagesize = values[6].unwrap_or(4096);
+            elements.push(ProcessMemoryElement::new(SqliteDbService::get_log_time(&log_time)?,
+                values[0].map(|f| f * pagesize),
+                values[1].map(|f| f * pagesize),
+                values[2].map(|f| f * pagesize),
+                values[3].map(|f| f * pagesize),
+                values[4].map(|f| f * pagesize),
+                values[5].map(|f| f * pagesize)));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the historical response time of a monitor.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `historical_params`: The historical parameters.
+     * 
+     * Returns: The average response time in each split.
+     * 
+     * Errors:
+     * - If there is an error getting the historical response time.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_historical_response_time(&self, monitor_name: &str, historical_params: &HistoricalParams) -> Result<Vec<ResponseTimeElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let (from_datetime, to_datetime, split) = SqliteDbService::get_historical_query_params(historical_params);
+        let mut statement = conn.prepare("SELECT strftime('%Y-%m-%d %H:%M:%S', min(log_time)), avg(connect_time), avg(first_byte_time), avg(total_time) FROM response_time WHERE server_name = ?1 and monitor_name = ?2 and log_time >= ?3 and log_time <= ?4 GROUP BY CAST(strftime('%s', log_time) AS INTEGER) / ?5 ORDER BY min(log_time)")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, monitor_name, from_datetime, to_datetime, split], |row| {
+            Ok((row.get::<usize, String>(0)?, row.get::<usize, Option<f64>>(1)?, row.get::<usize, Option<f64>>(2)?, row.get::<usize, Option<f64>>(3)?))
+        }).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<ResponseTimeElement> = Vec::new();
+        for row in rows {
+            let (log_time, connect_time, first_byte_time, total_time) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            elements.push(ResponseTimeElement::new(SqliteDbService::get_log_time(&log_time)?, connect_time, first_byte_time, total_time));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the stored statuses of a monitor, newest first.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `history_params`: The time window, status filter and page.
+     * 
+     * Returns: The statuses.
+     * 
+     * Errors:
+     * - If there is an error getting the statuses.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_monitor_status_history(&self, monitor_name: &str, history_params: &MonitorHistoryParams) -> Result<Vec<MonitorStatusElement>, ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut statement = conn.prepare("SELECT log_time, status, message FROM monitor_status WHERE server_name = ?1 AND monitor_name = ?2 AND log_time >= ?3 AND log_time <= ?4 AND (?5 IS NULL OR status = ?5) ORDER BY log_time DESC LIMIT ?6 OFFSET ?7")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![
+            self.server_name,
+            monitor_name,
+            DbService::get_log_time_db_repr(&history_params.from_datetime),
+            DbService::get_log_time_db_repr(&history_params.to_datetime),
+            history_params.status,
+            history_params.get_limit(),
+            history_params.get_offset(),
+        ], SqliteDbService::get_monitor_status_row).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut elements: Vec<MonitorStatusElement> = Vec::new();
+        for row in rows {
+            let (log_time, status, message) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            elements.push(MonitorStatusElement::new(DbService::get_log_time_from_db_repr(&log_time)?, &status, message));
+        }
+        Ok(elements)
+    }
+
+    /**
+     * Get the status changes of a monitor in a window, and the last status stored before the window.
+     * 
+     * `monitor_name`: The name of the monitor.
+     * `from_datetime`: The start of the window.
+     * `to_datetime`: The end of the window.
+     * 
+     * Returns: The last status before the window and the status changes ordered by time.
+     * 
+     * Errors:
+     * - If there is an error getting the status changes.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_monitor_status_changes(&self, monitor_name: &str, from_datetime: &DateTime<Utc>, to_datetime: &DateTime<Utc>) -> Result<(Option<String>, Vec<MonitorStatusElement>), ApplicationError> {
+        let conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let from_datetime = DbService::get_log_time_db_repr(from_datetime);
+        let to_datetime = DbService::get_log_time_db_repr(to_datetime);
+        let initial_status: Option<String> = conn.query_row("SELECT status FROM monitor_status WHERE server_name = ?1 AND monitor_name = ?2 AND log_time < ?3 ORDER BY log_time DESC LIMIT 1", rusqlite::params![
+            self.server_name,
+            monitor_name,
+            from_datetime,
+        ], |row| row.get(0)).optional().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut statement = conn.prepare("SELECT log_time, status, message FROM (SELECT log_time, status, message, LAG(status) OVER (ORDER BY log_time) AS previous_status FROM monitor_status WHERE server_name = ?1 AND monitor_name = ?2 AND log_time >= ?3 AND log_time <= ?4) changes WHERE previous_status IS NULL OR previous_status <> status ORDER BY log_time")
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let rows = statement.query_map(rusqlite::params![self.server_name, monitor_name, from_datetime, to_datetime], SqliteDbService::get_monitor_status_row)
+            .map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let mut changes: Vec<MonitorStatusElement> = Vec::new();
+        for row in rows {
+            let (log_time, status, message) = row.map_err(|err| ApplicationError::new(&err.to_string()))?;
+            changes.push(MonitorStatusElement::new(DbService::get_log_time_from_db_repr(&log_time)?, &status, message));
+        }
+        Ok((initial_status, changes))
+    }
+
+    /**
+     * Read a row with the log time, status and message.
+     * 
+     * `row`: The row.
+     * 
+     * Returns: The log time, status and message.
+     */
+    fn get_monitor_status_row(row: &rusqlite::Row) -> rusqlite::Result<(String, String, Option<String>)> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    }
+
+    /**
+     * Delete old data.
+     * 
+     * `max_time_stored_db`: The maximum time to store data in the database in hours.
+     * 
+     * Returns: Ok if the data was deleted successfully.
+     * 
+     * Errors:
+     * - If there is an error deleting the data.
+     */
+    #[tracing::instrument(level = "debug")]
+    fn delete_old_data(&self, max_time_stored_db: u32) -> Result<(), ApplicationError> {
+        let mut conn = self.pool.get().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let tx = conn.transaction().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        let modifier = format!("-{max_time_stored_db} hours");
+        for table in ["monitor_status", "loadavg", "meminfo", "diskinfo", "cpustat", "netdev", "statm", "response_time", "metrics"] {
+            tx.execute(&format!("DELETE FROM {table} WHERE log_time < strftime('%Y-%m-%d %H:%M:%f', 'now', ?1) AND server_name = ?2"), rusqlite::params![
+                modifier,
+                self.server_name,
+            ]).map_err(|err| ApplicationError::new(&err.to_string()))?;
+        }
+        tx.commit().map_err(|err| ApplicationError::new(&err.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use monitoring_agent_lib::proc::ProcsNetDevRate;
+
+    use crate::common::configuration::SpoolConfig;
+
+    use super::*;
+
+    /**
+     * Create a `Postgres` service against the local test database and apply the migrations.
+     * The server name is unique for the test so the tests can run in parallel.
+     */
+    async fn get_postgres_service(test_name: &str) -> PostgresDbService {
+        let database_config = DatabaseConfig {
+            dbtype: DatabaseType::Postgres,
+            host: "localhost".to_string(),
+            db_name: "test".to_string(),
+            user: "root".to_string(),
+            password: "root".to_string(),
+            port: 5432,
+            min_connections: 1,
+            max_connections: 2,
+            max_lifetime: 60,
+            path: None,
+            spool: get_spool_config(None),
+        };
+        let service = PostgresDbService::new(&database_config, &format!("{test_name}-{}", std::process::id())).await.unwrap();
+        service.migrate().await.unwrap();
+        service
+    }
+
+    /**
+     * Create a `Sqlite` database configuration using a new database file in the temp directory.
+     */
+    fn get_sqlite_config(test_name: &str, spool_path: Option<&std::path::Path>) -> DatabaseConfig {
+        let path = std::env::temp_dir().join(format!("monitoring-agent-{test_name}-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        DatabaseConfig {
+            dbtype: DatabaseType::Sqlite,
+            host: String::new(),
+            db_name: String::new(),
+            user: String::new(),
+            password: String::new(),
+            port: 0,
+            min_connections: 1,
+            max_connections: 2,
+            max_lifetime: 60,
+            path: Some(path.to_string_lossy().to_string()),
+            spool: get_spool_config(spool_path),
+        }
+    }
+
+    /**
+     * Create a `Sqlite` database service using a new database file in the temp directory.
+     */
+    async fn get_sqlite_service(test_name: &str) -> DbService {
+        let service = DbService::new_unconnected(&get_sqlite_config(test_name, None), test_name);
+        service.try_connect().await.unwrap();
+        service
+    }
+
+    /**
+     * Create a spool configuration. The spool is disabled if no path is given.
+     */
+    fn get_spool_config(path: Option<&std::path::Path>) -> SpoolConfig {
+        SpoolConfig {
+            enabled: path.is_some(),
+            path: path.map(|path| path.to_string_lossy().to_string()).unwrap_or_default(),
+            max_size: 1024 * 1024,
+            replay_schedule: "30 * * * * *".to_string(),
+        }
+    }
+
+    fn get_historical_params() -> HistoricalParams {
+        HistoricalParams {
+            from_datetime: chrono::Utc::now() - chrono::Duration::days(1),
+            to_datetime: chrono::Utc::now() + chrono::Duration::days(1),
+            split: 60,
+        }
+    }
+
+    fn get_monitor_history_params(status: Option<&str>, page: u32, page_size: u32) -> MonitorHistoryParams {
+        MonitorHistoryParams {
+            from_datetime: chrono::Utc::now() - chrono::Duration::days(1),
+            to_datetime: chrono::Utc::now() + chrono::Duration::days(1),
+            status: status.map(str::to_string),
+            page,
+            page_size,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_migrate() {
+        let service = get_postgres_service("migrate").await;
+        let latest_version = POSTGRES_MIGRATIONS[POSTGRES_MIGRATIONS.len() - 1].version;
+        assert_eq!(service.migrate().await.unwrap(), latest_version);
+        let conn = service.pool.get().await.unwrap();
+        let row = conn.query_one("SELECT count(*) FROM schema_version WHERE version = $1", &[&latest_version]).await.unwrap();
+        assert_eq!(row.get::<usize, i64>(0), 1);
+        let row = conn.query_one("SELECT count(*) FROM pg_indexes WHERE indexname = 'idx_monitor_status_server_name_log_time'", &[]).await.unwrap();
+        assert_eq!(row.get::<usize, i64>(0), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_historical_loadavg_and_meminfo() {
+        let service = get_postgres_service("loadavg").await;
+        service.store_loadavg(&Utc::now(), &ProcsLoadavg::new(Some(1.0), Some(2.0), Some(3.0), Some(1), Some(100))).await.unwrap();
+        service.store_loadavg(&Utc::now(), &ProcsLoadavg::new(Some(3.0), Some(2.0), Some(1.0), Some(1), Some(100))).await.unwrap();
+        service.store_meminfo(&Utc::now(), &ProcsMeminfo::new(Some(2000), Some(1000), Some(1000), Some(0), Some(0))).await.unwrap();
+        service.store_meminfo(&Utc::now(), &ProcsMeminfo::new(Some(2000), Some(500), Some(500), Some(0), Some(0))).await.unwrap();
+        let loadavg = service.get_historical_loadavg(get_historical_params()).await.unwrap();
+        assert_eq!(loadavg.len(), 1);
+        assert!((loadavg[0].loadavg1min - 2.0).abs() < f64::EPSILON);
+        assert!((loadavg[0].loadavg15min - 2.0).abs() < f64::EPSILON);
+        let meminfo = service.get_historical_meminfo(get_historical_params()).await.unwrap();
+        assert_eq!(meminfo.len(), 1);
+        assert_eq!(meminfo[0].freemem, 750);
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_loadavg(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_meminfo(get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_historical_diskinfo_cpustat_and_network() {
+        let service = get_postgres_service("disk").await;
+        let root_usage = ProcsDiskUsage::new("/", 1000, 200, 250, 100, 50);
+        service.store_diskinfo(&Utc::now(), &[root_usage.clone(), ProcsDiskUsage::new("/home", 1000, 500, 500, 100, 100)]).await.unwrap();
+        service.store_cpustat(&Utc::now(), &[ProcCpuUsage::new(Some("cpu"), 10.0, 5.0, 1.0, 0.0, 84.0, 15.0)]).await.unwrap();
+        service.store_network(&Utc::now(), &[ProcsNetDevRate::new("eth0", 100.0, 50.0, 1, 2, 3, 4, None)]).await.unwrap();
+        let diskinfo = service.get_historical_diskinfo(get_historical_params()).await.unwrap();
+        assert_eq!(diskinfo.len(), 2);
+        assert_eq!(diskinfo[0].mount_point, "/");
+        assert!((diskinfo[0].space_percent_used.unwrap() - root_usage.get_percent_space_used().unwrap()).abs() < 0.0001);
+        assert!((diskinfo[0].inodes_percent_used.unwrap() - root_usage.get_percent_inodes_used().unwrap()).abs() < 0.0001);
+        assert_eq!(diskinfo[1].mount_point, "/home");
+        let cpustat = service.get_historical_cpustat(get_historical_params()).await.unwrap();
+        assert_eq!(cpustat.len(), 1);
+        assert_eq!(cpustat[0].cpu_name, "cpu");
+        assert_eq!(cpustat[0].total, Some(15.0));
+        let network = service.get_historical_network(get_historical_params()).await.unwrap();
+        assert_eq!(network.len(), 1);
+        assert_eq!(network[0].interface, "eth0");
+        assert_eq!(network[0].rx_bytes_per_second, Some(100.0));
+        assert_eq!(network[0].errors, Some(3));
+        assert_eq!(network[0].drops, Some(7));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_diskinfo(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_cpustat(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_network(get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_process_memory_use() {
+        let service = get_postgres_service("statm").await;
+        let statm = ProcsStatm::new(&Some(30), &Some(20), &Some(10), &Some(1), &Some(2), &Some(0), &Some(0), &4096);
+        service.store_statm_values(&Utc::now(), "app", &1, &statm).await.unwrap();
+        service.store_statm_values(&Utc::now(), "app", &2, &statm).await.unwrap();
+        let elements = service.get_process_memory_use(&1, get_historical_params()).await.unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].resident, Some(20 * 4096));
+        assert_eq!(elements[0].share, Some(10 * 4096));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_process_memory_use(&1, get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_monitor_status_history_and_changes() {
+        let service = get_postgres_service("monitor-history").await;
+        let start = Utc::now() - chrono::Duration::hours(4);
+        service.insert_monitor_status(&(start - chrono::Duration::hours(1)), "monitor", &Status::Ok).await.unwrap();
+        service.insert_monitor_status(&(start + chrono::Duration::hours(1)), "monitor", &Status::Error { message: "Error".to_string() }).await.unwrap();
+        service.insert_monitor_status(&(start + chrono::Duration::hours(2)), "monitor", &Status::Error { message: "Error".to_string() }).await.unwrap();
+        service.insert_monitor_status(&(start + chrono::Duration::hours(3)), "monitor", &Status::Ok).await.unwrap();
+        let history = service.get_monitor_status_history("monitor", &get_monitor_history_params(Some("ERROR"), 0, 1)).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].timestamp.timestamp(), (start + chrono::Duration::hours(2)).timestamp());
+        assert_eq!(history[0].message, Some("Error".to_string()));
+        let (initial_status, changes) = service.get_monitor_status_changes("monitor", &start, &Utc::now()).await.unwrap();
+        assert_eq!(initial_status, Some("OK".to_string()));
+        assert_eq!(changes.iter().map(|change| change.status.as_str()).collect::<Vec<&str>>(), vec!["ERROR", "OK"]);
+        service.delete_old_data(0).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_response_time() {
+        let service = get_postgres_service("response-time").await;
+        let log_time = Utc::now();
+        service.store_response_time(&log_time, "monitor", &ResponseTime::new(Some(10.0), Some(40.0), 50.0)).await.unwrap();
+        service.store_response_time(&log_time, "monitor", &ResponseTime::new(Some(20.0), Some(60.0), 70.0)).await.unwrap();
+        service.store_response_time(&log_time, "other", &ResponseTime::new(None, None, 1000.0)).await.unwrap();
+        let elements = service.get_historical_response_time("monitor", &get_historical_params()).await.unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].connect_time, Some(15.0));
+        assert_eq!(elements[0].first_byte_time, Some(50.0));
+        assert_eq!(elements[0].total_time, Some(60.0));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_response_time("monitor", &get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires a local Postgres database."]
+    async fn test_postgres_metrics() {
+        let service = get_postgres_service("metrics").await;
+        let metrics = Metric::parse_perfdata("time=0.5s;1;2;0 'free space'=45%;20:;10:;0;100");
+        service.store_metrics(&Utc::now(), "monitor", &metrics).await.unwrap();
+        let conn = service.pool.get().await.unwrap();
+        let rows = conn.query("SELECT label, value::float8, unit, warn, crit, min_value::float8, max_value::float8 FROM metrics WHERE server_name = $1 ORDER BY label", &[&service.server_name]).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get::<usize, String>(0), "free space");
+        assert_eq!(rows[0].get::<usize, f64>(1), 45.0);
+        assert_eq!(rows[0].get::<usize, Option<String>>(3), Some("20:".to_string()));
+        assert_eq!(rows[0].get::<usize, Option<f64>>(6), Some(100.0));
+        assert_eq!(rows[1].get::<usize, Option<String>>(2), Some("s".to_string()));
+        assert_eq!(rows[1].get::<usize, Option<f64>>(6), None);
+        drop(conn);
+        service.delete_old_data(0).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_requires_path() {
+        let database_config = DatabaseConfig {
+            dbtype: DatabaseType::Sqlite,
+            host: String::new(),
+            db_name: String::new(),
+            user: String::new(),
+            password: String::new(),
+            port: 0,
+            min_connections: 1,
+            max_connections: 2,
+            max_lifetime: 60,
+            path: None,
+            spool: get_spool_config(None),
+        };
+        assert!(DbService::new_unconnected(&database_config, "test").try_connect().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_not_connected_without_spool() {
+        let service = DbService::new_unconnected(&get_sqlite_config("sqlite-unconnected", None), "test");
+        assert!(service.store_loadavg(&ProcsLoadavg::new(Some(1.0), Some(2.0), Some(3.0), Some(1), Some(100))).await.is_err());
+        assert!(service.get_historical_loadavg(get_historical_params()).await.is_err());
+        assert_eq!(service.replay_spool().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_spool_and_replay() {
+        let spool_path = std::env::temp_dir().join(format!("monitoring-agent-sqlite-spool-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&spool_path);
+        let service = DbService::new_unconnected(&get_sqlite_config("sqlite-spool", Some(&spool_path)), "test");
+        let measured_time = Utc::now() - chrono::Duration::minutes(10);
+        service.store(SpoolRecord::Loadavg { log_time: measured_time, loadavg: ProcsLoadavg::new(Some(1.0), Some(2.0), Some(3.0), Some(1), Some(100)) }).await.unwrap();
+        service.insert_monitor_status("monitor", &Status::Ok).await.unwrap();
+        assert!(spool_path.exists());
+        assert_eq!(service.replay_spool().await.unwrap(), 0);
+        service.try_connect().await.unwrap();
+        assert_eq!(service.replay_spool().await.unwrap(), 2);
+        assert!(!spool_path.exists());
+        let loadavg = service.get_historical_loadavg(get_historical_params()).await.unwrap();
+        assert_eq!(loadavg.len(), 1);
+        assert_eq!(loadavg[0].timestamp.timestamp(), measured_time.timestamp());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_rejected_write_is_not_spooled() {
+        let spool_path = std::env::temp_dir().join(format!("monitoring-agent-sqlite-rejected-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&spool_path);
+        let service = DbService::new_unconnected(&get_sqlite_config("sqlite-rejected", Some(&spool_path)), "test");
+        service.try_connect().await.unwrap();
+        let Ok(DbBackend::Sqlite(sqlite_service)) = service.get_backend() else {
+            panic!("Not a sqlite backend");
+        };
+        sqlite_service.pool.get().unwrap().execute_batch("DROP TABLE loadavg").unwrap();
+        assert!(service.store_loadavg(&ProcsLoadavg::new(Some(1.0), Some(2.0), Some(3.0), Some(1), Some(100))).await.is_err());
+        assert!(!spool_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_migrate() {
+        let service = get_sqlite_service("sqlite-migrate").await;
+        let latest_version = SQLITE_MIGRATIONS[SQLITE_MIGRATIONS.len() - 1].version;
+        assert_eq!(service.migrate().await.unwrap(), latest_version);
+        assert!(service.query_long_running_queries(0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_monitor_status_loadavg_and_meminfo() {
+        let service = get_sqlite_service("sqlite-loadavg").await;
+        service.insert_monitor_status("monitor", &Status::Error { message: "Error".to_string() }).await.unwrap();
+        service.store_loadavg(&ProcsLoadavg::new(Some(1.0), Some(2.0), Some(3.0), Some(1), Some(100))).await.unwrap();
+        service.store_loadavg(&ProcsLoadavg::new(Some(3.0), Some(2.0), Some(1.0), Some(1), Some(100))).await.unwrap();
+        service.store_meminfo(&ProcsMeminfo::new(Some(2000), Some(1000), Some(1000), Some(0), Some(0))).await.unwrap();
+        service.store_meminfo(&ProcsMeminfo::new(Some(2000), Some(500), Some(500), Some(0), Some(0))).await.unwrap();
+        let loadavg = service.get_historical_loadavg(get_historical_params()).await.unwrap();
+        assert_eq!(loadavg.len(), 1);
+        assert!((loadavg[0].loadavg1min - 2.0).abs() < f64::EPSILON);
+        assert!((loadavg[0].loadavg15min - 2.0).abs() < f64::EPSILON);
+        let meminfo = service.get_historical_meminfo(get_historical_params()).await.unwrap();
+        assert_eq!(meminfo.len(), 1);
+        assert_eq!(meminfo[0].freemem, 750);
+        service.delete_old_data(1).await.unwrap();
+        assert_eq!(service.get_historical_loadavg(get_historical_params()).await.unwrap().len(), 1);
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_loadavg(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_meminfo(get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_historical_diskinfo_cpustat_and_network() {
+        let service = get_sqlite_service("sqlite-disk").await;
+        let root_usage = ProcsDiskUsage::new("/", 1000, 200, 250, 100, 50);
+        service.store_diskinfo(&[root_usage.clone(), ProcsDiskUsage::new("/home", 1000, 500, 500, 100, 100)]).await.unwrap();
+        service.store_cpustat(&[ProcCpuUsage::new(Some("cpu"), 10.0, 5.0, 1.0, 0.0, 84.0, 15.0)]).await.unwrap();
+        service.store_network(&[ProcsNetDevRate::new("eth0", 100.0, 50.0, 1, 2, 3, 4, None)]).await.unwrap();
+        let diskinfo = service.get_historical_diskinfo(get_historical_params()).await.unwrap();
+        assert_eq!(diskinfo.len(), 2);
+        assert_eq!(diskinfo[0].mount_point, "/");
+        assert!((diskinfo[0].space_percent_used.unwrap() - root_usage.get_percent_space_used().unwrap()).abs() < 0.0001);
+        assert_eq!(diskinfo[1].mount_point, "/home");
+        let cpustat = service.get_historical_cpustat(get_historical_params()).await.unwrap();
+        assert_eq!(cpustat.len(), 1);
+        assert_eq!(cpustat[0].cpu_name, "cpu");
+        assert_eq!(cpustat[0].total, Some(15.0));
+        let network = service.get_historical_network(get_historical_params()).await.unwrap();
+        assert_eq!(network.len(), 1);
+        assert_eq!(network[0].interface, "eth0");
+        assert_eq!(network[0].rx_bytes_per_second, Some(100.0));
+        assert_eq!(network[0].errors, Some(3));
+        assert_eq!(network[0].drops, Some(7));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_diskinfo(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_cpustat(get_historical_params()).await.unwrap().is_empty());
+        assert!(service.get_historical_network(get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_process_memory_use() {
+        let service = get_sqlite_service("sqlite-statm").await;
+        let statm = ProcsStatm::new(&Some(30), &Some(20), &Some(10), &Some(1), &Some(2), &Some(0), &Some(0), &4096);
+        service.store_statm_values("app", &1, &statm).await.unwrap();
+        service.store_statm_values("app", &2, &statm).await.unwrap();
+        let elements = service.get_process_memory_use(1, get_historical_params()).await.unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].resident, Some(20 * 4096));
+        assert_eq!(elements[0].share, Some(10 * 4096));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_process_memory_use(1, get_historical_params()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_monitor_status_history_and_uptime() {
+        let service = get_sqlite_service("sqlite-monitor-history").await;
+        let start = Utc::now() - chrono::Duration::hours(4);
+        let statuses = [
+            (-1, Status::Ok),
+            (1, Status::Error { message: "Error".to_string() }),
+            (2, Status::Error { message: "Error".to_string() }),
+            (3, Status::Warn { message: "Warn".to_string() }),
+        ];
+        for (hours, status) in statuses {
+            service.store(SpoolRecord::MonitorStatus { log_time: start + chrono::Duration::hours(hours), name: "monitor".to_string(), status }).await.unwrap();
+        }
+        service.insert_monitor_status("other", &Status::Error { message: "Error".to_string() }).await.unwrap();
+        let history = service.get_monitor_status_history("monitor", get_monitor_history_params(None, 0, 3)).await.unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].status, "WARN");
+        assert_eq!(history[0].message, None);
+        assert_eq!(history[0].timestamp.timestamp(), (start + chrono::Duration::hours(3)).timestamp());
+        let history = service.get_monitor_status_history("monitor", get_monitor_history_params(None, 1, 3)).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, "OK");
+        let history = service.get_monitor_status_history("monitor", get_monitor_history_params(Some("ERROR"), 0, 10)).await.unwrap();
+        assert_eq!(history.len(), 2);
+        let uptime = service.get_monitor_uptime("monitor", &UptimeParams { from_datetime: start, to_datetime: start + chrono::Duration::hours(4) }).await.unwrap();
+        assert_eq!(uptime.outages, 1);
+        assert_eq!(uptime.monitored_seconds, 4 * 3600);
+        assert_eq!(uptime.downtime_seconds, 2 * 3600);
+        assert_eq!(uptime.availability_percent, Some(50.0));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_metrics() {
+        let service = get_sqlite_service("sqlite-metrics").await;
+        let metrics = Metric::parse_perfdata("time=0.5s;1;2;0 'free space'=45%;20:;10:;0;100");
+        service.store(SpoolRecord::Metrics { log_time: Utc::now() - chrono::Duration::hours(2), name: "monitor".to_string(), metrics: metrics.clone() }).await.unwrap();
+        service.store_metrics("current", &metrics).await.unwrap();
+        let Ok(DbBackend::Sqlite(backend)) = service.get_backend() else {
+            panic!("Expected sqlite backend");
+        };
+        let query = "SELECT label, value, unit, warn, crit, min_value, max_value FROM metrics WHERE monitor_name = 'monitor' ORDER BY label";
+        let rows: Vec<Metric> = {
+            let conn = backend.pool.get().unwrap();
+            let mut statement = conn.prepare(query).unwrap();
+            statement
+                .query_map([], |row| {
+                    Ok(Metric { label: row.get(0)?, value: row.get(1)?, unit: row.get(2)?, warn: row.get(3)?, crit: row.get(4)?, min: row.get(5)?, max: row.get(6)? })
+                })
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+        assert_eq!(rows, vec![metrics[1].clone(), metrics[0].clone()]);
+        service.delete_old_data(1).await.unwrap();
+        let conn = backend.pool.get().unwrap();
+        assert_eq!(conn.query_row("SELECT count(*) FROM metrics WHERE monitor_name = 'monitor'", [], |row| row.get::<usize, i64>(0)).unwrap(), 0);
+        assert_eq!(conn.query_row("SELECT count(*) FROM metrics WHERE monitor_name = 'current'", [], |row| row.get::<usize, i64>(0)).unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_response_time() {
+        let service = get_sqlite_service("sqlite-response-time").await;
+        let log_time = Utc::now();
+        for (name, response_time) in [
+            ("monitor", ResponseTime::new(Some(10.0), None, 30.0)),
+            ("monitor", ResponseTime::new(Some(20.0), None, 50.0)),
+            ("other", ResponseTime::new(Some(1.0), Some(3.0), 1000.0)),
+        ] {
+            service.store(SpoolRecord::ResponseTime { log_time, name: name.to_string(), response_time }).await.unwrap();
+        }
+        let elements = service.get_historical_response_time("monitor", get_historical_params()).await.unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].connect_time, Some(15.0));
+        assert_eq!(elements[0].first_byte_time, None);
+        assert_eq!(elements[0].total_time, Some(40.0));
+        service.delete_old_data(0).await.unwrap();
+        assert!(service.get_historical_response_time("monitor", get_historical_params()).await.unwrap().is_empty());
     }
 }
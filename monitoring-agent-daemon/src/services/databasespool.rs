@@ -0,0 +1,472 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use monitoring_agent_lib::proc::{ProcCpuUsage, ProcsDiskUsage, ProcsLoadavg, ProcsMeminfo, ProcsNetDevRate, ProcsStatm};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::common::configuration::SpoolConfig;
+use crate::common::{ApplicationError, Metric, ResponseTime, Status};
+
+/**
+ * A write to the database. The log time is set when the record is created, so records replayed
+ * from the spool are stored with the time they were measured.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SpoolRecord {
+    MonitorStatus {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        name: String,
+        status: Status,
+    },
+    Loadavg {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        loadavg: ProcsLoadavg,
+    },
+    Meminfo {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        meminfo: ProcsMeminfo,
+    },
+    Diskinfo {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        #[serde(rename = "diskUsage")]
+        disk_usage: Vec<ProcsDiskUsage>,
+    },
+    Cpustat {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        #[serde(rename = "cpuUsage")]
+        cpu_usage: Vec<ProcCpuUsage>,
+    },
+    Network {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        rates: Vec<ProcsNetDevRate>,
+    },
+    Statm {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        #[serde(rename = "appName")]
+        app_name: String,
+        pid: u32,
+        statm: ProcsStatm,
+    },
+    ResponseTime {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        name: String,
+        #[serde(rename = "responseTime")]
+        response_time: ResponseTime,
+    },
+    Metrics {
+        #[serde(rename = "logTime")]
+        log_time: DateTime<Utc>,
+        name: String,
+        metrics: Vec<Metric>,
+    },
+}
+
+/**
+ * A line of the spool. The number of failed replays is stored with the record. Lines written
+ * before the attempts were stored are read with zero attempts.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SpoolEntry {
+    /// The number of times the database rejected the record.
+    #[serde(rename = "attempts", default)]
+    attempts: u32,
+    /// The record.
+    #[serde(flatten)]
+    record: SpoolRecord,
+}
+
+/**
+ * The result of replaying a record.
+ *
+ * Stored: The record was stored.
+ * Rejected: The database is available, but did not store the record.
+ * Unavailable: The database is unavailable.
+ */
+#[derive(Debug)]
+pub enum ReplayOutcome {
+    Stored,
+    Rejected(ApplicationError),
+    Unavailable(ApplicationError),
+}
+
+/**
+ * The number of times the database may reject a record before it is dropped from the spool.
+ */
+const MAX_REPLAY_ATTEMPTS: u32 = 5;
+
+/**
+ * Bounded spool storing the database writes that failed. The records are appended to a file with
+ * one json record per line, and replayed in the order they were written.
+ *
+ * `path`: The spool file.
+ * `max_size`: The maximum size of the spool file in bytes.
+ * `lock`: Serializes the changes to the spool file.
+ * `replay_lock`: Serializes the replays.
+ */
+#[derive(Debug)]
+pub struct DbSpool {
+    /// The spool file.
+    path: PathBuf,
+    /// The maximum size of the spool file in bytes.
+    max_size: u64,
+    /// Serializes the changes to the spool file. Not held while records are stored in the database.
+    lock: Mutex<()>,
+    /// Serializes the replays.
+    replay_lock: Mutex<()>,
+}
+
+impl DbSpool {
+    /**
+     * Create a new spool.
+     *
+     * `spool_config`: The spool configuration.
+     *
+     * Returns: The spool or None if the spool is disabled.
+     */
+    pub fn new(spool_config: &SpoolConfig) -> Option<DbSpool> {
+        if !spool_config.enabled {
+            return None;
+        }
+        Some(DbSpool {
+            path: PathBuf::from(&spool_config.path),
+            max_size: spool_config.max_size,
+            lock: Mutex::new(()),
+            replay_lock: Mutex::new(()),
+        })
+    }
+
+    /**
+     * Append a record to the spool. The directory of the spool file is created if it does not exist.
+     * The records being replayed count towards the maximum size.
+     *
+     * `record`: The record to append.
+     *
+     * Errors:
+     * - If the spool is full.
+     * - If the record could not be written.
+     */
+    pub async fn append(&self, record: &SpoolRecord) -> Result<(), ApplicationError> {
+        let mut line = serde_json::to_string(&SpoolEntry { attempts: 0, record: record.clone() })
+            .map_err(|err| ApplicationError::new(&format!("Error serializing spool record: {err}")))?;
+        line.push('\n');
+        let _lock = self.lock.lock().await;
+        let size = DbSpool::get_size(&self.path).await + DbSpool::get_size(&self.get_replay_path()).await;
+        if size + line.len() as u64 > self.max_size {
+            return Err(ApplicationError::new(&format!("Spool {} is full, dropping record", self.path.display())));
+        }
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|err| ApplicationError::new(&format!("Error creating spool directory: {err}")))?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|err| ApplicationError::new(&format!("Error opening spool {}: {err}", self.path.display())))?;
+        file.write_all(line.as_bytes()).await.map_err(|err| ApplicationError::new(&format!("Error writing spool: {err}")))?;
+        file.flush().await.map_err(|err| ApplicationError::new(&format!("Error writing spool: {err}")))
+    }
+
+    /**
+     * Replay the records in the order they were written. The spool file is moved aside before the
+     * records are stored, so records can be appended while the database is written. Replaying stops
+     * when the database is unavailable, or when it rejects a record. A rejected record is retried on
+     * the next replay, and dropped after it has been rejected `MAX_REPLAY_ATTEMPTS` times. The records
+     * not replayed are put back in front of the records appended during the replay. Lines that can
+     * not be parsed are logged and dropped.
+     *
+     * `store`: Stores a record in the database.
+     *
+     * Returns: The number of records replayed.
+     *
+     * Errors:
+     * - If the spool could not be read or rewritten.
+     */
+    pub async fn replay<F, Fut>(&self, mut store: F) -> Result<usize, ApplicationError>
+    where
+        F: FnMut(SpoolRecord) -> Fut,
+        Fut: Future<Output = ReplayOutcome>,
+    {
+        let _replay_lock = self.replay_lock.lock().await;
+        let replay_path = self.get_replay_path();
+        {
+            /*
+             * A replay file left by an interrupted replay is replayed before the spool file is moved aside.
+             */
+            let _lock = self.lock.lock().await;
+            if !tokio::fs::try_exists(&replay_path).await.unwrap_or(false) {
+                match tokio::fs::rename(&self.path, &replay_path).await {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+                    Err(err) => return Err(ApplicationError::new(&format!("Error moving spool {}: {err}", self.path.display()))),
+                }
+            }
+        }
+        let content = tokio::fs::read_to_string(&replay_path).await
+            .map_err(|err| ApplicationError::new(&format!("Error reading spool {}: {err}", replay_path.display())))?;
+        let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
+        let mut remaining: Vec<String> = Vec::new();
+        let mut replayed = 0;
+        for (index, line) in lines.iter().enumerate() {
+            let mut entry = match serde_json::from_str::<SpoolEntry>(line) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    error!("Dropping invalid spool record: {err}");
+                    continue;
+                }
+            };
+            match store(entry.record.clone()).await {
+                ReplayOutcome::Stored => replayed += 1,
+                ReplayOutcome::Rejected(err) => {
+                    entry.attempts += 1;
+                    if entry.attempts >= MAX_REPLAY_ATTEMPTS {
+                        error!("Dropping spool record rejected {} times: {err:?}", entry.attempts);
+                        continue;
+                    }
+                    warn!("Stopped replaying spool, record rejected: {err:?}");
+                    remaining.push(serde_json::to_string(&entry).map_err(|err| ApplicationError::new(&format!("Error serializing spool record: {err}")))?);
+                    remaining.extend(lines[index + 1..].iter().map(|line| (*line).to_string()));
+                    break;
+                }
+                ReplayOutcome::Unavailable(err) => {
+                    warn!("Stopped replaying spool: {err:?}");
+                    remaining.extend(lines[index..].iter().map(|line| (*line).to_string()));
+                    break;
+                }
+            }
+        }
+        let _lock = self.lock.lock().await;
+        self.restore(&remaining, &replay_path).await?;
+        Ok(replayed)
+    }
+
+    /**
+     * Put the records not replayed back in front of the records appended during the replay, and remove
+     * the replay file. The spool is written to a temporary file that is renamed, so it is never partially
+     * written. The spool is removed when no lines remain.
+     *
+     * `remaining`: The lines not replayed.
+     * `replay_path`: The replay file.
+     *
+     * Errors:
+     * - If the spool could not be read or written.
+     */
+    async fn restore(&self, remaining: &[String], replay_path: &Path) -> Result<(), ApplicationError> {
+        let appended = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(ApplicationError::new(&format!("Error reading spool {}: {err}", self.path.display()))),
+        };
+        let lines: Vec<&str> = remaining.iter().map(String::as_str).chain(appended.lines().filter(|line| !line.trim().is_empty())).collect();
+        if lines.is_empty() {
+            if !appended.is_empty() {
+                tokio::fs::remove_file(&self.path).await.map_err(|err| ApplicationError::new(&format!("Error removing spool: {err}")))?;
+            }
+        } else if !remaining.is_empty() {
+            let mut content = lines.join("\n");
+            content.push('\n');
+            let temporary_path = self.path.with_extension("tmp");
+            tokio::fs::write(&temporary_path, content).await.map_err(|err| ApplicationError::new(&format!("Error writing spool: {err}")))?;
+            tokio::fs::rename(&temporary_path, &self.path).await.map_err(|err| ApplicationError::new(&format!("Error writing spool: {err}")))?;
+        }
+        tokio::fs::remove_file(replay_path).await.map_err(|err| ApplicationError::new(&format!("Error removing spool: {err}")))
+    }
+
+    /**
+     * Get the file the spool is moved to while it is replayed.
+     *
+     * Returns: The replay file.
+     */
+    fn get_replay_path(&self) -> PathBuf {
+        self.path.with_extension("replay")
+    }
+
+    /**
+     * Get the size of a file.
+     *
+     * `path`: The file.
+     *
+     * Returns: The size in bytes, or zero if the file does not exist.
+     */
+    async fn get_size(path: &Path) -> u64 {
+        tokio::fs::metadata(path).await.map(|metadata| metadata.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_spool(test_name: &str, max_size: u64) -> DbSpool {
+        let path = std::env::temp_dir().join(format!("monitoring-agent-spool-{test_name}-{}/spool.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        DbSpool::new(&SpoolConfig {
+            enabled: true,
+            path: path.to_string_lossy().to_string(),
+            max_size,
+            replay_schedule: "30 * * * * *".to_string(),
+        }).unwrap()
+    }
+
+    fn get_record(name: &str) -> SpoolRecord {
+        SpoolRecord::MonitorStatus { log_time: Utc::now(), name: name.to_string(), status: Status::Error { message: "Error".to_string() } }
+    }
+
+    #[test]
+    fn test_disabled() {
+        assert!(DbSpool::new(&SpoolConfig {
+            enabled: false,
+            path: "/tmp/spool.jsonl".to_string(),
+            max_size: 1024,
+            replay_schedule: "30 * * * * *".to_string(),
+        }).is_none());
+    }
+
+    #[test]
+    fn test_record_serialization() {
+        let record = SpoolRecord::Statm {
+            log_time: Utc::now(),
+            app_name: "app".to_string(),
+            pid: 1,
+            statm: ProcsStatm::new(&Some(30), &Some(20), &Some(10), &Some(1), &Some(2), &Some(0), &Some(0), &4096),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.starts_with("{\"type\":\"statm\""));
+        let SpoolRecord::Statm { app_name, pid, statm, .. } = serde_json::from_str(&json).unwrap() else {
+            panic!("Wrong record type");
+        };
+        assert_eq!(app_name, "app");
+        assert_eq!(pid, 1);
+        assert_eq!(statm.resident, Some(20));
+    }
+
+    #[tokio::test]
+    async fn test_replay_in_order() {
+        let spool = get_spool("order", 1024 * 1024);
+        for name in ["first", "second", "third"] {
+            spool.append(&get_record(name)).await.unwrap();
+        }
+        let mut names = Vec::new();
+        let replayed = spool.replay(|record| {
+            if let SpoolRecord::MonitorStatus { name, .. } = record {
+                names.push(name);
+            }
+            async { ReplayOutcome::Stored }
+        }).await.unwrap();
+        assert_eq!(replayed, 3);
+        assert_eq!(names, vec!["first", "second", "third"]);
+        assert!(!spool.path.exists());
+        assert_eq!(spool.replay(|_| async { ReplayOutcome::Stored }).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_keeps_remaining_records() {
+        let spool = get_spool("remaining", 1024 * 1024);
+        for name in ["first", "second", "third"] {
+            spool.append(&get_record(name)).await.unwrap();
+        }
+        let replayed = spool.replay(|record| {
+            let result = match record {
+                SpoolRecord::MonitorStatus { name, .. } if name == "second" => ReplayOutcome::Unavailable(ApplicationError::new("Database unavailable")),
+                _ => ReplayOutcome::Stored,
+            };
+            async { result }
+        }).await.unwrap();
+        assert_eq!(replayed, 1);
+        let mut names = Vec::new();
+        spool.replay(|record| {
+            if let SpoolRecord::MonitorStatus { name, .. } = record {
+                names.push(name);
+            }
+            async { ReplayOutcome::Stored }
+        }).await.unwrap();
+        assert_eq!(names, vec!["second", "third"]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_lines_are_dropped() {
+        let spool = get_spool("invalid", 1024 * 1024);
+        spool.append(&get_record("first")).await.unwrap();
+        let mut file = tokio::fs::OpenOptions::new().append(true).open(&spool.path).await.unwrap();
+        file.write_all(b"not json\n").await.unwrap();
+        spool.append(&get_record("second")).await.unwrap();
+        assert_eq!(spool.replay(|_| async { ReplayOutcome::Stored }).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_bounded() {
+        let record_size = serde_json::to_string(&SpoolEntry { attempts: 0, record: get_record("first") }).unwrap().len() as u64 + 1;
+        let spool = get_spool("bounded", record_size * 3 / 2);
+        spool.append(&get_record("first")).await.unwrap();
+        assert!(spool.append(&get_record("second")).await.is_err());
+        assert_eq!(spool.replay(|_| async { ReplayOutcome::Stored }).await.unwrap(), 1);
+        assert!(spool.append(&get_record("third")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_record_is_dropped() {
+        let spool = get_spool("rejected", 1024 * 1024);
+        for name in ["first", "second"] {
+            spool.append(&get_record(name)).await.unwrap();
+        }
+        for _ in 1..MAX_REPLAY_ATTEMPTS {
+            let replayed = spool.replay(|_| async { ReplayOutcome::Rejected(ApplicationError::new("Invalid record")) }).await.unwrap();
+            assert_eq!(replayed, 0);
+        }
+        let mut names = Vec::new();
+        let replayed = spool.replay(|record| {
+            let outcome = match record {
+                SpoolRecord::MonitorStatus { name, .. } if name == "first" => ReplayOutcome::Rejected(ApplicationError::new("Invalid record")),
+                SpoolRecord::MonitorStatus { name, .. } => {
+                    names.push(name);
+                    ReplayOutcome::Stored
+                },
+                _ => ReplayOutcome::Stored,
+            };
+            async { outcome }
+        }).await.unwrap();
+        assert_eq!(replayed, 1);
+        assert_eq!(names, vec!["second"]);
+        assert!(!spool.path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_append_during_replay() {
+        let spool = get_spool("append-during-replay", 1024 * 1024);
+        spool.append(&get_record("first")).await.unwrap();
+        spool.append(&get_record("second")).await.unwrap();
+        let replayed = spool.replay(|record| {
+            let spool = &spool;
+            async move {
+                spool.append(&get_record("appended")).await.unwrap();
+                match record {
+                    SpoolRecord::MonitorStatus { name, .. } if name == "second" => ReplayOutcome::Unavailable(ApplicationError::new("Database unavailable")),
+                    _ => ReplayOutcome::Stored,
+                }
+            }
+        }).await.unwrap();
+        assert_eq!(replayed, 1);
+        assert!(!spool.get_replay_path().exists());
+        let mut names = Vec::new();
+        spool.replay(|record| {
+            if let SpoolRecord::MonitorStatus { name, .. } = record {
+                names.push(name);
+            }
+            async { ReplayOutcome::Stored }
+        }).await.unwrap();
+        assert_eq!(names, vec!["second", "appended", "appended"]);
+    }
+}
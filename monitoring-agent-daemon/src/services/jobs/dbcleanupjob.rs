@@ -49,7 +49,7 @@ impl DbCleanupJob {
         let job_result = Job::new_async("0 */5 * * * *", move |_uuid, _locked| {
             let db_cleanup_job = db_cleanup_job.clone();
             Box::pin(async move {
-                let _ = db_cleanup_job.delete().map_err(|err| {
+                let _ = db_cleanup_job.delete().await.map_err(|err| {
                     error!("Error checking monitor: {:?}", err);
                 });
             })
@@ -63,7 +63,7 @@ impl DbCleanupJob {
      * # Returns
      * The result of the database cleanup job.
      */
-    fn delete(&self) -> Result<(), ApplicationError> {
+    async fn delete(&self) -> Result<(), ApplicationError> {
         info!("Running db cleanup job");        
         let db_service = self.db_service.as_ref();
         match db_service {
@@ -71,7 +71,7 @@ impl DbCleanupJob {
                 Err(ApplicationError::new("Database service not found"))
             }
             Some(db_service) => {
-                db_service.delete_old_data(self.max_time_stored_db)
+                db_service.delete_old_data(self.max_time_stored_db).await
             }
         }
     }
@@ -97,11 +97,11 @@ mod test {
         assert!(job.is_ok());
     }
 
-    #[test]
-    fn test_delete_if_no_db_configured() {
+    #[tokio::test]
+    async fn test_delete_if_no_db_configured() {
         let db_service = std::sync::Arc::new(None);
         let db_cleanup_job = DbCleanupJob::new(&db_service, 24);
-        let result = db_cleanup_job.delete();
+        let result = db_cleanup_job.delete().await;
         assert!(result.is_err());
     }
 }
\ No newline at end of file
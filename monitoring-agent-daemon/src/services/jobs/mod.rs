@@ -1,5 +1,7 @@
 mod dbcleanupjob;
 mod notificationjob;
+mod spoolreplayjob;
 
 pub use dbcleanupjob::DbCleanupJob;
 pub use notificationjob::NotificationJob;
+pub use spoolreplayjob::SpoolReplayJob;
@@ -1,10 +1,23 @@
 use chrono::{DateTime, Utc};
-use lettre::{message::Mailbox, Message, SmtpTransport, Transport};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::{Arc, Mutex}};
 use tokio_cron_scheduler::Job;
 use tracing::error;
 
-use crate::common::{ApplicationError, MonitorStatus, MonitorStatusType, Status};
+use crate::common::{configuration::{NotifierConfig, ThresholdLevel}, ApplicationError, MonitorStatus, MonitorStatusType, Status};
+use crate::services::notifiers::{Notification, NotificationType, Notifier};
+
+/**
+ * The last notification sent for a monitor.
+ */
+#[derive(Debug, Clone, PartialEq)]
+struct NotifiedStatus {
+    /// The notified level.
+    level: ThresholdLevel,
+    /// When the notification was sent.
+    notified_time: DateTime<Utc>,
+    /// The last successful time before the outage.
+    outage_start: Option<DateTime<Utc>>,
+}
 
 /**
  * The notification job.
@@ -13,18 +26,16 @@ use crate::common::{ApplicationError, MonitorStatus, MonitorStatusType, Status};
 pub struct NotificationJob {
     /// Monitor status
     status: MonitorStatusType,
-    /// Map of all the already notified errors.
-    already_notified: HashMap<String, DateTime<Utc>>,
-    /// The email recipients.
-    recipients: Vec<String>,
-    /// The email transport.
-    transport: SmtpTransport,
-    /// The from email.
-    from: Mailbox,
-    /// The reply to email.
-    reply_to: Mailbox,
+    /// Map of the monitors notified to be in error or warning. Shared with the running job so sent notifications can be recorded.
+    already_notified: Arc<Mutex<HashMap<String, NotifiedStatus>>>,
+    /// The notifiers to send the notifications with.
+    notifiers: Vec<Notifier>,
+    /// The name of the server.
+    server_name: String,
     /// After how many minutes to resend the notification when an error occurs.
     resend_after: i64,
+    /// The minimum severity to notify.
+    minimum_severity: ThresholdLevel,
     /// The notify schedule.
     notify_schedule: String,
 }
@@ -34,50 +45,44 @@ impl NotificationJob {
      * Create a new notification job.
      *
      * `status`: The status.
-     * `url`: The url.
-     * `recipients`: The recipients.
-     * `from`: The from.
-     * `reply_to`: The reply to.
+     * `notifier_configs`: The notifier configurations.
+     * `server_name`: The name of the server.
      * `resend_after`: The resend after.
+     * `minimum_severity`: The minimum severity to notify.
      * `notify_schedule`: The notify schedule.
      *
      * Returns a new `NotificationJob`.
      */
     pub fn new(
         status: &MonitorStatusType,
-        url: &str,
-        recipients: Vec<String>,
-        from: &str,
-        reply_to: &str,
+        notifier_configs: &[NotifierConfig],
+        server_name: &str,
         resend_after: i64,
+        minimum_severity: ThresholdLevel,
         notify_schedule: &str,
     ) -> Result<NotificationJob, ApplicationError> {
-        let transport = SmtpTransport::from_url(url)
-            .map_err(|err| {
-                ApplicationError::new(&format!("Error creating smtp transport: {err:?}"))
-            })?
-            .build();
-
-        let from: Mailbox = from
-            .parse()
-            .map_err(|err| ApplicationError::new(&format!("Error parsing from email: {err:?}")))?;
-
-        let reply_to: Mailbox = reply_to.parse().map_err(|err| {
-            ApplicationError::new(&format!("Error parsing reply to email: {err:?}"))
-        })?;
-
+        let notifiers = notifier_configs.iter().map(Notifier::new).collect::<Result<Vec<Notifier>, ApplicationError>>()?;
         Ok(NotificationJob {
             status: status.clone(),
-            already_notified: HashMap::new(),
-            recipients,
-            transport,
-            from,
-            reply_to,
+            already_notified: Arc::new(Mutex::new(HashMap::new())),
+            notifiers,
+            server_name: server_name.to_string(),
             resend_after,
+            minimum_severity,
             notify_schedule: notify_schedule.to_string(),
         })
     }
 
+    /**
+     * Keep the notified monitors of the job being replaced, so a reload does not notify the failing monitors again
+     * and still sends the resolved notifications. Must be called before the job is scheduled.
+     *
+     * `previous`: The notification job being replaced.
+     */
+    pub fn keep_notified(&mut self, previous: &NotificationJob) {
+        self.already_notified = previous.already_notified.clone();
+    }
+
     /**
      * Get the notification job.
      *
@@ -85,171 +90,281 @@ impl NotificationJob {
      * The notification job.
      */
     pub fn get_notification_job(&mut self) -> Result<Job, ApplicationError> {
-        let mut notification_job = self.clone();
-        let job_result = Job::new(self.notify_schedule.as_str(), move |_uuid, _locked| {
-            let _ = notification_job.check().map_err(|err| {
-                error!("Error checking notification job: {:?}", err);
-            });
+        let notification_job = self.clone();
+        let job_result = Job::new_async(self.notify_schedule.as_str(), move |_uuid, _locked| {
+            let notification_job = notification_job.clone();
+            Box::pin(async move {
+                match notification_job.get_notifications() {
+                    Ok(notifications) if !notifications.is_empty() => {
+                        if NotificationJob::notify(&notification_job.notifiers, &notifications, &notification_job.server_name).await {
+                            if let Err(err) = notification_job.mark_notified(&notifications) {
+                                error!("Error recording sent notifications: {:?}", err);
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(err) => error!("Error checking notification job: {:?}", err),
+                }
+            })
         });
         job_result.map_err(|err| {
-            ApplicationError::new(&format!("Error creating db cleanup job: {err:?}"))
+            ApplicationError::new(&format!("Error creating notification job: {err:?}"))
         })
     }
 
     /**
-     * Check the notification job.
+     * Get the notifications for the status transitions since the last check.
+     * A monitor changing to error, or to warning if the minimum severity is warn, is notified.
+     * The notification is resent after `resend_after` minutes while the monitor is still failing.
+     * A monitor changing from a notified error or warning back to ok is notified as resolved.
+     * The notifications are only recorded as sent by `mark_notified`, so notifications that could not be sent are
+     * returned again on the next check. Monitors that no longer exist, for example after a reload, are forgotten.
      *
      * # Returns
-     * The result of the notification job.
+     * The notifications to send.
      */
-    fn check(&mut self) -> Result<(), ApplicationError> {
-        self.remove_old_notifications();
-        let new_errors = self.get_new_errors()?;
-        if !new_errors.is_empty() {
-            self.notify(new_errors);
-        }
-        Ok(())
-    }
-
-    fn get_new_errors(&mut self) -> Result<Vec<MonitorStatus>, ApplicationError> {
-        let mut new_errors: Vec<MonitorStatus> = vec![];
+    fn get_notifications(&self) -> Result<Vec<Notification>, ApplicationError> {
+        let resend_before = Utc::now() - chrono::Duration::minutes(self.resend_after);
+        let mut notifications: Vec<Notification> = vec![];
         let status = self.status.lock();
         let statuses = status
             .map_err(|err| ApplicationError::new(&format!("Error getting status: {err:?}")))?;
-        for (_name, monitor_status) in statuses.iter() {
-            let current_monitor_status = monitor_status.status.clone();
-            if let Status::Error { message: _ } = current_monitor_status {
-                let name = monitor_status.name.clone();
-                self.already_notified.entry(name).or_insert_with(|| {
-                    new_errors.push(monitor_status.clone());
-                    Utc::now()
-                });
+        let mut already_notified = self.lock_already_notified()?;
+        already_notified.retain(|name, _| statuses.contains_key(name));
+        for monitor_status in statuses.values() {
+            let level = match monitor_status.status {
+                Status::Error { .. } => ThresholdLevel::Error,
+                Status::Warn { .. } if self.minimum_severity == ThresholdLevel::Warn => ThresholdLevel::Warn,
+                Status::Ok => {
+                    if let Some(notified_status) = already_notified.get(&monitor_status.name) {
+                        notifications.push(Notification::new(monitor_status, NotificationType::Resolved, notified_status.outage_start));
+                    }
+                    continue;
+                }
+                Status::Warn { .. } | Status::Unknown => continue,
+            };
+            let notified_status = already_notified.get(&monitor_status.name);
+            if notified_status.is_some_and(|notified_status| notified_status.level == level && notified_status.notified_time >= resend_before) {
+                continue;
             }
+            let outage_start = match notified_status {
+                Some(notified_status) => notified_status.outage_start,
+                None => NotificationJob::get_outage_start(monitor_status),
+            };
+            let notification_type = match level {
+                ThresholdLevel::Error => NotificationType::Error,
+                ThresholdLevel::Warn => NotificationType::Warn,
+            };
+            notifications.push(Notification::new(monitor_status, notification_type, outage_start));
         }
-        Ok(new_errors)
+        Ok(notifications)
     }
 
     /**
-     * Remove old notifications
+     * Record notifications as sent. Resolved monitors are forgotten, failing monitors are not notified again
+     * until the level changes or `resend_after` minutes have passed.
+     *
+     * `notifications`: The sent notifications.
      *
-     * If a notification is older than 2 hours, remove it.
+     * throws: `ApplicationError`: If the notified monitors could not be locked.
      */
-    fn remove_old_notifications(&mut self) {
-        let older_than = Utc::now() - chrono::Duration::minutes(self.resend_after);
-        let mut keys_to_remove = vec![];
-        for (key, value) in &self.already_notified {
-            if value < &older_than {
-                keys_to_remove.push(key.to_string());
-            }
-        }
-        for key in keys_to_remove {
-            self.already_notified.remove(&key);
+    fn mark_notified(&self, notifications: &[Notification]) -> Result<(), ApplicationError> {
+        let mut already_notified = self.lock_already_notified()?;
+        for notification in notifications {
+            let name = notification.monitor_status.name.clone();
+            let level = match notification.notification_type {
+                NotificationType::Error => ThresholdLevel::Error,
+                NotificationType::Warn => ThresholdLevel::Warn,
+                NotificationType::Resolved => {
+                    already_notified.remove(&name);
+                    continue;
+                }
+            };
+            already_notified.insert(name, NotifiedStatus { level, notified_time: Utc::now(), outage_start: notification.outage_start });
         }
+        Ok(())
     }
 
     /**
-     * Notify the user of the new errors.
+     * Lock the notified monitors.
      *
-     * `new_errors`: The new errors.
+     * Returns the locked notified monitors.
+     *
+     * throws: `ApplicationError`: If the lock is poisoned.
      */
-    fn notify(&self, new_errors: Vec<MonitorStatus>) {
-        let mut notification = "New errors: \n".to_string();
-        for monitor_status in new_errors {
-            notification.push_str(Self::get_notification_message(&monitor_status).as_str());
-        }
-        for recipient in &self.recipients {
-            let email = Message::builder()
-                .from(self.from.clone())
-                .reply_to(self.reply_to.clone())
-                .to(recipient.parse().unwrap())
-                .subject("Monitoring agent daemon notification")
-                .body(notification.clone())
-                .unwrap();
-
-            self.transport.send(&email).unwrap();
-        }
+    fn lock_already_notified(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, NotifiedStatus>>, ApplicationError> {
+        self.already_notified.lock().map_err(|err| ApplicationError::new(&format!("Error getting notified monitors: {err:?}")))
     }
 
     /**
-     * Get the notification message.
+     * Get the start of the outage of a failing monitor. This is the last successful time,
+     * or the last error time if the monitor has never been successful.
      *
      * `monitor_status`: The monitor status.
      *
-     * Returns: The notification message.
+     * Returns the start of the outage.
      */
-    fn get_notification_message(monitor_status: &MonitorStatus) -> String {
-        format!(
-            "Monitor: {:?} is in error. \nMessage: {:?} \n",
-            monitor_status.name, monitor_status.status
-        )
+    fn get_outage_start(monitor_status: &MonitorStatus) -> Option<DateTime<Utc>> {
+        match (monitor_status.last_successful_time, monitor_status.last_error_time) {
+            (Some(last_successful_time), Some(last_error_time)) if last_successful_time < last_error_time => Some(last_successful_time),
+            (_, last_error_time) => last_error_time,
+        }
+    }
+
+    /**
+     * Send the notifications with all notifiers. A failing notifier does not stop the others.
+     *
+     * `notifiers`: The notifiers.
+     * `notifications`: The notifications.
+     * `server_name`: The name of the server.
+     *
+     * Returns true if the notifications were sent. That is when any notifier succeeded, or there are no notifiers.
+     */
+    async fn notify(notifiers: &[Notifier], notifications: &[Notification], server_name: &str) -> bool {
+        let mut sent = notifiers.is_empty();
+        for notifier in notifiers {
+            match notifier.notify(notifications, server_name).await {
+                Ok(()) => sent = true,
+                Err(err) => error!("Error sending notification: {}", err.message),
+            }
+        }
+        sent
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
-
     use super::*;
 
-    #[test]
-    fn test_get_new_errors() {
-        let mut notification_job = NotificationJob {
+    fn get_notification_job(minimum_severity: ThresholdLevel) -> NotificationJob {
+        NotificationJob {
             status: Arc::new(Mutex::new(HashMap::new())),
-            already_notified: HashMap::new(),
-            recipients: vec![],
-            transport: SmtpTransport::unencrypted_localhost(),
-            from: "test@test.com".parse().unwrap(),
-            reply_to: "test@test.com".parse().unwrap(),
+            already_notified: Arc::new(Mutex::new(HashMap::new())),
+            notifiers: vec![],
+            server_name: "test".to_string(),
             resend_after: 120,
+            minimum_severity,
             notify_schedule: "".to_string(),
-        };
-        notification_job.status.lock().unwrap().insert(
-            "test".to_string(),
-            MonitorStatus {
-                name: "test".to_string(),
-                status: Status::Error {
-                    message: "test".to_string(),
-                },
-                description: Some("test".to_string()),
-                last_successful_time: None,
-                last_error: None,
-                last_error_time: None,
-            },
-        );
-        let new_errors = notification_job.get_new_errors().unwrap();
-        assert_eq!(new_errors.len(), 1);
-        let new_errors = notification_job.get_new_errors().unwrap();
-        assert_eq!(new_errors.len(), 0);
+        }
+    }
+
+    fn set_status(notification_job: &NotificationJob, status: Status) {
+        notification_job.status.lock().unwrap().entry("test".to_string())
+            .or_insert_with(|| MonitorStatus::new("test", &Some("test".to_string()), Status::Unknown))
+            .set_status(&status);
+    }
+
+    /**
+     * Get the notifications and record them as sent.
+     */
+    fn send_notifications(notification_job: &NotificationJob) -> Vec<Notification> {
+        let notifications = notification_job.get_notifications().unwrap();
+        notification_job.mark_notified(&notifications).unwrap();
+        notifications
     }
 
     #[test]
-    fn test_remove_old_notifications() {
-        let mut notification_job = NotificationJob {
-            status: Arc::new(Mutex::new(HashMap::new())),
-            already_notified: HashMap::new(),
-            recipients: vec![],
-            transport: SmtpTransport::unencrypted_localhost(),
-            from: "test@test.com".parse().unwrap(),
-            reply_to: "test@test.com".parse().unwrap(),
-            resend_after: 120,
-            notify_schedule: "".to_string(),
-        };
-        notification_job
-            .already_notified
-            .insert("test".to_string(), Utc::now());
-        notification_job.remove_old_notifications();
-        assert_eq!(notification_job.already_notified.len(), 1);
-        notification_job.already_notified.insert(
-            "test".to_string(),
-            Utc::now() - chrono::Duration::minutes(121),
-        );
-        notification_job.remove_old_notifications();
-        assert_eq!(notification_job.already_notified.len(), 0);
-        notification_job.already_notified.insert(
-            "test".to_string(),
-            Utc::now() - chrono::Duration::minutes(118),
-        );
-        notification_job.remove_old_notifications();
-        assert_eq!(notification_job.already_notified.len(), 1);
+    fn test_keep_notified() {
+        let previous = get_notification_job(ThresholdLevel::Error);
+        set_status(&previous, Status::Error { message: "test".to_string() });
+        assert_eq!(send_notifications(&previous).len(), 1);
+        let mut notification_job = get_notification_job(ThresholdLevel::Error);
+        notification_job.status = previous.status.clone();
+        notification_job.keep_notified(&previous);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        set_status(&notification_job, Status::Ok);
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification_type, NotificationType::Resolved);
+    }
+
+    #[test]
+    fn test_get_new_errors() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification_type, NotificationType::Error);
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 0);
+    }
+
+    #[test]
+    fn test_resend_old_notifications() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        assert_eq!(send_notifications(&notification_job).len(), 1);
+        notification_job.already_notified.lock().unwrap().get_mut("test").unwrap().notified_time = Utc::now() - chrono::Duration::minutes(118);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        notification_job.already_notified.lock().unwrap().get_mut("test").unwrap().notified_time = Utc::now() - chrono::Duration::minutes(121);
+        assert_eq!(send_notifications(&notification_job).len(), 1);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+    }
+
+    #[test]
+    fn test_resolved() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Ok);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        let outage_start = notification_job.status.lock().unwrap().get("test").unwrap().last_successful_time;
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications[0].outage_start, outage_start);
+        set_status(&notification_job, Status::Ok);
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification_type, NotificationType::Resolved);
+        assert_eq!(notifications[0].outage_start, outage_start);
+        assert!(notifications[0].get_outage_duration().is_some());
+        assert!(notification_job.already_notified.lock().unwrap().is_empty());
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+    }
+
+    #[test]
+    fn test_warn_ignored_by_default() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Warn { message: "test".to_string() });
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        set_status(&notification_job, Status::Ok);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+    }
+
+    #[test]
+    fn test_warn_opt_in() {
+        let notification_job = get_notification_job(ThresholdLevel::Warn);
+        set_status(&notification_job, Status::Warn { message: "test".to_string() });
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification_type, NotificationType::Warn);
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].notification_type, NotificationType::Error);
+        set_status(&notification_job, Status::Ok);
+        let notifications = send_notifications(&notification_job);
+        assert_eq!(notifications[0].notification_type, NotificationType::Resolved);
+    }
+
+    #[test]
+    fn test_unsent_notifications_are_returned_again() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        assert_eq!(notification_job.get_notifications().unwrap().len(), 1);
+        assert_eq!(send_notifications(&notification_job).len(), 1);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        set_status(&notification_job, Status::Ok);
+        assert_eq!(notification_job.get_notifications().unwrap()[0].notification_type, NotificationType::Resolved);
+        assert_eq!(send_notifications(&notification_job)[0].notification_type, NotificationType::Resolved);
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+    }
+
+    #[test]
+    fn test_removed_monitors_are_forgotten() {
+        let notification_job = get_notification_job(ThresholdLevel::Error);
+        set_status(&notification_job, Status::Error { message: "test".to_string() });
+        assert_eq!(send_notifications(&notification_job).len(), 1);
+        notification_job.status.lock().unwrap().remove("test");
+        assert_eq!(send_notifications(&notification_job).len(), 0);
+        assert!(notification_job.already_notified.lock().unwrap().is_empty());
     }
 }
@@ -0,0 +1,108 @@
+use log::info;
+use tokio_cron_scheduler::Job;
+use tracing::error;
+
+use crate::common::{ApplicationError, DatabaseServiceType};
+
+/**
+ * The `SpoolReplayJob` struct contains the database service and the replay schedule.
+ *  
+ * The `SpoolReplayJob` struct has the following fields:
+ * * `db_service`: Database service if defined.
+ * * `replay_schedule`: The schedule for replaying the spool.
+ * 
+ */
+#[derive(Debug, Clone)]
+pub struct SpoolReplayJob {
+    /// The database service.
+    pub db_service: DatabaseServiceType,
+    /// The schedule for replaying the spool.
+    pub replay_schedule: String
+}
+
+impl SpoolReplayJob {
+    /**
+     * Create a new `SpoolReplayJob` with the database service and the replay schedule.
+     * 
+     * # Arguments
+     * * `db_service` - The database service.
+     * * `replay_schedule` - The schedule for replaying the spool.
+     * 
+     * # Returns
+     * The `SpoolReplayJob` with the database service and the replay schedule.
+     */
+    pub fn new(db_service: &DatabaseServiceType, replay_schedule: &str) -> Self {
+        SpoolReplayJob {
+            db_service: db_service.clone(),
+            replay_schedule: replay_schedule.to_string()
+        }
+    }
+
+    /**
+     * Get the spool replay job.
+     * 
+     * # Returns
+     * The spool replay job.
+     */
+    pub fn get_spool_replay_job(&mut self) -> Result<Job, ApplicationError> {
+        let spool_replay_job = self.clone();
+        let job_result = Job::new_async(self.replay_schedule.as_str(), move |_uuid, _locked| {
+            let spool_replay_job = spool_replay_job.clone();
+            Box::pin(async move {
+                let _ = spool_replay_job.replay().await.map_err(|err| {
+                    error!("Error replaying spool: {:?}", err);
+                });
+            })
+        });
+        job_result.map_err(|err| ApplicationError::new(&format!("Error creating spool replay job: {err:?}")))
+    }
+
+    /**
+     * Replay the spooled writes to the database.
+     * 
+     * # Returns
+     * The number of replayed writes.
+     */
+    async fn replay(&self) -> Result<usize, ApplicationError> {
+        let db_service = self.db_service.as_ref();
+        match db_service {
+            None => {
+                Err(ApplicationError::new("Database service not found"))
+            }
+            Some(db_service) => {
+                let replayed = db_service.replay_spool().await?;
+                if replayed > 0 {
+                    info!("Replayed {replayed} spooled database writes");
+                }
+                Ok(replayed)
+            }
+        }
+    }
+}
+
+mod test {
+
+    #[cfg(test)]
+    use super::*;
+
+    #[test]
+    fn test_get_spool_replay_job() {
+        let db_service = std::sync::Arc::new(None);
+        let mut spool_replay_job = SpoolReplayJob::new(&db_service, "30 * * * * *");
+        assert!(spool_replay_job.get_spool_replay_job().is_ok());
+    }
+
+    #[test]
+    fn test_get_spool_replay_job_invalid_schedule() {
+        let db_service = std::sync::Arc::new(None);
+        let mut spool_replay_job = SpoolReplayJob::new(&db_service, "invalid");
+        assert!(spool_replay_job.get_spool_replay_job().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_if_no_db_configured() {
+        let db_service = std::sync::Arc::new(None);
+        let spool_replay_job = SpoolReplayJob::new(&db_service, "30 * * * * *");
+        assert!(spool_replay_job.replay().await.is_err());
+    }
+}
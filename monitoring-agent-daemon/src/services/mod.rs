@@ -5,14 +5,21 @@
  * `monitoringservice`: Handles the web service requests.
  * `schedulingservice`: Handles the scheduling of the monitoring tasks.
  * `databaseservice`: Handles the database operations.
+ * `databasemigrations`: The schema migrations applied to the database.
+ * `databasespool`: Spools the database writes while the database is unavailable.
+ * `jobs`: The jobs scheduled besides the monitors.
+ * `notifiers`: Sends the notifications about monitors in error.
  */
 mod monitors;
 mod monitoringservice;
 mod schedulingservice;
 mod databaseservice;
+mod databasemigrations;
+mod databasespool;
 mod jobs;
+mod notifiers;
 
-pub use monitoringservice::MonitoringService;
-pub use schedulingservice::SchedulingService;
+pub use monitoringservice::{MonitoringService, ProcessUsage, TrackedProcessStatm};
+pub use schedulingservice::{SchedulingService, MonitorChanges};
 pub use databaseservice::DbService;
 
@@ -1,10 +1,52 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use log::error;
-use monitoring_agent_lib::proc::{ProcStat, ProcsCpuinfo, ProcsLoadavg, ProcsMeminfo, ProcsProcess, ProcsStatm};
+use monitoring_agent_lib::proc::{ProcStat, ProcsCpuinfo, ProcsDiskUsage, ProcsLoadavg, ProcsMeminfo, ProcsNetDev, ProcsNetDevRate, ProcsProcess, ProcsProcessFd, ProcsProcessIo, ProcsProcessIoRate, ProcsProcessStat, ProcsStatm};
 
-use crate::common::{ApplicationError, MonitorStatus, MonitorStatusType};
+use crate::common::{ApplicationError, Monitor, MonitorStatus, MonitorStatusType, MonitorType};
+
+use super::monitors::ProcessMonitor;
+
+/**
+ * Time between the two samples used to compute the current network throughput and process cpu and I/O use.
+ */
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/**
+ * The memory use of a process tracked by a process monitor.
+ */
+#[derive(Debug, Clone)]
+pub struct TrackedProcessStatm {
+    /// The name of the process monitor.
+    pub monitor: String,
+    /// The label of the rule that matched the process.
+    pub rule: String,
+    /// The name of the process.
+    pub name: String,
+    /// The process id.
+    pub pid: u32,
+    /// The memory use of the process.
+    pub statm: ProcsStatm,
+}
+
+/**
+ * The cpu, I/O and file descriptor use of a process.
+ */
+#[derive(Debug, Clone)]
+pub struct ProcessUsage {
+    /// The cpu times of the process.
+    pub stat: ProcsProcessStat,
+    /// The cpu usage in percent of one core between the two samples.
+    pub cpu_percent: Option<f64>,
+    /// The I/O counters. None if they can not be read.
+    pub io: Option<ProcsProcessIo>,
+    /// The I/O throughput between the two samples.
+    pub io_rate: Option<ProcsProcessIoRate>,
+    /// The open file descriptors. None if they can not be read.
+    pub fd: Option<ProcsProcessFd>,
+}
 
 /**
  * Monitoring Service.
@@ -52,6 +94,43 @@ impl MonitoringService {
         }
     }
 
+    /**
+     * Get the current disk use of the mounted disks.
+     *
+     * result: The result of getting the current disk use.
+     */
+    #[allow(clippy::unused_self)]
+    pub fn get_current_diskinfo(&self) -> Result<Vec<ProcsDiskUsage>, ApplicationError> {
+        let diskinfo = ProcsDiskUsage::get_disks_usage();
+        match diskinfo {
+            Ok(diskinfo) => Ok(diskinfo),
+            Err(err) => {
+                error!("Error: {}", err.message);
+                Err(ApplicationError::new("Error getting diskinfo"))                
+            }
+        }
+    }
+
+    /**
+     * Get the current network throughput. The throughput is computed from two samples of the interface counters.
+     *
+     * result: The result of getting the current network throughput.
+     */
+    #[allow(clippy::unused_self)]
+    pub async fn get_current_network(&self) -> Result<Vec<ProcsNetDevRate>, ApplicationError> {
+        let start = Instant::now();
+        let previous = ProcsNetDev::get_netdev().map_err(|err| {
+            error!("Error: {}", err.message);
+            ApplicationError::new("Error getting network statistics")
+        })?;
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        let current = ProcsNetDev::get_netdev().map_err(|err| {
+            error!("Error: {}", err.message);
+            ApplicationError::new("Error getting network statistics")
+        })?;
+        Ok(ProcsNetDevRate::from_netdevs(&previous, &current, start.elapsed().as_secs_f64()))
+    }
+
     /**
      * Get the current cpu information.
      *
@@ -128,6 +207,46 @@ impl MonitoringService {
         }
     }
 
+    /**
+     * Get the cpu, I/O and file descriptor use of a process. The cpu usage and I/O throughput are computed from
+     * two samples of the process counters.
+     * 
+     * `pid`: The process id.
+     * 
+     * result: The result of getting the process use.
+     * 
+     * # Errors
+     * - If there is an error reading the cpu times of the process.
+     */
+    #[allow(clippy::unused_self)]
+    pub async fn get_process_usage(&self, pid: u32) -> Result<ProcessUsage, ApplicationError> {
+        let start = Instant::now();
+        let previous_stat = ProcsProcessStat::get_process_stat(pid).map_err(|err| {
+            error!("Error: {}", err.message);
+            ApplicationError::new("Error getting process stat")
+        })?;
+        let previous_io = ProcsProcessIo::get_process_io(pid).ok();
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        let stat = ProcsProcessStat::get_process_stat(pid).map_err(|err| {
+            error!("Error: {}", err.message);
+            ApplicationError::new("Error getting process stat")
+        })?;
+        let io = ProcsProcessIo::get_process_io(pid).ok();
+        let elapsed_seconds = start.elapsed().as_secs_f64();
+        let cpu_percent = ProcsProcessStat::get_cpu_percent(&previous_stat, &stat, elapsed_seconds);
+        let io_rate = match (&previous_io, &io) {
+            (Some(previous_io), Some(io)) if previous_stat.start_time == stat.start_time => ProcsProcessIoRate::from_io(previous_io, io, elapsed_seconds),
+            _ => None,
+        };
+        Ok(ProcessUsage {
+            stat,
+            cpu_percent,
+            io,
+            io_rate,
+            fd: ProcsProcessFd::get_process_fd(pid).ok(),
+        })
+    }
+
     /**
      * Get the process threads.
      * 
@@ -175,6 +294,44 @@ impl MonitoringService {
         }
     }
 
+    /**
+     * Get the current memory use of the processes tracked by the process monitors.
+     * Processes that exit before the memory use is read are skipped.
+     * 
+     * `monitors`: The monitor configurations. Only the process monitors are used.
+     * 
+     * result: The memory use of the tracked processes.
+     */
+    #[allow(clippy::unused_self)]
+    pub fn get_tracked_process_statm(&self, monitors: &[Monitor]) -> Vec<TrackedProcessStatm> {
+        let mut tracked_statm = Vec::new();
+        for monitor in monitors {
+            let MonitorType::Process { application_names, pids, regexp, rules, .. } = &monitor.details else {
+                continue;
+            };
+            let processes = match ProcessMonitor::get_tracked_processes(application_names, pids, regexp, rules) {
+                Ok(processes) => processes,
+                Err(err) => {
+                    error!("Error getting processes: {}", err.message);
+                    continue;
+                }
+            };
+            for (rule, process) in processes {
+                let Some(pid) = process.pid else { continue };
+                if let Ok(statm) = ProcsStatm::get_statm(pid) {
+                    tracked_statm.push(TrackedProcessStatm {
+                        monitor: monitor.name.clone(),
+                        rule,
+                        name: process.name.unwrap_or("Unknown".to_string()),
+                        pid,
+                        statm,
+                    });
+                }
+            }
+        }
+        tracked_statm
+    }
+
     /**
      * Get the current statm.
      * 
@@ -238,6 +395,54 @@ mod test {
         assert_eq!(status.lock().unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_get_current_diskinfo() {
+        let monitoring_service = MonitoringService::new();
+        let diskinfo = monitoring_service.get_current_diskinfo();
+        assert!(diskinfo.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_current_network() {
+        let monitoring_service = MonitoringService::new();
+        let network = monitoring_service.get_current_network().await;
+        assert!(network.unwrap().iter().any(|rate| rate.interface == "lo"));
+    }
+
+    #[test]
+    fn test_get_tracked_process_statm() {
+        let monitoring_service = MonitoringService::new();
+        let monitor = Monitor {
+            name: "process".to_string(),
+            description: None,
+            schedule: "* * * * * *".to_string(),
+            store: crate::common::configuration::DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
+            details: MonitorType::Process {
+                application_names: None,
+                pids: Some(vec![std::process::id()]),
+                regexp: None,
+                rules: None,
+                threshold_mem_warn: None,
+                threshold_mem_error: None,
+                threshold_cpu_warn: None,
+                threshold_cpu_error: None,
+                threshold_io_warn: None,
+                threshold_io_error: None,
+                threshold_fd_warn: None,
+                threshold_fd_error: None,
+                store_values: false,
+            },
+        };
+        let tracked_statm = monitoring_service.get_tracked_process_statm(&[monitor]);
+        assert_eq!(tracked_statm.len(), 1);
+        assert_eq!(tracked_statm[0].monitor, "process");
+        assert_eq!(tracked_statm[0].rule, format!("pid={}", std::process::id()));
+        assert_eq!(tracked_statm[0].pid, std::process::id());
+    }
+
     #[test]
     fn test_get_current_meminfo() {
         let monitoring_service = MonitoringService::new();
@@ -273,6 +478,17 @@ mod test {
         assert!(cpuinfo.is_ok());
     }    
 
+    #[tokio::test]
+    async fn test_get_process_usage() {
+        let monitoring_service = MonitoringService::new();
+        let usage = monitoring_service.get_process_usage(std::process::id()).await.unwrap();
+        assert_eq!(usage.stat.pid, std::process::id());
+        assert!(usage.cpu_percent.is_some());
+        assert!(usage.io_rate.is_some());
+        assert!(usage.fd.is_some());
+        assert!(monitoring_service.get_process_usage(0).await.is_err());
+    }
+
     #[test]
     fn test_get_threads() {
         let monitoring_service = MonitoringService::new();
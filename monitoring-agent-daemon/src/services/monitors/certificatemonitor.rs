@@ -1,19 +1,42 @@
-use openssl::{asn1::Asn1Time, x509::X509};
+use openssl::{asn1::Asn1Time, nid::Nid, pkey::{PKeyRef, Private}, x509::{X509Ref, X509VerifyResult, X509}};
+use openssl_sys::{
+    X509_V_ERR_CA_KEY_TOO_SMALL, X509_V_ERR_CA_MD_TOO_WEAK, X509_V_ERR_CERT_HAS_EXPIRED, X509_V_ERR_CERT_NOT_YET_VALID, X509_V_ERR_EE_KEY_TOO_SMALL,
+    X509_V_ERR_HOSTNAME_MISMATCH, X509_V_ERR_IP_ADDRESS_MISMATCH, X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT, X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY,
+    X509_V_ERR_UNABLE_TO_VERIFY_LEAF_SIGNATURE,
+};
 use tokio_cron_scheduler::Job;
 use tracing::{debug, error};
 
 use crate::{
-    common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status},
-    services::monitors::Monitor,
+    common::{configuration::{CertificateEndpoint, CertificateFile, DatabaseStoreLevel}, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status},
+    services::monitors::{
+        certificatefile::{expand_path, read_certificates, read_private_key, verify_chain},
+        common::check_with_timeout,
+        remotecertificate::{fetch_certificates, get_server_name},
+        Monitor,
+    },
 };
 
+/// Signature algorithms flagged as weak.
+const WEAK_SIGNATURE_ALGORITHMS: [Nid; 8] = [
+    Nid::MD2WITHRSAENCRYPTION,
+    Nid::MD4WITHRSAENCRYPTION,
+    Nid::MD5WITHRSAENCRYPTION,
+    Nid::MD5WITHRSA,
+    Nid::SHA1WITHRSAENCRYPTION,
+    Nid::SHA1WITHRSA,
+    Nid::DSAWITHSHA1,
+    Nid::ECDSA_WITH_SHA1,
+];
+
 /**
  * The certificate monitor.
  *
  * Fields:
  * `name`: The name.
  * `status`: The status.
- * `certificates`: Paths to the certificates.
+ * `certificates`: Certificate files, directories and glob patterns.
+ * `endpoints`: Remote endpoints presenting certificates.
  * `threshold_days_warn`: The threshold days for a warning.
  * `threshold_days_error`: The threshold days for an error.
  * `database_service`: The database service.
@@ -25,8 +48,10 @@ pub struct CertificateMonitor {
     name: String,
     /// The status.
     status: MonitorStatusType,
-    /// Paths to certificates.
-    certificates: Vec<String>,
+    /// Certificate files, directories and glob patterns.
+    certificates: Vec<CertificateFile>,
+    /// Remote endpoints presenting certificates.
+    endpoints: Vec<CertificateEndpoint>,
     /// The threshold days for a warning.
     threshold_days_warn: u32,
     /// The threshold days for an error.
@@ -44,7 +69,8 @@ impl CertificateMonitor {
      * `name`: The name.
      * `description`: The description.
      * `status`: The status.
-     * `certificates`: The certificates.
+     * `certificates`: The certificate files, directories and glob patterns.
+     * `endpoints`: The remote endpoints.
      * `threshold_days_warn`: The threshold days for a warning.
      * `threshold_days_error`: The threshold days for an error.
      * `database_service`: The database service.
@@ -57,7 +83,8 @@ impl CertificateMonitor {
         name: &str,
         description: &Option<String>,
         status: &MonitorStatusType,
-        certificates: Vec<String>,
+        certificates: Vec<CertificateFile>,
+        endpoints: Vec<CertificateEndpoint>,
         threshold_days_warn: u32,
         threshold_days_error: u32,
         database_service: &DatabaseServiceType,
@@ -79,6 +106,7 @@ impl CertificateMonitor {
             name: name.to_string(),
             status: status.clone(),
             certificates,
+            endpoints,
             threshold_days_warn,
             threshold_days_error,
             database_service: database_service.clone(),
@@ -86,14 +114,17 @@ impl CertificateMonitor {
         }
     }
 
-    pub fn get_certificate_job(certificate_monitor: CertificateMonitor, schedule: &str) -> Result<Job, ApplicationError> {
+    pub fn get_certificate_job(certificate_monitor: CertificateMonitor, schedule: &str, timeout: Option<u64>) -> Result<Job, ApplicationError> {
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut certificate_monitor = certificate_monitor.clone();
+                let certificate_monitor = certificate_monitor.clone();
                 async move {
-                    let _ = certificate_monitor.check().await.map_err(|err| {
-                        error!("Error checking monitor: {:?}", err);
-                    });
+                    check_with_timeout(certificate_monitor, timeout, |mut certificate_monitor| async move {
+                        let _ = certificate_monitor.check().await.map_err(|err| {
+                            error!("Error checking monitor: {:?}", err);
+                        });
+                    })
+                    .await;
                 }
             })
         });
@@ -117,49 +148,252 @@ impl CertificateMonitor {
         let warn_time = Asn1Time::days_from_now(self.threshold_days_warn)
             .map_err(|err| ApplicationError::new(&format!("Error getting warn time: {err:?}")))?;
         /*
-         * Loop through the certificates and check.
+         * Loop through the certificate files and check.
          */
         for certificate in &self.certificates {
-            /*
-             * Read the certificate.
-             */
-            let cert = Self::read_certificate(certificate)?;
-            /*
-             * Check if the not after is less than the error time.
-             */
-            let not_after_error = Self::check_not_after(&cert, &error_time)?;
-            /*
-             * If the not after is less than the error time, add to errors.
-             */
-            if not_after_error {
-                vec_errors.push(format!(
-                    "Certificate: {:?} will expire in less than {:?} days",
-                    certificate, self.threshold_days_error
-                ));
-                continue;
+            self.check_certificate_file(certificate, &error_time, &warn_time, &mut vec_errors, &mut vec_warns)?;
+        }
+        /*
+         * Check the certificates presented by the endpoints.
+         */
+        for endpoint in &self.endpoints {
+            self.check_endpoint(endpoint, &error_time, &warn_time, &mut vec_errors, &mut vec_warns).await?;
+        }
+        /*
+         * Set the status.
+         */
+        self.set_status_info(vec_errors, vec_warns).await;
+        Ok(())
+    }
+
+    /**
+     * Check the certificates in the files of a path. Every certificate in a file is checked against
+     * the thresholds. The first certificate in a file is checked against the private key if it is a
+     * leaf certificate, and the chain is verified against the CA file if configured. A file that can
+     * not be read is an error.
+     *
+     * `certificate`: The certificate file, directory or glob pattern.
+     * `error_time`: The error time.
+     * `warn_time`: The warn time.
+     * `vec_errors`: Errors found.
+     * `vec_warns`: Warnings found.
+     *
+     * Errors:
+     * - Error comparing certificate not after to the thresholds.
+     * - Error reading the public key of a certificate.
+     */
+    fn check_certificate_file(&self, certificate: &CertificateFile, error_time: &Asn1Time, warn_time: &Asn1Time, vec_errors: &mut Vec<String>, vec_warns: &mut Vec<String>) -> Result<(), ApplicationError> {
+        let paths = match expand_path(&certificate.path) {
+            Ok(paths) => paths,
+            Err(err) => {
+                vec_errors.push(format!("Certificate: {:?} {}", certificate.path, err.message));
+                return Ok(());
+            }
+        };
+        let private_key = match &certificate.private_key {
+            Some(private_key) => match read_private_key(private_key, &certificate.private_key_password) {
+                Ok(private_key) => Some(private_key),
+                Err(err) => {
+                    vec_errors.push(format!("Private key: {:?} {}", private_key, err.message));
+                    None
+                }
+            },
+            None => None,
+        };
+        let mut bundles = Vec::new();
+        for path in paths {
+            let source = path.display().to_string();
+            match read_certificates(&path, &certificate.password) {
+                Ok(bundle) => bundles.push((source, bundle)),
+                Err(err) => vec_errors.push(format!("Certificate: {:?} {}", source, err.message)),
+            }
+        }
+        let certificates: Vec<&X509> = bundles.iter().flat_map(|(_, bundle)| &bundle.chain).collect();
+        for (source, bundle) in &bundles {
+            for cert in &bundle.chain {
+                self.check_expiry(source, cert, error_time, warn_time, vec_errors, vec_warns)?;
+            }
+            let leaf = &bundle.chain[0];
+            let private_key = if Self::is_leaf(leaf, &certificates) {
+                private_key.as_ref().or(bundle.private_key.as_ref())
+            } else {
+                bundle.private_key.as_ref()
             };
+            if let Some(private_key) = private_key {
+                if !Self::is_key_pair(leaf, private_key)? {
+                    vec_errors.push(format!("Certificate: {:?} {} does not match the private key", source, Self::get_subject(leaf)));
+                }
+            }
+            if let Some(ca_file) = &certificate.ca_file {
+                match verify_chain(&bundle.chain, ca_file) {
+                    Ok(Some(verify_error)) => {
+                        for problem in Self::get_chain_problems(&[verify_error], "") {
+                            vec_errors.push(format!("Certificate: {:?} {} {}", source, Self::get_subject(leaf), problem));
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(err) => vec_errors.push(format!("Certificate: {:?} {}", source, err.message)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Check the certificate chain presented by an endpoint. Every certificate in the chain is checked
+     * against the thresholds. Hostname mismatch and an incomplete chain are errors, weak signature
+     * algorithms are warnings. An endpoint that can not be reached is an error.
+     *
+     * `endpoint`: The endpoint.
+     * `error_time`: The error time.
+     * `warn_time`: The warn time.
+     * `vec_errors`: Errors found.
+     * `vec_warns`: Warnings found.
+     *
+     * Errors:
+     * - Error comparing certificate not after to the thresholds.
+     */
+    async fn check_endpoint(&self, endpoint: &CertificateEndpoint, error_time: &Asn1Time, warn_time: &Asn1Time, vec_errors: &mut Vec<String>, vec_warns: &mut Vec<String>) -> Result<(), ApplicationError> {
+        let server_name = get_server_name(endpoint)?;
+        let certificates = match fetch_certificates(endpoint).await {
+            Ok(certificates) => certificates,
+            Err(err) => {
+                vec_errors.push(format!("Endpoint: {:?} could not be checked: {}", endpoint.address, err.message));
+                return Ok(());
+            }
+        };
+        for (index, cert) in certificates.chain.iter().enumerate() {
+            self.check_expiry(&endpoint.address, cert, error_time, warn_time, vec_errors, vec_warns)?;
             /*
-             * Check if the not after is less than the warn time.
-             */
-            let not_after_warn = Self::check_not_after(&cert, &warn_time)?;
-            /*
-             * If the not after is less than the warn time, add to warnings.
+             * The signature of a self signed root is not used when verifying the chain.
              */
-            if not_after_warn {
+            if (index == 0 || cert.issued(cert) != X509VerifyResult::OK) && Self::is_weak_signature(cert) {
                 vec_warns.push(format!(
-                    "Certificate: {:?} will expire in less than {:?} days",
-                    certificate, self.threshold_days_warn
+                    "Certificate: {:?} {} is signed with weak algorithm {}",
+                    endpoint.address, Self::get_subject(cert), cert.signature_algorithm().object()
                 ));
-                continue;
-            };
+            }
+        }
+        for problem in Self::get_chain_problems(&certificates.verify_errors, &server_name) {
+            vec_errors.push(format!("Endpoint: {:?} {}", endpoint.address, problem));
+        }
+        Ok(())
+    }
+
+    /**
+     * Check the certificate against the thresholds.
+     *
+     * `source`: The file or endpoint of the certificate.
+     * `cert`: The certificate.
+     * `error_time`: The error time.
+     * `warn_time`: The warn time.
+     * `vec_errors`: Errors found.
+     * `vec_warns`: Warnings found.
+     *
+     * Errors:
+     * - Error comparing certificate not after to the thresholds.
+     */
+    fn check_expiry(&self, source: &str, cert: &X509Ref, error_time: &Asn1Time, warn_time: &Asn1Time, vec_errors: &mut Vec<String>, vec_warns: &mut Vec<String>) -> Result<(), ApplicationError> {
+        if Self::check_not_after(cert, error_time)? {
+            vec_errors.push(format!(
+                "Certificate: {:?} {} will expire in less than {:?} days",
+                source, Self::get_subject(cert), self.threshold_days_error
+            ));
+        } else if Self::check_not_after(cert, warn_time)? {
+            vec_warns.push(format!(
+                "Certificate: {:?} {} will expire in less than {:?} days",
+                source, Self::get_subject(cert), self.threshold_days_warn
+            ));
         }
-        /*
-         * Set the status.
-         */
-        self.set_status_info(vec_errors, vec_warns).await;
         Ok(())
     }
 
+    /**
+     * Check if the private key belongs to the certificate.
+     *
+     * `cert`: The certificate.
+     * `private_key`: The private key.
+     *
+     * Returns: True if the public key of the certificate matches the private key.
+     *
+     * Errors:
+     * - Error reading the public key of the certificate.
+     */
+    fn is_key_pair(cert: &X509Ref, private_key: &PKeyRef<Private>) -> Result<bool, ApplicationError> {
+        let public_key = cert
+            .public_key()
+            .map_err(|err| ApplicationError::new(&format!("Error reading public key: {err:?}")))?;
+        Ok(public_key.public_eq(private_key))
+    }
+
+    /**
+     * Check if a certificate is a leaf certificate, a certificate that did not issue any of the
+     * other certificates. Like the intermediate certificate in chain.pem is not a leaf certificate.
+     *
+     * `cert`: The certificate.
+     * `certificates`: The certificates read from the files.
+     *
+     * Returns: True if the certificate is a leaf certificate.
+     */
+    fn is_leaf(cert: &X509Ref, certificates: &[&X509]) -> bool {
+        !certificates.iter().any(|other| *other != cert && cert.issued(other) == X509VerifyResult::OK)
+    }
+
+    /**
+     * Get the problems found verifying a chain. Expiry and weak algorithms are left out, they are checked
+     * on the certificates.
+     *
+     * `verify_errors`: The errors found verifying the chain.
+     * `server_name`: The server name the chain was verified against.
+     *
+     * Returns: The problems.
+     */
+    fn get_chain_problems(verify_errors: &[X509VerifyResult], server_name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        for verify_error in verify_errors {
+            let problem = match verify_error.as_raw() {
+                X509_V_ERR_HOSTNAME_MISMATCH | X509_V_ERR_IP_ADDRESS_MISMATCH => format!("hostname mismatch, certificate is not valid for {server_name}"),
+                X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT | X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY | X509_V_ERR_UNABLE_TO_VERIFY_LEAF_SIGNATURE => format!("incomplete chain: {verify_error}"),
+                X509_V_ERR_CERT_NOT_YET_VALID | X509_V_ERR_CERT_HAS_EXPIRED | X509_V_ERR_EE_KEY_TOO_SMALL | X509_V_ERR_CA_KEY_TOO_SMALL | X509_V_ERR_CA_MD_TOO_WEAK => continue,
+                _ => format!("chain verification failed: {verify_error}"),
+            };
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
+        }
+        problems
+    }
+
+    /**
+     * Check if the certificate is signed with a weak signature algorithm.
+     *
+     * `cert`: The certificate.
+     *
+     * Returns: True if the signature algorithm is weak.
+     */
+    fn is_weak_signature(cert: &X509Ref) -> bool {
+        WEAK_SIGNATURE_ALGORITHMS.contains(&cert.signature_algorithm().object().nid())
+    }
+
+    /**
+     * Get the subject of the certificate, e.g. CN=www.example.com, O=Example.
+     *
+     * `cert`: The certificate.
+     *
+     * Returns: The subject.
+     */
+    fn get_subject(cert: &X509Ref) -> String {
+        cert.subject_name()
+            .entries()
+            .map(|entry| {
+                let name = entry.object().nid().short_name().unwrap_or("?");
+                let value = entry.data().to_string().unwrap_or_default();
+                format!("{name}={value}")
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
     /**
      * Set the status.
      *
@@ -182,25 +416,6 @@ impl CertificateMonitor {
         }
     }
 
-    /**
-     * Read the certificate.
-     *
-     * `certificate`: The certificate.
-     *
-     * Returns: The certificate.
-     *
-     * Errors:
-     * - Error reading certificate.
-     * - Error parsing certificate.
-     */
-    fn read_certificate(certificate: &String) -> Result<X509, ApplicationError> {
-        let cert_vec = std::fs::read(certificate)
-            .map_err(|err| ApplicationError::new(&format!("Error reading certificate: {err:?}")))?;
-        let cert = X509::from_pem(&cert_vec)
-            .map_err(|err| ApplicationError::new(&format!("Error parsing certificate: {err:?}")))?;
-        Ok(cert)
-    }
-
     /**
      * Check if the certificate is older than the error time.
      *
@@ -212,7 +427,7 @@ impl CertificateMonitor {
      * Errors:
      * - Error comparing certificate not after to error threshold.
      */
-    fn check_not_after(cert: &X509, check_time: &Asn1Time) -> Result<bool, ApplicationError> {
+    fn check_not_after(cert: &X509Ref, check_time: &Asn1Time) -> Result<bool, ApplicationError> {
         let not_after_error = cert.not_after().compare(check_time).map_err(|err| {
             ApplicationError::new(&format!(
                 "Error comparing certificate not after to error threshold: {err:?}"
@@ -268,13 +483,225 @@ mod test {
 
     use std::{collections::HashMap, sync::{Arc, Mutex}};
 
+    use openssl::{hash::MessageDigest, pkey::PKey, x509::X509};
+
+    use crate::{common::configuration::CertificateSource, services::monitors::remotecertificate::test::{create_certificate, start_tls_server}};
+
     use super::*;
 
+    /**
+     * Check a single endpoint serving the chain and return the status.
+     *
+     * `test_name`: Name used for the CA file.
+     * `chain`: The chain presented by the server.
+     * `key`: The key of the leaf certificate.
+     * `ca`: The CA trusted by the monitor.
+     * `server_name`: The server name checked.
+     */
+    async fn check_endpoint_status(test_name: &str, chain: Vec<X509>, key: PKey<Private>, ca: Option<&X509>, server_name: &str) -> Status {
+        let ca_file = ca.map(|ca| {
+            let path = std::env::temp_dir().join(format!("monitoring-agent-ca-{test_name}-{}.pem", std::process::id()));
+            std::fs::write(&path, ca.to_pem().unwrap()).unwrap();
+            path.to_string_lossy().to_string()
+        });
+        let address = start_tls_server(chain, key, None);
+        let endpoint = CertificateEndpoint { address, server_name: Some(server_name.to_string()), starttls: None, ca_file };
+        let mut certificate_monitor = CertificateMonitor::new(
+            "test",
+            &None,
+            &Arc::new(Mutex::new(HashMap::new())),
+            vec![],
+            vec![endpoint],
+            10,
+            5,
+            &Arc::new(None),
+            &DatabaseStoreLevel::None,
+        );
+        certificate_monitor.check().await.unwrap();
+        let status = certificate_monitor.get_status();
+        let status_lock = status.lock().unwrap();
+        status_lock.get("test").unwrap().status.clone()
+    }
+
+    /**
+     * Check the certificate files and return the status.
+     *
+     * `certificates`: The certificate files.
+     */
+    async fn check_files_status(certificates: Vec<CertificateFile>) -> Status {
+        let mut certificate_monitor = CertificateMonitor::new(
+            "test",
+            &None,
+            &Arc::new(Mutex::new(HashMap::new())),
+            certificates,
+            vec![],
+            10,
+            5,
+            &Arc::new(None),
+            &DatabaseStoreLevel::None,
+        );
+        certificate_monitor.check().await.unwrap();
+        let status = certificate_monitor.get_status();
+        let status_lock = status.lock().unwrap();
+        status_lock.get("test").unwrap().status.clone()
+    }
+
+    #[tokio::test]
+    async fn test_check_certificate_files() {
+        let directory = std::env::temp_dir().join(format!("monitoring-agent-certificate-files-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        for site in ["a.example.com", "b.example.com"] {
+            std::fs::create_dir_all(directory.join(site)).unwrap();
+        }
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (valid, valid_key) = create_certificate("a.example.com", &["a.example.com"], Some((&ca, &ca_key)), MessageDigest::sha256(), 30);
+        let (expiring, _) = create_certificate("b.example.com", &["b.example.com"], Some((&ca, &ca_key)), MessageDigest::sha256(), 3);
+        let (other_ca, _) = create_certificate("Other CA", &[], None, MessageDigest::sha256(), 365);
+        std::fs::write(directory.join("a.example.com/fullchain.pem"), [valid.to_pem().unwrap(), ca.to_pem().unwrap()].concat()).unwrap();
+        std::fs::write(directory.join("a.example.com/privkey.pem"), valid_key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        std::fs::write(directory.join("b.example.com/fullchain.pem"), [expiring.to_pem().unwrap(), ca.to_pem().unwrap()].concat()).unwrap();
+        std::fs::write(directory.join("ca.pem"), ca.to_pem().unwrap()).unwrap();
+        std::fs::write(directory.join("other-ca.pem"), other_ca.to_pem().unwrap()).unwrap();
+        let pattern = format!("{}/*/fullchain.pem", directory.display());
+        let file = |path: &str, private_key: Option<&str>, ca_file: &str| CertificateFile {
+            path: path.to_string(),
+            password: None,
+            private_key: private_key.map(|private_key| directory.join(private_key).to_string_lossy().to_string()),
+            private_key_password: None,
+            ca_file: Some(directory.join(ca_file).to_string_lossy().to_string()),
+        };
+
+        let status = check_files_status(vec![file(&directory.join("a.example.com/fullchain.pem").to_string_lossy(), Some("a.example.com/privkey.pem"), "ca.pem")]).await;
+        assert_eq!(status, Status::Ok);
+
+        std::fs::write(directory.join("a.example.com/cert.pem"), valid.to_pem().unwrap()).unwrap();
+        std::fs::write(directory.join("a.example.com/chain.pem"), ca.to_pem().unwrap()).unwrap();
+        let status = check_files_status(vec![file(&directory.join("a.example.com").to_string_lossy(), Some("a.example.com/privkey.pem"), "ca.pem")]).await;
+        assert_eq!(status, Status::Ok);
+
+        let Status::Error { message } = check_files_status(vec![file(&pattern, Some("a.example.com/privkey.pem"), "other-ca.pem")]).await else {
+            panic!("Expected error");
+        };
+        let a_file = directory.join("a.example.com/fullchain.pem").display().to_string();
+        let b_file = directory.join("b.example.com/fullchain.pem").display().to_string();
+        assert!(message.contains(&format!("Certificate: {a_file:?} CN=a.example.com chain verification failed: self-signed certificate in certificate chain")), "{message}");
+        assert!(message.contains(&format!("Certificate: {b_file:?} CN=b.example.com will expire in less than 5 days")), "{message}");
+        assert!(message.contains(&format!("Certificate: {b_file:?} CN=b.example.com does not match the private key")), "{message}");
+        assert!(!message.contains(&format!("Certificate: {a_file:?} CN=a.example.com does not match")), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_check_certificate_files_unreadable() {
+        let Status::Error { message } = check_files_status(vec![
+            CertificateSource::Path("resources/test/client_cert/missing.cer".to_string()).get_file(),
+            CertificateSource::Path("resources/test/server_cert/server_private_key.pem".to_string()).get_file(),
+        ]).await else {
+            panic!("Expected error");
+        };
+        assert!(message.contains("Certificate: \"resources/test/client_cert/missing.cer\" Error reading certificate"), "{message}");
+        assert!(message.contains("Certificate: \"resources/test/server_cert/server_private_key.pem\" No certificates in file"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 30);
+        let status = check_endpoint_status("ok", vec![leaf], leaf_key, Some(&ca), "localhost").await;
+        assert_eq!(status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_expiry() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 7);
+        let Status::Warn { message } = check_endpoint_status("warn", vec![leaf], leaf_key, Some(&ca), "localhost").await else {
+            panic!("Expected warning");
+        };
+        assert!(message.ends_with("CN=localhost will expire in less than 10 days"), "{message}");
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 3);
+        let Status::Error { message } = check_endpoint_status("error", vec![leaf], leaf_key, Some(&ca), "localhost").await else {
+            panic!("Expected error");
+        };
+        assert!(message.contains("CN=localhost will expire in less than 5 days"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_hostname_mismatch() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 30);
+        let Status::Error { message } = check_endpoint_status("mismatch", vec![leaf], leaf_key, Some(&ca), "www.example.com").await else {
+            panic!("Expected error");
+        };
+        assert!(message.contains("hostname mismatch, certificate is not valid for www.example.com"), "{message}");
+        assert!(!message.contains("incomplete chain"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_untrusted_root() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (other_ca, _) = create_certificate("Other CA", &[], None, MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 30);
+        let Status::Error { message } = check_endpoint_status("untrusted", vec![leaf, ca], leaf_key, Some(&other_ca), "localhost").await else {
+            panic!("Expected error");
+        };
+        assert!(message.contains("chain verification failed: self-signed certificate in certificate chain"), "{message}");
+        assert!(!message.contains("hostname mismatch"), "{message}");
+    }
+
     #[test]
-    fn test_read_certificate() {
-        let certificate = "resources/test/client_cert/client.cer".to_string();
-        let result = CertificateMonitor::read_certificate(&certificate);
-        assert!(result.is_ok());
+    fn test_get_chain_problems() {
+        let verify_errors = [
+            openssl_sys::X509_V_ERR_HOSTNAME_MISMATCH,
+            openssl_sys::X509_V_ERR_IP_ADDRESS_MISMATCH,
+            openssl_sys::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY,
+            openssl_sys::X509_V_ERR_CERT_HAS_EXPIRED,
+            openssl_sys::X509_V_ERR_CA_MD_TOO_WEAK,
+            openssl_sys::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN,
+        ]
+        .map(|verify_error| unsafe { X509VerifyResult::from_raw(verify_error) });
+        assert_eq!(CertificateMonitor::get_chain_problems(&verify_errors, "www.example.com"), vec![
+            "hostname mismatch, certificate is not valid for www.example.com".to_string(),
+            "incomplete chain: unable to get local issuer certificate".to_string(),
+            "chain verification failed: self-signed certificate in certificate chain".to_string(),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_incomplete_chain() {
+        let (root, root_key) = create_certificate("Test Root", &[], None, MessageDigest::sha256(), 365);
+        let (intermediate, intermediate_key) = create_certificate("Test Intermediate", &[], Some((&root, &root_key)), MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&intermediate, &intermediate_key)), MessageDigest::sha256(), 30);
+        let Status::Error { message } = check_endpoint_status("incomplete", vec![leaf.clone()], leaf_key.clone(), Some(&root), "localhost").await else {
+            panic!("Expected error");
+        };
+        assert!(message.contains("incomplete chain"), "{message}");
+        let status = check_endpoint_status("complete", vec![leaf, intermediate], leaf_key, Some(&root), "localhost").await;
+        assert_eq!(status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_weak_signature() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha1(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha1(), 30);
+        let status = check_endpoint_status("weak", vec![leaf, ca.clone()], leaf_key, Some(&ca), "localhost").await;
+        let Status::Warn { message } = status else {
+            panic!("Expected warning: {status:?}");
+        };
+        assert!(message.ends_with("CN=localhost is signed with weak algorithm ecdsa-with-SHA1"), "{message}");
+        assert!(!message.contains("Test CA"), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_check_endpoint_unreachable() {
+        let endpoint = CertificateEndpoint { address: "127.0.0.1:1".to_string(), server_name: None, starttls: None, ca_file: None };
+        let mut certificate_monitor = CertificateMonitor::new("test", &None, &Arc::new(Mutex::new(HashMap::new())), vec![], vec![endpoint], 10, 5, &Arc::new(None), &DatabaseStoreLevel::None);
+        certificate_monitor.check().await.unwrap();
+        let status = certificate_monitor.get_status();
+        let status_lock = status.lock().unwrap();
+        let Status::Error { message } = &status_lock.get("test").unwrap().status else {
+            panic!("Expected error");
+        };
+        assert!(message.starts_with("Endpoint: \"127.0.0.1:1\" could not be checked: Error connecting to 127.0.0.1:1"), "{message}");
     }
 
     #[test]
@@ -297,7 +724,8 @@ mod test {
             "test",
             &None,
             &Arc::new(Mutex::new(HashMap::new())),
-            vec!["resources/test/client_cert/client.cer".to_string()],
+            vec![CertificateSource::Path("resources/test/client_cert/client.cer".to_string()).get_file()],
+            vec![],
             1,
             2,
             &Arc::new(None),
@@ -325,7 +753,8 @@ mod test {
             "test",
             &None,
             &Arc::new(Mutex::new(HashMap::new())),
-            vec!["resources/test/client_cert/client.cer".to_string()],
+            vec![CertificateSource::Path("resources/test/client_cert/client.cer".to_string()).get_file()],
+            vec![],
             1,
             2,
             &Arc::new(None),
@@ -353,7 +782,8 @@ mod test {
             "test",
             &None,
             &Arc::new(Mutex::new(HashMap::new())),
-            vec!["resources/test/client_cert/client.cer".to_string()],
+            vec![CertificateSource::Path("resources/test/client_cert/client.cer".to_string()).get_file()],
+            vec![],
             1,
             2,
             &Arc::new(None),
@@ -381,7 +811,8 @@ mod test {
             "test",
             &None,
             &Arc::new(Mutex::new(HashMap::new())),
-            vec!["resources/test/client_cert/client.cer".to_string()],
+            vec![CertificateSource::Path("resources/test/client_cert/client.cer".to_string()).get_file()],
+            vec![],
             1,
             2,
             &Arc::new(None),
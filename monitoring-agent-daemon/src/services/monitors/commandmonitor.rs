@@ -1,11 +1,89 @@
+use std::{collections::HashMap, process::Stdio};
+
 use log::{debug, error, info};
+use regex::Regex;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio_cron_scheduler::Job;
 
 use crate::{
-    common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status},
-    services::monitors::Monitor,
+    common::{configuration::{default_max_output_size, CommandMode, DatabaseStoreLevel}, ApplicationError, DatabaseServiceType, Metric, MonitorStatus, MonitorStatusType, Status},
+    services::monitors::{common::check_with_timeout, Monitor},
 };
 
+/// Maximum number of characters of the standard error included in the status message.
+const STDERR_MESSAGE_LENGTH: usize = 200;
+
+/**
+ * The options of a command monitor, as given in the command monitor configuration.
+ *
+ * `args`: The arguments to the command.
+ * `expected`: The expected output of the command.
+ * `mode`: How the result of the command is interpreted.
+ * `store_values`: Store the perfdata of Nagios plugins.
+ * `expected_exit_codes`: The accepted exit codes.
+ * `stdout_regex`: Regular expression the standard output must match.
+ * `stderr_regex`: Regular expression the standard error must match.
+ * `environment`: Environment variables for the command.
+ * `working_directory`: The directory the command runs in.
+ * `stdin`: Written to the standard input of the command.
+ * `user`: User name or id to run the command as.
+ * `group`: Group name or id to run the command as. Defaults to the primary group of the user.
+ * `max_output_size`: Maximum number of bytes read from the standard output and standard error.
+ *
+ */
+#[derive(Debug, Clone)]
+pub struct CommandOptions {
+    /// The arguments to the command.
+    pub args: Option<Vec<String>>,
+    /// The expected output of the command.
+    pub expected: Option<String>,
+    /// How the result of the command is interpreted.
+    pub mode: CommandMode,
+    /// Store the perfdata of Nagios plugins.
+    pub store_values: bool,
+    /// The accepted exit codes. If not given then only 0 is accepted.
+    pub expected_exit_codes: Option<Vec<i32>>,
+    /// Regular expression the standard output must match.
+    pub stdout_regex: Option<String>,
+    /// Regular expression the standard error must match.
+    pub stderr_regex: Option<String>,
+    /// Environment variables added to the environment of the daemon.
+    pub environment: Option<HashMap<String, String>>,
+    /// The directory the command runs in.
+    pub working_directory: Option<String>,
+    /// Written to the standard input of the command.
+    pub stdin: Option<String>,
+    /// User name or id to run the command as.
+    pub user: Option<String>,
+    /// Group name or id to run the command as.
+    pub group: Option<String>,
+    /// Maximum number of bytes read from the standard output and standard error.
+    pub max_output_size: usize,
+}
+
+impl Default for CommandOptions {
+    /**
+     * The defaults of the command monitor configuration.
+     */
+    fn default() -> Self {
+        CommandOptions {
+            args: None,
+            expected: None,
+            mode: CommandMode::Standard,
+            store_values: false,
+            expected_exit_codes: None,
+            stdout_regex: None,
+            stderr_regex: None,
+            environment: None,
+            working_directory: None,
+            stdin: None,
+            user: None,
+            group: None,
+            max_output_size: default_max_output_size(),
+        }
+    }
+}
+
 /**
  * Command Monitor.
  *
@@ -16,6 +94,17 @@ use crate::{
  * `command`: The command to run.
  * `args`: The arguments to the command.
  * `expected`: The expected output of the command.
+ * `mode`: How the result of the command is interpreted.
+ * `store_values`: Store the perfdata of Nagios plugins.
+ * `expected_exit_codes`: The accepted exit codes.
+ * `stdout_regex`: Regular expression the standard output must match.
+ * `stderr_regex`: Regular expression the standard error must match.
+ * `environment`: Environment variables for the command.
+ * `working_directory`: The directory the command runs in.
+ * `stdin`: Written to the standard input of the command.
+ * `uid`: The user id to run the command as.
+ * `gid`: The group id to run the command as.
+ * `max_output_size`: Maximum number of bytes read from the standard output and standard error.
  * `status`: The status of the monitor.
  * `database_service`: The database service.
  * `database_store_level`: The database store level.
@@ -31,6 +120,28 @@ pub struct CommandMonitor {
     pub args: Option<Vec<String>>,
     /// The expected output of the command. Used to check if the command ran successfully.
     pub expected: Option<String>,
+    /// How the result of the command is interpreted.
+    pub mode: CommandMode,
+    /// Store the perfdata of Nagios plugins.
+    store_values: bool,
+    /// The accepted exit codes. If not given then only 0 is accepted.
+    expected_exit_codes: Option<Vec<i32>>,
+    /// Regular expression the standard output must match.
+    stdout_regex: Option<Regex>,
+    /// Regular expression the standard error must match.
+    stderr_regex: Option<Regex>,
+    /// Environment variables added to the environment of the daemon.
+    environment: Option<HashMap<String, String>>,
+    /// The directory the command runs in.
+    working_directory: Option<String>,
+    /// Written to the standard input of the command.
+    stdin: Option<String>,
+    /// The user id to run the command as.
+    uid: Option<u32>,
+    /// The group id to run the command as.
+    gid: Option<u32>,
+    /// Maximum number of bytes read from the standard output and standard error.
+    max_output_size: usize,
     /// The current status of the monitor.
     pub status: MonitorStatusType,
     /// The database service.
@@ -44,27 +155,33 @@ impl CommandMonitor {
      * Create a new command monitor.
      *
      * name: The name of the monitor.
+     * `description`: The description of the monitor.
      * command: The command to run.
-     * args: The arguments to the command.
-     * expected: The expected output of the command.
+     * `options`: The options of the command.
      * status: The status of the monitor.
      * `database_service`: The database service.
      * `database_store_level`: The database store level.
      *
      * Returns: A new command monitor.
      *
+     * throws: `ApplicationError`: If a regular expression is invalid, the user or group does not exist, or a user
+     * or group is given and the daemon does not run as root.
      */
-    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: &str,
         description: &Option<String>,
         command: &str,
-        args: Option<Vec<String>>,
-        expected: Option<String>,
+        options: CommandOptions,
         status: &MonitorStatusType,
         database_service: &DatabaseServiceType,
         database_store_level: &DatabaseStoreLevel,
-    ) -> CommandMonitor {
+    ) -> Result<CommandMonitor, ApplicationError> {
+        let stdout_regex = CommandMonitor::get_regex("stdoutRegex", &options.stdout_regex)?;
+        let stderr_regex = CommandMonitor::get_regex("stderrRegex", &options.stderr_regex)?;
+        let (uid, gid) = CommandMonitor::get_ids(&options.user, &options.group)?;
+        if (uid.is_some() || gid.is_some()) && !CommandMonitor::is_root() {
+            return Err(ApplicationError::new("Running the command as another user or group requires the daemon to run as root"));
+        }
         let status_lock = status.lock();
         match status_lock {
             Ok(mut lock) => {
@@ -78,15 +195,110 @@ impl CommandMonitor {
             }
         }
 
-        CommandMonitor {
+        Ok(CommandMonitor {
             name: name.to_string(),
             command: command.to_string(),
-            args,
-            expected,
+            args: options.args,
+            expected: options.expected,
+            mode: options.mode,
+            store_values: options.store_values,
+            expected_exit_codes: options.expected_exit_codes,
+            stdout_regex,
+            stderr_regex,
+            environment: options.environment,
+            working_directory: options.working_directory,
+            stdin: options.stdin,
+            uid,
+            gid,
+            max_output_size: options.max_output_size,
             status: status.clone(),
             database_service: database_service.clone(),
             database_store_level: database_store_level.clone(),
+        })
+    }
+
+    /**
+     * Compile a regular expression from the configuration.
+     *
+     * `config_name`: The name of the configuration, used in the error message.
+     * `regex`: The regular expression.
+     *
+     * Returns: The compiled regular expression.
+     *
+     * throws: `ApplicationError`: If the regular expression is invalid.
+     */
+    fn get_regex(config_name: &str, regex: &Option<String>) -> Result<Option<Regex>, ApplicationError> {
+        regex
+            .as_ref()
+            .map(|regex| Regex::new(regex).map_err(|err| ApplicationError::new(&format!("Invalid {config_name} {regex}: {err}"))))
+            .transpose()
+    }
+
+    /**
+     * Get the user and group id to run the command as. Names are looked up in /etc/passwd and /etc/group,
+     * numeric ids are used as they are.
+     *
+     * `user`: User name or id.
+     * `group`: Group name or id. Defaults to the primary group of the user.
+     *
+     * Returns: The user id and the group id.
+     *
+     * throws: `ApplicationError`: If the user or group does not exist.
+     */
+    fn get_ids(user: &Option<String>, group: &Option<String>) -> Result<(Option<u32>, Option<u32>), ApplicationError> {
+        let mut uid = None;
+        let mut gid = None;
+        if let Some(user) = user {
+            match CommandMonitor::find_id_entry("/etc/passwd", user)? {
+                Some(fields) => {
+                    uid = fields.get(2).and_then(|id| id.parse::<u32>().ok());
+                    gid = fields.get(3).and_then(|id| id.parse::<u32>().ok());
+                }
+                None => uid = user.parse::<u32>().ok(),
+            }
+            if uid.is_none() {
+                return Err(ApplicationError::new(&format!("Unknown user {user}")));
+            }
+        }
+        if let Some(group) = group {
+            gid = match CommandMonitor::find_id_entry("/etc/group", group)? {
+                Some(fields) => fields.get(2).and_then(|id| id.parse::<u32>().ok()),
+                None => group.parse::<u32>().ok(),
+            };
+            if gid.is_none() {
+                return Err(ApplicationError::new(&format!("Unknown group {group}")));
+            }
         }
+        Ok((uid, gid))
+    }
+
+    /**
+     * Find the entry of a user or group in a passwd or group file.
+     *
+     * `path`: The file.
+     * `name`: The name or the id.
+     *
+     * Returns: The fields of the entry, or None if not found.
+     *
+     * throws: `ApplicationError`: If the file can not be read.
+     */
+    fn find_id_entry(path: &str, name: &str) -> Result<Option<Vec<String>>, ApplicationError> {
+        let content = std::fs::read_to_string(path).map_err(|err| ApplicationError::new(&format!("Could not read {path}: {err}")))?;
+        let entries: Vec<Vec<&str>> = content.lines().map(|line| line.split(':').collect()).collect();
+        let entry = entries
+            .iter()
+            .find(|fields| fields.first() == Some(&name))
+            .or_else(|| entries.iter().find(|fields| fields.get(2) == Some(&name)));
+        Ok(entry.map(|fields| fields.iter().map(|field| (*field).to_string()).collect()))
+    }
+
+    /**
+     * Check if the daemon runs as root.
+     *
+     * Returns: True if the effective user is root.
+     */
+    fn is_root() -> bool {
+        unsafe { libc::geteuid() == 0 }
     }
 
     /**
@@ -98,20 +310,73 @@ impl CommandMonitor {
      * Returns true if the command ran successfully, false otherwise.
      */
     fn is_command_success(&mut self, output: &std::process::Output, output_resp: &str) -> bool {
-        output.status.success()
-            && (self.expected.is_none()
-                || (self.expected.is_some()
-                    && self
-                        .expected
-                        .as_ref()
-                        .unwrap_or(&String::new())
-                        .eq(output_resp)))
+        self.get_failures(output, output_resp).is_empty()
+    }
+
+    /**
+     * Get the reasons the command failed.
+     *
+     * `output`: The output of the command.
+     * `output_resp`: The response of the command.
+     *
+     * Returns: The reasons, empty if the command ran successfully.
+     */
+    fn get_failures(&self, output: &std::process::Output, output_resp: &str) -> Vec<String> {
+        let mut failures = Vec::new();
+        match output.status.code() {
+            None => failures.push("terminated by a signal".to_string()),
+            Some(code) => {
+                let expected = match &self.expected_exit_codes {
+                    Some(expected_exit_codes) => expected_exit_codes.contains(&code),
+                    None => code == 0,
+                };
+                if !expected {
+                    failures.push(format!("exit code {code} is not expected"));
+                }
+            }
+        }
+        if self.expected.as_ref().is_some_and(|expected| expected != output_resp) {
+            failures.push("stdout is not the expected output".to_string());
+        }
+        if let Some(stdout_regex) = &self.stdout_regex {
+            if !stdout_regex.is_match(output_resp) {
+                failures.push(format!("stdout does not match {:?}", stdout_regex.as_str()));
+            }
+        }
+        if let Some(stderr_regex) = &self.stderr_regex {
+            if !stderr_regex.is_match(&String::from_utf8_lossy(&output.stderr)) {
+                failures.push(format!("stderr does not match {:?}", stderr_regex.as_str()));
+            }
+        }
+        failures
+    }
+
+    /**
+     * Get the status message of a failed command, with the standard error truncated.
+     *
+     * `output`: The output of the command.
+     * `output_resp`: The response of the command.
+     *
+     * Returns: The message.
+     */
+    fn get_error_message(&self, output: &std::process::Output, output_resp: &str) -> String {
+        let message = format!("Command failed: {}", self.get_failures(output, output_resp).join("; "));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            message
+        } else if stderr.chars().count() > STDERR_MESSAGE_LENGTH {
+            format!("{message}. stderr: {}...", stderr.chars().take(STDERR_MESSAGE_LENGTH).collect::<String>())
+        } else {
+            format!("{message}. stderr: {stderr}")
+        }
     }
 
     /**
      * Get a command monitor job.
      *
      * `schedule`: The schedule.
+     * `timeout`: The timeout of the check in seconds.
      * `name`: The name of the monitor.
      * `command`: The command to monitor.
      * `args`: The arguments.
@@ -127,15 +392,19 @@ impl CommandMonitor {
     pub fn get_command_monitor_job(
         command_monitor: CommandMonitor,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating Command monitor: {}", &command_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut command_monitor = command_monitor.clone();
+                let command_monitor = command_monitor.clone();
                 async move {
-                    let _ = command_monitor.check().await.map_err(|err| {
-                        error!("Error checking monitor: {:?}", err);
-                    });
+                    check_with_timeout(command_monitor, timeout, |mut command_monitor| async move {
+                        let _ = command_monitor.check().await.map_err(|err| {
+                            error!("Error checking monitor: {:?}", err);
+                        });
+                    })
+                    .await;
                 }
             })
         });
@@ -157,22 +426,19 @@ impl CommandMonitor {
      */
     async fn check(&mut self) -> Result<(), ApplicationError> {
         debug!("Checking monitor: {}", &self.name);
-        let mut command = std::process::Command::new(&self.command);
-        let command = match &self.args {
-            Some(args) => command.args(args),
-            None => &mut command,
-        };
-        let command_result = command.output();
+        let command_result = self.run_command().await;
         match command_result {
             Ok(output) => {
                 let output_resp = String::from_utf8_lossy(&output.stdout);
                 debug!("Command output: {}", output_resp);
-                if self.is_command_success(&output, &output_resp) {
+                if self.mode == CommandMode::Nagios {
+                    self.check_nagios_output(&output, &output_resp).await;
+                } else if self.is_command_success(&output, &output_resp) {
                     self.set_status(&Status::Ok).await;
                 } else {
                     info!("Monitor status error: {} - {:?}", &self.name, output);
                     self.set_status(&Status::Error {
-                        message: format!("Error running command: {output:?}"),
+                        message: self.get_error_message(&output, &output_resp),
                     })
                     .await;
                 }
@@ -190,6 +456,171 @@ impl CommandMonitor {
             }
         }
     }
+
+    /**
+     * Run the command. The standard input is written while the standard output and standard error are
+     * read, so a command filling a pipe does not block.
+     *
+     * Returns: The output of the command.
+     *
+     * throws: `std::io::Error`: If the command can not be started.
+     */
+    async fn run_command(&self) -> Result<std::process::Output, std::io::Error> {
+        let mut command = tokio::process::Command::new(&self.command);
+        if let Some(args) = &self.args {
+            command.args(args);
+        }
+        if let Some(environment) = &self.environment {
+            command.envs(environment);
+        }
+        if let Some(working_directory) = &self.working_directory {
+            command.current_dir(working_directory);
+        }
+        if let Some(uid) = self.uid {
+            command.uid(uid);
+        }
+        if let Some(gid) = self.gid {
+            command.gid(gid);
+        }
+        /*
+         * Kill the command if the check is cancelled by a timeout.
+         */
+        let mut child = command
+            .stdin(if self.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child.stdin.take();
+        let write_stdin = async {
+            if let (Some(mut stdin), Some(input)) = (stdin, &self.stdin) {
+                if let Err(err) = stdin.write_all(input.as_bytes()).await {
+                    debug!("Could not write stdin of {}: {:?}", &self.name, err);
+                }
+            }
+        };
+        let (_, stdout, stderr) = tokio::join!(
+            write_stdin,
+            CommandMonitor::read_output(child.stdout.take(), self.max_output_size),
+            CommandMonitor::read_output(child.stderr.take(), self.max_output_size)
+        );
+        Ok(std::process::Output { status: child.wait().await?, stdout: stdout?, stderr: stderr? })
+    }
+
+    /**
+     * Read the output of a command up to a maximum size. The rest is read and discarded.
+     *
+     * `reader`: The standard output or standard error.
+     * `max_output_size`: Maximum number of bytes kept.
+     *
+     * Returns: The output.
+     *
+     * throws: `std::io::Error`: If reading fails.
+     */
+    async fn read_output<R: AsyncRead + Unpin>(reader: Option<R>, max_output_size: usize) -> Result<Vec<u8>, std::io::Error> {
+        let mut output = Vec::new();
+        if let Some(mut reader) = reader {
+            (&mut reader).take(max_output_size as u64).read_to_end(&mut output).await?;
+            tokio::io::copy(&mut reader, &mut tokio::io::sink()).await?;
+        }
+        Ok(output)
+    }
+
+    /**
+     * Set the status from the result of a Nagios plugin, and store the perfdata. The message of the
+     * plugin is kept in the details of the status, since an Unknown status has no message.
+     *
+     * `output`: The output of the command.
+     * `output_resp`: The standard output of the command.
+     */
+    async fn check_nagios_output(&mut self, output: &std::process::Output, output_resp: &str) {
+        let (message, metrics) = CommandMonitor::parse_nagios_output(output_resp);
+        let message = CommandMonitor::get_nagios_message(output.status.code(), message);
+        let status = CommandMonitor::get_nagios_status(output.status.code(), &message);
+        if status != Status::Ok {
+            info!("Monitor status: {} - {:?} - {}", &self.name, status, message);
+        }
+        self.set_details(Some(message));
+        self.set_status(&status).await;
+        self.store_metrics(&metrics).await;
+    }
+
+    /**
+     * Parse the output of a Nagios plugin. The first line is the message. Perfdata follows a pipe on the
+     * first line, and a pipe on a later line starts perfdata continuing to the end of the output.
+     *
+     * `output_resp`: The standard output of the plugin.
+     *
+     * Returns: The message and the metrics.
+     */
+    fn parse_nagios_output(output_resp: &str) -> (String, Vec<Metric>) {
+        let mut lines = output_resp.lines();
+        let first_line = lines.next().unwrap_or_default();
+        let (message, first_perfdata) = first_line.split_once('|').unwrap_or((first_line, ""));
+        let mut perfdata = first_perfdata.to_string();
+        let mut in_long_perfdata = false;
+        for line in lines {
+            if in_long_perfdata {
+                perfdata.push(' ');
+                perfdata.push_str(line);
+            } else if let Some((_, long_perfdata)) = line.split_once('|') {
+                in_long_perfdata = true;
+                perfdata.push(' ');
+                perfdata.push_str(long_perfdata);
+            }
+        }
+        (message.trim().to_string(), Metric::parse_perfdata(&perfdata))
+    }
+
+    /**
+     * Get the message of a Nagios plugin. A plugin without output gets a message with the exit code.
+     *
+     * `code`: The exit code.
+     * `message`: The first output line of the plugin.
+     *
+     * Returns: The message.
+     */
+    fn get_nagios_message(code: Option<i32>, message: String) -> String {
+        if message.is_empty() {
+            format!("Plugin exited with code {}", code.unwrap_or(-1))
+        } else {
+            message
+        }
+    }
+
+    /**
+     * Get the status from the exit code of a Nagios plugin. Exit code 0, 1, 2 and 3 is Ok, Warn, Error
+     * and Unknown. Other exit codes, or a plugin killed by a signal, are Unknown.
+     *
+     * `code`: The exit code.
+     * `message`: The message of the plugin.
+     *
+     * Returns: The status.
+     */
+    fn get_nagios_status(code: Option<i32>, message: &str) -> Status {
+        match code {
+            Some(0) => Status::Ok,
+            Some(1) => Status::Warn { message: message.to_string() },
+            Some(2) => Status::Error { message: message.to_string() },
+            _ => Status::Unknown,
+        }
+    }
+
+    /**
+     * Store the metrics.
+     *
+     * `metrics`: The metrics reported by the plugin.
+     */
+    async fn store_metrics(&self, metrics: &[Metric]) {
+        if !self.store_values || metrics.is_empty() {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            if let Err(err) = database_service.store_metrics(&self.name, metrics).await {
+                error!("Error storing metrics: {:?}", err);
+            }
+        }
+    }
 }
 
 /**
@@ -246,16 +677,7 @@ mod test {
     async fn test_check_ls() {
         let status: MonitorStatusType =
             std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "ls",
-            None,
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "ls", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         monitor.check().await.unwrap();
         assert_eq!(
             status.lock().unwrap().get("test").unwrap().status,
@@ -270,16 +692,7 @@ mod test {
     async fn test_check_systemctl() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "systemctl",
-            Some(vec!["status".to_string(), "dbus.service".to_string()]),
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "systemctl", CommandOptions { args: Some(vec!["status".to_string(), "dbus.service".to_string()]), ..Default::default() }, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         monitor.check().await.unwrap();
         assert_eq!(
             status.lock().unwrap().get("test").unwrap().status,
@@ -294,16 +707,7 @@ mod test {
     async fn test_check_non_existing_command() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "grumpy",
-            None,
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "grumpy", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         let _ = monitor.check().await;
         assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Error running command: Os { code: 2, kind: NotFound, message: \"No such file or directory\" }".to_string() });
     }
@@ -315,20 +719,7 @@ mod test {
     async fn test_check_systemctl_service_is_active_command() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "systemctl",
-            Some(vec![
-                "show".to_string(),
-                "dbus.service".to_string(),
-                "--property=ActiveState".to_string(),
-            ]),
-            Some("ActiveState=active\n".to_string()),
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "systemctl", CommandOptions { args: Some(vec!["show".to_string(), "dbus.service".to_string(), "--property=ActiveState".to_string()]), expected: Some("ActiveState=active\n".to_string()), ..Default::default() }, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         let _ = monitor.check().await;
         assert_eq!(
             status.lock().unwrap().get("test").unwrap().status,
@@ -340,61 +731,246 @@ mod test {
     fn test_is_command_success_exitstatus_0() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "ls",
-            None,
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "ls", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         let output = std::process::Output {
             status: std::process::ExitStatus::from_raw(0),
             stdout: Vec::new(),
             stderr: Vec::new(),
         };
-        assert_eq!(monitor.is_command_success(&output, ""), true);
+        assert!(monitor.is_command_success(&output, ""));
     }
 
     #[test]
     fn test_is_command_success_exitstatus_1() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let mut monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "ls",
-            None,
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
-        );
+        let mut monitor = CommandMonitor::new("test", &None, "ls", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
         let output = std::process::Output {
             status: std::process::ExitStatus::from_raw(1),
             stdout: Vec::new(),
             stderr: Vec::new(),
         };
-        assert_eq!(monitor.is_command_success(&output, ""), false);
+        assert!(!monitor.is_command_success(&output, ""));
+    }
+
+    /**
+     * Test the check method with timeout. The command should be cancelled and the status set to error.
+     */
+    #[tokio::test]
+    async fn test_check_timeout() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitor = CommandMonitor::new("test", &None, "sleep", CommandOptions { args: Some(vec!["5".to_string()]), ..Default::default() }, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
+        let start = std::time::Instant::now();
+        check_with_timeout(monitor, Some(1), |mut monitor| async move {
+            let _ = monitor.check().await;
+        })
+        .await;
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+        assert_eq!(
+            status.lock().unwrap().get("test").unwrap().status,
+            Status::Error { message: "Check timed out after 1s".to_string() }
+        );
+    }
+
+    /**
+     * Test the check method with a timeout that is not reached.
+     */
+    #[tokio::test]
+    async fn test_check_within_timeout() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitor = CommandMonitor::new("test", &None, "ls", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
+        check_with_timeout(monitor, Some(5), |mut monitor| async move {
+            let _ = monitor.check().await;
+        })
+        .await;
+        assert_eq!(
+            status.lock().unwrap().get("test").unwrap().status,
+            Status::Ok
+        );
+    }
+
+    /**
+     * Create a command monitor running a shell script as a Nagios plugin.
+     *
+     * `script`: The script.
+     * `status`: The status.
+     */
+    fn get_nagios_monitor(script: &str, status: &MonitorStatusType) -> CommandMonitor {
+        CommandMonitor::new("test", &None, "sh", CommandOptions { args: Some(vec!["-c".to_string(), script.to_string()]), mode: CommandMode::Nagios, store_values: true, ..Default::default() }, status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None)
+        .unwrap()
+    }
+
+    /**
+     * Test the check method in Nagios mode. The exit code maps to the status and the first line is the message.
+     */
+    #[tokio::test]
+    async fn test_check_nagios() {
+        for (script, expected, details) in [
+            ("echo 'DISK OK - free space: / 60% | /=40%;80;90;0;100'", Status::Ok, "DISK OK - free space: / 60%"),
+            ("echo 'DISK WARNING - free space: / 15% | /=85%;80;90;0;100'; exit 1", Status::Warn { message: "DISK WARNING - free space: / 15%".to_string() }, "DISK WARNING - free space: / 15%"),
+            ("echo 'DISK CRITICAL - free space: / 5%'; echo 'long text'; exit 2", Status::Error { message: "DISK CRITICAL - free space: / 5%".to_string() }, "DISK CRITICAL - free space: / 5%"),
+            ("echo 'DISK UNKNOWN - no such mount'; exit 3", Status::Unknown, "DISK UNKNOWN - no such mount"),
+            ("exit 2", Status::Error { message: "Plugin exited with code 2".to_string() }, "Plugin exited with code 2"),
+            ("echo 'unexpected'; exit 4", Status::Unknown, "unexpected"),
+        ] {
+            let status: MonitorStatusType =
+            std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+            let mut monitor = get_nagios_monitor(script, &status);
+            monitor.set_status(&Status::Ok).await;
+            monitor.check().await.unwrap();
+            let monitor_status = status.lock().unwrap().get("test").unwrap().clone();
+            assert_eq!(monitor_status.status, expected, "{script}");
+            assert_eq!(monitor_status.details, Some(details.to_string()), "{script}");
+        }
     }
 
     #[test]
-    fn test_get_command_monitor_job() {
+    fn test_parse_nagios_output() {
+        let (message, metrics) = CommandMonitor::parse_nagios_output("DISK OK - free space: / 3326 MB (56%); | /=2643MB;5948;5958;0;5968\n/ 15272 MB (77%);\n/boot 68 MB (69%); | /boot=68MB;88;93;0;98\n/home=69357MB;253404;253409;0;253414\n");
+        assert_eq!(message, "DISK OK - free space: / 3326 MB (56%);");
+        assert_eq!(metrics.iter().map(|metric| metric.label.as_str()).collect::<Vec<&str>>(), vec!["/", "/boot", "/home"]);
+        assert_eq!(metrics[0].value, 2643.0);
+        assert_eq!(metrics[0].unit, Some("MB".to_string()));
+        assert_eq!(metrics[2].max, Some(253_414.0));
+        let (message, metrics) = CommandMonitor::parse_nagios_output("PING OK\n");
+        assert_eq!(message, "PING OK");
+        assert!(metrics.is_empty());
+        assert_eq!(CommandMonitor::parse_nagios_output(""), (String::new(), Vec::new()));
+    }
+
+    /**
+     * Create a command monitor running a shell script.
+     *
+     * `script`: The script.
+     * `status`: The status.
+     */
+    fn get_shell_monitor(script: &str, status: &MonitorStatusType) -> CommandMonitor {
+        CommandMonitor::new("test", &None, "sh", CommandOptions { args: Some(vec!["-c".to_string(), script.to_string()]), ..Default::default() }, status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None)
+        .unwrap()
+    }
+
+    /**
+     * Test the check method with expected exit codes.
+     */
+    #[tokio::test]
+    async fn test_check_expected_exit_codes() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_shell_monitor("exit 3", &status);
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Command failed: exit code 3 is not expected".to_string() });
+        monitor.expected_exit_codes = Some(vec![0, 3]);
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    /**
+     * Test the check method with regular expressions on stdout and stderr. The failed status includes stderr.
+     */
+    #[tokio::test]
+    async fn test_check_regex() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_shell_monitor("echo 'backup finished in 3s'; echo 'disk almost full' >&2", &status);
+        monitor.stdout_regex = Some(Regex::new("^backup finished").unwrap());
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+        monitor.stdout_regex = Some(Regex::new("^backup failed").unwrap());
+        monitor.stderr_regex = Some(Regex::new("^$").unwrap());
+        monitor.check().await.unwrap();
+        assert_eq!(
+            status.lock().unwrap().get("test").unwrap().status,
+            Status::Error { message: "Command failed: stdout does not match \"^backup failed\"; stderr does not match \"^$\". stderr: disk almost full".to_string() }
+        );
+        let mut monitor = get_shell_monitor("printf '%0300d' 0 >&2; exit 1", &status);
+        monitor.check().await.unwrap();
+        assert_eq!(
+            status.lock().unwrap().get("test").unwrap().status,
+            Status::Error { message: format!("Command failed: exit code 1 is not expected. stderr: {}...", "0".repeat(STDERR_MESSAGE_LENGTH)) }
+        );
+    }
+
+    /**
+     * Test the check method with environment variables, working directory and stdin.
+     */
+    #[tokio::test]
+    async fn test_check_environment() {
         let status: MonitorStatusType =
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
-        let monitor = CommandMonitor::new(
-            "test",
-            &None,
-            "ls",
-            None,
-            None,
-            &status,
-            &std::sync::Arc::new(None),
-            &DatabaseStoreLevel::None,
+        let mut monitor = get_shell_monitor("read line; echo \"$GREETING $line $(pwd)\"", &status);
+        monitor.environment = Some(HashMap::from([("GREETING".to_string(), "hello".to_string())]));
+        monitor.working_directory = Some("/".to_string());
+        monitor.stdin = Some("world\n".to_string());
+        monitor.expected = Some("hello world /\n".to_string());
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    /**
+     * Test the check method with output larger than the maximum output size. The rest of the output is discarded.
+     */
+    #[tokio::test]
+    async fn test_check_max_output_size() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_shell_monitor("printf '%0200000d' 0", &status);
+        monitor.max_output_size = 10;
+        monitor.stdout_regex = Some(Regex::new("^0{10}$").unwrap());
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    /**
+     * Test the check method running the command as another user. The monitor can only be created when the tests
+     * run as root.
+     */
+    #[tokio::test]
+    async fn test_check_user() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let options = CommandOptions {
+            args: Some(vec!["-c".to_string(), "echo $(id -u):$(id -g)".to_string()]),
+            expected: Some("1234:1235\n".to_string()),
+            user: Some("1234".to_string()),
+            group: Some("1235".to_string()),
+            ..Default::default()
+        };
+        let monitor = CommandMonitor::new("test", &None, "sh", options, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None);
+        if CommandMonitor::is_root() {
+            monitor.unwrap().check().await.unwrap();
+            assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+        } else {
+            assert_eq!(monitor.unwrap_err().message, "Running the command as another user or group requires the daemon to run as root");
+        }
+    }
+
+    #[test]
+    fn test_get_ids() {
+        assert_eq!(CommandMonitor::get_ids(&None, &None).unwrap(), (None, None));
+        assert_eq!(CommandMonitor::get_ids(&Some("root".to_string()), &None).unwrap(), (Some(0), Some(0)));
+        assert_eq!(CommandMonitor::get_ids(&Some("0".to_string()), &Some("root".to_string())).unwrap(), (Some(0), Some(0)));
+        assert_eq!(CommandMonitor::get_ids(&None, &Some("4321".to_string())).unwrap(), (None, Some(4321)));
+        assert!(CommandMonitor::get_ids(&Some("no-such-user".to_string()), &None).is_err());
+        assert!(CommandMonitor::get_ids(&None, &Some("no-such-group".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert_eq!(
+            CommandMonitor::get_regex("stdoutRegex", &Some("(".to_string())).unwrap_err().message.lines().next().unwrap(),
+            "Invalid stdoutRegex (: regex parse error:"
         );
-        let job = CommandMonitor::get_command_monitor_job(monitor, "0 * * * * *");
+        assert!(CommandMonitor::get_regex("stdoutRegex", &None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_command_monitor_job() {
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitor = CommandMonitor::new("test", &None, "ls", CommandOptions::default(), &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap();
+        let job = CommandMonitor::get_command_monitor_job(monitor, "0 * * * * *", None);
         assert!(job.is_ok());
     }
 }
@@ -1,4 +1,6 @@
-use log::{debug, error};
+use std::{future::Future, time::Duration};
+
+use log::{debug, error, info};
 
 use crate::common::{configuration::DatabaseStoreLevel, DatabaseServiceType, MonitorStatusType, Status};
 
@@ -33,15 +35,47 @@ pub trait Monitor {
     fn get_database_store_level(&self) -> DatabaseStoreLevel;
 
     /**
-     * Set the status of the monitor.
+     * Set the status of the monitor. The status stored in the database is the status after the failure and
+     * success thresholds are applied, so the history shows the same status as the monitor.
      *
      * `new_status`: The new status.
      *
      */
     async fn set_status(&mut self, new_status: &Status) {
-        self.insert_monitor_status(new_status).await;
+        if let Some(status) = self.apply_status(new_status) {
+            self.insert_monitor_status(&status).await;
+        }
+    }
+
+    /**
+     * Set the details of the last check, like output the status has no message for.
+     *
+     * `details`: The details.
+     *
+     */
+    fn set_details(&self, details: Option<String>) {
+        match self.get_status().lock() {
+            Ok(mut monitor_lock) => {
+                if let Some(monitor_status) = monitor_lock.get_mut(self.get_name()) {
+                    monitor_status.details = details;
+                }
+            }
+            Err(err) => {
+                error!("Error updating monitor status details: {:?}", err);
+            }
+        }
+    }
+
+    /**
+     * Apply the result of a check to the status of the monitor.
+     *
+     * `new_status`: The result of the check.
+     *
+     * Returns: The status of the monitor after the thresholds are applied, or None if the monitor status was not found.
+     */
+    fn apply_status(&self, new_status: &Status) -> Option<Status> {
         let status = self.get_status();
-        match status.lock() {
+        let applied_status = match status.lock() {
             Ok(mut monitor_lock) => {
                 debug!(
                     "Setting monitor status for {} to: {:?}",
@@ -49,14 +83,17 @@ pub trait Monitor {
                 );
                 let Some(monitor_status) = monitor_lock.get_mut(self.get_name()) else {
                     error!("Monitor status not found for: {}", &self.get_name());
-                    return;
+                    return None;
                 };
                 monitor_status.set_status(new_status);
+                Some(monitor_status.status.clone())
             }
             Err(err) => {
                 error!("Error updating monitor status: {:?}", err);
+                None
             }
         };
+        applied_status
     }
 
    /**
@@ -98,6 +135,93 @@ pub trait Monitor {
     }  
 }
 
+/**
+ * Run a monitor check with a timeout.
+ *
+ * The check is cancelled if it runs longer than the timeout, and the status of the monitor is set to error.
+ * Child processes are killed when the check is cancelled, so commands must be started with `kill_on_drop`.
+ * Cancelling drops the check future, which stops async work at its next await point. Work already handed to
+ * `spawn_blocking` can not be cancelled and runs to completion in the background. Such work must bound itself:
+ * the remote certificate fetch uses socket timeouts, and the disk monitor starts no new read while an earlier
+ * read of a hanging filesystem is still blocked.
+ *
+ * `monitor`: The monitor to check.
+ * `timeout`: The timeout in seconds. The check runs without timeout if None.
+ * `check`: The check to run on the monitor.
+ */
+pub async fn check_with_timeout<M, F, Fut>(monitor: M, timeout: Option<u64>, check: F)
+where
+    M: Monitor + Clone,
+    F: FnOnce(M) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let Some(timeout) = timeout else {
+        check(monitor).await;
+        return;
+    };
+    let mut timeout_monitor = monitor.clone();
+    if tokio::time::timeout(Duration::from_secs(timeout), check(monitor)).await.is_err() {
+        info!("Monitor timed out: {} after {timeout}s", timeout_monitor.get_name());
+        timeout_monitor.set_status(&Status::Error { message: format!("Check timed out after {timeout}s") }).await;
+    }
+}
+
+/**
+ * Check a value against the error and warning thresholds.
+ *
+ * `value`: The current value.
+ * `threshold_error`: The error threshold.
+ * `threshold_warn`: The warning threshold.
+ * `message`: Creates the message from whether the error threshold was exceeded and the exceeded threshold.
+ *
+ * Returns: Error if the value is more than the error threshold, Warn if it is more than the warning threshold, otherwise Ok.
+ */
+pub fn check_threshold(value: f64, threshold_error: Option<f64>, threshold_warn: Option<f64>, message: impl Fn(bool, f64) -> String) -> Status {
+    if let Some(threshold_error) = threshold_error {
+        if value > threshold_error {
+            return Status::Error { message: message(true, threshold_error) };
+        }
+    }
+    if let Some(threshold_warn) = threshold_warn {
+        if value > threshold_warn {
+            return Status::Warn { message: message(false, threshold_warn) };
+        }
+    }
+    Status::Ok
+}
+
+/**
+ * Combine the statuses of several checks into one status.
+ *
+ * The messages of the errors are listed before the messages of the warnings.
+ *
+ * `prefix`: The start of the message, like "Disk check failed".
+ * `statuses`: The statuses of the checks.
+ *
+ * Returns: Error if any check failed, Warn if any check warned, otherwise Ok.
+ */
+pub fn get_combined_status(prefix: &str, statuses: &[Status]) -> Status {
+    let errors = statuses.iter().filter_map(|status| match status {
+        Status::Error { message } => Some(message.as_str()),
+        _ => None,
+    });
+    let warnings = statuses.iter().filter_map(|status| match status {
+        Status::Warn { message } => Some(message.as_str()),
+        _ => None,
+    });
+    let has_errors = statuses.iter().any(|status| matches!(status, Status::Error { .. }));
+    let messages: Vec<&str> = errors.chain(warnings).collect();
+    if messages.is_empty() {
+        return Status::Ok;
+    }
+    let message = format!("{prefix}: {}", messages.join(", "));
+    if has_errors {
+        Status::Error { message }
+    } else {
+        Status::Warn { message }
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -135,4 +259,64 @@ mod test {
         assert_eq!(monitorstatus.last_error, Some("test error".to_string()));
     }
 
-}
\ No newline at end of file
+    /**
+     * A monitor used to test the default methods of the `Monitor` trait.
+     */
+    struct TestMonitor {
+        status: MonitorStatusType,
+    }
+
+    impl Monitor for TestMonitor {
+        fn get_name(&self) -> &str {
+            "test_monitor"
+        }
+
+        fn get_status(&self) -> MonitorStatusType {
+            self.status.clone()
+        }
+
+        fn get_database_service(&self) -> DatabaseServiceType {
+            std::sync::Arc::new(None)
+        }
+
+        fn get_database_store_level(&self) -> DatabaseStoreLevel {
+            DatabaseStoreLevel::All
+        }
+    }
+
+    #[test]
+    fn test_apply_status_returns_debounced_status() {
+        let mut monitor_status = crate::common::MonitorStatus::new("test_monitor", &None, Status::Ok);
+        monitor_status.set_thresholds(2, 1);
+        let monitor = TestMonitor { status: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::from([("test_monitor".to_string(), monitor_status)]))) };
+        let error = Status::Error { message: "test error".to_string() };
+        assert_eq!(monitor.apply_status(&error), Some(Status::Ok));
+        assert_eq!(monitor.apply_status(&error), Some(error.clone()));
+        assert_eq!(monitor.apply_status(&Status::Ok), Some(Status::Ok));
+        let missing = TestMonitor { status: std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())) };
+        assert_eq!(missing.apply_status(&error), None);
+    }
+
+    #[test]
+    fn test_check_threshold() {
+        let message = |is_error: bool, threshold: f64| format!("{is_error} {threshold}");
+        assert_eq!(check_threshold(50.0, Some(90.0), Some(80.0), message), Status::Ok);
+        assert_eq!(check_threshold(85.0, Some(90.0), Some(80.0), message), Status::Warn { message: "false 80".to_string() });
+        assert_eq!(check_threshold(95.0, Some(90.0), Some(80.0), message), Status::Error { message: "true 90".to_string() });
+        assert_eq!(check_threshold(95.0, None, None, message), Status::Ok);
+    }
+
+    #[test]
+    fn test_get_combined_status() {
+        assert_eq!(get_combined_status("Check failed", &[Status::Ok, Status::Unknown]), Status::Ok);
+        assert_eq!(
+            get_combined_status("Check failed", &[Status::Warn { message: "warn 1".to_string() }, Status::Ok, Status::Warn { message: "warn 2".to_string() }]),
+            Status::Warn { message: "Check failed: warn 1, warn 2".to_string() }
+        );
+        assert_eq!(
+            get_combined_status("Check failed", &[Status::Warn { message: "warn".to_string() }, Status::Error { message: "error".to_string() }]),
+            Status::Error { message: "Check failed: error, warn".to_string() }
+        );
+    }
+
+}
@@ -0,0 +1,385 @@
+use std::sync::{Arc, Mutex};
+
+use log::{debug, error, info};
+use monitoring_agent_lib::proc::{ProcCpuUsage, ProcStat};
+use tokio_cron_scheduler::Job;
+
+use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
+
+use super::{common::{check_threshold, check_with_timeout, get_combined_status}, Monitor};
+
+/**
+ * Cpu monitor.
+ *
+ * This struct represents a cpu monitor. The cpu utilisation is computed from the /proc/stat counters
+ * between two runs of the monitor, so the first run only stores the sample.
+ *
+ * `name`: The name of the monitor.
+ * `error_percentage_total`: The maximum percentage of cpu time used before error.
+ * `warn_percentage_total`: The maximum percentage of cpu time used before warning.
+ * `error_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before error.
+ * `warn_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before warning.
+ * `per_core`: Check the thresholds for each core instead of the total of all cores.
+ * `previous_stat`: The sample from the previous run. Shared between the runs of the job.
+ * `status`: The status of the monitor.
+ * `database_service`: The database service.
+ * `database_store_level`: The database store level.
+ * `store_current_cpustat`: Store the current cpu utilisation.
+ */
+#[derive(Debug, Clone)]
+pub struct CpuMonitor {
+    /// The name of the monitor.
+    pub name: String,
+    /// Maximum percentage of cpu time used before error.
+    pub error_percentage_total: Option<f64>,
+    /// Maximum percentage of cpu time used before warning.
+    pub warn_percentage_total: Option<f64>,
+    /// Maximum percentage of cpu time waiting for I/O before error.
+    pub error_percentage_iowait: Option<f64>,
+    /// Maximum percentage of cpu time waiting for I/O before warning.
+    pub warn_percentage_iowait: Option<f64>,
+    /// Check the thresholds for each core.
+    pub per_core: bool,
+    /// The sample from the previous run.
+    previous_stat: Arc<Mutex<Option<ProcStat>>>,
+    /// The status of the monitor.
+    pub status: MonitorStatusType,
+    /// The database service
+    database_service: DatabaseServiceType,
+    /// The database store level.
+    database_store_level: DatabaseStoreLevel,
+    /// Store the current cpu utilisation.
+    store_current_cpustat: bool,
+}
+
+impl CpuMonitor {
+
+    /**
+     * Create a new `CpuMonitor`.
+     *
+     * `name`: The name of the monitor.
+     * `description`: The description of the monitor.
+     * `error_percentage_total`: The maximum percentage of cpu time used before error.
+     * `warn_percentage_total`: The maximum percentage of cpu time used before warning.
+     * `error_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before error.
+     * `warn_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before warning.
+     * `per_core`: Check the thresholds for each core instead of the total of all cores.
+     * `status`: The status of the monitor.
+     * `database_service`: The database service.
+     * `database_store_level`: The database store level.
+     * `store_current_cpustat`: Store the current cpu utilisation.
+     *
+     * Returns: A new `CpuMonitor`.
+     *
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        description: &Option<String>,
+        error_percentage_total: Option<f64>,
+        warn_percentage_total: Option<f64>,
+        error_percentage_iowait: Option<f64>,
+        warn_percentage_iowait: Option<f64>,
+        per_core: bool,
+        status: &MonitorStatusType,
+        database_service: &DatabaseServiceType,
+        database_store_level: &DatabaseStoreLevel,
+        store_current_cpustat: bool,
+    ) -> CpuMonitor {
+
+        let status_lock = status.lock();
+        match status_lock {
+            Ok(mut lock) => {
+                lock.insert(name.to_string(), MonitorStatus::new(name, description, Status::Unknown));
+            }
+            Err(err) => {
+                error!("Error creating cpu monitor: {:?}", err);
+            }
+        }
+
+        CpuMonitor {
+            name: name.to_string(),
+            error_percentage_total,
+            warn_percentage_total,
+            error_percentage_iowait,
+            warn_percentage_iowait,
+            per_core,
+            previous_stat: Arc::new(Mutex::new(None)),
+            status: status.clone(),
+            database_service: database_service.clone(),
+            database_store_level: database_store_level.clone(),
+            store_current_cpustat,
+        }
+    }
+
+    /**
+     * Get the cpu utilisation since the previous sample and replace the previous sample with the current.
+     *
+     * `current_stat`: The current sample.
+     *
+     * Returns: The cpu utilisation or None if there is no previous sample.
+     *
+     * throws: `ApplicationError`: If the previous sample could not be locked.
+     */
+    fn get_cpu_usage(&self, current_stat: ProcStat) -> Result<Option<Vec<ProcCpuUsage>>, ApplicationError> {
+        let mut previous_stat = self.previous_stat.lock().map_err(|err| ApplicationError::new(&format!("Error locking previous cpu sample: {err:?}")))?;
+        let cpu_usage = previous_stat.as_ref().map(|previous_stat| ProcCpuUsage::from_stats(previous_stat, &current_stat));
+        *previous_stat = Some(current_stat);
+        Ok(cpu_usage)
+    }
+
+    /**
+     * Check the cpu utilisation.
+     *
+     * `cpu_usage`: The cpu utilisation of the cpus.
+     *
+     */
+    async fn check_cpu_usage(&mut self, cpu_usage: &[ProcCpuUsage]) {
+        let mut statuses = Vec::new();
+        for usage in cpu_usage.iter().filter(|usage| usage.is_total() != self.per_core) {
+            let cpu_name = usage.name.clone().unwrap_or_default();
+            statuses.push(CpuMonitor::check_cpu_values(self.error_percentage_total, self.warn_percentage_total, usage.total, "use", &cpu_name));
+            statuses.push(CpuMonitor::check_cpu_values(self.error_percentage_iowait, self.warn_percentage_iowait, usage.iowait, "iowait", &cpu_name));
+        }
+        self.set_status(&get_combined_status("Cpu check failed", &statuses)).await;
+    }
+
+    /**
+     * Check the cpu values.
+     *
+     * `error`: The error threshold.
+     * `warn`: The warning threshold.
+     * `current`: The current percentage.
+     * `kind`: Use or iowait.
+     * `cpu_name`: The name of the cpu.
+     *
+     * Returns: The status of the check.
+     *
+     */
+    fn check_cpu_values(error: Option<f64>, warn: Option<f64>, current: f64, kind: &str, cpu_name: &str) -> Status {
+        check_threshold(current, error, warn, |is_error, threshold| {
+            let level = if is_error { "Error" } else { "Warning" };
+            format!("{level} {kind} {current:0.2}% on {cpu_name} is more than {threshold:0.2}%")
+        })
+    }
+
+    /**
+     * Store the current cpu utilisation.
+     *
+     * `cpu_usage`: The current cpu utilisation.
+     */
+    async fn store_current_cpustat(&self, cpu_usage: &[ProcCpuUsage]) {
+        if !self.store_current_cpustat {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            match database_service.store_cpustat(cpu_usage).await {
+                Ok(()) => {}
+                Err(err) => {
+                    error!("Error storing cpu utilisation: {:?}", err);
+                }
+            }
+        }
+    }
+
+    /**
+     * Get cpu monitor job.
+     *
+     * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
+     *
+     * Returns: The cpu monitor job.
+     *
+     */
+    pub fn get_cpu_monitor_job(
+        cpu_monitor: Self,
+        schedule: &str,
+        timeout: Option<u64>,
+    ) -> Result<Job, ApplicationError> {
+        info!("Creating cpu monitor: {}", &cpu_monitor.name);
+        let job_result = Job::new_async(schedule, move |_uuid, _locked| {
+            let cpu_monitor = cpu_monitor.clone();
+            Box::pin(async move {
+                check_with_timeout(cpu_monitor, timeout, |mut cpu_monitor| async move {
+                    cpu_monitor.check().await;
+                })
+                .await;
+            })
+        });
+        match job_result {
+            Ok(job) => Ok(job),
+            Err(err) => Err(ApplicationError::new(
+                format!("Could not create job: {err}").as_str(),
+            )),
+        }
+    }
+
+    /**
+     * Check the monitor.
+     */
+    async fn check(&mut self) {
+        debug!("Checking monitor: {}", &self.name);
+        let current_stat = match ProcStat::get_stat() {
+            Ok(current_stat) => current_stat,
+            Err(err) => {
+                error!("Error getting cpu statistics: {:?}", err);
+                self.set_status(&Status::Error { message: format!("Error getting cpu statistics: {}", err.message) }).await;
+                return;
+            }
+        };
+        match self.get_cpu_usage(current_stat) {
+            Ok(Some(cpu_usage)) => {
+                self.store_current_cpustat(&cpu_usage).await;
+                self.check_cpu_usage(&cpu_usage).await;
+            }
+            Ok(None) => {
+                debug!("No previous cpu sample for monitor: {}", &self.name);
+            }
+            Err(err) => {
+                error!("Error getting cpu utilisation: {:?}", err);
+            }
+        }
+    }
+
+}
+
+/**
+ * Implement the `Monitor` trait for `CpuMonitor`.
+ */
+impl super::Monitor for CpuMonitor {
+    /**
+     * Get the name of the monitor.
+     *
+     * Returns: The name of the monitor.
+     */
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * Get the status of the monitor.
+     *
+     * Returns: The status of the monitor.
+     */
+    fn get_status(&self) -> MonitorStatusType {
+        self.status.clone()
+    }
+
+    /**
+     * Get the database service.
+     *
+     * Returns: The database service.
+     */
+    fn get_database_service(&self) -> DatabaseServiceType {
+        self.database_service.clone()
+    }
+
+    /**
+     * Get the database store level.
+     *
+     * Returns: The database store level.
+     */
+    fn get_database_store_level(&self) -> DatabaseStoreLevel {
+        self.database_store_level.clone()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use monitoring_agent_lib::proc::ProcCpuStat;
+
+    use super::*;
+
+    fn get_cpu_monitor(status: &MonitorStatusType, per_core: bool) -> CpuMonitor {
+        CpuMonitor::new(
+            "test",
+            &None,
+            Some(90.0),
+            Some(80.0),
+            Some(40.0),
+            Some(20.0),
+            per_core,
+            status,
+            &Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false,
+        )
+    }
+
+    fn get_stat(cpus: Vec<ProcCpuStat>) -> ProcStat {
+        ProcStat::new(Some(cpus), None, None, None, None, None, None)
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = CpuMonitor::new("test", &None, None, None, None, None, false, &status, &Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Unknown);
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        monitor.clone().check().await;
+        assert!(monitor.previous_stat.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_get_cpu_usage() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let monitor = get_cpu_monitor(&status, false);
+        let first = monitor.get_cpu_usage(get_stat(vec![ProcCpuStat::new(Some("cpu"), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0))])).unwrap();
+        assert!(first.is_none());
+        let second = monitor.clone().get_cpu_usage(get_stat(vec![ProcCpuStat::new(Some("cpu"), Some(50), Some(0), Some(0), Some(50), Some(0), Some(0), Some(0), Some(0))])).unwrap().unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].total, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_check_cpu_usage_ok() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_cpu_monitor(&status, false);
+        monitor.check_cpu_usage(&[ProcCpuUsage::new(Some("cpu"), 10.0, 5.0, 1.0, 0.0, 84.0, 15.0)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_cpu_usage_warn() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_cpu_monitor(&status, false);
+        monitor.check_cpu_usage(&[ProcCpuUsage::new(Some("cpu"), 80.0, 5.0, 1.0, 0.0, 14.0, 85.0)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Warn { message: "Cpu check failed: Warning use 85.00% on cpu is more than 80.00%".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_check_cpu_usage_per_core() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let cpu_usage = [
+            ProcCpuUsage::new(Some("cpu"), 10.0, 5.0, 25.0, 0.0, 60.0, 15.0),
+            ProcCpuUsage::new(Some("cpu0"), 10.0, 5.0, 45.0, 0.0, 40.0, 15.0),
+            ProcCpuUsage::new(Some("cpu1"), 10.0, 5.0, 5.0, 0.0, 80.0, 15.0),
+        ];
+        let mut monitor = get_cpu_monitor(&status, false);
+        monitor.check_cpu_usage(&cpu_usage).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Warn { message: "Cpu check failed: Warning iowait 25.00% on cpu is more than 20.00%".to_string() });
+        let mut monitor = get_cpu_monitor(&status, true);
+        monitor.check_cpu_usage(&cpu_usage).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Cpu check failed: Error iowait 45.00% on cpu0 is more than 40.00%".to_string() });
+    }
+
+    #[test]
+    fn test_check_cpu_values() {
+        assert_eq!(CpuMonitor::check_cpu_values(None, None, 99.0, "use", "cpu"), Status::Ok);
+        assert_eq!(CpuMonitor::check_cpu_values(Some(90.0), Some(80.0), 79.0, "use", "cpu"), Status::Ok);
+        assert_eq!(CpuMonitor::check_cpu_values(Some(90.0), Some(80.0), 81.0, "use", "cpu"), Status::Warn { message: "Warning use 81.00% on cpu is more than 80.00%".to_string() });
+        assert_eq!(CpuMonitor::check_cpu_values(Some(90.0), Some(80.0), 91.0, "use", "cpu"), Status::Error { message: "Error use 91.00% on cpu is more than 90.00%".to_string() });
+    }
+
+    #[test]
+    fn test_get_cpu_monitor_job() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let monitor = get_cpu_monitor(&status, false);
+        let job = CpuMonitor::get_cpu_monitor_job(monitor, "0 0 * * * *", None);
+        assert!(job.is_ok());
+    }
+}
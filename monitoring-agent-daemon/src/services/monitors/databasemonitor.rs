@@ -3,7 +3,7 @@ use tokio_cron_scheduler::Job;
 
 use crate::{
     common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status},
-    services::monitors::Monitor,
+    services::monitors::{common::check_with_timeout, Monitor},
 };
 
 /**
@@ -78,19 +78,23 @@ impl DatabaseMonitor {
      * Get meminfo monitor job.
      *
      * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
      *
      * Returns: The meminfo monitor job.
      *
      */
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::similar_names)]
-    pub fn get_database_monitor_job(database_monitor :DatabaseMonitor, schedule: &str) -> Result<Job, ApplicationError> {
+    pub fn get_database_monitor_job(database_monitor :DatabaseMonitor, schedule: &str, timeout: Option<u64>) -> Result<Job, ApplicationError> {
         info!("Creating database monitor: {}", &database_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut database_monitor = database_monitor.clone();
+                let database_monitor = database_monitor.clone();
                 async move {
-                    database_monitor.check().await;
+                    check_with_timeout(database_monitor, timeout, |mut database_monitor| async move {
+                        database_monitor.check().await;
+                    })
+                    .await;
                 }
             })
         });
@@ -112,9 +116,9 @@ impl DatabaseMonitor {
             return;
         };
         let mut status = Status::Ok;
-        if self.query_max_time.is_some() {
+        if let Some(query_max_time) = self.query_max_time {
             let overtimed_query = match database_service
-                .query_long_running_queries(self.query_max_time.unwrap())
+                .query_long_running_queries(query_max_time)
                 .await
             {
                 Ok(query) => query,
@@ -212,7 +216,7 @@ mod test {
             &database_service,
             &database_store_level,
         );
-        let job = DatabaseMonitor::get_database_monitor_job(database_monitor, "* * * * * *");
+        let job = DatabaseMonitor::get_database_monitor_job(database_monitor, "* * * * * *", None);
         assert!(job.is_ok());
     }
 
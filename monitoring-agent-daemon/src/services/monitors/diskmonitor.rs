@@ -0,0 +1,400 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use log::{debug, error, info};
+use monitoring_agent_lib::proc::ProcsDiskUsage;
+use tokio_cron_scheduler::Job;
+
+use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
+
+use super::{common::{check_threshold, check_with_timeout, get_combined_status}, Monitor};
+
+/**
+ * Disk monitor.
+ *
+ * This struct represents a disk monitor. It checks the space and inode use of mounted filesystems.
+ *
+ * `name`: The name of the monitor.
+ * `mounts`: The mount points to check. If None then the mounted disks are discovered.
+ * `error_percentage_used_space`: The maximum percentage space used before error.
+ * `warn_percentage_used_space`: The maximum percentage space used before warning.
+ * `error_percentage_used_inodes`: The maximum percentage inodes used before error.
+ * `warn_percentage_used_inodes`: The maximum percentage inodes used before warning.
+ * `status`: The status of the monitor.
+ * `database_service`: The database service.
+ * `database_store_level`: The database store level.
+ * `store_current_diskinfo`: Store the current disk use.
+ * `reading_disk_usage`: Set while the disk use is read on a blocking thread.
+ */
+#[derive(Debug, Clone)]
+pub struct DiskMonitor {
+    /// The name of the monitor.
+    pub name: String,
+    /// The mount points to check. If None then the mounted disks are discovered.
+    pub mounts: Option<Vec<String>>,
+    /// Maximum percentage space used before error.
+    pub error_percentage_used_space: Option<f64>,
+    /// Maximum percentage space used before warning.
+    pub warn_percentage_used_space: Option<f64>,
+    /// Maximum percentage inodes used before error.
+    pub error_percentage_used_inodes: Option<f64>,
+    /// Maximum percentage inodes used before warning.
+    pub warn_percentage_used_inodes: Option<f64>,
+    /// The status of the monitor.
+    pub status: MonitorStatusType,
+    /// The database service
+    database_service: DatabaseServiceType,
+    /// The database store level.
+    database_store_level: DatabaseStoreLevel,
+    /// Store the current disk use.
+    store_current_diskinfo: bool,
+    /// Set while the disk use is read on a blocking thread.
+    reading_disk_usage: Arc<AtomicBool>,
+}
+
+impl DiskMonitor {
+
+    /**
+     * Create a new `DiskMonitor`.
+     *
+     * `name`: The name of the monitor.
+     * `description`: The description of the monitor.
+     * `mounts`: The mount points to check. If None then the mounted disks are discovered.
+     * `error_percentage_used_space`: The maximum percentage space used before error.
+     * `warn_percentage_used_space`: The maximum percentage space used before warning.
+     * `error_percentage_used_inodes`: The maximum percentage inodes used before error.
+     * `warn_percentage_used_inodes`: The maximum percentage inodes used before warning.
+     * `status`: The status of the monitor.
+     * `database_service`: The database service.
+     * `database_store_level`: The database store level.
+     * `store_current_diskinfo`: Store the current disk use.
+     *
+     * Returns: A new `DiskMonitor`.
+     *
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        description: &Option<String>,
+        mounts: Option<Vec<String>>,
+        error_percentage_used_space: Option<f64>,
+        warn_percentage_used_space: Option<f64>,
+        error_percentage_used_inodes: Option<f64>,
+        warn_percentage_used_inodes: Option<f64>,
+        status: &MonitorStatusType,
+        database_service: &DatabaseServiceType,
+        database_store_level: &DatabaseStoreLevel,
+        store_current_diskinfo: bool,
+    ) -> DiskMonitor {
+
+        let status_lock = status.lock();
+        match status_lock {
+            Ok(mut lock) => {
+                lock.insert(name.to_string(), MonitorStatus::new(name, description, Status::Unknown));
+            }
+            Err(err) => {
+                error!("Error creating disk monitor: {:?}", err);
+            }
+        }
+
+        DiskMonitor {
+            name: name.to_string(),
+            mounts,
+            error_percentage_used_space,
+            warn_percentage_used_space,
+            error_percentage_used_inodes,
+            warn_percentage_used_inodes,
+            status: status.clone(),
+            database_service: database_service.clone(),
+            database_store_level: database_store_level.clone(),
+            store_current_diskinfo,
+            reading_disk_usage: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /**
+     * Get the disk use of the configured mount points, or of the discovered disks if no mount points are configured.
+     * Discovered filesystems without size are skipped.
+     *
+     * `mounts`: The mount points to check.
+     *
+     * Returns: The disk use or an error.
+     *
+     * throws: `ApplicationError`: If the mounts or the disk use could not be read.
+     */
+    pub fn get_disk_usage(mounts: Option<&Vec<String>>) -> Result<Vec<ProcsDiskUsage>, ApplicationError> {
+        let Some(mounts) = mounts else {
+            return ProcsDiskUsage::get_disks_usage().map_err(|err| ApplicationError::new(&err.message));
+        };
+        let mut disk_usage = Vec::new();
+        for mount in mounts {
+            disk_usage.push(ProcsDiskUsage::get_disk_usage(mount).map_err(|err| ApplicationError::new(&err.message))?);
+        }
+        Ok(disk_usage)
+    }
+
+    /**
+     * Check the disk use.
+     *
+     * `disk_usage`: The disk use of the mount points.
+     *
+     */
+    async fn check_disk_usage(&mut self, disk_usage: &[ProcsDiskUsage]) {
+        let mut statuses = Vec::new();
+        for usage in disk_usage {
+            statuses.push(DiskMonitor::check_disk_values(self.error_percentage_used_space, self.warn_percentage_used_space, usage.get_percent_space_used(), "space", &usage.mount_point));
+            statuses.push(DiskMonitor::check_disk_values(self.error_percentage_used_inodes, self.warn_percentage_used_inodes, usage.get_percent_inodes_used(), "inode", &usage.mount_point));
+        }
+        self.set_status(&get_combined_status("Disk check failed", &statuses)).await;
+    }
+
+    /**
+     * Check the disk values.
+     *
+     * `error`: The error threshold.
+     * `warn`: The warning threshold.
+     * `current`: The current percentage used.
+     * `kind`: Space or inode.
+     * `mount_point`: The mount point.
+     *
+     * Returns: The status of the check.
+     *
+     */
+    fn check_disk_values(error: Option<f64>, warn: Option<f64>, current: Option<f64>, kind: &str, mount_point: &str) -> Status {
+        let Some(current) = current else { return Status::Ok };
+        check_threshold(current, error, warn, |is_error, threshold| {
+            let level = if is_error { "Error" } else { "Warning" };
+            format!("{level} {kind} use {current:0.2}% on {mount_point} is more than {threshold:0.2}%")
+        })
+    }
+
+    /**
+     * Store the current disk use.
+     *
+     * `disk_usage`: The current disk use.
+     */
+    async fn store_current_diskinfo(&self, disk_usage: &[ProcsDiskUsage]) {
+        if !self.store_current_diskinfo {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            match database_service.store_diskinfo(disk_usage).await {
+                Ok(()) => {}
+                Err(err) => {
+                    error!("Error storing disk use: {:?}", err);
+                }
+            }
+        }
+    }
+
+    /**
+     * Get disk monitor job.
+     *
+     * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
+     *
+     * Returns: The disk monitor job.
+     *
+     */
+    pub fn get_disk_monitor_job(
+        disk_monitor: Self,
+        schedule: &str,
+        timeout: Option<u64>,
+    ) -> Result<Job, ApplicationError> {
+        info!("Creating disk monitor: {}", &disk_monitor.name);
+        let job_result = Job::new_async(schedule, move |_uuid, _locked| {
+            let disk_monitor = disk_monitor.clone();
+            Box::pin(async move {
+                check_with_timeout(disk_monitor, timeout, |mut disk_monitor| async move {
+                    disk_monitor.check().await;
+                })
+                .await;
+            })
+        });
+        match job_result {
+            Ok(job) => Ok(job),
+            Err(err) => Err(ApplicationError::new(
+                format!("Could not create job: {err}").as_str(),
+            )),
+        }
+    }
+
+    /**
+     * Check the monitor.
+     *
+     * statvfs can block on unresponsive network filesystems, so the disk use is read on a blocking thread.
+     * The blocking read can not be cancelled. While a read from an earlier check is still running no new
+     * read is started and the monitor is set to error, so a hanging filesystem holds at most one thread.
+     */
+    async fn check(&mut self) {
+        debug!("Checking monitor: {}", &self.name);
+        if self.reading_disk_usage.swap(true, Ordering::SeqCst) {
+            self.set_status(&Status::Error { message: "Disk use is still being read by an earlier check".to_string() }).await;
+            return;
+        }
+        let mounts = self.mounts.clone();
+        let reading_disk_usage = self.reading_disk_usage.clone();
+        let disk_usage = tokio::task::spawn_blocking(move || {
+            let disk_usage = DiskMonitor::get_disk_usage(mounts.as_ref());
+            reading_disk_usage.store(false, Ordering::SeqCst);
+            disk_usage
+        }).await;
+        match disk_usage {
+            Ok(Ok(disk_usage)) => {
+                self.store_current_diskinfo(&disk_usage).await;
+                self.check_disk_usage(&disk_usage).await;
+            }
+            Ok(Err(err)) => {
+                error!("Error getting disk use: {:?}", err);
+                self.set_status(&Status::Error { message: format!("Error getting disk use: {}", err.message) }).await;
+            }
+            Err(err) => {
+                error!("Error getting disk use: {:?}", err);
+            }
+        }
+    }
+
+}
+
+/**
+ * Implement the `Monitor` trait for `DiskMonitor`.
+ */
+impl super::Monitor for DiskMonitor {
+    /**
+     * Get the name of the monitor.
+     *
+     * Returns: The name of the monitor.
+     */
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * Get the status of the monitor.
+     *
+     * Returns: The status of the monitor.
+     */
+    fn get_status(&self) -> MonitorStatusType {
+        self.status.clone()
+    }
+
+    /**
+     * Get the database service.
+     *
+     * Returns: The database service.
+     */
+    fn get_database_service(&self) -> DatabaseServiceType {
+        self.database_service.clone()
+    }
+
+    /**
+     * Get the database store level.
+     *
+     * Returns: The database store level.
+     */
+    fn get_database_store_level(&self) -> DatabaseStoreLevel {
+        self.database_store_level.clone()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use super::*;
+
+    fn get_disk_monitor(status: &MonitorStatusType, mounts: Option<Vec<String>>) -> DiskMonitor {
+        DiskMonitor::new(
+            "test",
+            &None,
+            mounts,
+            Some(90.0),
+            Some(80.0),
+            Some(95.0),
+            Some(85.0),
+            status,
+            &Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = DiskMonitor::new("test", &None, None, None, None, None, None, &status, &Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_non_existing_mount() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_disk_monitor(&status, Some(vec!["/non/existing/path".to_string()]));
+        monitor.check().await;
+        assert!(matches!(status.lock().unwrap().get("test").unwrap().status, Status::Error { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_check_still_reading() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = DiskMonitor::new("test", &None, None, None, None, None, None, &status, &Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.reading_disk_usage.store(true, Ordering::SeqCst);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Disk use is still being read by an earlier check".to_string() });
+        monitor.reading_disk_usage.store(false, Ordering::SeqCst);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+        assert!(!monitor.reading_disk_usage.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_get_disk_usage() {
+        let disk_usage = DiskMonitor::get_disk_usage(Some(&vec!["/".to_string()])).unwrap();
+        assert_eq!(disk_usage.len(), 1);
+        assert_eq!(disk_usage[0].mount_point, "/");
+        assert!(DiskMonitor::get_disk_usage(None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_disk_usage_ok() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_disk_monitor(&status, None);
+        monitor.check_disk_usage(&[ProcsDiskUsage::new("/", 1000, 500, 500, 100, 50)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_disk_usage_warn() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_disk_monitor(&status, None);
+        monitor.check_disk_usage(&[ProcsDiskUsage::new("/", 1000, 150, 150, 100, 50)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Warn { message: "Disk check failed: Warning space use 85.00% on / is more than 80.00%".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_check_disk_usage_error() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_disk_monitor(&status, None);
+        monitor.check_disk_usage(&[ProcsDiskUsage::new("/", 1000, 150, 150, 100, 50), ProcsDiskUsage::new("/var", 1000, 500, 500, 100, 2)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Disk check failed: Error inode use 98.00% on /var is more than 95.00%, Warning space use 85.00% on / is more than 80.00%".to_string() });
+    }
+
+    #[test]
+    fn test_check_disk_values() {
+        assert_eq!(DiskMonitor::check_disk_values(Some(90.0), Some(80.0), None, "space", "/"), Status::Ok);
+        assert_eq!(DiskMonitor::check_disk_values(None, None, Some(99.0), "space", "/"), Status::Ok);
+        assert_eq!(DiskMonitor::check_disk_values(Some(90.0), Some(80.0), Some(79.0), "space", "/"), Status::Ok);
+        assert_eq!(DiskMonitor::check_disk_values(Some(90.0), Some(80.0), Some(81.0), "space", "/"), Status::Warn { message: "Warning space use 81.00% on / is more than 80.00%".to_string() });
+        assert_eq!(DiskMonitor::check_disk_values(Some(90.0), Some(80.0), Some(91.0), "space", "/"), Status::Error { message: "Error space use 91.00% on / is more than 90.00%".to_string() });
+    }
+
+    #[test]
+    fn test_get_disk_monitor_job() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let monitor = get_disk_monitor(&status, None);
+        let job = DiskMonitor::get_disk_monitor_job(monitor, "0 0 * * * *", None);
+        assert!(job.is_ok());
+    }
+}
@@ -0,0 +1,386 @@
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::configuration::HttpAssertions;
+use crate::common::ApplicationError;
+
+/**
+ * A segment of a JSONPath expression.
+ */
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathSegment {
+    /// Member of an object.
+    Key(String),
+    /// Element of an array.
+    Index(usize),
+}
+
+/**
+ * A JSONPath expression. Supports the root $, members as .name or ['name'] and array elements
+ * as [index].
+ *
+ * `expression`: The expression.
+ * `segments`: The segments of the expression.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    /// The expression.
+    expression: String,
+    /// The segments of the expression.
+    segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    /**
+     * Parse a JSONPath expression.
+     *
+     * `expression`: The JSONPath expression.
+     *
+     * Returns: The JSONPath.
+     *
+     * Errors:
+     * - If the expression is not supported.
+     */
+    pub fn new(expression: &str) -> Result<JsonPath, ApplicationError> {
+        let invalid = || ApplicationError::new(&format!("Invalid jsonPath {expression}"));
+        let mut rest = expression.strip_prefix('$').ok_or_else(invalid)?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(member) = rest.strip_prefix('.') {
+                let end = member.find(['.', '[']).unwrap_or(member.len());
+                if end == 0 {
+                    return Err(invalid());
+                }
+                segments.push(JsonPathSegment::Key(member[..end].to_string()));
+                rest = &member[end..];
+            } else if let Some(subscript) = rest.strip_prefix('[') {
+                let end = subscript.find(']').ok_or_else(invalid)?;
+                let inner = &subscript[..end];
+                let key = inner.strip_prefix('\'').and_then(|key| key.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|key| key.strip_suffix('"')));
+                match key {
+                    Some(key) => segments.push(JsonPathSegment::Key(key.to_string())),
+                    None => segments.push(JsonPathSegment::Index(inner.parse::<usize>().map_err(|_| invalid())?)),
+                }
+                rest = &subscript[end + 1..];
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(JsonPath { expression: expression.to_string(), segments })
+    }
+
+    /**
+     * Get the expression.
+     *
+     * Returns: The expression.
+     */
+    pub fn get_expression(&self) -> &str {
+        &self.expression
+    }
+
+    /**
+     * Get the value at the expression.
+     *
+     * `json`: The JSON document.
+     *
+     * Returns: The value or None if it does not exist.
+     */
+    pub fn get_value<'a>(&self, json: &'a Value) -> Option<&'a Value> {
+        self.segments.iter().try_fold(json, |value, segment| match segment {
+            JsonPathSegment::Key(key) => value.get(key.as_str()),
+            JsonPathSegment::Index(index) => value.get(*index),
+        })
+    }
+}
+
+/**
+ * The assertions on a http response, prepared from the configuration.
+ *
+ * `status_codes`: The expected status codes.
+ * `body_contains`: Text the body must contain.
+ * `body_regex`: Regular expression the body must match.
+ * `json_path`: The JSONPath expression.
+ * `json_path_value`: The expected value at the JSONPath expression.
+ * `required_headers`: Headers the response must have.
+ * `max_body_size`: The maximum body size in bytes.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct ResponseAssertions {
+    /// The expected status codes.
+    status_codes: Option<Vec<u16>>,
+    /// Text the body must contain.
+    body_contains: Option<String>,
+    /// Regular expression the body must match.
+    body_regex: Option<Regex>,
+    /// The JSONPath expression.
+    json_path: Option<JsonPath>,
+    /// The expected value at the JSONPath expression.
+    json_path_value: Option<Value>,
+    /// Headers the response must have.
+    required_headers: Vec<String>,
+    /// The maximum body size in bytes.
+    max_body_size: Option<u64>,
+}
+
+impl ResponseAssertions {
+    /**
+     * Create the response assertions.
+     *
+     * `assertions`: The assertions configuration.
+     *
+     * Returns: The response assertions.
+     *
+     * Errors:
+     * - If the body regular expression is invalid.
+     * - If the JSONPath expression is invalid.
+     */
+    pub fn new(assertions: &Option<HttpAssertions>) -> Result<ResponseAssertions, ApplicationError> {
+        let Some(assertions) = assertions else {
+            return Ok(ResponseAssertions::default());
+        };
+        let body_regex = match &assertions.body_regex {
+            Some(body_regex) => Some(Regex::new(body_regex).map_err(|err| ApplicationError::new(&format!("Invalid bodyRegex {body_regex}: {err}")))?),
+            None => None,
+        };
+        let json_path = match &assertions.json_path {
+            Some(json_path) => Some(JsonPath::new(json_path)?),
+            None => None,
+        };
+        Ok(ResponseAssertions {
+            status_codes: assertions.status_codes.clone(),
+            body_contains: assertions.body_contains.clone(),
+            body_regex,
+            json_path,
+            json_path_value: assertions.json_path_value.clone(),
+            required_headers: assertions.required_headers.clone().unwrap_or_default(),
+            max_body_size: assertions.max_body_size,
+        })
+    }
+
+    /**
+     * Check if the status code is accepted before the response is read. Any status code is accepted
+     * when the expected status codes are set, since they are checked with the other assertions.
+     *
+     * `status`: The status code of the response.
+     *
+     * Returns: True if the status code is accepted.
+     */
+    pub fn is_accepted_status(&self, status: StatusCode) -> bool {
+        self.status_codes.is_some() || status.is_success()
+    }
+
+    /**
+     * Read the body of the response. Reading stops when the body is more than the maximum body size,
+     * and one byte more than the maximum is kept so `check` can report the size.
+     *
+     * `response`: The response.
+     *
+     * Returns: The body.
+     *
+     * Errors:
+     * - If the body could not be read.
+     */
+    pub async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, ApplicationError> {
+        let url = response.url().to_string();
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|err| ApplicationError::new(&format!("Error reading response from {url} with error: {err}")))? {
+            body.extend_from_slice(&chunk);
+            if let Some(max_body_size) = self.max_body_size {
+                if body.len() as u64 > max_body_size {
+                    body.truncate(ResponseAssertions::get_max_length(max_body_size).saturating_add(1));
+                    break;
+                }
+            }
+        }
+        Ok(body)
+    }
+
+    /**
+     * Check the response against the assertions. A body more than the maximum body size fails, and the
+     * other assertions are checked on the body truncated to the maximum size.
+     *
+     * `status`: The status code of the response.
+     * `headers`: The headers of the response.
+     * `body`: The body of the response.
+     *
+     * Errors:
+     * - If any assertion fails. Every failed assertion is named in the message.
+     */
+    pub fn check(&self, status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<(), ApplicationError> {
+        let mut failures: Vec<String> = Vec::new();
+        let body = match self.max_body_size {
+            Some(max_body_size) if body.len() as u64 > max_body_size => {
+                failures.push(format!("maxBodySize: body is more than {max_body_size} bytes"));
+                &body[..ResponseAssertions::get_max_length(max_body_size)]
+            }
+            _ => body,
+        };
+        if let Some(status_codes) = &self.status_codes {
+            if !status_codes.contains(&status.as_u16()) {
+                failures.push(format!("statusCodes: status {} is not one of {status_codes:?}", status.as_u16()));
+            }
+        }
+        for header in &self.required_headers {
+            if !headers.contains_key(header.as_str()) {
+                failures.push(format!("requiredHeaders: header {header} is missing"));
+            }
+        }
+        let text = String::from_utf8_lossy(body);
+        if let Some(body_contains) = &self.body_contains {
+            if !text.contains(body_contains.as_str()) {
+                failures.push(format!("bodyContains: body does not contain {body_contains:?}"));
+            }
+        }
+        if let Some(body_regex) = &self.body_regex {
+            if !body_regex.is_match(&text) {
+                failures.push(format!("bodyRegex: body does not match {:?}", body_regex.as_str()));
+            }
+        }
+        if let Some(json_path) = &self.json_path {
+            if let Some(failure) = self.check_json_path(json_path, body) {
+                failures.push(failure);
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(ApplicationError::new(&format!("Assertions failed: {}", failures.join("; "))))
+        }
+    }
+
+    /**
+     * Get the maximum body size as a length.
+     *
+     * `max_body_size`: The maximum body size in bytes.
+     *
+     * Returns: The maximum length of the body.
+     */
+    fn get_max_length(max_body_size: u64) -> usize {
+        usize::try_from(max_body_size).unwrap_or(usize::MAX)
+    }
+
+    /**
+     * Check the value at the JSONPath expression.
+     *
+     * `json_path`: The JSONPath expression.
+     * `body`: The body of the response.
+     *
+     * Returns: The failure or None if the assertion holds.
+     */
+    fn check_json_path(&self, json_path: &JsonPath, body: &[u8]) -> Option<String> {
+        let json: Value = match serde_json::from_slice(body) {
+            Ok(json) => json,
+            Err(err) => return Some(format!("jsonPath: body is not valid JSON: {err}")),
+        };
+        let expression = json_path.get_expression();
+        match (json_path.get_value(&json), &self.json_path_value) {
+            (None, _) => Some(format!("jsonPath: {expression} not found")),
+            (Some(value), Some(expected)) if value != expected => Some(format!("jsonPath: {expression} is {value}, expected {expected}")),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn get_assertions(assertions: HttpAssertions) -> ResponseAssertions {
+        ResponseAssertions::new(&Some(assertions)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_json_path() {
+        assert_eq!(JsonPath::new("$").unwrap().segments, vec![]);
+        assert_eq!(
+            JsonPath::new("$.checks[1]['status'].name").unwrap().segments,
+            vec![
+                JsonPathSegment::Key("checks".to_string()),
+                JsonPathSegment::Index(1),
+                JsonPathSegment::Key("status".to_string()),
+                JsonPathSegment::Key("name".to_string()),
+            ]
+        );
+        assert!(JsonPath::new("status").is_err());
+        assert!(JsonPath::new("$..status").is_err());
+        assert!(JsonPath::new("$[x]").is_err());
+        assert!(JsonPath::new("$[0").is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(ResponseAssertions::new(&Some(HttpAssertions { body_regex: Some("(".to_string()), ..Default::default() })).is_err());
+    }
+
+    #[test]
+    fn test_no_assertions() {
+        let assertions = ResponseAssertions::new(&None).unwrap();
+        assert!(assertions.is_accepted_status(StatusCode::OK));
+        assert!(!assertions.is_accepted_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(assertions.check(StatusCode::OK, &HeaderMap::new(), b"").is_ok());
+    }
+
+    #[test]
+    fn test_check_ok() {
+        let assertions = get_assertions(HttpAssertions {
+            status_codes: Some(vec![200, 401]),
+            body_contains: Some("UP".to_string()),
+            body_regex: Some("\"version\":\\s*\"\\d+\"".to_string()),
+            json_path: Some("$.components[0].status".to_string()),
+            json_path_value: Some(Value::String("UP".to_string())),
+            required_headers: Some(vec!["Content-Type".to_string()]),
+            max_body_size: Some(1024),
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        let body = br#"{"version": "1", "components": [{"status": "UP"}]}"#;
+        assert!(assertions.is_accepted_status(StatusCode::UNAUTHORIZED));
+        assert!(assertions.check(StatusCode::UNAUTHORIZED, &headers, body).is_ok());
+    }
+
+    #[test]
+    fn test_check_names_failed_assertions() {
+        let assertions = get_assertions(HttpAssertions {
+            status_codes: Some(vec![200]),
+            body_contains: Some("UP".to_string()),
+            body_regex: Some("^OK$".to_string()),
+            json_path: Some("$.status".to_string()),
+            json_path_value: Some(Value::String("UP".to_string())),
+            required_headers: Some(vec!["X-Request-Id".to_string()]),
+            max_body_size: None,
+        });
+        let err = assertions.check(StatusCode::SERVICE_UNAVAILABLE, &HeaderMap::new(), br#"{"status": "DOWN"}"#).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Assertions failed: statusCodes: status 503 is not one of [200]; requiredHeaders: header X-Request-Id is missing; \
+            bodyContains: body does not contain \"UP\"; bodyRegex: body does not match \"^OK$\"; jsonPath: $.status is \"DOWN\", expected \"UP\""
+        );
+    }
+
+    #[test]
+    fn test_check_json_path() {
+        let assertions = get_assertions(HttpAssertions { json_path: Some("$.count".to_string()), ..Default::default() });
+        assert!(assertions.check(StatusCode::OK, &HeaderMap::new(), br#"{"count": 0}"#).is_ok());
+        assert_eq!(assertions.check(StatusCode::OK, &HeaderMap::new(), br#"{"total": 0}"#).unwrap_err().message, "Assertions failed: jsonPath: $.count not found");
+        assert!(assertions.check(StatusCode::OK, &HeaderMap::new(), b"<html>").unwrap_err().message.starts_with("Assertions failed: jsonPath: body is not valid JSON"));
+        let assertions = get_assertions(HttpAssertions { json_path: Some("$.count".to_string()), json_path_value: Some(Value::from(3)), ..Default::default() });
+        assert!(assertions.check(StatusCode::OK, &HeaderMap::new(), br#"{"count": 3}"#).is_ok());
+        assert_eq!(assertions.check(StatusCode::OK, &HeaderMap::new(), br#"{"count": "3"}"#).unwrap_err().message, "Assertions failed: jsonPath: $.count is \"3\", expected 3");
+    }
+
+    #[test]
+    fn test_check_max_body_size() {
+        let assertions = get_assertions(HttpAssertions { body_contains: Some("UP".to_string()), max_body_size: Some(4), ..Default::default() });
+        assert!(assertions.check(StatusCode::OK, &HeaderMap::new(), b"UP").is_ok());
+        assert_eq!(assertions.check(StatusCode::OK, &HeaderMap::new(), b"UP UP").unwrap_err().message, "Assertions failed: maxBodySize: body is more than 4 bytes");
+        assert_eq!(
+            assertions.check(StatusCode::OK, &HeaderMap::new(), b"DOWN UP").unwrap_err().message,
+            "Assertions failed: maxBodySize: body is more than 4 bytes; bodyContains: body does not contain \"UP\""
+        );
+    }
+}
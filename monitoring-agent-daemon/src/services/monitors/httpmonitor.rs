@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 use std::fs;
-use std::time::Duration;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
 use log::info;
 use log::{debug, error};
@@ -9,13 +13,108 @@ use reqwest::{Certificate, RequestBuilder};
 use reqwest::Identity;
 use tokio_cron_scheduler::Job;
 
-use crate::common::configuration::DatabaseStoreLevel;
+use crate::common::configuration::{DatabaseStoreLevel, HttpAssertions};
 use crate::common::ApplicationError;
 use crate::common::DatabaseServiceType;
 use crate::common::HttpMethod;
 use crate::common::MonitorStatusType;
+use crate::common::ResponseTime;
 use crate::common::{MonitorStatus, Status};
-use crate::services::monitors::Monitor;
+use crate::services::monitors::{common::check_with_timeout, Monitor};
+use crate::services::monitors::httpassertions::ResponseAssertions;
+
+/**
+ * Timeout of the request.
+ */
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/**
+ * Connector layer of the http client measuring the time used to open the connections, including the
+ * TLS handshake for https. The times of the connections opened since the last `take` are summed.
+ *
+ * `connect_time`: The time used to open connections in milliseconds.
+ */
+#[derive(Debug, Clone, Default)]
+struct ConnectTimer {
+    /// The time used to open connections in milliseconds.
+    connect_time: Arc<Mutex<Option<f64>>>,
+}
+
+impl ConnectTimer {
+    /**
+     * Take the time used to open connections, and start measuring again.
+     *
+     * Returns: The time in milliseconds, or None if no connection was opened.
+     */
+    fn take(&self) -> Option<f64> {
+        match self.connect_time.lock() {
+            Ok(mut connect_time) => connect_time.take(),
+            Err(err) => {
+                error!("Error reading connect time: {err:?}");
+                None
+            }
+        }
+    }
+
+    /**
+     * Add the time used to open a connection.
+     *
+     * `time`: The time in milliseconds.
+     */
+    fn add(&self, time: f64) {
+        match self.connect_time.lock() {
+            Ok(mut connect_time) => *connect_time = Some(connect_time.unwrap_or_default() + time),
+            Err(err) => error!("Error storing connect time: {err:?}"),
+        }
+    }
+}
+
+impl<S> tower_layer::Layer<S> for ConnectTimer {
+    type Service = ConnectTimerService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTimerService { inner, connect_timer: self.clone() }
+    }
+}
+
+/**
+ * Connector service measuring the time used by the inner connector to open a connection.
+ *
+ * `inner`: The connector of the http client.
+ * `connect_timer`: Collects the measured times.
+ */
+#[derive(Debug, Clone)]
+struct ConnectTimerService<S> {
+    /// The connector of the http client.
+    inner: S,
+    /// Collects the measured times.
+    connect_timer: ConnectTimer,
+}
+
+impl<S, R> tower_service::Service<R> for ConnectTimerService<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connect_timer = self.connect_timer.clone();
+        let start = Instant::now();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let connection = connecting.await?;
+            connect_timer.add(ResponseTime::get_elapsed_ms(&start));
+            Ok(connection)
+        })
+    }
+}
 
 /**
  * HTTP Monitor.
@@ -28,7 +127,11 @@ use crate::services::monitors::Monitor;
  * method: The HTTP method to use.
  * body: The body of the request.
  * headers: The headers of the request.
+ * `warn_latency_ms`: The response time in milliseconds before warning.
+ * `error_latency_ms`: The response time in milliseconds before error.
+ * assertions: The assertions on the response.
  * status: The status of the monitor.
+ * `store_values`: Store the response times.
  */
 #[derive(Debug, Clone)]
 pub struct HttpMonitor {
@@ -44,14 +147,24 @@ pub struct HttpMonitor {
     pub headers: Option<HashMap<String, String>>,
     /// Number of retries if error occurs.
     pub retry: Option<u16>,
+    /// The response time in milliseconds before warning.
+    warn_latency_ms: Option<u64>,
+    /// The response time in milliseconds before error.
+    error_latency_ms: Option<u64>,
+    /// The assertions on the response.
+    assertions: ResponseAssertions,
     /// The HTTP client.
     client: reqwest::Client,
+    /// Measures the time the client uses to open connections.
+    connect_timer: ConnectTimer,
     /// The status of the monitor.
     pub status: MonitorStatusType,
     /// The database service.
     database_service: DatabaseServiceType,
     /// The database store level.
     database_store_level: DatabaseStoreLevel,
+    /// Store the response times.
+    store_values: bool,
 }
 
 impl HttpMonitor {
@@ -69,6 +182,11 @@ impl HttpMonitor {
      * `root_certificate`: The root certificate.
      * `identity`: The identity.
      * `identity_password`: The password for the identity.
+     * `retry`: Number of retries if the request fails.
+     * `warn_latency_ms`: The response time in milliseconds before warning.
+     * `error_latency_ms`: The response time in milliseconds before error.
+     * `store_values`: Store the response times.
+     * `assertions`: The assertions on the response.
      * `status`: The status of the monitor.
      * `database_service`: The database service.
      *
@@ -90,53 +208,29 @@ impl HttpMonitor {
         identity: Option<String>,
         identity_password: Option<String>,
         retry: Option<u16>,
+        warn_latency_ms: Option<u64>,
+        error_latency_ms: Option<u64>,
+        store_values: bool,
+        assertions: &Option<HttpAssertions>,
         status: &MonitorStatusType,
         database_service: &DatabaseServiceType,
         database_store_level: &DatabaseStoreLevel,
     ) -> Result<HttpMonitor, ApplicationError> {
         debug!("Creating HTTP monitor: {}", &name);
         /*
-         *  Start create http client.
-         */
-        let client = reqwest::Client::builder()
-            .tls_built_in_root_certs(use_builtin_root_certs)
-            .danger_accept_invalid_certs(accept_invalid_certs)
-            .use_native_tls()
-            .tls_info(tls_info);
-
-        /*
-         * Add root certificate if included.
+         *  Create the http client. Idle connections are not kept, so every check opens a connection
+         *  and measures connecting to the server.
          */
-        let client = match root_certificate {
-            Some(root_certificate) => {
-                let root_certificate =
-                    HttpMonitor::get_root_certificate(root_certificate.as_str())?;
-                client.add_root_certificate(root_certificate)
-            }
-            None => client,
-        };
-
-        /*
-         * Set identity if included.
-         */
-        let client = match identity {
-            Some(identity) => {
-                let identity = HttpMonitor::get_identity(identity, identity_password)?;
-                client.identity(identity)
-            }
-            None => client,
-        };
-        /*
-         * Get client
-         */
-        let client = match client.build() {
-            Ok(client) => client,
-            Err(err) => {
-                return Err(ApplicationError::new(&format!(
-                    "Error creating HTTP client: {err}"
-                )));
-            }
-        };
+        let connect_timer = ConnectTimer::default();
+        let client = HttpMonitor::create_client(
+            reqwest::Client::builder().pool_max_idle_per_host(0).connector_layer(connect_timer.clone()),
+            use_builtin_root_certs,
+            accept_invalid_certs,
+            tls_info,
+            root_certificate,
+            identity,
+            identity_password,
+        )?;
 
         /*
          * Set monitor status.
@@ -164,13 +258,122 @@ impl HttpMonitor {
             body: body.clone(),
             headers: headers.clone(),
             retry,
+            warn_latency_ms,
+            error_latency_ms,
+            assertions: ResponseAssertions::new(assertions)?,
             status: status.clone(),
             client,
+            connect_timer,
             database_service: database_service.clone(),
             database_store_level: database_store_level.clone(),
+            store_values,
         })
     }
 
+    /**
+     * Create the http client with the certificate and identity configuration.
+     *
+     * `client`: The client builder.
+     * `use_builtin_root_certs`: Use the builtin root certificates.
+     * `accept_invalid_certs`: Accept invalid certificates.
+     * `tls_info`: Get TLS information.
+     * `root_certificate`: The root certificate.
+     * `identity`: The identity.
+     * `identity_password`: The password for the identity.
+     *
+     * Returns: The http client.
+     *
+     * Errors:
+     * - If the root certificate or identity could not be read.
+     * - If the client could not be created.
+     */
+    pub fn create_client(
+        client: reqwest::ClientBuilder,
+        use_builtin_root_certs: bool,
+        accept_invalid_certs: bool,
+        tls_info: bool,
+        root_certificate: Option<String>,
+        identity: Option<String>,
+        identity_password: Option<String>,
+    ) -> Result<reqwest::Client, ApplicationError> {
+        let root_certificate = root_certificate.map(|root_certificate| HttpMonitor::get_root_certificate(root_certificate.as_str())).transpose()?;
+        let identity = identity.map(|identity| HttpMonitor::get_identity(identity, identity_password)).transpose()?;
+        HttpMonitor::build_client(client, use_builtin_root_certs, accept_invalid_certs, tls_info, root_certificate, identity)
+    }
+
+    /**
+     * Build the http client with an already read certificate and identity.
+     *
+     * `client`: The client builder.
+     * `use_builtin_root_certs`: Use the builtin root certificates.
+     * `accept_invalid_certs`: Accept invalid certificates.
+     * `tls_info`: Get TLS information.
+     * `root_certificate`: The root certificate.
+     * `identity`: The identity.
+     *
+     * Returns: The http client.
+     *
+     * Errors:
+     * - If the client could not be created.
+     */
+    pub fn build_client(
+        client: reqwest::ClientBuilder,
+        use_builtin_root_certs: bool,
+        accept_invalid_certs: bool,
+        tls_info: bool,
+        root_certificate: Option<Certificate>,
+        identity: Option<Identity>,
+    ) -> Result<reqwest::Client, ApplicationError> {
+        let client = client
+            .tls_built_in_root_certs(use_builtin_root_certs)
+            .danger_accept_invalid_certs(accept_invalid_certs)
+            .use_native_tls()
+            .tls_info(tls_info);
+
+        /*
+         * Add root certificate if included.
+         */
+        let client = match root_certificate {
+            Some(root_certificate) => client.add_root_certificate(root_certificate),
+            None => client,
+        };
+
+        /*
+         * Set identity if included.
+         */
+        let client = match identity {
+            Some(identity) => client.identity(identity),
+            None => client,
+        };
+        /*
+         * Get client
+         */
+        match client.build() {
+            Ok(client) => Ok(client),
+            Err(err) => Err(ApplicationError::new(&format!(
+                "Error creating HTTP client: {err}"
+            ))),
+        }
+    }
+
+    /**
+     * Get the request method.
+     *
+     * `method`: The http method.
+     *
+     * Returns the request method.
+     */
+    pub fn get_method(method: HttpMethod) -> reqwest::Method {
+        match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Option => reqwest::Method::OPTIONS,
+            HttpMethod::Head => reqwest::Method::HEAD,
+        }
+    }
+
     /**
      * This method converts a `HashMap` to a `HeaderMap`.
      *
@@ -236,7 +439,7 @@ impl HttpMonitor {
      * Returns a `HeaderMap`.
      *
      */
-    fn get_headers(
+    pub fn get_headers(
         headers: &Option<HashMap<String, String>>,
     ) -> Result<reqwest::header::HeaderMap, ApplicationError> {
         match headers {
@@ -246,18 +449,18 @@ impl HttpMonitor {
     }
 
     /**
-     * Check the response and set the status of the monitor.
+     * Check the response.
      *
      * `response`: The response from the request.
      * 
-     * Returns: The result of checking the response and setting the status.
+     * Returns: The response if the request was successful.
      * 
      */
-    fn check_response_and_set_status(&mut self, response: Result<reqwest::Response, reqwest::Error> ) -> Result<(), ApplicationError> {
+    fn check_response(&self, response: Result<reqwest::Response, reqwest::Error> ) -> Result<reqwest::Response, ApplicationError> {
         match response {
             Ok(response) => {
-                if response.status().is_success() {
-                    Ok(())
+                if self.assertions.is_accepted_status(response.status()) {
+                    Ok(response)
                 } else {                    
                     Err(ApplicationError::new(&format!(
                         "Error checking monitor: {} ", response.status()
@@ -269,6 +472,50 @@ impl HttpMonitor {
             }
         }
     }
+
+    /**
+     * Send the request and measure the response time. The connect time is the time used to open the
+     * connection of the request, including the TLS handshake for https. The time to first byte and the
+     * total time include connecting.
+     *
+     * `request`: The request.
+     * 
+     * Returns: The response time if the request was successful.
+     * 
+     * Errors:
+     * - If the request fails or the response is not successful.
+     * - If the response body could not be read.
+     * - If an assertion on the response fails.
+     */
+    async fn send_request(&self, request: RequestBuilder) -> Result<ResponseTime, ApplicationError> {
+        self.connect_timer.take();
+        let start = Instant::now();
+        let response = self.check_response(request.send().await)?;
+        let first_byte_time = ResponseTime::get_elapsed_ms(&start);
+        let connect_time = self.connect_timer.take();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = self.assertions.read_body(response).await?;
+        let total_time = ResponseTime::get_elapsed_ms(&start);
+        self.assertions.check(status, &headers, &body)?;
+        Ok(ResponseTime::new(connect_time, Some(first_byte_time), total_time))
+    }
+
+    /**
+     * Store the response time.
+     *
+     * `response_time`: The response time of the check.
+     */
+    async fn store_response_time(&self, response_time: &ResponseTime) {
+        if !self.store_values {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            if let Err(err) = database_service.store_response_time(&self.name, response_time).await {
+                error!("Error storing response time: {:?}", err);
+            }
+        }
+    }
     
     /**
      * Get identity.
@@ -279,7 +526,7 @@ impl HttpMonitor {
      * Returns an Identity.
      *
      */
-    fn get_identity(
+    pub fn get_identity(
         identity: String,
         identity_password: Option<String>,
     ) -> Result<Identity, ApplicationError> {
@@ -322,7 +569,7 @@ impl HttpMonitor {
      * Returns a certificate.
      *
      */
-    fn get_root_certificate(root_certificate: &str) -> Result<Certificate, ApplicationError> {
+    pub fn get_root_certificate(root_certificate: &str) -> Result<Certificate, ApplicationError> {
         /*
          * Read root certificate.
          */
@@ -352,6 +599,7 @@ impl HttpMonitor {
      * Get an HTTP monitor job.
      *
      * `schedule`: The schedule.
+     * `timeout`: The timeout of the check in seconds.
      * `name`: The name of the monitor.
      * `url`: The URL to monitor.
      * `method`: The HTTP method.
@@ -365,15 +613,19 @@ impl HttpMonitor {
     pub fn get_http_monitor_job(
         http_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating http monitor: {}", &http_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut http_monitor = http_monitor.clone();
+                let http_monitor = http_monitor.clone();
                 async move {
-                    let _ = http_monitor.check().await.map_err(|err| {
-                        error!("Error checking monitor: {:?}", err);
-                    });
+                    check_with_timeout(http_monitor, timeout, |mut http_monitor| async move {
+                        let _ = http_monitor.check().await.map_err(|err| {
+                            error!("Error checking monitor: {:?}", err);
+                        });
+                    })
+                    .await;
                 }
             })
         });
@@ -390,15 +642,24 @@ impl HttpMonitor {
      */
     async fn check(&mut self) -> Result<(), ApplicationError> {
         debug!("Checking monitor: {}", &self.name);
-        let status = self.connect().await?;
+        let (status, response_time) = self.connect().await?;
+        let status = match &response_time {
+            Some(response_time) => {
+                self.store_response_time(response_time).await;
+                response_time.check_latency(status, self.warn_latency_ms, self.error_latency_ms)
+            },
+            None => status,
+        };
         self.set_status(&status).await;    
         Ok(())   
     }  
 
     /**
-     * Check the monitor.
+     * Send the request with retries.
+     * 
+     * Returns: The status and the response time of the successful request.
      */
-    async fn connect(&mut self) -> Result<Status, ApplicationError> {
+    async fn connect(&mut self) -> Result<(Status, Option<ResponseTime>), ApplicationError> {
         debug!("Checking monitor: {}", &self.name);
         /*
          * Build the request.
@@ -407,11 +668,9 @@ impl HttpMonitor {
         /*
          * Send request.
          */
-        let req_response = request.send().await;
-
-        let mut current_err = match self.check_response_and_set_status(req_response) {
-            Ok(()) => {
-                return Ok(Status::Ok);
+        let mut current_err = match self.send_request(request).await {
+            Ok(response_time) => {
+                return Ok((Status::Ok, Some(response_time)));
             },
             Err(err) => {
                 err.message 
@@ -424,10 +683,9 @@ impl HttpMonitor {
                 * Build the request.
                 */
                 let request = self.build_request()?;
-                let req_response = request.send().await;
-                match self.check_response_and_set_status(req_response) {
-                    Ok(()) => {
-                        return Ok(Status::Warn { message: format!("Success after retries {index}. Previous err: {current_err:?}") });
+                match self.send_request(request).await {
+                    Ok(response_time) => {
+                        return Ok((Status::Warn { message: format!("Success after retries {index}. Previous err: {current_err:?}") }, Some(response_time)));
                     },
                     Err(err) => {
                         current_err = format!("Error after {index} retries. Error: {err:?}");
@@ -435,7 +693,7 @@ impl HttpMonitor {
                 };
             }            
         }                    
-        Ok(Status::Error { message: current_err })
+        Ok((Status::Error { message: current_err }, None))
     }
 
     /**
@@ -447,14 +705,7 @@ impl HttpMonitor {
         /*
          * Set http method.
          */
-        let request_builder = match &self.method {
-            HttpMethod::Get => self.client.get(&self.url),
-            HttpMethod::Post => self.client.post(&self.url),
-            HttpMethod::Put => self.client.put(&self.url),
-            HttpMethod::Delete => self.client.delete(&self.url),
-            HttpMethod::Option => self.client.request(reqwest::Method::OPTIONS, &self.url),
-            HttpMethod::Head => self.client.head(&self.url),
-        };
+        let request_builder = self.client.request(HttpMonitor::get_method(self.method), &self.url);
         /*
          * Set headers.
          */
@@ -469,7 +720,7 @@ impl HttpMonitor {
         /*
          * Set timeout.
          */
-        Ok(request_builder.timeout(Duration::from_secs(5)))
+        Ok(request_builder.timeout(REQUEST_TIMEOUT))
     }
 
 }
@@ -547,6 +798,10 @@ mod test {
             Some("./resources/test/client_cert/client.p12".to_string()),
             Some("test".to_string()),
             Some(1),
+            None,
+            None,
+            false,
+            &None,
             &status,
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
@@ -556,6 +811,113 @@ mod test {
         assert_eq!(status.lock().unwrap().get("localhost").unwrap().status, Status::Error { message: "Error after 1 retries. Error: ApplicationError { message: \"Error connecting to http://localhost:65000 with error: error sending request for url (http://localhost:65000/)\" }".to_string() });
     }
 
+    /**
+     * Start a local http server answering every request with the response.
+     *
+     * Returns the port of the server.
+     */
+    async fn start_http_server(response: &'static str) -> u16 {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = [0; 1024];
+                    if let Ok(length) = stream.read(&mut buffer).await {
+                        if length > 0 {
+                            let _ = stream.write_all(response.as_bytes()).await;
+                        }
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    /**
+     * Test the response time and latency thresholds toward a local http server.
+     */
+    #[tokio::test]
+    async fn test_check_response_time() {
+        let port = start_http_server("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK").await;
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = HttpMonitor::new(
+            &format!("http://127.0.0.1:{port}/"),
+            HttpMethod::Get,
+            &None,
+            &None,
+            "local",
+            &None,
+            true,
+            true,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(0),
+            false,
+            &None,
+            &status,
+            &std::sync::Arc::new(None),
+            &DatabaseStoreLevel::None,
+        )
+        .unwrap();
+        let (connect_status, response_time) = monitor.connect().await.unwrap();
+        assert_eq!(connect_status, Status::Ok);
+        let response_time = response_time.unwrap();
+        assert!(response_time.connect_time.is_some());
+        assert!(response_time.first_byte_time.unwrap() <= response_time.total_time);
+        monitor.check().await.unwrap();
+        assert!(matches!(&status.lock().unwrap().get("local").unwrap().status, Status::Error { message } if message.starts_with("Response time")));
+    }
+
+    /**
+     * Test the assertions toward a local http server returning a health document.
+     */
+    #[tokio::test]
+    async fn test_check_assertions() {
+        let port = start_http_server("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 17\r\nConnection: close\r\n\r\n{\"status\":\"DOWN\"}").await;
+        let status: MonitorStatusType =
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let assertions = HttpAssertions {
+            status_codes: Some(vec![200]),
+            json_path: Some("$.status".to_string()),
+            json_path_value: Some(serde_json::Value::String("UP".to_string())),
+            required_headers: Some(vec!["Content-Type".to_string()]),
+            ..Default::default()
+        };
+        let mut monitor = HttpMonitor::new(
+            &format!("http://127.0.0.1:{port}/health"),
+            HttpMethod::Get,
+            &None,
+            &None,
+            "health",
+            &None,
+            true,
+            true,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            &Some(assertions),
+            &status,
+            &std::sync::Arc::new(None),
+            &DatabaseStoreLevel::None,
+        )
+        .unwrap();
+        monitor.check().await.unwrap();
+        assert_eq!(status.lock().unwrap().get("health").unwrap().status, Status::Error { message: "Assertions failed: jsonPath: $.status is \"DOWN\", expected \"UP\"".to_string() });
+    }
+
     /**
      * Test the `get_headers` method.
      */
@@ -593,6 +955,10 @@ mod test {
             None,
             None,
             None,
+            None,
+            None,
+            false,
+            &None,
             &status,
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
@@ -623,12 +989,16 @@ mod test {
             None,
             None,
             None,
+            None,
+            None,
+            false,
+            &None,
             &status,
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
         )
         .unwrap();
-        let job = HttpMonitor::get_http_monitor_job(monitor, "0 0 * * * *");
+        let job = HttpMonitor::get_http_monitor_job(monitor, "0 0 * * * *", None);
         assert!(job.is_ok());
     }
 }
@@ -0,0 +1,770 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::{debug, error, info};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use reqwest::cookie::Jar;
+use reqwest::header::HeaderMap;
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Identity};
+use tokio_cron_scheduler::Job;
+
+use crate::common::configuration::{DatabaseStoreLevel, HttpExtraction, HttpScenarioStep};
+use crate::common::ApplicationError;
+use crate::common::DatabaseServiceType;
+use crate::common::HttpMethod;
+use crate::common::MonitorStatusType;
+use crate::common::ResponseTime;
+use crate::common::{MonitorStatus, Status};
+use crate::services::monitors::httpassertions::{JsonPath, ResponseAssertions};
+use crate::services::monitors::httpmonitor::REQUEST_TIMEOUT;
+use crate::services::monitors::{common::check_with_timeout, HttpMonitor, Monitor};
+
+/**
+ * The maximum number of redirects followed by a step.
+ */
+const MAX_REDIRECTS: usize = 10;
+
+/**
+ * The characters encoded when a variable is used in a url. Only the unreserved characters are kept.
+ */
+const URL_VARIABLE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/**
+ * Extraction of a value from a response into a variable, prepared from the configuration.
+ */
+#[derive(Debug, Clone)]
+enum Extraction {
+    /// JSONPath expression evaluated on the body.
+    JsonPath(JsonPath),
+    /// Regular expression on the body.
+    Regex(Regex),
+    /// Name of a response header.
+    Header(String),
+}
+
+impl Extraction {
+    /**
+     * Create the extraction.
+     *
+     * `extraction`: The extraction configuration.
+     *
+     * Returns: The extraction.
+     *
+     * Errors:
+     * - If the JSONPath expression or the regular expression is invalid.
+     */
+    fn new(extraction: &HttpExtraction) -> Result<Extraction, ApplicationError> {
+        match extraction {
+            HttpExtraction::JsonPath(json_path) => Ok(Extraction::JsonPath(JsonPath::new(json_path)?)),
+            HttpExtraction::Regex(regex) => Ok(Extraction::Regex(Regex::new(regex).map_err(|err| ApplicationError::new(&format!("Invalid regex {regex}: {err}")))?)),
+            HttpExtraction::Header(header) => Ok(Extraction::Header(header.clone())),
+        }
+    }
+
+    /**
+     * Extract the value from the response. JSON strings are used without quotes, other JSON
+     * values as JSON.
+     *
+     * `headers`: The headers of the response.
+     * `body`: The body of the response.
+     *
+     * Returns: The extracted value.
+     *
+     * Errors:
+     * - If the value is not found.
+     */
+    fn extract(&self, headers: &HeaderMap, body: &[u8]) -> Result<String, ApplicationError> {
+        match self {
+            Extraction::JsonPath(json_path) => {
+                let json: serde_json::Value = serde_json::from_slice(body).map_err(|err| ApplicationError::new(&format!("Body is not valid JSON: {err}")))?;
+                match json_path.get_value(&json) {
+                    Some(serde_json::Value::String(value)) => Ok(value.clone()),
+                    Some(value) => Ok(value.to_string()),
+                    None => Err(ApplicationError::new(&format!("jsonPath {} not found", json_path.get_expression()))),
+                }
+            }
+            Extraction::Regex(regex) => {
+                let text = String::from_utf8_lossy(body);
+                let captures = regex.captures(&text).ok_or_else(|| ApplicationError::new(&format!("regex {} not found", regex.as_str())))?;
+                let value = captures.get(1).or_else(|| captures.get(0)).map(|value| value.as_str().to_string());
+                value.ok_or_else(|| ApplicationError::new(&format!("regex {} not found", regex.as_str())))
+            }
+            Extraction::Header(header) => {
+                let value = headers.get(header.as_str()).ok_or_else(|| ApplicationError::new(&format!("header {header} not found")))?;
+                value.to_str().map(str::to_string).map_err(|err| ApplicationError::new(&format!("header {header} is not text: {err}")))
+            }
+        }
+    }
+}
+
+/**
+ * A request in the scenario, prepared from the configuration.
+ *
+ * `name`: The name of the step.
+ * `url`: The url of the request.
+ * `method`: The method of the request.
+ * `body`: The body of the request.
+ * `headers`: The headers of the request.
+ * `follow_redirects`: Follow redirects.
+ * `assertions`: The assertions on the response.
+ * `extract`: The variables extracted from the response, sorted by name.
+ */
+#[derive(Debug, Clone)]
+struct ScenarioStep {
+    /// The name of the step.
+    name: String,
+    /// The url of the request.
+    url: String,
+    /// The method of the request.
+    method: HttpMethod,
+    /// The body of the request.
+    body: Option<String>,
+    /// The headers of the request.
+    headers: Option<HashMap<String, String>>,
+    /// Follow redirects.
+    follow_redirects: bool,
+    /// The assertions on the response.
+    assertions: ResponseAssertions,
+    /// The variables extracted from the response.
+    extract: Vec<(String, Extraction)>,
+}
+
+impl ScenarioStep {
+    /**
+     * Create the step.
+     *
+     * `step`: The step configuration.
+     *
+     * Returns: The step.
+     *
+     * Errors:
+     * - If an assertion or extraction is invalid.
+     */
+    fn new(step: &HttpScenarioStep) -> Result<ScenarioStep, ApplicationError> {
+        let mut extract = Vec::new();
+        if let Some(extractions) = &step.extract {
+            for (variable, extraction) in extractions {
+                extract.push((variable.clone(), Extraction::new(extraction)?));
+            }
+        }
+        extract.sort_by(|first, second| first.0.cmp(&second.0));
+        Ok(ScenarioStep {
+            name: step.name.clone(),
+            url: step.url.clone(),
+            method: step.method,
+            body: step.body.clone(),
+            headers: step.headers.clone(),
+            follow_redirects: step.follow_redirects,
+            assertions: ResponseAssertions::new(&step.assertions)?,
+            extract,
+        })
+    }
+}
+
+/**
+ * The TLS configuration of the client of a scenario. The root certificate and identity are read
+ * when the monitor is created.
+ *
+ * `use_builtin_root_certs`: Use the built-in root certificates.
+ * `accept_invalid_certs`: Accept invalid certificates.
+ * `tls_info`: Use TLS info.
+ * `root_certificate`: The root certificate.
+ * `identity`: The identity.
+ */
+#[derive(Debug, Clone)]
+struct ClientSettings {
+    /// Use the built-in root certificates.
+    use_builtin_root_certs: bool,
+    /// Accept invalid certificates.
+    accept_invalid_certs: bool,
+    /// Use TLS info.
+    tls_info: bool,
+    /// The root certificate.
+    root_certificate: Option<Certificate>,
+    /// The identity.
+    identity: Option<Identity>,
+}
+
+impl ClientSettings {
+    /**
+     * Create the client of a scenario run. The client has a new cookie jar, so the cookies are kept
+     * between the steps but not between the runs. The cookies are only sent to the domains and
+     * paths that set them. Redirects are followed while `follow_redirects` is set, so the steps
+     * choose whether their redirects are followed.
+     *
+     * `follow_redirects`: Set while the running step follows redirects.
+     *
+     * Returns: The client.
+     *
+     * Errors:
+     * - If the client could not be created.
+     */
+    fn create_client(&self, follow_redirects: &Arc<AtomicBool>) -> Result<reqwest::Client, ApplicationError> {
+        let follow_redirects = follow_redirects.clone();
+        /*
+         * Reqwest removes the Authorization and Cookie headers when a redirect goes to another host.
+         */
+        let redirect = Policy::custom(move |attempt| {
+            if !follow_redirects.load(Ordering::SeqCst) {
+                attempt.stop()
+            } else if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("Too many redirects")
+            } else {
+                attempt.follow()
+            }
+        });
+        HttpMonitor::build_client(
+            reqwest::Client::builder().cookie_provider(Arc::new(Jar::default())).redirect(redirect),
+            self.use_builtin_root_certs,
+            self.accept_invalid_certs,
+            self.tls_info,
+            self.root_certificate.clone(),
+            self.identity.clone(),
+        )
+    }
+}
+
+/**
+ * HTTP scenario monitor.
+ *
+ * This struct represents a monitor running an ordered list of HTTP requests. The steps share a
+ * cookie jar and the variables extracted from earlier responses.
+ *
+ * name: The name of the monitor.
+ * steps: The requests, run in order.
+ * client_settings: The TLS configuration of the client. The client is created for each run.
+ * status: The status of the monitor.
+ */
+#[derive(Debug, Clone)]
+pub struct HttpScenarioMonitor {
+    /// The name of the monitor.
+    pub name: String,
+    /// The requests, run in order.
+    steps: Vec<ScenarioStep>,
+    /// The TLS configuration of the client.
+    client_settings: ClientSettings,
+    /// The status of the monitor.
+    pub status: MonitorStatusType,
+    /// The database service.
+    database_service: DatabaseServiceType,
+    /// The database store level.
+    database_store_level: DatabaseStoreLevel,
+}
+
+impl HttpScenarioMonitor {
+    /**
+     * Create a new HTTP scenario monitor.
+     *
+     * `name`: The name of the monitor.
+     * `description`: The description of the monitor.
+     * `steps`: The requests, run in order.
+     * `use_builtin_root_certs`: Use the built-in root certificates.
+     * `accept_invalid_certs`: Accept invalid certificates.
+     * `tls_info`: Use TLS info.
+     * `root_certificate`: The root certificate.
+     * `identity`: The identity.
+     * `identity_password`: The password for the identity.
+     * `status`: The status of the monitor.
+     * `database_service`: The database service.
+     * `database_store_level`: The database store level.
+     *
+     * Returns: A new HTTP scenario monitor.
+     *
+     * Errors:
+     * - If there are no steps.
+     * - If an assertion or extraction is invalid.
+     * - If the root certificate or identity could not be read.
+     * - If the client could not be created.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        description: &Option<String>,
+        steps: &[HttpScenarioStep],
+        use_builtin_root_certs: bool,
+        accept_invalid_certs: bool,
+        tls_info: bool,
+        root_certificate: Option<String>,
+        identity: Option<String>,
+        identity_password: Option<String>,
+        status: &MonitorStatusType,
+        database_service: &DatabaseServiceType,
+        database_store_level: &DatabaseStoreLevel,
+    ) -> Result<HttpScenarioMonitor, ApplicationError> {
+        debug!("Creating HTTP scenario monitor: {}", &name);
+        if steps.is_empty() {
+            return Err(ApplicationError::new("Http scenario must have at least one step"));
+        }
+        let steps = steps.iter().map(ScenarioStep::new).collect::<Result<Vec<ScenarioStep>, ApplicationError>>()?;
+        let client_settings = ClientSettings {
+            use_builtin_root_certs,
+            accept_invalid_certs,
+            tls_info,
+            root_certificate: root_certificate.map(|root_certificate| HttpMonitor::get_root_certificate(root_certificate.as_str())).transpose()?,
+            identity: identity.map(|identity| HttpMonitor::get_identity(identity, identity_password)).transpose()?,
+        };
+        client_settings.create_client(&Arc::new(AtomicBool::new(true)))?;
+        match status.lock() {
+            Ok(mut lock) => {
+                lock.insert(name.to_string(), MonitorStatus::new(name, description, Status::Unknown));
+            }
+            Err(err) => {
+                error!("Error creating HTTP scenario monitor: {:?}", err);
+            }
+        };
+        Ok(HttpScenarioMonitor {
+            name: name.to_string(),
+            steps,
+            client_settings,
+            status: status.clone(),
+            database_service: database_service.clone(),
+            database_store_level: database_store_level.clone(),
+        })
+    }
+
+    /**
+     * Get the HTTP scenario monitor job.
+     *
+     * `http_scenario_monitor`: The HTTP scenario monitor.
+     * `schedule`: The schedule.
+     * `timeout`: The timeout of the whole scenario in seconds.
+     *
+     * Returns: The HTTP scenario monitor job.
+     *
+     * Errors:
+     * - If the job could not be created.
+     */
+    pub fn get_http_scenario_monitor_job(
+        http_scenario_monitor: Self,
+        schedule: &str,
+        timeout: Option<u64>,
+    ) -> Result<Job, ApplicationError> {
+        info!("Creating http scenario monitor: {}", &http_scenario_monitor.name);
+        let job_result = Job::new_async(schedule, move |_uuid, _locked| {
+            Box::pin({
+                let http_scenario_monitor = http_scenario_monitor.clone();
+                async move {
+                    check_with_timeout(http_scenario_monitor, timeout, |mut http_scenario_monitor| async move {
+                        http_scenario_monitor.check().await;
+                    })
+                    .await;
+                }
+            })
+        });
+        match job_result {
+            Ok(job) => Ok(job),
+            Err(err) => Err(ApplicationError::new(
+                format!("Could not create job: {err}").as_str(),
+            )),
+        }
+    }
+
+    /**
+     * Check the monitor. The steps are run in order and the scenario stops at the first failed
+     * step. The status message names the failed step and the time of each step run, and the
+     * details of the status hold the time of each step run.
+     */
+    async fn check(&mut self) {
+        debug!("Checking monitor: {}", &self.name);
+        let (status, timings) = self.run_scenario().await;
+        self.set_details(Some(format!("Timings: {timings}")));
+        self.set_status(&status).await;
+    }
+
+    /**
+     * Run the steps of the scenario.
+     *
+     * Returns: The status of the scenario and the time of each step run.
+     */
+    async fn run_scenario(&self) -> (Status, String) {
+        let follow_redirects = Arc::new(AtomicBool::new(true));
+        let client = match self.client_settings.create_client(&follow_redirects) {
+            Ok(client) => client,
+            Err(err) => return (Status::Error { message: err.message }, String::new()),
+        };
+        let mut variables: HashMap<String, String> = HashMap::new();
+        let mut timings: Vec<String> = Vec::new();
+        for step in &self.steps {
+            follow_redirects.store(step.follow_redirects, Ordering::SeqCst);
+            let start = Instant::now();
+            let result = HttpScenarioMonitor::run_step(&client, step, &mut variables).await;
+            timings.push(format!("{} {:0.0} ms", step.name, ResponseTime::get_elapsed_ms(&start)));
+            if let Err(err) = result {
+                let timings = timings.join(", ");
+                return (Status::Error { message: format!("Step {} failed: {}. Timings: {timings}", step.name, err.message) }, timings);
+            }
+        }
+        let timings = timings.join(", ");
+        debug!("Scenario {} succeeded. Timings: {timings}", &self.name);
+        (Status::Ok, timings)
+    }
+
+    /**
+     * Run a step.
+     *
+     * `client`: The client of the step.
+     * `step`: The step.
+     * `variables`: The variables extracted by the previous steps.
+     *
+     * Errors:
+     * - If a variable is unknown.
+     * - If the request fails.
+     * - If the status code is not accepted or an assertion fails.
+     * - If a value could not be extracted.
+     */
+    async fn run_step(client: &reqwest::Client, step: &ScenarioStep, variables: &mut HashMap<String, String>) -> Result<(), ApplicationError> {
+        let url = HttpScenarioMonitor::substitute_url(&step.url, variables)?;
+        let body = step.body.as_ref().map(|body| HttpScenarioMonitor::substitute(body, variables)).transpose()?;
+        let headers = match &step.headers {
+            Some(headers) => {
+                let mut substituted = HashMap::new();
+                for (name, value) in headers {
+                    substituted.insert(name.clone(), HttpScenarioMonitor::substitute(value, variables)?);
+                }
+                HttpMonitor::get_headers(&Some(substituted))?
+            }
+            None => HeaderMap::new(),
+        };
+        let mut request = client.request(HttpMonitor::get_method(step.method), &url).headers(headers).timeout(REQUEST_TIMEOUT);
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        let response = request.send().await.map_err(|err| ApplicationError::new(&format!("Error connecting to {url} with error: {err}")))?;
+        let status = response.status();
+        if !step.assertions.is_accepted_status(status) {
+            return Err(ApplicationError::new(&format!("Status {status} from {url}")));
+        }
+        let response_headers = response.headers().clone();
+        let response_body = step.assertions.read_body(response).await?;
+        step.assertions.check(status, &response_headers, &response_body)?;
+        for (variable, extraction) in &step.extract {
+            let value = extraction.extract(&response_headers, &response_body).map_err(|err| ApplicationError::new(&format!("Extracting {variable}: {}", err.message)))?;
+            variables.insert(variable.clone(), value);
+        }
+        Ok(())
+    }
+
+    /**
+     * Replace the variables written as ${name} in the text.
+     *
+     * `text`: The text.
+     * `variables`: The variables.
+     *
+     * Returns: The text with the variables replaced.
+     *
+     * Errors:
+     * - If a variable is unknown.
+     */
+    fn substitute(text: &str, variables: &HashMap<String, String>) -> Result<String, ApplicationError> {
+        HttpScenarioMonitor::replace_variables(text, variables, str::to_string)
+    }
+
+    /**
+     * Replace the variables written as ${name} in the url. The values are percent-encoded.
+     *
+     * `url`: The url.
+     * `variables`: The variables.
+     *
+     * Returns: The url with the variables replaced.
+     *
+     * Errors:
+     * - If a variable is unknown.
+     */
+    fn substitute_url(url: &str, variables: &HashMap<String, String>) -> Result<String, ApplicationError> {
+        HttpScenarioMonitor::replace_variables(url, variables, |value| utf8_percent_encode(value, URL_VARIABLE_ENCODE_SET).to_string())
+    }
+
+    /**
+     * Replace the variables written as ${name} in the text.
+     *
+     * `text`: The text.
+     * `variables`: The variables.
+     * `encode`: Encoding of the values.
+     *
+     * Returns: The text with the variables replaced.
+     *
+     * Errors:
+     * - If a variable is unknown.
+     */
+    fn replace_variables(text: &str, variables: &HashMap<String, String>, encode: impl Fn(&str) -> String) -> Result<String, ApplicationError> {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let Some(end) = rest[start + 2..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 2..start + 2 + end];
+            let value = variables.get(name).ok_or_else(|| ApplicationError::new(&format!("Unknown variable {name}")))?;
+            result.push_str(&rest[..start]);
+            result.push_str(&encode(value));
+            rest = &rest[start + 3 + end..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+/**
+ * Implement the `Monitor` trait for `HttpScenarioMonitor`.
+ */
+impl super::Monitor for HttpScenarioMonitor {
+    /**
+     * Get the name of the monitor.
+     *
+     * Returns: The name of the monitor.
+     */
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * Get the status of the monitor.
+     *
+     * Returns: The status of the monitor.
+     */
+    fn get_status(&self) -> MonitorStatusType {
+        self.status.clone()
+    }
+
+    /**
+     * Get the database service.
+     *
+     * Returns: The database service.
+     */
+    fn get_database_service(&self) -> DatabaseServiceType {
+        self.database_service.clone()
+    }
+
+    /**
+     * Get the database store level.
+     *
+     * Returns: The database store level.
+     */
+    fn get_database_store_level(&self) -> DatabaseStoreLevel {
+        self.database_store_level.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+
+    use crate::common::configuration::HttpAssertions;
+
+    use super::*;
+
+    /**
+     * Login stand-in. Sets the session cookie and redirects to the home page.
+     */
+    async fn login(body: String) -> HttpResponse {
+        if body != r#"{"user":"admin"}"# {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::SeeOther()
+            .insert_header(("Location", "/home"))
+            .insert_header(("Set-Cookie", "session=abc; Path=/; HttpOnly"))
+            .finish()
+    }
+
+    /**
+     * Home page stand-in. Requires the session cookie and returns the api token.
+     */
+    async fn home(req: HttpRequest) -> HttpResponse {
+        match req.cookie("session") {
+            Some(cookie) if cookie.value() == "abc" => HttpResponse::Ok()
+                .insert_header(("X-Csrf", "csrf-1"))
+                .json(serde_json::json!({ "token": "t-123" })),
+            _ => HttpResponse::Unauthorized().finish(),
+        }
+    }
+
+    /**
+     * Health stand-in. Requires the api token and the csrf value.
+     */
+    async fn health(req: HttpRequest, query: web::Query<HashMap<String, String>>) -> HttpResponse {
+        let authorized = req.headers().get("Authorization").is_some_and(|value| value == "Bearer t-123");
+        if !authorized || query.get("csrf").map(String::as_str) != Some("csrf-1") {
+            return HttpResponse::Unauthorized().finish();
+        }
+        HttpResponse::Ok().json(serde_json::json!({ "status": "UP", "version": "2" }))
+    }
+
+    /**
+     * Redirect stand-in. Redirects to the forwarded headers on another host name of the server.
+     */
+    async fn redirect(req: HttpRequest) -> HttpResponse {
+        let port = req.app_config().local_addr().port();
+        HttpResponse::Found().insert_header(("Location", format!("http://localhost:{port}/forwarded"))).finish()
+    }
+
+    /**
+     * Forwarded headers stand-in. Returns the sensitive headers of the request.
+     */
+    async fn forwarded(req: HttpRequest) -> HttpResponse {
+        let headers: Vec<&str> = ["Authorization", "Cookie"].into_iter().filter(|header| req.headers().contains_key(*header)).collect();
+        HttpResponse::Ok().json(serde_json::json!({ "headers": headers.join(",") }))
+    }
+
+    /**
+     * Start the stand-in server.
+     *
+     * Returns the port and the handle of the server.
+     */
+    fn start_server() -> (u16, actix_web::dev::ServerHandle) {
+        let server = HttpServer::new(|| {
+            App::new()
+                .route("/login", web::post().to(login))
+                .route("/home", web::get().to(home))
+                .route("/api/health", web::get().to(health))
+                .route("/redirect", web::get().to(redirect))
+                .route("/forwarded", web::get().to(forwarded))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let port = server.addrs()[0].port();
+        let server = server.run();
+        let handle = server.handle();
+        actix_web::rt::spawn(server);
+        (port, handle)
+    }
+
+    fn get_steps(port: u16, expected_status: &str) -> Vec<HttpScenarioStep> {
+        vec![
+            HttpScenarioStep {
+                name: "login".to_string(),
+                url: format!("http://127.0.0.1:{port}/login"),
+                method: HttpMethod::Post,
+                body: Some(r#"{"user":"admin"}"#.to_string()),
+                headers: None,
+                follow_redirects: true,
+                assertions: None,
+                extract: Some(HashMap::from([
+                    ("token".to_string(), HttpExtraction::JsonPath("$.token".to_string())),
+                    ("csrf".to_string(), HttpExtraction::Header("X-Csrf".to_string())),
+                ])),
+            },
+            HttpScenarioStep {
+                name: "health".to_string(),
+                url: format!("http://127.0.0.1:{port}/api/health?csrf=${{csrf}}"),
+                method: HttpMethod::Get,
+                body: None,
+                headers: Some(HashMap::from([("Authorization".to_string(), "Bearer ${token}".to_string())])),
+                follow_redirects: true,
+                assertions: Some(HttpAssertions {
+                    json_path: Some("$.status".to_string()),
+                    json_path_value: Some(serde_json::Value::String(expected_status.to_string())),
+                    ..Default::default()
+                }),
+                extract: Some(HashMap::from([("version".to_string(), HttpExtraction::Regex("\"version\":\"(\\d+)\"".to_string()))])),
+            },
+        ]
+    }
+
+    fn get_monitor(steps: &[HttpScenarioStep], status: &MonitorStatusType) -> HttpScenarioMonitor {
+        HttpScenarioMonitor::new("scenario", &None, steps, true, false, false, None, None, None, status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_check_scenario() {
+        let (port, handle) = start_server();
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_monitor(&get_steps(port, "UP"), &status);
+        monitor.check().await;
+        let monitor_status = status.lock().unwrap().get("scenario").unwrap().clone();
+        assert_eq!(monitor_status.status, Status::Ok);
+        assert!(monitor_status.details.unwrap().starts_with("Timings: login "));
+        handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn test_check_scenario_failed_assertion() {
+        let (port, handle) = start_server();
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_monitor(&get_steps(port, "DOWN"), &status);
+        monitor.check().await;
+        let Status::Error { message } = status.lock().unwrap().get("scenario").unwrap().status.clone() else {
+            panic!("Expected error status");
+        };
+        assert!(message.starts_with("Step health failed: Assertions failed: jsonPath: $.status is \"UP\", expected \"DOWN\". Timings: login "));
+        assert!(message.contains(" ms, health "));
+        handle.stop(false).await;
+    }
+
+    #[actix_web::test]
+    async fn test_check_scenario_without_session() {
+        let (port, handle) = start_server();
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut steps = get_steps(port, "UP");
+        steps[0].follow_redirects = false;
+        steps[0].assertions = Some(HttpAssertions { status_codes: Some(vec![303]), ..Default::default() });
+        steps[0].extract = None;
+        let monitor = get_monitor(&steps, &status);
+        let (Status::Error { message }, _) = monitor.run_scenario().await else {
+            panic!("Expected error status");
+        };
+        assert!(message.starts_with("Step health failed: Unknown variable csrf."));
+        handle.stop(false).await;
+    }
+
+    #[test]
+    fn test_new_invalid_root_certificate() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let result = HttpScenarioMonitor::new("scenario", &None, &get_steps(1, "UP"), true, false, false, Some("/non/existing/root.pem".to_string()), None, None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None);
+        assert!(result.unwrap_err().message.starts_with("Error reading root_certificate"));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let variables = HashMap::from([("token".to_string(), "t-123".to_string()), ("id".to_string(), "7".to_string())]);
+        assert_eq!(HttpScenarioMonitor::substitute("Bearer ${token}", &variables).unwrap(), "Bearer t-123");
+        assert_eq!(HttpScenarioMonitor::substitute("/items/${id}/${id}?a=${token}", &variables).unwrap(), "/items/7/7?a=t-123");
+        assert_eq!(HttpScenarioMonitor::substitute("no variables ${", &variables).unwrap(), "no variables ${");
+        assert_eq!(HttpScenarioMonitor::substitute("${missing}", &variables).unwrap_err().message, "Unknown variable missing");
+        let variables = HashMap::from([("csrf".to_string(), "a+b/c=d&e f~g".to_string())]);
+        assert_eq!(HttpScenarioMonitor::substitute_url("/health?csrf=${csrf}", &variables).unwrap(), "/health?csrf=a%2Bb%2Fc%3Dd%26e%20f~g");
+        assert_eq!(HttpScenarioMonitor::substitute("csrf=${csrf}", &variables).unwrap(), "csrf=a+b/c=d&e f~g");
+    }
+
+    #[actix_web::test]
+    async fn test_check_scenario_cross_host_redirect() {
+        let (port, handle) = start_server();
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut steps = get_steps(port, "UP");
+        steps[1] = HttpScenarioStep {
+            name: "redirect".to_string(),
+            url: format!("http://127.0.0.1:{port}/redirect"),
+            method: HttpMethod::Get,
+            body: None,
+            headers: Some(HashMap::from([("Authorization".to_string(), "Bearer ${token}".to_string())])),
+            follow_redirects: true,
+            assertions: Some(HttpAssertions {
+                json_path: Some("$.headers".to_string()),
+                json_path_value: Some(serde_json::Value::String(String::new())),
+                ..Default::default()
+            }),
+            extract: None,
+        };
+        let monitor = get_monitor(&steps, &status);
+        assert_eq!(monitor.run_scenario().await.0, Status::Ok);
+        handle.stop(false).await;
+    }
+
+    #[test]
+    fn test_extract() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Token", "header-token".parse().unwrap());
+        let body = br#"{"token": "t-123", "count": 3, "version":"2"}"#;
+        assert_eq!(Extraction::new(&HttpExtraction::JsonPath("$.token".to_string())).unwrap().extract(&headers, body).unwrap(), "t-123");
+        assert_eq!(Extraction::new(&HttpExtraction::JsonPath("$.count".to_string())).unwrap().extract(&headers, body).unwrap(), "3");
+        assert_eq!(Extraction::new(&HttpExtraction::Regex("\"version\":\"(\\d+)\"".to_string())).unwrap().extract(&headers, body).unwrap(), "2");
+        assert_eq!(Extraction::new(&HttpExtraction::Regex("t-\\d+".to_string())).unwrap().extract(&headers, body).unwrap(), "t-123");
+        assert_eq!(Extraction::new(&HttpExtraction::Header("x-token".to_string())).unwrap().extract(&headers, body).unwrap(), "header-token");
+        assert!(Extraction::new(&HttpExtraction::Header("X-Missing".to_string())).unwrap().extract(&headers, body).is_err());
+        assert!(Extraction::new(&HttpExtraction::Regex("(".to_string())).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use tokio_cron_scheduler::Job;
 
 use crate::common::{configuration::{DatabaseStoreLevel, ThresholdLevel}, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
 
-use super::Monitor;
+use super::{common::check_with_timeout, Monitor};
 /**
  * Values for the status of the monitor to identify
  * the highest state.
@@ -250,6 +250,7 @@ impl LoadAvgMonitor {
      * Get a loadavg monitor job.
      * 
      * `schedule`: The schedule.
+     * `timeout`: The timeout of the check in seconds.
      * `name`: The name of the monitor.
      * `threshold_1min`: The threshold for the 1 minute load average.
      * `threshold_5min`: The threshold for the 5 minute load average.
@@ -266,12 +267,16 @@ impl LoadAvgMonitor {
     pub fn get_loadavg_monitor_job(
         loadavg_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating Loadavg monitor: {}", &loadavg_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {                
-            let mut loadavg_monitor = loadavg_monitor.clone();
+            let loadavg_monitor = loadavg_monitor.clone();
             Box::pin(async move {
-                loadavg_monitor.check().await;
+                check_with_timeout(loadavg_monitor, timeout, |mut loadavg_monitor| async move {
+                    loadavg_monitor.check().await;
+                })
+                .await;
             })        
         });        
         match job_result {
@@ -575,7 +580,7 @@ mod test {
             &DatabaseStoreLevel::None,
             false,    
         );
-        let job = LoadAvgMonitor::get_loadavg_monitor_job(monitor, "0 0 * * * *");
+        let job = LoadAvgMonitor::get_loadavg_monitor_job(monitor, "0 0 * * * *", None);
         assert!(job.is_ok());
     }   
 
@@ -6,7 +6,7 @@ use tokio_cron_scheduler::Job;
 
 use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
 
-use super::Monitor;
+use super::{common::check_with_timeout, Monitor};
 
 /**
  * Meminfo monitor.
@@ -201,6 +201,7 @@ impl MeminfoMonitor {
      * Get meminfo monitor job.
      * 
      * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
      * 
      * Returns: The meminfo monitor job.
      * 
@@ -208,12 +209,16 @@ impl MeminfoMonitor {
     pub fn get_meminfo_monitor_job(
         memory_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating meminfo monitor: {}", &memory_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {                
-            let mut memory_monitor = memory_monitor.clone();
+            let memory_monitor = memory_monitor.clone();
             Box::pin(async move {
-                memory_monitor.check().await;
+                check_with_timeout(memory_monitor, timeout, |mut memory_monitor| async move {
+                    memory_monitor.check().await;
+                })
+                .await;
             })  
         });        
         match job_result {
@@ -477,7 +482,7 @@ mod test {
             &super::DatabaseStoreLevel::None,
             false,
         );
-        let job = MeminfoMonitor::get_meminfo_monitor_job(monitor ,"0 0 * * * *");
+        let job = MeminfoMonitor::get_meminfo_monitor_job(monitor ,"0 0 * * * *", None);
         assert!(job.is_ok());
     }  
 }
\ No newline at end of file
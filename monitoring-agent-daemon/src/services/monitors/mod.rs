@@ -4,32 +4,52 @@
  * `common`: Common functionality for monitors.
  * `commandmonitor`: Monitor that runs a command and checks the output.
  * `httpmonitor`: Monitor that checks the status of an HTTP service.
+ * `httpassertions`: Assertions on the response of an HTTP request.
+ * `httpscenariomonitor`: Monitor that runs an ordered list of HTTP requests.
  * `tcpmonitor`: Monitor that checks the status of a TCP service. 
  * `loadavgmonitor`: Monitor that checks the load average of the system.
  * `meminfomonitor`: Monitor that checks the memory information of the system.
  * `systemctlmonitor`: Monitor that checks the status of a systemd service.
  * `databasemonitor`: Monitor that checks the status of a database service.
  * `processmonitor`: Monitor that checks the status of a process.
+ * `processrule`: Rules matching the processes of a process monitor.
  * `certificatemonitor`: Monitor that checks the status of a certificate.
+ * `certificatefile`: Reads the certificates and private keys in local files.
+ * `remotecertificate`: Fetches the certificate chain presented by a remote endpoint.
+ * `diskmonitor`: Monitor that checks the disk space and inode use of the system.
+ * `cpumonitor`: Monitor that checks the cpu utilisation of the system.
+ * `networkmonitor`: Monitor that checks the throughput and errors of the network interfaces.
  */
 mod common;
 mod commandmonitor;
 mod httpmonitor;
+mod httpassertions;
+mod httpscenariomonitor;
 mod tcpmonitor;
 mod loadavgmonitor;
 mod meminfomonitor;
 mod systemctlmonitor;
 mod databasemonitor;
 mod processmonitor;
+mod processrule;
 mod certificatemonitor;
+mod certificatefile;
+mod remotecertificate;
+mod diskmonitor;
+mod cpumonitor;
+mod networkmonitor;
 
 pub use common::Monitor;
-pub use commandmonitor::CommandMonitor;
+pub use commandmonitor::{CommandMonitor, CommandOptions};
 pub use httpmonitor::HttpMonitor;
+pub use httpscenariomonitor::HttpScenarioMonitor;
 pub use tcpmonitor::TcpMonitor;
 pub use loadavgmonitor::LoadAvgMonitor;
 pub use meminfomonitor::MeminfoMonitor;
 pub use systemctlmonitor::SystemctlMonitor;
 pub use databasemonitor::DatabaseMonitor;
 pub use processmonitor::ProcessMonitor;
-pub use certificatemonitor::CertificateMonitor;
\ No newline at end of file
+pub use certificatemonitor::CertificateMonitor;
+pub use diskmonitor::DiskMonitor;
+pub use cpumonitor::CpuMonitor;
+pub use networkmonitor::NetworkMonitor;
\ No newline at end of file
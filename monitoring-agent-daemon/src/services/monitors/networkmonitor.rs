@@ -0,0 +1,419 @@
+use std::{sync::{Arc, Mutex}, time::Instant};
+
+use log::{debug, error, info};
+use monitoring_agent_lib::proc::{ProcsNetDev, ProcsNetDevRate};
+use tokio_cron_scheduler::Job;
+
+use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
+
+use super::{common::{check_threshold, check_with_timeout, get_combined_status}, Monitor};
+
+/**
+ * The loopback interface. Skipped when no interfaces are configured.
+ */
+const LOOPBACK_INTERFACE: &str = "lo";
+
+/**
+ * A sample of the interface counters and when it was taken.
+ */
+type NetDevSample = (Instant, Vec<ProcsNetDev>);
+
+/**
+ * Network monitor.
+ *
+ * This struct represents a network monitor. The throughput, errors and drops of the interfaces are computed
+ * from the /proc/net/dev counters between two runs of the monitor, so the first run only stores the sample.
+ *
+ * `name`: The name of the monitor.
+ * `interfaces`: The interfaces to check. If None then all interfaces except loopback are checked.
+ * `error_percentage_saturation`: The maximum percentage of the link speed used before error.
+ * `warn_percentage_saturation`: The maximum percentage of the link speed used before warning.
+ * `error_on_errors`: Change to error when the receive or transmit errors increase.
+ * `warn_on_drops`: Change to warning when the dropped packets increase.
+ * `previous_netdev`: The sample from the previous run and when it was taken. Shared between the runs of the job.
+ * `status`: The status of the monitor.
+ * `database_service`: The database service.
+ * `database_store_level`: The database store level.
+ * `store_current_network`: Store the current throughput.
+ */
+#[derive(Debug, Clone)]
+pub struct NetworkMonitor {
+    /// The name of the monitor.
+    pub name: String,
+    /// The interfaces to check.
+    pub interfaces: Option<Vec<String>>,
+    /// Maximum percentage of the link speed used before error.
+    pub error_percentage_saturation: Option<f64>,
+    /// Maximum percentage of the link speed used before warning.
+    pub warn_percentage_saturation: Option<f64>,
+    /// Change to error when the errors increase.
+    pub error_on_errors: bool,
+    /// Change to warning when the dropped packets increase.
+    pub warn_on_drops: bool,
+    /// The sample from the previous run.
+    previous_netdev: Arc<Mutex<Option<NetDevSample>>>,
+    /// The status of the monitor.
+    pub status: MonitorStatusType,
+    /// The database service
+    database_service: DatabaseServiceType,
+    /// The database store level.
+    database_store_level: DatabaseStoreLevel,
+    /// Store the current throughput.
+    store_current_network: bool,
+}
+
+impl NetworkMonitor {
+
+    /**
+     * Create a new `NetworkMonitor`.
+     *
+     * `name`: The name of the monitor.
+     * `description`: The description of the monitor.
+     * `interfaces`: The interfaces to check. If None then all interfaces except loopback are checked.
+     * `error_percentage_saturation`: The maximum percentage of the link speed used before error.
+     * `warn_percentage_saturation`: The maximum percentage of the link speed used before warning.
+     * `error_on_errors`: Change to error when the receive or transmit errors increase.
+     * `warn_on_drops`: Change to warning when the dropped packets increase.
+     * `status`: The status of the monitor.
+     * `database_service`: The database service.
+     * `database_store_level`: The database store level.
+     * `store_current_network`: Store the current throughput.
+     *
+     * Returns: A new `NetworkMonitor`.
+     *
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        description: &Option<String>,
+        interfaces: Option<Vec<String>>,
+        error_percentage_saturation: Option<f64>,
+        warn_percentage_saturation: Option<f64>,
+        error_on_errors: bool,
+        warn_on_drops: bool,
+        status: &MonitorStatusType,
+        database_service: &DatabaseServiceType,
+        database_store_level: &DatabaseStoreLevel,
+        store_current_network: bool,
+    ) -> NetworkMonitor {
+
+        let status_lock = status.lock();
+        match status_lock {
+            Ok(mut lock) => {
+                lock.insert(name.to_string(), MonitorStatus::new(name, description, Status::Unknown));
+            }
+            Err(err) => {
+                error!("Error creating network monitor: {:?}", err);
+            }
+        }
+
+        NetworkMonitor {
+            name: name.to_string(),
+            interfaces,
+            error_percentage_saturation,
+            warn_percentage_saturation,
+            error_on_errors,
+            warn_on_drops,
+            previous_netdev: Arc::new(Mutex::new(None)),
+            status: status.clone(),
+            database_service: database_service.clone(),
+            database_store_level: database_store_level.clone(),
+            store_current_network,
+        }
+    }
+
+    /**
+     * Get the throughput since the previous sample and replace the previous sample with the current.
+     *
+     * `current_netdev`: The current sample.
+     * `now`: When the current sample was taken.
+     *
+     * Returns: The throughput of the monitored interfaces or None if there is no previous sample.
+     *
+     * throws: `ApplicationError`: If the previous sample could not be locked.
+     */
+    fn get_network_rates(&self, current_netdev: Vec<ProcsNetDev>, now: Instant) -> Result<Option<Vec<ProcsNetDevRate>>, ApplicationError> {
+        let current_netdev: Vec<ProcsNetDev> = current_netdev.into_iter().filter(|netdev| self.is_monitored(&netdev.interface)).collect();
+        let mut previous_netdev = self.previous_netdev.lock().map_err(|err| ApplicationError::new(&format!("Error locking previous network sample: {err:?}")))?;
+        let rates = previous_netdev.as_ref().map(|(previous_time, previous_netdev)| {
+            ProcsNetDevRate::from_netdevs(previous_netdev, &current_netdev, now.duration_since(*previous_time).as_secs_f64())
+        });
+        *previous_netdev = Some((now, current_netdev));
+        Ok(rates)
+    }
+
+    /**
+     * Check if the interface is monitored.
+     *
+     * `interface`: The name of the interface.
+     *
+     * Returns: True if the interface is configured, or if no interfaces are configured and it is not loopback.
+     */
+    fn is_monitored(&self, interface: &str) -> bool {
+        match &self.interfaces {
+            Some(interfaces) => interfaces.iter().any(|configured| configured == interface),
+            None => interface != LOOPBACK_INTERFACE,
+        }
+    }
+
+    /**
+     * Check the network throughput.
+     *
+     * `rates`: The throughput of the interfaces.
+     *
+     */
+    async fn check_network_rates(&mut self, rates: &[ProcsNetDevRate]) {
+        let mut statuses = Vec::new();
+        for rate in rates {
+            statuses.push(NetworkMonitor::check_saturation(self.error_percentage_saturation, self.warn_percentage_saturation, rate.get_percent_saturation(), &rate.interface));
+            let interface_errors = rate.rx_errors + rate.tx_errors;
+            if self.error_on_errors && interface_errors > 0 {
+                statuses.push(Status::Error { message: format!("Errors increased by {interface_errors} on {}", rate.interface) });
+            }
+            let interface_drops = rate.rx_drop + rate.tx_drop;
+            if self.warn_on_drops && interface_drops > 0 {
+                statuses.push(Status::Warn { message: format!("Dropped packets increased by {interface_drops} on {}", rate.interface) });
+            }
+        }
+        self.set_status(&get_combined_status("Network check failed", &statuses)).await;
+    }
+
+    /**
+     * Check the link saturation.
+     *
+     * `error`: The error threshold.
+     * `warn`: The warning threshold.
+     * `current`: The current percentage of the link speed used.
+     * `interface`: The name of the interface.
+     *
+     * Returns: The status of the check.
+     *
+     */
+    fn check_saturation(error: Option<f64>, warn: Option<f64>, current: Option<f64>, interface: &str) -> Status {
+        let Some(current) = current else { return Status::Ok };
+        check_threshold(current, error, warn, |is_error, threshold| {
+            let level = if is_error { "Error" } else { "Warning" };
+            format!("{level} saturation {current:0.2}% on {interface} is more than {threshold:0.2}%")
+        })
+    }
+
+    /**
+     * Store the current throughput.
+     *
+     * `rates`: The current throughput.
+     */
+    async fn store_current_network(&self, rates: &[ProcsNetDevRate]) {
+        if !self.store_current_network {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            match database_service.store_network(rates).await {
+                Ok(()) => {}
+                Err(err) => {
+                    error!("Error storing network throughput: {:?}", err);
+                }
+            }
+        }
+    }
+
+    /**
+     * Get network monitor job.
+     *
+     * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
+     *
+     * Returns: The network monitor job.
+     *
+     */
+    pub fn get_network_monitor_job(
+        network_monitor: Self,
+        schedule: &str,
+        timeout: Option<u64>,
+    ) -> Result<Job, ApplicationError> {
+        info!("Creating network monitor: {}", &network_monitor.name);
+        let job_result = Job::new_async(schedule, move |_uuid, _locked| {
+            let network_monitor = network_monitor.clone();
+            Box::pin(async move {
+                check_with_timeout(network_monitor, timeout, |mut network_monitor| async move {
+                    network_monitor.check().await;
+                })
+                .await;
+            })
+        });
+        match job_result {
+            Ok(job) => Ok(job),
+            Err(err) => Err(ApplicationError::new(
+                format!("Could not create job: {err}").as_str(),
+            )),
+        }
+    }
+
+    /**
+     * Check the monitor.
+     */
+    async fn check(&mut self) {
+        debug!("Checking monitor: {}", &self.name);
+        let current_netdev = match ProcsNetDev::get_netdev() {
+            Ok(current_netdev) => current_netdev,
+            Err(err) => {
+                error!("Error getting network statistics: {:?}", err);
+                self.set_status(&Status::Error { message: format!("Error getting network statistics: {}", err.message) }).await;
+                return;
+            }
+        };
+        match self.get_network_rates(current_netdev, Instant::now()) {
+            Ok(Some(rates)) => {
+                self.store_current_network(&rates).await;
+                self.check_network_rates(&rates).await;
+            }
+            Ok(None) => {
+                debug!("No previous network sample for monitor: {}", &self.name);
+            }
+            Err(err) => {
+                error!("Error getting network throughput: {:?}", err);
+            }
+        }
+    }
+
+}
+
+/**
+ * Implement the `Monitor` trait for `NetworkMonitor`.
+ */
+impl super::Monitor for NetworkMonitor {
+    /**
+     * Get the name of the monitor.
+     *
+     * Returns: The name of the monitor.
+     */
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /**
+     * Get the status of the monitor.
+     *
+     * Returns: The status of the monitor.
+     */
+    fn get_status(&self) -> MonitorStatusType {
+        self.status.clone()
+    }
+
+    /**
+     * Get the database service.
+     *
+     * Returns: The database service.
+     */
+    fn get_database_service(&self) -> DatabaseServiceType {
+        self.database_service.clone()
+    }
+
+    /**
+     * Get the database store level.
+     *
+     * Returns: The database store level.
+     */
+    fn get_database_store_level(&self) -> DatabaseStoreLevel {
+        self.database_store_level.clone()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, time::Duration};
+
+    use super::*;
+
+    fn get_network_monitor(status: &MonitorStatusType, interfaces: Option<Vec<String>>) -> NetworkMonitor {
+        NetworkMonitor::new(
+            "test",
+            &None,
+            interfaces,
+            Some(90.0),
+            Some(70.0),
+            true,
+            true,
+            status,
+            &Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = NetworkMonitor::new("test", &None, None, None, None, false, false, &status, &Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Unknown);
+        monitor.clone().check().await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    #[test]
+    fn test_get_network_rates() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let monitor = get_network_monitor(&status, None);
+        let start = Instant::now();
+        let first = monitor.get_network_rates(vec![
+            ProcsNetDev::new("lo", 0, 0, 0, 0, 0, 0, 0, 0, None),
+            ProcsNetDev::new("eth0", 0, 0, 0, 0, 0, 0, 0, 0, None),
+        ], start).unwrap();
+        assert!(first.is_none());
+        let second = monitor.clone().get_network_rates(vec![
+            ProcsNetDev::new("lo", 1000, 0, 0, 0, 0, 0, 0, 0, None),
+            ProcsNetDev::new("eth0", 2000, 0, 0, 0, 4000, 0, 0, 0, None),
+        ], start + Duration::from_secs(2)).unwrap().unwrap();
+        assert_eq!(second, vec![ProcsNetDevRate::new("eth0", 1000.0, 2000.0, 0, 0, 0, 0, None)]);
+    }
+
+    #[test]
+    fn test_is_monitored() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        assert!(!get_network_monitor(&status, None).is_monitored("lo"));
+        assert!(get_network_monitor(&status, None).is_monitored("eth0"));
+        assert!(get_network_monitor(&status, Some(vec!["lo".to_string()])).is_monitored("lo"));
+        assert!(!get_network_monitor(&status, Some(vec!["lo".to_string()])).is_monitored("eth0"));
+    }
+
+    #[tokio::test]
+    async fn test_check_network_rates_ok() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_network_monitor(&status, None);
+        monitor.check_network_rates(&[ProcsNetDevRate::new("eth0", 1_250_000.0, 0.0, 0, 0, 0, 0, Some(100))]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_check_network_rates_warn() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_network_monitor(&status, None);
+        monitor.check_network_rates(&[ProcsNetDevRate::new("eth0", 10_000_000.0, 0.0, 0, 0, 1, 1, Some(100))]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Warn { message: "Network check failed: Warning saturation 80.00% on eth0 is more than 70.00%, Dropped packets increased by 2 on eth0".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_check_network_rates_error() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let mut monitor = get_network_monitor(&status, None);
+        monitor.check_network_rates(&[ProcsNetDevRate::new("eth0", 0.0, 0.0, 1, 2, 0, 0, None)]).await;
+        assert_eq!(status.lock().unwrap().get("test").unwrap().status, Status::Error { message: "Network check failed: Errors increased by 3 on eth0".to_string() });
+    }
+
+    #[test]
+    fn test_check_saturation() {
+        assert_eq!(NetworkMonitor::check_saturation(Some(90.0), Some(70.0), None, "eth0"), Status::Ok);
+        assert_eq!(NetworkMonitor::check_saturation(Some(90.0), Some(70.0), Some(69.0), "eth0"), Status::Ok);
+        assert_eq!(NetworkMonitor::check_saturation(Some(90.0), Some(70.0), Some(71.0), "eth0"), Status::Warn { message: "Warning saturation 71.00% on eth0 is more than 70.00%".to_string() });
+        assert_eq!(NetworkMonitor::check_saturation(Some(90.0), Some(70.0), Some(91.0), "eth0"), Status::Error { message: "Error saturation 91.00% on eth0 is more than 90.00%".to_string() });
+    }
+
+    #[test]
+    fn test_get_network_monitor_job() {
+        let status: MonitorStatusType = Arc::new(Mutex::new(HashMap::new()));
+        let monitor = get_network_monitor(&status, None);
+        let job = NetworkMonitor::get_network_monitor_job(monitor, "0 0 * * * *", None);
+        assert!(job.is_ok());
+    }
+}
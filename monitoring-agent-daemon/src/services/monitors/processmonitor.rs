@@ -1,21 +1,28 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, sync::{Arc, Mutex}, time::Instant};
+
 use log::{debug, error, info};
-use monitoring_agent_lib::proc::{ProcsProcess, ProcsStatm};
+use monitoring_agent_lib::proc::{ProcsProcess, ProcsProcessFd, ProcsProcessIo, ProcsProcessIoRate, ProcsProcessStat, ProcsStatm};
 use tokio_cron_scheduler::Job;
-use regex::Regex;
 
-use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
-use super::Monitor;
+use crate::common::{configuration::{DatabaseStoreLevel, ProcessMatchRule}, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
+use super::{common::{self, check_with_timeout}, processrule::ProcessRule, Monitor};
 
 /**
  * The `ProcessMonitor` struct represents a moniitor for checking processes.
  * 
  * `name`: The monitor name.
  * `description`: The description of the monitor.
- * `application_names`: The application names to monitor.
- * `pids`: The process ids to monitor.
- * `regexp`: The regular expression.
+ * `rules`: The rules matching the processes to monitor.
+ * `previous_pids`: The process ids matching each rule in the previous run. Shared between the runs of the job.
  * `threshold_mem_error`: The max memory usage.
  * `threshold_mem_warn`: The warn memory usage.
+ * `threshold_cpu_error`: The max cpu usage in percent of one core.
+ * `threshold_cpu_warn`: The warn cpu usage in percent of one core.
+ * `threshold_io_error`: The max bytes read and written per second.
+ * `threshold_io_warn`: The warn bytes read and written per second.
+ * `threshold_fd_error`: The max percentage of the open files limit in use.
+ * `threshold_fd_warn`: The warn percentage of the open files limit in use.
+ * `previous_samples`: The cpu and I/O counters of each process in the previous run. Shared between the runs of the job.
  * `status`: The status of the monitor.
  * `database_service`: The database service.
  * `database_store_level`: The database store level.
@@ -26,16 +33,28 @@ use super::Monitor;
 pub struct ProcessMonitor {
     /// The monitor name.
     name: String,
-    /// Application names to monitor.
-    application_names: Option<Vec<String>>,
-    /// Pids to monitor.
-    pids: Option<Vec<u32>>,
-    /// regexp.
-    regexp: Option<String>,
+    /// The rules matching the processes to monitor.
+    rules: Vec<ProcessRule>,
+    /// The process ids matching each rule in the previous run, by the index of the rule.
+    previous_pids: Arc<Mutex<HashMap<usize, BTreeSet<u32>>>>,
     /// The error threshold.
     threshold_mem_error: Option<u64>,        
     /// The warn threshold.
     threshold_mem_warn: Option<u64>,
+    /// The cpu error threshold.
+    threshold_cpu_error: Option<f64>,
+    /// The cpu warn threshold.
+    threshold_cpu_warn: Option<f64>,
+    /// The I/O error threshold.
+    threshold_io_error: Option<u64>,
+    /// The I/O warn threshold.
+    threshold_io_warn: Option<u64>,
+    /// The file descriptor error threshold.
+    threshold_fd_error: Option<f64>,
+    /// The file descriptor warn threshold.
+    threshold_fd_warn: Option<f64>,
+    /// The cpu and I/O counters of each process in the previous run, by process id.
+    previous_samples: Arc<Mutex<HashMap<u32, ProcessSample>>>,
     /// The status of the monitor.
     status: MonitorStatusType,    
     /// The database service
@@ -54,14 +73,24 @@ impl ProcessMonitor {
      * `application_names`: The application names to monitor.
      * `pids`: The process ids to monitor.
      * `regexp`: The regular expression. 
+     * `rules`: The match rules.
      * `threshold_mem_warn`: The warn threshold.
      * `threshold_mem_error`: The error threshold.
+     * `threshold_cpu_warn`: The cpu warn threshold in percent of one core.
+     * `threshold_cpu_error`: The cpu error threshold in percent of one core.
+     * `threshold_io_warn`: The I/O warn threshold in bytes per second.
+     * `threshold_io_error`: The I/O error threshold in bytes per second.
+     * `threshold_fd_warn`: The file descriptor warn threshold in percent of the open files limit.
+     * `threshold_fd_error`: The file descriptor error threshold in percent of the open files limit.
      * `status`: The status of the monitor.
      * `database_service`: The database service.
      * `database_store_level`: The database store level.
      * `store_current_statm`: Store the current statm.
      * 
      * Returns a new `ProcessMonitor`.
+     * 
+     * # Errors
+     * - If a match rule is invalid.
      */
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -70,14 +99,22 @@ impl ProcessMonitor {
         application_names: Option<Vec<String>>,
         pids: Option<Vec<u32>>,
         regexp: Option<String>,
+        rules: &Option<Vec<ProcessMatchRule>>,
         threshold_mem_warn: Option<u64>,
         threshold_mem_error: Option<u64>,
+        threshold_cpu_warn: Option<f64>,
+        threshold_cpu_error: Option<f64>,
+        threshold_io_warn: Option<u64>,
+        threshold_io_error: Option<u64>,
+        threshold_fd_warn: Option<f64>,
+        threshold_fd_error: Option<f64>,
         status: &MonitorStatusType,
         database_service: &DatabaseServiceType,
         database_store_level: &DatabaseStoreLevel,
         store_current_statm: bool
-    ) -> ProcessMonitor {
+    ) -> Result<ProcessMonitor, ApplicationError> {
         debug!("Creating Process monitor: {}", &name);
+        let rules = ProcessRule::get_rules(&application_names, &pids, &regexp, rules)?;
         let status_lock = status.lock();
         match status_lock {
             Ok(mut lock) => {
@@ -87,35 +124,46 @@ impl ProcessMonitor {
                 error!("Error creating systemctl monitor: {err:?}");
             }
         }            
-        ProcessMonitor {
+        Ok(ProcessMonitor {
             name: name.to_string(),
-            application_names,
-            pids,
-            regexp,
+            rules,
+            previous_pids: Arc::new(Mutex::new(HashMap::new())),
             threshold_mem_warn,
             threshold_mem_error,
+            threshold_cpu_warn,
+            threshold_cpu_error,
+            threshold_io_warn,
+            threshold_io_error,
+            threshold_fd_warn,
+            threshold_fd_error,
+            previous_samples: Arc::new(Mutex::new(HashMap::new())),
             status: status.clone(),
             database_service: database_service.clone(),
             database_store_level: database_store_level.clone(),
             store_current_statm
-        }
+        })
     }
 
     /**
      * Get process job.
      *
      * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
      */
     pub fn get_process_monitor_job(
         process_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating Process monitor: {}", &process_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut process_monitor = process_monitor.clone();
-                async move {                
-                    let _ = process_monitor.check().await.map_err(|err| error!("Error checking process monitor: {err:?}"));
+                let process_monitor = process_monitor.clone();
+                async move {
+                    check_with_timeout(process_monitor, timeout, |mut process_monitor| async move {
+                        let _ = process_monitor.check().await.map_err(|err| error!("Error checking process monitor: {err:?}"));
+                    })
+                    .await;
                 }
             })              
         });        
@@ -128,46 +176,62 @@ impl ProcessMonitor {
     }
 
     /**
-     * Check the applications.
+     * Check the applications. Each rule must match between its minimum and maximum number of processes,
+     * and the memory, cpu, I/O and file descriptor use of every matching process is checked. The cpu and
+     * I/O use are rates since the previous run, so they are checked from the second run.
      * 
      * Returns: The result of the check.
      * 
      * # Errors
-     * - If there is an error checking the application.
+     * - If there is an error reading the processes.
+     * - If the previous process ids or samples could not be locked.
      * 
      */
     pub async fn check(&mut self) -> Result<(), ApplicationError> {
+        let processes = match ProcsProcess::get_all_processes() {
+            Ok(processes) => processes,
+            Err(err) => {
+                self.set_status(&Status::Error { message: format!("Error reading processes: {}", err.message) }).await;
+                return Err(ApplicationError::new(&err.message));
+            }
+        };
         let mut statuses: Vec<Status> = Vec::new();
-        let processes = ProcsProcess::get_all_processes();
-        let regexp: Option<Regex> = match self.regexp {
-            Some(ref regexp) => {
-                match Regex::new(regexp) {
-                    Ok(regexp) => Some(regexp),
-                    Err(err) => {
-                        error!("Error creating regular expression: {err:?}");
-                        None
-                    }
+        let mut matching_processes: BTreeMap<u32, (&str, &ProcsProcess)> = BTreeMap::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            let mut pids: BTreeSet<u32> = BTreeSet::new();
+            for process in rule.get_matches(&processes) {
+                if let Some(pid) = process.pid {
+                    pids.insert(pid);
+                    matching_processes.entry(pid).or_insert((rule.label.as_str(), process));
                 }
             }
-            None => None
-        };
-        if let Ok(processes) = processes {
-            for process in processes {
-                if  Self::check_pids(&self.pids, &process) || 
-                    Self::check_application_names(&self.application_names, &process) || 
-                    Self::check_regexp(&regexp, &process) {
-                    let check_process = self.check_process(&process).await;
-                    match check_process {
-                        Ok(status) => {
-                            statuses.push(status);
-                        }
-                        Err(err) => {
-                            error!("Error checking process: {err:?}");
-                        }
-                    }
+            statuses.push(Self::check_instances(rule, pids.len()));
+            statuses.push(self.check_restart(index, rule, &pids)?);
+        }
+        let previous_samples = std::mem::take(&mut *self.lock_previous_samples()?);
+        let mut samples: HashMap<u32, ProcessSample> = HashMap::new();
+        for (label, process) in matching_processes.values() {
+            let check_process = self.check_process(label, process).await;
+            match check_process {
+                Ok(status) => {
+                    statuses.push(status);
+                }
+                Err(err) => {
+                    error!("Error checking process: {err:?}");
+                }
+            }
+            let Some(pid) = process.pid else { continue };
+            if let Some(sample) = self.get_sample(pid) {
+                statuses.extend(self.check_usage(label, pid, previous_samples.get(&pid), &sample));
+                samples.insert(pid, sample);
+            }
+            if self.threshold_fd_warn.is_some() || self.threshold_fd_error.is_some() {
+                if let Ok(fd) = ProcsProcessFd::get_process_fd(pid) {
+                    statuses.push(self.check_fd(label, pid, &fd));
                 }
             }
-        }   
+        }
+        *self.lock_previous_samples()? = samples;
 
         let new_status = Status::get_max_status(statuses);
         self.set_status(&new_status).await;
@@ -175,70 +239,191 @@ impl ProcessMonitor {
     }    
 
     /**
-     * Check regexp.
+     * Get the processes matching the pids, application names, regular expression or match rules of a process monitor.
      * 
+     * `application_names`: The application names.
+     * `pids`: The process ids.
      * `regexp`: The regular expression.
-     * `process`: The process.
+     * `rules`: The match rules.
      * 
-     * Returns: The status of the check.
+     * Returns: The matching processes, labelled by the first rule that matched them.
+     * 
+     * # Errors
+     * - If there is an error reading the processes.
+     * - If a match rule is invalid.
      */
-    fn check_regexp(regexp: &Option<Regex>, process: &ProcsProcess) -> bool {
-        if let Some(regexp) = regexp {
-            if let Some(name) = &process.name {
-                return regexp.is_match(name);
+    pub fn get_tracked_processes(application_names: &Option<Vec<String>>, pids: &Option<Vec<u32>>, regexp: &Option<String>, rules: &Option<Vec<ProcessMatchRule>>) -> Result<Vec<(String, ProcsProcess)>, ApplicationError> {
+        let rules = ProcessRule::get_rules(application_names, pids, regexp, rules)?;
+        let processes = ProcsProcess::get_all_processes().map_err(|err| ApplicationError::new(&err.message))?;
+        let mut tracked_processes: BTreeMap<u32, (String, ProcsProcess)> = BTreeMap::new();
+        for rule in &rules {
+            for process in rule.get_matches(&processes) {
+                if let Some(pid) = process.pid {
+                    tracked_processes.entry(pid).or_insert_with(|| (rule.label.clone(), process.clone()));
+                }
             }
         }
-        false
+        Ok(tracked_processes.into_values().collect())
     }
 
     /**
-     * Check application names.
+     * Check the number of processes matching a rule.
      * 
-     * `application_names`: The application names.
-     * `process`: The process.
+     * `rule`: The rule.
+     * `instances`: The number of matching processes.
      * 
      * Returns: The status of the check.
      */
-    fn check_application_names(application_names: &Option<Vec<String>>, process: &ProcsProcess) -> bool {
-        if let Some(application_names) = application_names {
-            if let Some(name) = &process.name {
-                return application_names.contains(name);
+    fn check_instances(rule: &ProcessRule, instances: usize) -> Status {
+        let instances = u32::try_from(instances).unwrap_or(u32::MAX);
+        if instances == 0 && rule.min_instances > 0 {
+            return Status::Error { message: format!("No process matches {}", rule.label) };
+        }
+        if instances < rule.min_instances {
+            return Status::Error { message: format!("{instances} processes match {}, expected at least {}", rule.label, rule.min_instances) };
+        }
+        if let Some(max_instances) = rule.max_instances {
+            if instances > max_instances {
+                return Status::Error { message: format!("{instances} processes match {}, expected at most {max_instances}", rule.label) };
             }
         }
-        false
+        Status::Ok
     }
 
     /**
-     * Check if pid is in the pids to monitor.
+     * Check if the processes matching a rule have restarted, by comparing the process ids with the previous run.
+     * A rule without matching processes in this or the previous run is not a restart.
      * 
-     * `pids`: The pids to monitor.
+     * `index`: The index of the rule.
+     * `rule`: The rule.
+     * `pids`: The process ids matching the rule.
      * 
-     * Returns: The status of the check.
+     * Returns: Warn if the process ids changed.
+     * 
+     * # Errors
+     * - If the previous process ids could not be locked.
+     */
+    fn check_restart(&self, index: usize, rule: &ProcessRule, pids: &BTreeSet<u32>) -> Result<Status, ApplicationError> {
+        let mut previous_pids = self.previous_pids.lock().map_err(|err| ApplicationError::new(&format!("Error locking previous process ids: {err:?}")))?;
+        let previous = previous_pids.insert(index, pids.clone());
+        match previous {
+            Some(previous) if rule.warn_on_restart && !previous.is_empty() && !pids.is_empty() && previous != *pids => {
+                Ok(Status::Warn { message: format!("Process restarted: {} pids changed from {previous:?} to {pids:?}", rule.label) })
+            }
+            _ => Ok(Status::Ok),
+        }
+    }
+
+    /**
+     * Lock the samples of the previous run.
+     * 
+     * Returns: The locked samples.
+     * 
+     * # Errors
+     * - If the previous samples could not be locked.
      */
-    fn check_pids(pids: &Option<Vec<u32>>, process: &ProcsProcess) -> bool {
-        if let Some(pids) = pids {
-            if let Some(pid) = process.pid {
-                return pids.contains(&pid);
+    fn lock_previous_samples(&self) -> Result<std::sync::MutexGuard<'_, HashMap<u32, ProcessSample>>, ApplicationError> {
+        self.previous_samples.lock().map_err(|err| ApplicationError::new(&format!("Error locking previous process samples: {err:?}")))
+    }
+
+    /**
+     * Read the cpu and I/O counters of a process. The counters are only read if a cpu or I/O threshold is set.
+     * The I/O counters of a process owned by another user can only be read by root.
+     * 
+     * `pid`: The process id.
+     * 
+     * Returns: The sample, or None if no threshold is set or the process no longer exists.
+     */
+    fn get_sample(&self, pid: u32) -> Option<ProcessSample> {
+        let check_io = self.threshold_io_warn.is_some() || self.threshold_io_error.is_some();
+        if !check_io && self.threshold_cpu_warn.is_none() && self.threshold_cpu_error.is_none() {
+            return None;
+        }
+        let stat = ProcsProcessStat::get_process_stat(pid).ok()?;
+        let io = if check_io { ProcsProcessIo::get_process_io(pid).ok() } else { None };
+        Some(ProcessSample { stat, io, time: Instant::now() })
+    }
+
+    /**
+     * Check the cpu usage and I/O rate of a process since the previous run. A process id reused by a new
+     * process is not compared with the previous process.
+     * 
+     * `label`: The label of the rule that matched the process.
+     * `pid`: The process id.
+     * `previous`: The sample of the previous run.
+     * `sample`: The sample of this run.
+     * 
+     * Returns: The statuses of the checks.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    fn check_usage(&self, label: &str, pid: u32, previous: Option<&ProcessSample>, sample: &ProcessSample) -> Vec<Status> {
+        let Some(previous) = previous else { return Vec::new() };
+        let elapsed_seconds = sample.time.duration_since(previous.time).as_secs_f64();
+        let mut statuses = Vec::new();
+        if let Some(cpu_percent) = ProcsProcessStat::get_cpu_percent(&previous.stat, &sample.stat, elapsed_seconds) {
+            let description = format!("Process cpu usage of {label} pid {pid}");
+            statuses.push(Self::check_threshold(&description, cpu_percent, "%", self.threshold_cpu_warn, self.threshold_cpu_error));
+        }
+        if previous.stat.start_time == sample.stat.start_time {
+            if let (Some(previous_io), Some(io)) = (&previous.io, &sample.io) {
+                if let Some(rate) = ProcsProcessIoRate::from_io(previous_io, io, elapsed_seconds) {
+                    let description = format!("Process I/O of {label} pid {pid}");
+                    statuses.push(Self::check_threshold(&description, rate.get_total_bytes_per_second(), " B/s", self.threshold_io_warn.map(|threshold| threshold as f64), self.threshold_io_error.map(|threshold| threshold as f64)));
+                }
             }
         }
-        false
+        statuses
+    }
+
+    /**
+     * Check the open file descriptors of a process against its open files limit.
+     * 
+     * `label`: The label of the rule that matched the process.
+     * `pid`: The process id.
+     * `fd`: The file descriptor usage.
+     * 
+     * Returns: The status of the check. Ok if the process has no limit.
+     */
+    fn check_fd(&self, label: &str, pid: u32, fd: &ProcsProcessFd) -> Status {
+        let Some(percent_usage) = fd.get_percent_usage() else { return Status::Ok };
+        let description = format!("Process open files of {label} pid {pid} ({} of {})", fd.open, fd.soft_limit.unwrap_or_default());
+        Self::check_threshold(&description, percent_usage, "%", self.threshold_fd_warn, self.threshold_fd_error)
+    }
+
+    /**
+     * Check a value against the warn and error thresholds.
+     * 
+     * `description`: What is measured, used in the message.
+     * `value`: The value.
+     * `unit`: The unit, used in the message.
+     * `threshold_warn`: The warn threshold.
+     * `threshold_error`: The error threshold.
+     * 
+     * Returns: The status of the check.
+     */
+    fn check_threshold(description: &str, value: f64, unit: &str, threshold_warn: Option<f64>, threshold_error: Option<f64>) -> Status {
+        common::check_threshold(value, threshold_error, threshold_warn, |is_error, threshold| {
+            let level = if is_error { "error" } else { "warn" };
+            format!("{description} is over the {level} limit: {value:.1}{unit} > {threshold}{unit}")
+        })
     }
 
     /**
      * Check the process.
      * 
+     * `label`: The label of the rule that matched the process.
      * `process`: The process.
      *  
      * Returns: The status of the check.
      */
-    async fn check_process(&self, process: &ProcsProcess) -> Result<Status, ApplicationError> {
+    async fn check_process(&self, label: &str, process: &ProcsProcess) -> Result<Status, ApplicationError> {
         debug!("Checking process: {process:?}");
         let Some(pid) = process.pid else { return Ok(Status::Ok) };
         let name = process.name.clone().unwrap_or("Unknown".to_string());
         let statm = ProcsStatm::get_statm(pid);
         if let Ok(statm) = statm {
             self.store_statm_values(pid, &name, &statm).await;
-            Ok(self.check_max(&statm))
+            Ok(self.check_max(label, pid, &statm))
         } else {
             info!("Error getting statm for process, this could because the process no longer exist: {process:?}");
             Ok(Status::Ok)
@@ -248,23 +433,25 @@ impl ProcessMonitor {
     /**
      * Check the max memory usage.
      * 
+     * `label`: The label of the rule that matched the process.
+     * `pid`: The process id.
      * `statm`: The statm values.
      * 
      * Returns: The status of the check.
      */
-    fn check_max(&self, statm: &ProcsStatm) -> Status {        
+    fn check_max(&self, label: &str, pid: u32, statm: &ProcsStatm) -> Status {        
         let Some(resident) = statm.resident else { return Status::Ok };   
         let Some(pagesize) = statm.pagesize else { return Status::Ok };        
         let resident = u64::from(resident);
         let pagesize = u64::from(pagesize);           
         if let Some(threshold_mem_error) = self.threshold_mem_error {            
             if (resident * pagesize) > threshold_mem_error {
-                return Status::Error { message: format!("Process memory usage is over the error limit: {:?} > {threshold_mem_error:?}", (resident * pagesize))};
+                return Status::Error { message: format!("Process memory usage of {label} pid {pid} is over the error limit: {:?} > {threshold_mem_error:?}", (resident * pagesize))};
             }
         } 
         if let Some(threshold_mem_warn) = self.threshold_mem_warn {         
             if (resident * pagesize) > threshold_mem_warn {
-                return Status::Warn { message: format!("Process memory usage is over the warn limit: {:?} > {threshold_mem_warn:?}", (resident * pagesize))};
+                return Status::Warn { message: format!("Process memory usage of {label} pid {pid} is over the warn limit: {:?} > {threshold_mem_warn:?}", (resident * pagesize))};
             }
         }         
         Status::Ok
@@ -309,6 +496,23 @@ impl ProcessMonitor {
 
 }
 
+/**
+ * The cpu and I/O counters of a process at the time they were read.
+ *
+ * `stat`: The cpu times.
+ * `io`: The I/O counters. None if not read or not readable.
+ * `time`: When the counters were read.
+ */
+#[derive(Debug, Clone)]
+struct ProcessSample {
+    /// The cpu times.
+    stat: ProcsProcessStat,
+    /// The I/O counters.
+    io: Option<ProcsProcessIo>,
+    /// When the counters were read.
+    time: Instant,
+}
+
 /**
  * Implement the `Monitor` trait for `MemoryinfoMonitor`.
  */
@@ -364,14 +568,21 @@ mod test {
             Some(vec!["test_app".to_string()]),
             Some(vec![100u32]),
             Some("test".to_string()),
+            &None,
             Some(100),
             Some(100),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
             &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
             false
-        );
-        let job_result = ProcessMonitor::get_process_monitor_job(process_monitor, "* * * * * *");
+        ).unwrap();
+        let job_result = ProcessMonitor::get_process_monitor_job(process_monitor, "* * * * * *", None);
         assert!(job_result.is_ok());
     }
 
@@ -383,17 +594,33 @@ mod test {
             Some(vec!["test_app".to_string()].clone()),
             Some(vec![100u32]),
             Some("test".to_string()),
+            &None,
             Some(50),
             Some(100),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
             &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
             false
-        );
+        ).unwrap();
         let check_result = process_monitor.check().await;
         assert!(check_result.is_ok());
     }
 
+    #[test]
+    fn test_get_tracked_processes() {
+        let processes = ProcessMonitor::get_tracked_processes(&None, &Some(vec![std::process::id()]), &None, &None).unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].0, format!("pid={}", std::process::id()));
+        assert_eq!(processes[0].1.pid, Some(std::process::id()));
+        assert!(ProcessMonitor::get_tracked_processes(&None, &None, &Some("(".to_string()), &None).is_err());
+    }
+
     #[tokio::test]
     async fn test_check_systemd_status_not_ok() {
         let mut process_monitor = ProcessMonitor::new(
@@ -402,36 +629,292 @@ mod test {
             Some(vec!["systemd".to_string()]),
             None,
             None,
+            &None,
             Some(500),
             Some(1000),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
             &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
             false
-        );
+        ).unwrap();
         let check_result = process_monitor.check().await;
         assert!(check_result.is_ok());
         assert!(process_monitor.get_status().lock().unwrap().get("systemd monitor").unwrap().status != Status::Ok);
     }
 
+    /**
+     * Test the check method with a running process within the thresholds.
+     */
     #[tokio::test]
-    async fn test_check_systemd_status_ok() {
+    async fn test_check_status_ok() {
         let mut process_monitor = ProcessMonitor::new(
-            "systemd monitor",
+            "own monitor",
             &None,
-            Some(vec!["/sbin/init".to_string()]),
+            None,
+            Some(vec![std::process::id()]),
+            None,
+            &None,
+            Some(10_000_000_000),
+            Some(10_000_000_000),
+            None,
+            None,
+            None,
+            None,
             None,
             None,
-            Some(500),
-            Some(100000000),
             &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
             false
-        );
+        ).unwrap();
         let check_result = process_monitor.check().await;
         assert!(check_result.is_ok());
-        assert!(process_monitor.get_status().lock().unwrap().get("systemd monitor").unwrap().status == Status::Ok);
+        assert!(process_monitor.get_status().lock().unwrap().get("own monitor").unwrap().status == Status::Ok);
     }    
 
+    /**
+     * Create a process monitor with match rules.
+     * 
+     * `rules`: The match rules.
+     */
+    fn get_rules_monitor(rules: Vec<ProcessMatchRule>) -> ProcessMonitor {
+        ProcessMonitor::new(
+            "rules monitor",
+            &None,
+            None,
+            None,
+            None,
+            &Some(rules),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            &std::sync::Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false
+        ).unwrap()
+    }
+
+    /**
+     * Create a match rule on a pid.
+     * 
+     * `pid`: The process id.
+     * `min_instances`: The minimum number of matching processes.
+     * `max_instances`: The maximum number of matching processes.
+     */
+    fn get_pid_rule(pid: u32, min_instances: u32, max_instances: Option<u32>) -> ProcessMatchRule {
+        ProcessMatchRule {
+            name: Some("own".to_string()),
+            application_name: None,
+            regexp: None,
+            pid: Some(pid),
+            cmdline_regex: None,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            pidfile: None,
+            min_instances,
+            max_instances,
+            warn_on_restart: true,
+        }
+    }
+
+    /**
+     * Get the status of the rules monitor.
+     */
+    fn get_rules_status(process_monitor: &ProcessMonitor) -> Status {
+        process_monitor.get_status().lock().unwrap().get("rules monitor").unwrap().status.clone()
+    }
+
+    /**
+     * Test the check method with the minimum and maximum number of instances.
+     */
+    #[tokio::test]
+    async fn test_check_instances() {
+        let mut process_monitor = get_rules_monitor(vec![get_pid_rule(std::process::id(), 1, Some(1))]);
+        process_monitor.check().await.unwrap();
+        assert_eq!(get_rules_status(&process_monitor), Status::Ok);
+        let mut process_monitor = get_rules_monitor(vec![get_pid_rule(std::process::id(), 0, Some(0))]);
+        process_monitor.check().await.unwrap();
+        assert_eq!(get_rules_status(&process_monitor), Status::Error { message: "1 processes match own, expected at most 0".to_string() });
+        let mut process_monitor = get_rules_monitor(vec![get_pid_rule(std::process::id(), 2, None)]);
+        process_monitor.check().await.unwrap();
+        assert_eq!(get_rules_status(&process_monitor), Status::Error { message: "1 processes match own, expected at least 2".to_string() });
+        let mut process_monitor = get_rules_monitor(vec![get_pid_rule(u32::MAX, 1, None)]);
+        process_monitor.check().await.unwrap();
+        assert_eq!(get_rules_status(&process_monitor), Status::Error { message: "No process matches own".to_string() });
+        let mut process_monitor = get_rules_monitor(vec![get_pid_rule(u32::MAX, 0, None)]);
+        process_monitor.check().await.unwrap();
+        assert_eq!(get_rules_status(&process_monitor), Status::Ok);
+    }
+
+    /**
+     * Test that the memory status names the rule that matched the process.
+     */
+    #[tokio::test]
+    async fn test_check_memory_labelled_by_rule() {
+        let mut process_monitor = ProcessMonitor::new(
+            "rules monitor",
+            &None,
+            None,
+            None,
+            None,
+            &Some(vec![get_pid_rule(std::process::id(), 1, None)]),
+            Some(1),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            &std::sync::Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false
+        ).unwrap();
+        process_monitor.check().await.unwrap();
+        let Status::Warn { message } = get_rules_status(&process_monitor) else {
+            panic!("Expected warn status");
+        };
+        assert!(message.starts_with(&format!("Process memory usage of own pid {} is over the warn limit: ", std::process::id())));
+    }
+
+    /**
+     * Test that a change of the matching process ids between two runs is a restart.
+     */
+    #[test]
+    fn test_check_restart() {
+        let process_monitor = get_rules_monitor(vec![get_pid_rule(1, 1, None)]);
+        let rule = &process_monitor.rules[0];
+        assert_eq!(process_monitor.check_restart(0, rule, &BTreeSet::from([10, 11])).unwrap(), Status::Ok);
+        assert_eq!(process_monitor.check_restart(0, rule, &BTreeSet::from([10, 11])).unwrap(), Status::Ok);
+        assert_eq!(
+            process_monitor.check_restart(0, rule, &BTreeSet::from([10, 12])).unwrap(),
+            Status::Warn { message: "Process restarted: own pids changed from {10, 11} to {10, 12}".to_string() }
+        );
+        assert_eq!(process_monitor.check_restart(0, rule, &BTreeSet::new()).unwrap(), Status::Ok);
+        assert_eq!(process_monitor.check_restart(0, rule, &BTreeSet::from([13])).unwrap(), Status::Ok);
+        let process_monitor = get_rules_monitor(vec![ProcessMatchRule { warn_on_restart: false, ..get_pid_rule(1, 1, None) }]);
+        let rule = &process_monitor.rules[0];
+        process_monitor.check_restart(0, rule, &BTreeSet::from([10])).unwrap();
+        assert_eq!(process_monitor.check_restart(0, rule, &BTreeSet::from([11])).unwrap(), Status::Ok);
+    }
+
+
+    /**
+     * Create a process monitor with cpu, I/O and file descriptor thresholds.
+     */
+    fn get_usage_monitor() -> ProcessMonitor {
+        ProcessMonitor::new(
+            "usage monitor",
+            &None,
+            None,
+            Some(vec![std::process::id()]),
+            None,
+            &None,
+            None,
+            None,
+            Some(50.0),
+            Some(90.0),
+            Some(1000),
+            Some(10_000),
+            Some(50.0),
+            Some(90.0),
+            &std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            &std::sync::Arc::new(None),
+            &DatabaseStoreLevel::None,
+            false
+        ).unwrap()
+    }
+
+    /**
+     * Create a sample of a process.
+     * 
+     * `cpu_ticks`: The user mode clock ticks.
+     * `start_time`: The start time of the process.
+     * `read_bytes`: The bytes read.
+     * `time`: When the sample was read.
+     */
+    fn get_sample(cpu_ticks: u64, start_time: u64, read_bytes: u64, time: Instant) -> ProcessSample {
+        ProcessSample {
+            stat: ProcsProcessStat::new(10, cpu_ticks, 0, start_time, 0, 100, 100),
+            io: Some(ProcsProcessIo::new(0, 0, read_bytes, 0)),
+            time,
+        }
+    }
+
+    /**
+     * Test the cpu and I/O checks between two samples.
+     */
+    #[test]
+    fn test_check_usage() {
+        let process_monitor = get_usage_monitor();
+        let time = Instant::now();
+        let previous = get_sample(100, 1, 0, time);
+        assert!(process_monitor.check_usage("own", 10, None, &previous).is_empty());
+        let sample = get_sample(120, 1, 1000, time + std::time::Duration::from_secs(2));
+        assert_eq!(process_monitor.check_usage("own", 10, Some(&previous), &sample), vec![Status::Ok, Status::Ok]);
+        let sample = get_sample(250, 1, 4000, time + std::time::Duration::from_secs(2));
+        assert_eq!(
+            process_monitor.check_usage("own", 10, Some(&previous), &sample),
+            vec![
+                Status::Warn { message: "Process cpu usage of own pid 10 is over the warn limit: 75.0% > 50%".to_string() },
+                Status::Warn { message: "Process I/O of own pid 10 is over the warn limit: 2000.0 B/s > 1000 B/s".to_string() },
+            ]
+        );
+        let sample = get_sample(300, 1, 40_000, time + std::time::Duration::from_secs(2));
+        assert_eq!(
+            process_monitor.check_usage("own", 10, Some(&previous), &sample),
+            vec![
+                Status::Error { message: "Process cpu usage of own pid 10 is over the error limit: 100.0% > 90%".to_string() },
+                Status::Error { message: "Process I/O of own pid 10 is over the error limit: 20000.0 B/s > 10000 B/s".to_string() },
+            ]
+        );
+        let restarted = get_sample(300, 2, 40_000, time + std::time::Duration::from_secs(2));
+        assert!(process_monitor.check_usage("own", 10, Some(&previous), &restarted).is_empty());
+    }
+
+    /**
+     * Test the file descriptor check against the open files limit.
+     */
+    #[test]
+    fn test_check_fd() {
+        let process_monitor = get_usage_monitor();
+        assert_eq!(process_monitor.check_fd("own", 10, &ProcsProcessFd::new(10, Some(100), None)), Status::Ok);
+        assert_eq!(
+            process_monitor.check_fd("own", 10, &ProcsProcessFd::new(60, Some(100), None)),
+            Status::Warn { message: "Process open files of own pid 10 (60 of 100) is over the warn limit: 60.0% > 50%".to_string() }
+        );
+        assert_eq!(
+            process_monitor.check_fd("own", 10, &ProcsProcessFd::new(95, Some(100), None)),
+            Status::Error { message: "Process open files of own pid 10 (95 of 100) is over the error limit: 95.0% > 90%".to_string() }
+        );
+        assert_eq!(process_monitor.check_fd("own", 10, &ProcsProcessFd::new(1_000_000, None, None)), Status::Ok);
+    }
+
+    /**
+     * Test that the samples are kept between two runs.
+     */
+    #[tokio::test]
+    async fn test_check_keeps_samples() {
+        let mut process_monitor = get_usage_monitor();
+        process_monitor.check().await.unwrap();
+        process_monitor.check().await.unwrap();
+        let previous_samples = process_monitor.previous_samples.lock().unwrap();
+        assert_eq!(previous_samples.len(), 1);
+        assert!(previous_samples.contains_key(&std::process::id()));
+    }
+
 }
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use log::debug;
+use monitoring_agent_lib::proc::{ProcsCmdLine, ProcsProcess};
+use regex::Regex;
+
+use crate::common::{configuration::ProcessMatchRule, ApplicationError};
+
+/**
+ * A rule matching processes, prepared from the configuration. All the criteria given must match.
+ *
+ * `label`: The name of the rule used in the status message.
+ * `application_name`: The exact application name.
+ * `regexp`: Regular expression on the application name.
+ * `pid`: The process id.
+ * `cmdline_regex`: Regular expression on the full command line.
+ * `user`: The name of the effective user.
+ * `parent_pid`: The parent process id.
+ * `parent_name`: The application name of the parent process.
+ * `pidfile`: File containing the process id.
+ * `min_instances`: The minimum number of matching processes.
+ * `max_instances`: The maximum number of matching processes.
+ * `warn_on_restart`: Warn when the matching process ids change between two runs.
+ */
+#[derive(Debug, Clone)]
+pub struct ProcessRule {
+    /// The name of the rule used in the status message.
+    pub label: String,
+    /// The exact application name.
+    application_name: Option<String>,
+    /// Regular expression on the application name.
+    regexp: Option<Regex>,
+    /// The process id.
+    pid: Option<u32>,
+    /// Regular expression on the full command line.
+    cmdline_regex: Option<Regex>,
+    /// The name of the effective user.
+    user: Option<String>,
+    /// The parent process id.
+    parent_pid: Option<u32>,
+    /// The application name of the parent process.
+    parent_name: Option<String>,
+    /// File containing the process id.
+    pidfile: Option<String>,
+    /// The minimum number of matching processes.
+    pub min_instances: u32,
+    /// The maximum number of matching processes.
+    pub max_instances: Option<u32>,
+    /// Warn when the matching process ids change between two runs.
+    pub warn_on_restart: bool,
+}
+
+impl ProcessRule {
+    /**
+     * Create a process rule.
+     *
+     * `rule`: The rule configuration.
+     *
+     * Returns: The process rule.
+     *
+     * Errors:
+     * - If the rule has no criteria.
+     * - If a regular expression is invalid.
+     * - If maxInstances is less than minInstances.
+     */
+    pub fn new(rule: &ProcessMatchRule) -> Result<ProcessRule, ApplicationError> {
+        let criteria = ProcessRule::get_criteria(rule);
+        if criteria.is_empty() {
+            return Err(ApplicationError::new("Process match rule has no criteria"));
+        }
+        let label = rule.name.clone().unwrap_or(criteria);
+        if rule.max_instances.is_some_and(|max_instances| max_instances < rule.min_instances) {
+            return Err(ApplicationError::new(&format!("Process match rule {label} has maxInstances less than minInstances")));
+        }
+        let regexp = match &rule.regexp {
+            Some(regexp) => Some(Regex::new(regexp).map_err(|err| ApplicationError::new(&format!("Invalid regexp {regexp}: {err}")))?),
+            None => None,
+        };
+        let cmdline_regex = match &rule.cmdline_regex {
+            Some(cmdline_regex) => Some(Regex::new(cmdline_regex).map_err(|err| ApplicationError::new(&format!("Invalid cmdlineRegex {cmdline_regex}: {err}")))?),
+            None => None,
+        };
+        Ok(ProcessRule {
+            label,
+            application_name: rule.application_name.clone(),
+            regexp,
+            pid: rule.pid,
+            cmdline_regex,
+            user: rule.user.clone(),
+            parent_pid: rule.parent_pid,
+            parent_name: rule.parent_name.clone(),
+            pidfile: rule.pidfile.clone(),
+            min_instances: rule.min_instances,
+            max_instances: rule.max_instances,
+            warn_on_restart: rule.warn_on_restart,
+        })
+    }
+
+    /**
+     * Get the rules of a process monitor. Each application name and pid of the monitor is a rule expecting
+     * at least one process, and the regular expression of the monitor is a rule allowing no processes. These
+     * rules do not warn on restarts. The match rules follow.
+     *
+     * `application_names`: The application names.
+     * `pids`: The process ids.
+     * `regexp`: The regular expression.
+     * `rules`: The match rules.
+     *
+     * Returns: The process rules.
+     *
+     * Errors:
+     * - If the regular expression or a match rule is invalid.
+     */
+    pub fn get_rules(
+        application_names: &Option<Vec<String>>,
+        pids: &Option<Vec<u32>>,
+        regexp: &Option<String>,
+        rules: &Option<Vec<ProcessMatchRule>>,
+    ) -> Result<Vec<ProcessRule>, ApplicationError> {
+        let empty_rule = ProcessMatchRule {
+            name: None,
+            application_name: None,
+            regexp: None,
+            pid: None,
+            cmdline_regex: None,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            pidfile: None,
+            min_instances: 1,
+            max_instances: None,
+            warn_on_restart: false,
+        };
+        let mut process_rules = Vec::new();
+        for application_name in application_names.iter().flatten() {
+            process_rules.push(ProcessRule::new(&ProcessMatchRule { application_name: Some(application_name.clone()), ..empty_rule.clone() })?);
+        }
+        for pid in pids.iter().flatten() {
+            process_rules.push(ProcessRule::new(&ProcessMatchRule { pid: Some(*pid), ..empty_rule.clone() })?);
+        }
+        if let Some(regexp) = regexp {
+            process_rules.push(ProcessRule::new(&ProcessMatchRule { regexp: Some(regexp.clone()), min_instances: 0, ..empty_rule.clone() })?);
+        }
+        for rule in rules.iter().flatten() {
+            process_rules.push(ProcessRule::new(rule)?);
+        }
+        Ok(process_rules)
+    }
+
+    /**
+     * Describe the criteria of a rule, like `applicationName=nginx user=www-data`.
+     *
+     * `rule`: The rule configuration.
+     *
+     * Returns: The criteria, empty if the rule has none.
+     */
+    fn get_criteria(rule: &ProcessMatchRule) -> String {
+        let mut criteria = Vec::new();
+        if let Some(application_name) = &rule.application_name {
+            criteria.push(format!("applicationName={application_name}"));
+        }
+        if let Some(regexp) = &rule.regexp {
+            criteria.push(format!("regexp={regexp}"));
+        }
+        if let Some(pid) = rule.pid {
+            criteria.push(format!("pid={pid}"));
+        }
+        if let Some(cmdline_regex) = &rule.cmdline_regex {
+            criteria.push(format!("cmdlineRegex={cmdline_regex}"));
+        }
+        if let Some(user) = &rule.user {
+            criteria.push(format!("user={user}"));
+        }
+        if let Some(parent_pid) = rule.parent_pid {
+            criteria.push(format!("parentPid={parent_pid}"));
+        }
+        if let Some(parent_name) = &rule.parent_name {
+            criteria.push(format!("parentName={parent_name}"));
+        }
+        if let Some(pidfile) = &rule.pidfile {
+            criteria.push(format!("pidfile={pidfile}"));
+        }
+        criteria.join(" ")
+    }
+
+    /**
+     * Get the processes matching the rule. The pidfile is read once, and a missing or invalid pidfile
+     * matches no processes.
+     *
+     * `processes`: All the processes.
+     *
+     * Returns: The matching processes.
+     */
+    pub fn get_matches<'a>(&self, processes: &'a [ProcsProcess]) -> Vec<&'a ProcsProcess> {
+        let pidfile_pid = match &self.pidfile {
+            Some(pidfile) => match ProcessRule::read_pidfile(pidfile) {
+                Some(pid) => Some(pid),
+                None => return Vec::new(),
+            },
+            None => None,
+        };
+        let names: HashMap<u32, &str> = match self.parent_name {
+            Some(_) => processes.iter().filter_map(|process| Some((process.pid?, process.name.as_deref()?))).collect(),
+            None => HashMap::new(),
+        };
+        processes.iter().filter(|process| self.is_match(process, &names, pidfile_pid)).collect()
+    }
+
+    /**
+     * Read the process id from a pidfile.
+     *
+     * `pidfile`: The pidfile.
+     *
+     * Returns: The process id, or None if the file can not be read or does not contain a process id.
+     */
+    fn read_pidfile(pidfile: &str) -> Option<u32> {
+        match std::fs::read_to_string(pidfile) {
+            Ok(content) => content.lines().next().and_then(|pid| pid.trim().parse::<u32>().ok()),
+            Err(err) => {
+                debug!("Could not read pidfile {pidfile}: {err:?}");
+                None
+            }
+        }
+    }
+
+    /**
+     * Check if a process matches the rule. The command line is only read when all the other criteria match.
+     *
+     * `process`: The process.
+     * `names`: The application names by process id. Only used for the parent name.
+     * `pidfile_pid`: The process id read from the pidfile.
+     *
+     * Returns: True if all the criteria match.
+     */
+    fn is_match(&self, process: &ProcsProcess, names: &HashMap<u32, &str>, pidfile_pid: Option<u32>) -> bool {
+        if let Some(application_name) = &self.application_name {
+            if process.name.as_ref() != Some(application_name) {
+                return false;
+            }
+        }
+        if let Some(regexp) = &self.regexp {
+            if !process.name.as_ref().is_some_and(|name| regexp.is_match(name)) {
+                return false;
+            }
+        }
+        if self.pid.is_some_and(|pid| process.pid != Some(pid)) || pidfile_pid.is_some_and(|pid| process.pid != Some(pid)) {
+            return false;
+        }
+        if let Some(user) = &self.user {
+            if process.uid.as_ref().and_then(|uid| uid.get(1)) != Some(user) {
+                return false;
+            }
+        }
+        if self.parent_pid.is_some_and(|parent_pid| process.parent_pid != Some(parent_pid)) {
+            return false;
+        }
+        if let Some(parent_name) = &self.parent_name {
+            if process.parent_pid.and_then(|parent_pid| names.get(&parent_pid)) != Some(&parent_name.as_str()) {
+                return false;
+            }
+        }
+        if let Some(cmdline_regex) = &self.cmdline_regex {
+            let Some(pid) = process.pid else { return false };
+            return match ProcsCmdLine::read_cmdline(pid) {
+                Ok(cmdline) => cmdline_regex.is_match(&ProcessRule::get_command_line(&cmdline.fullpath)),
+                Err(err) => {
+                    debug!("Could not read command line of {pid}: {err:?}");
+                    false
+                }
+            };
+        }
+        true
+    }
+
+    /**
+     * Get the command line with the arguments separated by spaces instead of nul characters.
+     *
+     * `cmdline`: The content of /proc/[pid]/cmdline.
+     *
+     * Returns: The command line.
+     */
+    fn get_command_line(cmdline: &str) -> String {
+        cmdline.trim_end_matches('\0').replace('\0', " ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /**
+     * Create a rule configuration with defaults.
+     */
+    fn get_rule() -> ProcessMatchRule {
+        ProcessMatchRule {
+            name: None,
+            application_name: None,
+            regexp: None,
+            pid: None,
+            cmdline_regex: None,
+            user: None,
+            parent_pid: None,
+            parent_name: None,
+            pidfile: None,
+            min_instances: 1,
+            max_instances: None,
+            warn_on_restart: true,
+        }
+    }
+
+    /**
+     * Get the process ids matching a rule.
+     *
+     * `rule`: The rule configuration.
+     * `processes`: The processes.
+     */
+    fn get_matching_pids(rule: ProcessMatchRule, processes: &[ProcsProcess]) -> Vec<u32> {
+        ProcessRule::new(&rule).unwrap().get_matches(processes).iter().filter_map(|process| process.pid).collect()
+    }
+
+    #[test]
+    fn test_is_match() {
+        let process = ProcsProcess::new(Some(10), Some(1), Some("nginx".to_string()), None, None, None, None, None, None);
+        let rule = ProcessRule::new(&ProcessMatchRule { application_name: Some("nginx".to_string()), regexp: Some("^ng".to_string()), ..get_rule() }).unwrap();
+        assert_eq!(rule.label, "applicationName=nginx regexp=^ng");
+        assert_eq!(rule.get_matches(std::slice::from_ref(&process)).len(), 1);
+        let rule = ProcessRule::new(&ProcessMatchRule { name: Some("web".to_string()), application_name: Some("nginx".to_string()), pid: Some(11), ..get_rule() }).unwrap();
+        assert_eq!(rule.label, "web");
+        assert!(rule.get_matches(std::slice::from_ref(&process)).is_empty());
+        let rule = ProcessRule::new(&ProcessMatchRule { regexp: Some("^apache".to_string()), ..get_rule() }).unwrap();
+        assert!(rule.get_matches(std::slice::from_ref(&process)).is_empty());
+    }
+
+    #[test]
+    fn test_is_match_user_and_parent() {
+        let user = |name: &str| Some(vec!["root".to_string(), name.to_string(), name.to_string(), name.to_string()]);
+        let processes = [
+            ProcsProcess::new(Some(1), Some(0), Some("systemd".to_string()), None, None, None, None, user("root"), None),
+            ProcsProcess::new(Some(10), Some(1), Some("java".to_string()), None, None, None, None, user("root"), None),
+            ProcsProcess::new(Some(11), Some(10), Some("java".to_string()), None, None, None, None, user("tomcat"), None),
+            ProcsProcess::new(Some(12), Some(1), Some("java".to_string()), None, None, None, None, user("tomcat"), None),
+        ];
+        let java = ProcessMatchRule { application_name: Some("java".to_string()), ..get_rule() };
+        assert_eq!(get_matching_pids(java.clone(), &processes), vec![10, 11, 12]);
+        assert_eq!(get_matching_pids(ProcessMatchRule { user: Some("tomcat".to_string()), ..java.clone() }, &processes), vec![11, 12]);
+        assert_eq!(get_matching_pids(ProcessMatchRule { user: Some("tomcat".to_string()), parent_pid: Some(1), ..java.clone() }, &processes), vec![12]);
+        assert_eq!(get_matching_pids(ProcessMatchRule { parent_name: Some("java".to_string()), ..java.clone() }, &processes), vec![11]);
+        assert_eq!(get_matching_pids(ProcessMatchRule { parent_name: Some("systemd".to_string()), ..java }, &processes), vec![10, 12]);
+    }
+
+    #[test]
+    fn test_is_match_pidfile_and_cmdline() {
+        let own_pid = std::process::id();
+        let processes = [ProcsProcess::get_process(own_pid).unwrap()];
+        let pidfile = std::env::temp_dir().join(format!("monitoring-agent-processrule-{own_pid}.pid"));
+        std::fs::write(&pidfile, format!("{own_pid}\n")).unwrap();
+        let pidfile = pidfile.to_str().unwrap().to_string();
+        assert_eq!(get_matching_pids(ProcessMatchRule { pidfile: Some(pidfile.clone()), ..get_rule() }, &processes), vec![own_pid]);
+        assert!(get_matching_pids(ProcessMatchRule { pidfile: Some("/nonexisting/process.pid".to_string()), ..get_rule() }, &processes).is_empty());
+        let executable = std::env::args().next().unwrap();
+        let cmdline_regex = format!("^{}( |$)", regex::escape(&executable));
+        assert_eq!(get_matching_pids(ProcessMatchRule { pidfile: Some(pidfile.clone()), cmdline_regex: Some(cmdline_regex), ..get_rule() }, &processes), vec![own_pid]);
+        assert!(get_matching_pids(ProcessMatchRule { cmdline_regex: Some("^nonexisting ".to_string()), ..get_rule() }, &processes).is_empty());
+        std::fs::remove_file(pidfile).unwrap();
+    }
+
+    #[test]
+    fn test_get_command_line() {
+        assert_eq!(ProcessRule::get_command_line("/usr/sbin/apache2\0-k\0start\0"), "/usr/sbin/apache2 -k start");
+        assert_eq!(ProcessRule::get_command_line(""), "");
+    }
+
+    #[test]
+    fn test_new_invalid() {
+        assert!(ProcessRule::new(&get_rule()).is_err());
+        assert!(ProcessRule::new(&ProcessMatchRule { regexp: Some("(".to_string()), ..get_rule() }).is_err());
+        assert!(ProcessRule::new(&ProcessMatchRule { cmdline_regex: Some("(".to_string()), ..get_rule() }).is_err());
+        assert!(ProcessRule::new(&ProcessMatchRule { pid: Some(1), min_instances: 2, max_instances: Some(1), ..get_rule() }).is_err());
+    }
+
+    #[test]
+    fn test_get_rules() {
+        let rules = ProcessRule::get_rules(
+            &Some(vec!["app1".to_string(), "app2".to_string()]),
+            &Some(vec![100]),
+            &Some("^legacy".to_string()),
+            &Some(vec![ProcessMatchRule { regexp: Some("^app".to_string()), min_instances: 0, warn_on_restart: true, ..get_rule() }]),
+        )
+        .unwrap();
+        assert_eq!(rules.iter().map(|rule| rule.label.as_str()).collect::<Vec<&str>>(), vec!["applicationName=app1", "applicationName=app2", "pid=100", "regexp=^legacy", "regexp=^app"]);
+        assert_eq!(rules.iter().map(|rule| rule.min_instances).collect::<Vec<u32>>(), vec![1, 1, 1, 0, 0]);
+        assert_eq!(rules.iter().map(|rule| rule.warn_on_restart).collect::<Vec<bool>>(), vec![false, false, false, false, true]);
+        assert!(ProcessRule::get_rules(&None, &None, &Some("(".to_string()), &None).is_err());
+    }
+}
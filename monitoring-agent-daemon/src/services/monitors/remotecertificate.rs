@@ -0,0 +1,485 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use openssl::{
+    ssl::{SslConnector, SslMethod, SslVerifyMode},
+    x509::{X509VerifyResult, X509},
+};
+
+use crate::common::{
+    configuration::{CertificateEndpoint, StartTls},
+    ApplicationError,
+};
+
+/// Timeout for connecting to, reading from and writing to the endpoint.
+pub const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The Postgres SSLRequest message. The length 8 followed by the request code 80877103.
+const POSTGRES_SSL_REQUEST: [u8; 8] = [0, 0, 0, 8, 4, 210, 22, 47];
+
+/**
+ * The certificate chain presented by a remote endpoint.
+ *
+ * `chain`: The certificates in the order presented, starting with the leaf certificate.
+ * `verify_errors`: The errors found verifying the chain and the server name.
+ */
+#[derive(Debug)]
+pub struct RemoteCertificates {
+    /// The certificates in the order presented, starting with the leaf certificate.
+    pub chain: Vec<X509>,
+    /// The errors found verifying the chain and the server name.
+    pub verify_errors: Vec<X509VerifyResult>,
+}
+
+/**
+ * Get the server name of an endpoint. The configured server name is used if given, otherwise the host of the address.
+ *
+ * `endpoint`: The endpoint.
+ *
+ * Returns: The server name.
+ *
+ * Errors:
+ * - If the address is not on the form host:port.
+ */
+pub fn get_server_name(endpoint: &CertificateEndpoint) -> Result<String, ApplicationError> {
+    if let Some(server_name) = &endpoint.server_name {
+        return Ok(server_name.clone());
+    }
+    let (host, _port) = endpoint
+        .address
+        .rsplit_once(':')
+        .ok_or_else(|| ApplicationError::new(&format!("Address {} is not on the form host:port", endpoint.address)))?;
+    Ok(host.trim_start_matches('[').trim_end_matches(']').to_string())
+}
+
+/**
+ * Fetch the certificate chain presented by an endpoint. The chain is not rejected when the verification
+ * fails, the verification errors are returned with the chain.
+ *
+ * `endpoint`: The endpoint.
+ *
+ * Returns: The presented certificates.
+ *
+ * Errors:
+ * - If the endpoint could not be reached.
+ * - If the STARTTLS negotiation failed.
+ * - If the TLS handshake failed.
+ */
+pub async fn fetch_certificates(endpoint: &CertificateEndpoint) -> Result<RemoteCertificates, ApplicationError> {
+    let endpoint = endpoint.clone();
+    tokio::task::spawn_blocking(move || fetch_certificates_blocking(&endpoint))
+        .await
+        .map_err(|err| ApplicationError::new(&format!("Error fetching certificates: {err:?}")))?
+}
+
+/**
+ * Fetch the certificate chain presented by an endpoint using blocking io.
+ *
+ * `endpoint`: The endpoint.
+ *
+ * Returns: The presented certificates.
+ *
+ * Errors:
+ * - If the endpoint could not be reached.
+ * - If the STARTTLS negotiation failed.
+ * - If the TLS handshake failed.
+ */
+fn fetch_certificates_blocking(endpoint: &CertificateEndpoint) -> Result<RemoteCertificates, ApplicationError> {
+    let server_name = get_server_name(endpoint)?;
+    let mut stream = connect(&endpoint.address)?;
+    if let Some(starttls) = &endpoint.starttls {
+        negotiate_starttls(&mut stream, starttls)?;
+    }
+    /*
+     * Record the verification errors and continue the handshake, so the chain can be inspected
+     * even when it is not trusted. The security level is lowered to accept weak chains.
+     */
+    let verify_errors = Arc::new(Mutex::new(Vec::new()));
+    let mut connector = SslConnector::builder(SslMethod::tls_client())
+        .map_err(|err| ApplicationError::new(&format!("Error creating tls connector: {err:?}")))?;
+    connector.set_security_level(0);
+    if let Some(ca_file) = &endpoint.ca_file {
+        connector
+            .set_ca_file(ca_file)
+            .map_err(|err| ApplicationError::new(&format!("Error reading CA file {ca_file}: {err:?}")))?;
+    }
+    let callback_errors = verify_errors.clone();
+    connector.set_verify_callback(SslVerifyMode::PEER, move |preverify_ok, context| {
+        if !preverify_ok {
+            if let Ok(mut errors) = callback_errors.lock() {
+                if !errors.contains(&context.error()) {
+                    errors.push(context.error());
+                }
+            }
+        }
+        true
+    });
+    let connector = connector.build();
+    let configuration = connector
+        .configure()
+        .map_err(|err| ApplicationError::new(&format!("Error configuring tls connection: {err:?}")))?;
+    let ssl_stream = configuration
+        .connect(&server_name, stream)
+        .map_err(|err| ApplicationError::new(&format!("Error in tls handshake: {err}")))?;
+    let chain = ssl_stream
+        .ssl()
+        .peer_cert_chain()
+        .map(|chain| chain.iter().map(|cert| cert.to_owned()).collect::<Vec<X509>>())
+        .unwrap_or_default();
+    if chain.is_empty() {
+        return Err(ApplicationError::new("No certificates presented"));
+    }
+    let verify_errors = verify_errors
+        .lock()
+        .map_err(|err| ApplicationError::new(&format!("Error reading verify errors: {err:?}")))?
+        .clone();
+    Ok(RemoteCertificates { chain, verify_errors })
+}
+
+/**
+ * Connect to the address. Each resolved address is tried in order.
+ *
+ * `address`: The address as host:port.
+ *
+ * Returns: The connected stream with read and write timeouts.
+ *
+ * Errors:
+ * - If the address could not be resolved.
+ * - If none of the resolved addresses could be connected.
+ */
+fn connect(address: &str) -> Result<TcpStream, ApplicationError> {
+    let socket_addresses = address
+        .to_socket_addrs()
+        .map_err(|err| ApplicationError::new(&format!("Error resolving {address}: {err}")))?;
+    let mut last_error = None;
+    for socket_address in socket_addresses {
+        match TcpStream::connect_timeout(&socket_address, ENDPOINT_TIMEOUT) {
+            Ok(stream) => {
+                stream
+                    .set_read_timeout(Some(ENDPOINT_TIMEOUT))
+                    .and_then(|()| stream.set_write_timeout(Some(ENDPOINT_TIMEOUT)))
+                    .map_err(|err| ApplicationError::new(&format!("Error setting timeouts: {err}")))?;
+                return Ok(stream);
+            }
+            Err(err) => last_error = Some(err),
+        }
+    }
+    match last_error {
+        Some(err) => Err(ApplicationError::new(&format!("Error connecting to {address}: {err}"))),
+        None => Err(ApplicationError::new(&format!("No addresses found for {address}"))),
+    }
+}
+
+/**
+ * Upgrade a plain connection to TLS.
+ *
+ * `stream`: The connected stream.
+ * `starttls`: The protocol of the connection.
+ *
+ * Errors:
+ * - If the server does not accept the upgrade.
+ */
+fn negotiate_starttls(stream: &mut TcpStream, starttls: &StartTls) -> Result<(), ApplicationError> {
+    match starttls {
+        StartTls::Smtp => negotiate_smtp(stream),
+        StartTls::Imap => negotiate_imap(stream),
+        StartTls::Postgres => negotiate_postgres(stream),
+    }
+}
+
+/**
+ * Send STARTTLS to an SMTP server after the greeting and EHLO.
+ *
+ * `stream`: The connected stream.
+ *
+ * Errors:
+ * - If a reply has an unexpected code.
+ */
+fn negotiate_smtp(stream: &mut TcpStream) -> Result<(), ApplicationError> {
+    let mut reader = BufReader::new(stream.try_clone().map_err(|err| ApplicationError::new(&format!("Error reading stream: {err}")))?);
+    read_smtp_reply(&mut reader, "220")?;
+    write_line(stream, "EHLO monitoring-agent")?;
+    read_smtp_reply(&mut reader, "250")?;
+    write_line(stream, "STARTTLS")?;
+    read_smtp_reply(&mut reader, "220")
+}
+
+/**
+ * Read an SMTP reply. Multiline replies are read until the last line.
+ *
+ * `reader`: The reader.
+ * `code`: The expected reply code.
+ *
+ * Errors:
+ * - If a line does not start with the expected code.
+ */
+fn read_smtp_reply<R: BufRead>(reader: &mut R, code: &str) -> Result<(), ApplicationError> {
+    loop {
+        let line = read_line(reader)?;
+        if !line.starts_with(code) {
+            return Err(ApplicationError::new(&format!("Unexpected SMTP reply: {line}")));
+        }
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+/**
+ * Send STARTTLS to an IMAP server after the greeting.
+ *
+ * `stream`: The connected stream.
+ *
+ * Errors:
+ * - If the greeting or the tagged response is not OK.
+ */
+fn negotiate_imap(stream: &mut TcpStream) -> Result<(), ApplicationError> {
+    let mut reader = BufReader::new(stream.try_clone().map_err(|err| ApplicationError::new(&format!("Error reading stream: {err}")))?);
+    let greeting = read_line(&mut reader)?;
+    if !greeting.starts_with("* OK") {
+        return Err(ApplicationError::new(&format!("Unexpected IMAP greeting: {greeting}")));
+    }
+    write_line(stream, "a001 STARTTLS")?;
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.starts_with("a001 OK") {
+            return Ok(());
+        }
+        if line.starts_with("a001 ") {
+            return Err(ApplicationError::new(&format!("Unexpected IMAP response: {line}")));
+        }
+    }
+}
+
+/**
+ * Send the SSLRequest to a Postgres server.
+ *
+ * `stream`: The connected stream.
+ *
+ * Errors:
+ * - If the server does not accept SSL.
+ */
+fn negotiate_postgres(stream: &mut TcpStream) -> Result<(), ApplicationError> {
+    stream
+        .write_all(&POSTGRES_SSL_REQUEST)
+        .map_err(|err| ApplicationError::new(&format!("Error writing SSLRequest: {err}")))?;
+    let mut response = [0u8; 1];
+    stream
+        .read_exact(&mut response)
+        .map_err(|err| ApplicationError::new(&format!("Error reading SSLRequest response: {err}")))?;
+    if response[0] != b'S' {
+        return Err(ApplicationError::new("Postgres server does not accept SSL"));
+    }
+    Ok(())
+}
+
+/**
+ * Read a line without the line ending.
+ *
+ * `reader`: The reader.
+ *
+ * Errors:
+ * - If the line could not be read or the connection was closed.
+ */
+fn read_line<R: BufRead>(reader: &mut R) -> Result<String, ApplicationError> {
+    let mut line = String::new();
+    let read = reader
+        .read_line(&mut line)
+        .map_err(|err| ApplicationError::new(&format!("Error reading line: {err}")))?;
+    if read == 0 {
+        return Err(ApplicationError::new("Connection closed"));
+    }
+    Ok(line.trim_end().to_string())
+}
+
+/**
+ * Write a line ending with CRLF.
+ *
+ * `stream`: The stream.
+ * `line`: The line without line ending.
+ *
+ * Errors:
+ * - If the line could not be written.
+ */
+fn write_line(stream: &mut TcpStream, line: &str) -> Result<(), ApplicationError> {
+    stream
+        .write_all(format!("{line}\r\n").as_bytes())
+        .map_err(|err| ApplicationError::new(&format!("Error writing line: {err}")))
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use std::net::TcpListener;
+
+    use openssl::{
+        asn1::Asn1Time,
+        bn::{BigNum, MsbOption},
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        ssl::{SslAcceptor, SslMethod},
+        x509::{extension::{BasicConstraints, SubjectAlternativeName}, X509Name, X509},
+    };
+
+    use super::*;
+
+    /**
+     * Create a certificate with an EC key.
+     *
+     * `common_name`: The common name of the subject.
+     * `dns_names`: The subject alternative names. A CA certificate is created if empty.
+     * `issuer`: The issuer certificate and key. The certificate is self signed if None.
+     * `digest`: The signature digest.
+     * `days`: The number of days the certificate is valid.
+     */
+    pub fn create_certificate(common_name: &str, dns_names: &[&str], issuer: Option<(&X509, &PKey<Private>)>, digest: MessageDigest, days: u32) -> (X509, PKey<Private>) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, common_name).unwrap();
+        let name = name.build();
+        let mut serial = BigNum::new().unwrap();
+        serial.rand(64, MsbOption::MAYBE_ZERO, false).unwrap();
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_serial_number(&serial.to_asn1_integer().unwrap()).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(days).unwrap()).unwrap();
+        if dns_names.is_empty() {
+            builder.append_extension(BasicConstraints::new().critical().ca().build().unwrap()).unwrap();
+        } else {
+            let mut subject_alternative_name = SubjectAlternativeName::new();
+            for dns_name in dns_names {
+                subject_alternative_name.dns(dns_name);
+            }
+            let extension = subject_alternative_name.build(&builder.x509v3_context(issuer.map(|(cert, _)| cert.as_ref()), None)).unwrap();
+            builder.append_extension(extension).unwrap();
+        }
+        match issuer {
+            Some((issuer_cert, issuer_key)) => {
+                builder.set_issuer_name(issuer_cert.subject_name()).unwrap();
+                builder.sign(issuer_key, digest).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder.sign(&key, digest).unwrap();
+            }
+        }
+        (builder.build(), key)
+    }
+
+    /**
+     * Start a server accepting a single TLS connection.
+     *
+     * `chain`: The certificates presented, starting with the leaf certificate.
+     * `key`: The key of the leaf certificate.
+     * `starttls`: The protocol negotiated before the handshake.
+     *
+     * Returns: The address of the server.
+     */
+    pub fn start_tls_server(chain: Vec<X509>, key: PKey<Private>, starttls: Option<StartTls>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls_server()).unwrap();
+        acceptor.set_security_level(0);
+        acceptor.set_private_key(&key).unwrap();
+        acceptor.set_certificate(&chain[0]).unwrap();
+        for cert in chain.iter().skip(1) {
+            acceptor.add_extra_chain_cert(cert.clone()).unwrap();
+        }
+        let acceptor = acceptor.build();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            match starttls {
+                Some(StartTls::Smtp) => {
+                    write_line(&mut stream, "220 mail.example.com ESMTP").unwrap();
+                    assert!(read_line(&mut reader).unwrap().starts_with("EHLO"));
+                    write_line(&mut stream, "250-mail.example.com").unwrap();
+                    write_line(&mut stream, "250 STARTTLS").unwrap();
+                    assert_eq!(read_line(&mut reader).unwrap(), "STARTTLS");
+                    write_line(&mut stream, "220 Ready to start TLS").unwrap();
+                }
+                Some(StartTls::Imap) => {
+                    write_line(&mut stream, "* OK IMAP4rev1 ready").unwrap();
+                    assert_eq!(read_line(&mut reader).unwrap(), "a001 STARTTLS");
+                    write_line(&mut stream, "a001 OK Begin TLS negotiation now").unwrap();
+                }
+                Some(StartTls::Postgres) => {
+                    let mut request = [0u8; 8];
+                    stream.read_exact(&mut request).unwrap();
+                    assert_eq!(request, POSTGRES_SSL_REQUEST);
+                    stream.write_all(b"S").unwrap();
+                }
+                None => {}
+            }
+            if let Ok(mut ssl_stream) = acceptor.accept(stream) {
+                let _ = ssl_stream.shutdown();
+            }
+        });
+        address
+    }
+
+    fn get_endpoint(address: &str, starttls: Option<StartTls>) -> CertificateEndpoint {
+        CertificateEndpoint { address: address.to_string(), server_name: Some("localhost".to_string()), starttls, ca_file: None }
+    }
+
+    #[test]
+    fn test_get_server_name() {
+        let mut endpoint = CertificateEndpoint { address: "www.example.com:443".to_string(), server_name: None, starttls: None, ca_file: None };
+        assert_eq!(get_server_name(&endpoint).unwrap(), "www.example.com");
+        endpoint.address = "[::1]:443".to_string();
+        assert_eq!(get_server_name(&endpoint).unwrap(), "::1");
+        endpoint.server_name = Some("example.com".to_string());
+        assert_eq!(get_server_name(&endpoint).unwrap(), "example.com");
+        endpoint.server_name = None;
+        endpoint.address = "www.example.com".to_string();
+        assert!(get_server_name(&endpoint).is_err());
+    }
+
+    #[test]
+    fn test_read_smtp_reply() {
+        let mut reader = "250-mail.example.com\r\n250-SIZE 1000\r\n250 STARTTLS\r\n".as_bytes();
+        assert!(read_smtp_reply(&mut reader, "250").is_ok());
+        assert!(reader.is_empty());
+        let mut reader = "554 No service\r\n".as_bytes();
+        assert!(read_smtp_reply(&mut reader, "220").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_certificates() {
+        let (ca, ca_key) = create_certificate("Test CA", &[], None, MessageDigest::sha256(), 365);
+        let (leaf, leaf_key) = create_certificate("localhost", &["localhost"], Some((&ca, &ca_key)), MessageDigest::sha256(), 30);
+        let address = start_tls_server(vec![leaf.clone(), ca.clone()], leaf_key, None);
+        let certificates = fetch_certificates(&get_endpoint(&address, None)).await.unwrap();
+        assert_eq!(certificates.chain.len(), 2);
+        assert_eq!(certificates.chain[0].to_der().unwrap(), leaf.to_der().unwrap());
+        assert_eq!(certificates.chain[1].to_der().unwrap(), ca.to_der().unwrap());
+        assert!(!certificates.verify_errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_certificates_starttls() {
+        for starttls in [StartTls::Smtp, StartTls::Imap, StartTls::Postgres] {
+            let (cert, key) = create_certificate("localhost", &["localhost"], None, MessageDigest::sha256(), 30);
+            let address = start_tls_server(vec![cert.clone()], key, Some(starttls.clone()));
+            let certificates = fetch_certificates(&get_endpoint(&address, Some(starttls))).await.unwrap();
+            assert_eq!(certificates.chain[0].to_der().unwrap(), cert.to_der().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_certificates_not_listening() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        assert!(fetch_certificates(&get_endpoint(&address, None)).await.is_err());
+    }
+}
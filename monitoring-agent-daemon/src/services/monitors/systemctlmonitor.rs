@@ -5,7 +5,7 @@ use tokio_cron_scheduler::Job;
 
 use crate::common::{configuration::DatabaseStoreLevel, ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
 
-use super::Monitor;
+use super::{common::check_with_timeout, Monitor};
 
 const SYSTEMD_ACTIVE_STATUS: &str = "active";
 
@@ -79,17 +79,22 @@ impl SystemctlMonitor {
      *
      * `systemctl_monitor`: The systemctl monitor.
      * `schedule`: The schedule for the job.
+     * `timeout`: The timeout of the check in seconds.
      */
     pub fn get_systemctl_monitor_job(
         systemctl_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating Systemctl monitor: {}", &systemctl_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut systemctl_monitor = systemctl_monitor.clone();
+                let systemctl_monitor = systemctl_monitor.clone();
                 async move {
-                    systemctl_monitor.check().await;
+                    check_with_timeout(systemctl_monitor, timeout, |mut systemctl_monitor| async move {
+                        systemctl_monitor.check().await;
+                    })
+                    .await;
                 }
             })              
         });        
@@ -108,6 +113,7 @@ impl SystemctlMonitor {
         debug!("Checking monitor: {}", &self.name);
         let output = tokio::process::Command::new("systemctl")
             .arg("--all")
+            .kill_on_drop(true)
             .output()
             .await
             .expect("failed to execute process");
@@ -272,7 +278,7 @@ mod test {
             &DatabaseStoreLevel::None,
             vec![],
         );
-        let job = SystemctlMonitor::get_systemctl_monitor_job(monitor, "0 0 * * * *");
+        let job = SystemctlMonitor::get_systemctl_monitor_job(monitor, "0 0 * * * *", None);
         assert!(job.is_ok());
     }  
 
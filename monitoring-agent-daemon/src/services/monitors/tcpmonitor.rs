@@ -1,11 +1,14 @@
+use std::time::Instant;
+
 use log::info;
 use log::{debug, error};
+use tokio::io::AsyncWriteExt;
 use tokio_cron_scheduler::Job;
 
 use crate::common::configuration::DatabaseStoreLevel;
-use crate::common::{ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, Status};
+use crate::common::{ApplicationError, DatabaseServiceType, MonitorStatus, MonitorStatusType, ResponseTime, Status};
 
-use super::Monitor;
+use super::{common::check_with_timeout, Monitor};
 
 
 /**
@@ -17,7 +20,10 @@ use super::Monitor;
  * description: The description of the monitor.
  * host: The host to monitor.
  * port: The port to monitor.
+ * `warn_latency_ms`: The response time in milliseconds before warning.
+ * `error_latency_ms`: The response time in milliseconds before error.
  * status: The status of the monitor.
+ * `store_values`: Store the response times.
  *
  */
 #[derive(Debug, Clone)]
@@ -30,12 +36,18 @@ pub struct TcpMonitor {
     pub port: u16,
     /// Number of retries if error occurs.
     retry: Option<u16>,
+    /// The response time in milliseconds before warning.
+    warn_latency_ms: Option<u64>,
+    /// The response time in milliseconds before error.
+    error_latency_ms: Option<u64>,
     /// The status of the monitor.
     pub status: MonitorStatusType,
     /// The database service.
     database_service: DatabaseServiceType,
     /// The database store level.
     database_store_level: DatabaseStoreLevel,
+    /// Store the response times.
+    store_values: bool,
 }
 
 impl TcpMonitor {
@@ -44,8 +56,12 @@ impl TcpMonitor {
      *
      * host: The host to monitor.
      * port: The port to monitor.
+     * retry: Number of retries if the connection fails.
+     * `warn_latency_ms`: The response time in milliseconds before warning.
+     * `error_latency_ms`: The response time in milliseconds before error.
      * name: The name of the monitor.
      * status: The status of the monitor.
+     * `store_values`: Store the response times.
      *
      */
     #[allow(clippy::too_many_arguments)]
@@ -53,11 +69,14 @@ impl TcpMonitor {
         host: &str,
         port: u16,
         retry: Option<u16>,
+        warn_latency_ms: Option<u64>,
+        error_latency_ms: Option<u64>,
         name: &str,
         description: &Option<String>,
         status: &MonitorStatusType,
         database_service: &DatabaseServiceType,
         database_store_level: &DatabaseStoreLevel,
+        store_values: bool,
     ) -> TcpMonitor {
         debug!("Creating TCP monitor: {}", &name);
         let status_lock = status.lock();
@@ -75,9 +94,12 @@ impl TcpMonitor {
             host: host.to_string(),
             port,
             retry,
+            warn_latency_ms,
+            error_latency_ms,
             status: status.clone(),
             database_service: database_service.clone(),
             database_store_level: database_store_level.clone(),
+            store_values,
         }
     }
 
@@ -87,8 +109,8 @@ impl TcpMonitor {
      * `tcp_stream`: The TCP stream to close.
      *
      */
-    fn close_connection(tcp_stream: &std::net::TcpStream) {
-        let _ = tcp_stream.shutdown(std::net::Shutdown::Both).map_err(|err| error!("Error closing connection: {:?}", err));
+    async fn close_connection(tcp_stream: &mut tokio::net::TcpStream) {
+        let _ = tcp_stream.shutdown().await.map_err(|err| error!("Error closing connection: {:?}", err));
     }
 
     /**
@@ -96,19 +118,24 @@ impl TcpMonitor {
      *
      * `tcp_monitor`: The TCP monitor.
      * `schedule`: The schedule.
+     * `timeout`: The timeout of the check in seconds.
      *
      * `result`: The result of getting the TCP monitor job.
      */
     pub fn get_tcp_monitor_job(
         tcp_monitor: Self,
         schedule: &str,
+        timeout: Option<u64>,
     ) -> Result<Job, ApplicationError> {
         info!("Creating Tcp monitor: {}", tcp_monitor.name);
         let job_result = Job::new_async(schedule, move |_uuid, _locked| {
             Box::pin({
-                let mut tcp_monitor = tcp_monitor.clone();
+                let tcp_monitor = tcp_monitor.clone();
                 async move {
-                    tcp_monitor.check().await;
+                    check_with_timeout(tcp_monitor, timeout, |mut tcp_monitor| async move {
+                        tcp_monitor.check().await;
+                    })
+                    .await;
                 }
             })              
         });        
@@ -125,22 +152,26 @@ impl TcpMonitor {
      */
     async fn check(&mut self) {
         debug!("Checking monitor: {}", &self.name);
-        let status = self.connect();
+        let (status, response_time) = self.connect().await;
+        let status = match &response_time {
+            Some(response_time) => {
+                self.store_response_time(response_time).await;
+                response_time.check_latency(status, self.warn_latency_ms, self.error_latency_ms)
+            },
+            None => status,
+        };
         self.set_status(&status).await;       
     }    
 
     /**
      * Connect with retries.
      * 
-     * 
+     * Returns: The status and the response time of the successful connection.
      */
-    fn connect(&self) -> Status {
-        let tcp_stream = std::net::TcpStream::connect(format!("{}:{}", &self.host, &self.port));
-        
-        let mut current_err = match tcp_stream {
-            Ok(tcp_stream) => {
-                TcpMonitor::close_connection(&tcp_stream);
-                return Status::Ok;
+    async fn connect(&self) -> (Status, Option<ResponseTime>) {
+        let mut current_err = match self.connect_once().await {
+            Ok(response_time) => {
+                return (Status::Ok, Some(response_time));
             },
             Err(err) => {
                 Status::Error { message: format!("Error connection to {}:{}. Error: {err:?}", self.host, self.port) }
@@ -149,11 +180,9 @@ impl TcpMonitor {
         
         if let Some(retry) = self.retry {
             for index in 1..=retry {
-                let tcp_stream = std::net::TcpStream::connect(format!("{}:{}", &self.host, &self.port));
-                match tcp_stream {
-                    Ok(tcp_stream) => {
-                        TcpMonitor::close_connection(&tcp_stream);
-                        return Status::Warn { message: format!("Success after retries {index}. Previous err: {current_err:?}") };
+                match self.connect_once().await {
+                    Ok(response_time) => {
+                        return (Status::Warn { message: format!("Success after retries {index}. Previous err: {current_err:?}") }, Some(response_time));
                     },
                     Err(err) => {
                         current_err = Status::Error { message: format!("Error connection to {}:{} after {index} retries. Error: {err:?}", self.host, self.port) };
@@ -161,7 +190,39 @@ impl TcpMonitor {
                 };
             }
         } 
-        current_err
+        (current_err, None)
+    }
+
+    /**
+     * Open and close a connection and measure the time.
+     * 
+     * Returns: The connect time and the total time including closing the connection.
+     * 
+     * Errors:
+     * - If the connection fails.
+     */
+    async fn connect_once(&self) -> Result<ResponseTime, std::io::Error> {
+        let start = Instant::now();
+        let mut tcp_stream = tokio::net::TcpStream::connect(format!("{}:{}", &self.host, &self.port)).await?;
+        let connect_time = ResponseTime::get_elapsed_ms(&start);
+        TcpMonitor::close_connection(&mut tcp_stream).await;
+        Ok(ResponseTime::new(Some(connect_time), None, ResponseTime::get_elapsed_ms(&start)))
+    }
+
+    /**
+     * Store the response time.
+     *
+     * `response_time`: The response time of the check.
+     */
+    async fn store_response_time(&self, response_time: &ResponseTime) {
+        if !self.store_values {
+            return;
+        }
+        if let Some(database_service) = self.database_service.as_ref() {
+            if let Err(err) = database_service.store_response_time(&self.name, response_time).await {
+                error!("Error storing response time: {:?}", err);
+            }
+        }
     }
 
 }
@@ -222,7 +283,7 @@ mod test {
     #[tokio::test]
     async fn test_check_port_139() {
         let status = std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
-        let mut monitor = TcpMonitor::new("localhost", 139, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None);
+        let mut monitor = TcpMonitor::new("localhost", 139, None, None, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None, false);
         monitor.check().await;
         assert_eq!(
             status.lock().unwrap().get("localhost").unwrap().status,
@@ -237,11 +298,31 @@ mod test {
     async fn test_check_port_65000() {
         let status: MonitorStatusType =
             std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
-        let mut monitor = TcpMonitor::new("localhost", 65000, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None);
+        let mut monitor = TcpMonitor::new("localhost", 65000, None, None, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None, false);
         monitor.check().await;
         assert_eq!(status.lock().unwrap().get("localhost").unwrap().status, Status::Error { message: "Error connection to localhost:65000. Error: Os { code: 111, kind: ConnectionRefused, message: \"Connection refused\" }".to_string() });
     }
 
+    /**
+     * Test the latency thresholds toward a local listener.
+     */
+    #[tokio::test]
+    async fn test_check_latency() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((_stream, _)) = listener.accept().await {}
+        });
+        let status: MonitorStatusType =
+            std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+        let mut monitor = TcpMonitor::new("127.0.0.1", port, None, Some(0), None, "latency", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.check().await;
+        assert!(matches!(&status.lock().unwrap().get("latency").unwrap().status, Status::Warn { message } if message.starts_with("Response time")));
+        let mut monitor = TcpMonitor::new("127.0.0.1", port, None, None, Some(60000), "latency", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None, false);
+        monitor.check().await;
+        assert_eq!(status.lock().unwrap().get("latency").unwrap().status, Status::Ok);
+    }
+
     /**
      * Test the `set_status` method.
      */
@@ -249,7 +330,7 @@ mod test {
     async fn test_set_status() {
         let status: MonitorStatusType =
             std::sync::Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
-        let mut monitor = TcpMonitor::new("localhost", 65000, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None);
+        let mut monitor = TcpMonitor::new("localhost", 65000, None, None, None, "localhost", &None, &status, &std::sync::Arc::new(None), &DatabaseStoreLevel::None, false);
         monitor.set_status(&Status::Ok).await;
         assert_eq!(
             status.lock().unwrap().get("localhost").unwrap().status,
@@ -265,13 +346,16 @@ mod test {
             "localhost",
             65000,
             None,
+            None,
+            None,
             "localhost",
             &None,
             &status,
             &std::sync::Arc::new(None),
             &DatabaseStoreLevel::None,
+            false,
         );
-        let job = TcpMonitor::get_tcp_monitor_job(monitor, "0 0 * * * *");
+        let job = TcpMonitor::get_tcp_monitor_job(monitor, "0 0 * * * *", None);
         assert!(job.is_ok());
     }      
 }
@@ -0,0 +1,171 @@
+use lettre::{message::Mailbox, Message, SmtpTransport, Transport};
+
+use crate::common::ApplicationError;
+
+use super::{Notification, NotificationType};
+
+/**
+ * Notifier sending the notifications as email.
+ */
+#[derive(Debug, Clone)]
+pub struct EmailNotifier {
+    /// The email recipients.
+    recipients: Vec<Mailbox>,
+    /// The email transport.
+    transport: SmtpTransport,
+    /// The from email.
+    from: Mailbox,
+    /// The reply to email.
+    reply_to: Mailbox,
+}
+
+impl EmailNotifier {
+    /**
+     * Create a new email notifier.
+     *
+     * `url`: The url of the smtp server.
+     * `recipients`: The recipients.
+     * `from`: The from.
+     * `reply_to`: The reply to.
+     *
+     * Returns a new `EmailNotifier`.
+     *
+     * throws: `ApplicationError`: If the url or an email address is invalid.
+     */
+    pub fn new(
+        url: &str,
+        recipients: Vec<String>,
+        from: &str,
+        reply_to: &str,
+    ) -> Result<EmailNotifier, ApplicationError> {
+        let transport = SmtpTransport::from_url(url)
+            .map_err(|err| {
+                ApplicationError::new(&format!("Error creating smtp transport: {err:?}"))
+            })?
+            .build();
+
+        let from: Mailbox = from
+            .parse()
+            .map_err(|err| ApplicationError::new(&format!("Error parsing from email: {err:?}")))?;
+
+        let reply_to: Mailbox = reply_to.parse().map_err(|err| {
+            ApplicationError::new(&format!("Error parsing reply to email: {err:?}"))
+        })?;
+
+        let recipients = recipients.iter().map(|recipient| recipient.parse().map_err(|err| {
+            ApplicationError::new(&format!("Error parsing recipient email: {err:?}"))
+        })).collect::<Result<Vec<Mailbox>, ApplicationError>>()?;
+
+        Ok(EmailNotifier {
+            recipients,
+            transport,
+            from,
+            reply_to,
+        })
+    }
+
+    /**
+     * Send an email with the notifications to all recipients. The smtp transport is blocking, so
+     * the emails are sent on the blocking thread pool.
+     *
+     * `notifications`: The notifications.
+     * `server_name`: The name of the server.
+     *
+     * throws: `ApplicationError`: If an email could not be sent.
+     */
+    pub async fn notify(&self, notifications: &[Notification], server_name: &str) -> Result<(), ApplicationError> {
+        let mut notification = format!("Monitor changes on {server_name}: \n");
+        for monitor_notification in notifications {
+            notification.push_str(EmailNotifier::get_notification_message(monitor_notification).as_str());
+        }
+        let email_notifier = self.clone();
+        tokio::task::spawn_blocking(move || email_notifier.send(&notification))
+            .await
+            .map_err(|err| ApplicationError::new(&format!("Error sending email: {err:?}")))?
+    }
+
+    /**
+     * Send the notification to all recipients.
+     *
+     * `notification`: The notification text.
+     *
+     * throws: `ApplicationError`: If an email could not be sent.
+     */
+    fn send(&self, notification: &str) -> Result<(), ApplicationError> {
+        for recipient in &self.recipients {
+            let email = Message::builder()
+                .from(self.from.clone())
+                .reply_to(self.reply_to.clone())
+                .to(recipient.clone())
+                .subject("Monitoring agent daemon notification")
+                .body(notification.to_string())
+                .map_err(|err| ApplicationError::new(&format!("Error creating email: {err:?}")))?;
+
+            self.transport.send(&email).map_err(|err| ApplicationError::new(&format!("Error sending email: {err:?}")))?;
+        }
+        Ok(())
+    }
+
+    /**
+     * Get the notification message.
+     *
+     * `notification`: The notification.
+     *
+     * Returns: The notification message.
+     */
+    fn get_notification_message(notification: &Notification) -> String {
+        let monitor_status = &notification.monitor_status;
+        let flapping = if monitor_status.flapping { "The monitor is flapping. \n" } else { "" };
+        let outage_duration = notification.get_outage_duration_text().map(|duration| format!("Outage duration: {duration} \n")).unwrap_or_default();
+        match notification.notification_type {
+            NotificationType::Error => format!(
+                "Monitor: {:?} is in error. \nMessage: {:?} \n{outage_duration}{flapping}",
+                monitor_status.name, monitor_status.status
+            ),
+            NotificationType::Warn => format!(
+                "Monitor: {:?} is in warning. \nMessage: {:?} \n{outage_duration}{flapping}",
+                monitor_status.name, monitor_status.status
+            ),
+            NotificationType::Resolved => format!(
+                "RESOLVED: Monitor: {:?} is ok. \n{outage_duration}",
+                monitor_status.name
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{MonitorStatus, Status};
+
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(EmailNotifier::new("smtp://localhost:25", vec!["test@test.com".to_string()], "test@test.com", "test@test.com").is_ok());
+        assert!(EmailNotifier::new("smtp://localhost:25", vec!["invalid".to_string()], "test@test.com", "test@test.com").is_err());
+        assert!(EmailNotifier::new("smtp://localhost:25", vec![], "invalid", "test@test.com").is_err());
+    }
+
+    #[test]
+    fn test_get_notification_message() {
+        let mut monitor_status = MonitorStatus::new("test", &None, Status::Error { message: "Error".to_string() });
+        let notification = Notification::new(&monitor_status, NotificationType::Error, None);
+        assert_eq!(EmailNotifier::get_notification_message(&notification), "Monitor: \"test\" is in error. \nMessage: Error { message: \"Error\" } \n");
+        monitor_status.flapping = true;
+        let notification = Notification::new(&monitor_status, NotificationType::Error, None);
+        assert!(EmailNotifier::get_notification_message(&notification).ends_with("The monitor is flapping. \n"));
+        monitor_status.status = Status::Warn { message: "Warning".to_string() };
+        let notification = Notification::new(&monitor_status, NotificationType::Warn, None);
+        assert!(EmailNotifier::get_notification_message(&notification).starts_with("Monitor: \"test\" is in warning. \n"));
+    }
+
+    #[test]
+    fn test_get_notification_message_resolved() {
+        let mut monitor_status = MonitorStatus::new("test", &None, Status::Ok);
+        let outage_start = chrono::Utc::now() - chrono::Duration::seconds(125);
+        monitor_status.last_successful_time = Some(outage_start + chrono::Duration::seconds(125));
+        let notification = Notification::new(&monitor_status, NotificationType::Resolved, Some(outage_start));
+        assert_eq!(EmailNotifier::get_notification_message(&notification), "RESOLVED: Monitor: \"test\" is ok. \nOutage duration: 0h 2m 5s \n");
+    }
+}
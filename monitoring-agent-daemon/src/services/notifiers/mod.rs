@@ -0,0 +1,65 @@
+/**
+ * Modules for sending notifications.
+ *
+ * `emailnotifier`: Notifier that sends the notifications as email.
+ * `webhooknotifier`: Notifier that posts the notifications as json to urls.
+ * `notification`: A notification about a status transition of a monitor.
+ */
+mod emailnotifier;
+mod webhooknotifier;
+mod notification;
+
+use crate::common::{configuration::NotifierConfig, ApplicationError};
+
+pub use emailnotifier::EmailNotifier;
+pub use webhooknotifier::WebhookNotifier;
+pub use notification::{Notification, NotificationType};
+
+/**
+ * A notifier used by the notification job.
+ *
+ * Email: Sends the notifications as email.
+ * Webhook: Posts the notifications as json.
+ */
+#[derive(Debug, Clone)]
+pub enum Notifier {
+    Email(EmailNotifier),
+    Webhook(WebhookNotifier),
+}
+
+impl Notifier {
+    /**
+     * Create a new notifier from the configuration.
+     *
+     * `notifier_config`: The notifier configuration.
+     *
+     * result: The notifier.
+     *
+     * throws: `ApplicationError`: If the notifier configuration is invalid.
+     */
+    pub fn new(notifier_config: &NotifierConfig) -> Result<Notifier, ApplicationError> {
+        match notifier_config {
+            NotifierConfig::Email { url, recipients, from, reply_to } => {
+                Ok(Notifier::Email(EmailNotifier::new(url, recipients.clone(), from, reply_to)?))
+            },
+            NotifierConfig::Webhook { urls, headers, secret, retries, retry_delay, timeout } => {
+                Ok(Notifier::Webhook(WebhookNotifier::new(urls.clone(), headers, secret, *retries, *retry_delay, *timeout)?))
+            },
+        }
+    }
+
+    /**
+     * Send the notifications.
+     *
+     * `notifications`: The notifications.
+     * `server_name`: The name of the server.
+     *
+     * throws: `ApplicationError`: If the notification could not be sent.
+     */
+    pub async fn notify(&self, notifications: &[Notification], server_name: &str) -> Result<(), ApplicationError> {
+        match self {
+            Notifier::Email(email_notifier) => email_notifier.notify(notifications, server_name).await,
+            Notifier::Webhook(webhook_notifier) => webhook_notifier.notify(notifications, server_name).await,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::common::MonitorStatus;
+
+/**
+ * The type of notification.
+ *
+ * Error: The monitor changed to error.
+ * Warn: The monitor changed to warn.
+ * Resolved: The monitor changed from error or warn back to ok.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationType {
+    Error,
+    Warn,
+    Resolved,
+}
+
+/**
+ * A notification about a status transition of a monitor.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// The current status of the monitor.
+    pub monitor_status: MonitorStatus,
+    /// The type of notification.
+    pub notification_type: NotificationType,
+    /// The start of the outage. The last successful time before the outage.
+    pub outage_start: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    /**
+     * Create a new notification.
+     *
+     * `monitor_status`: The current status of the monitor.
+     * `notification_type`: The type of notification.
+     * `outage_start`: The start of the outage.
+     *
+     * Returns a new `Notification`.
+     */
+    pub fn new(monitor_status: &MonitorStatus, notification_type: NotificationType, outage_start: Option<DateTime<Utc>>) -> Notification {
+        Notification {
+            monitor_status: monitor_status.clone(),
+            notification_type,
+            outage_start,
+        }
+    }
+
+    /**
+     * Get the duration of the outage. For an ongoing outage this is the time until the last error,
+     * for a resolved outage the time until the monitor was successful again.
+     *
+     * Returns the outage duration or None if the start or end of the outage is not known.
+     */
+    pub fn get_outage_duration(&self) -> Option<Duration> {
+        let outage_end = match self.notification_type {
+            NotificationType::Resolved => self.monitor_status.last_successful_time,
+            NotificationType::Error | NotificationType::Warn => self.monitor_status.last_error_time,
+        };
+        match (self.outage_start, outage_end) {
+            (Some(outage_start), Some(outage_end)) if outage_end >= outage_start => Some(outage_end - outage_start),
+            _ => None,
+        }
+    }
+
+    /**
+     * Get the outage duration as text. Example 1h 2m 3s.
+     *
+     * Returns the outage duration text or None if the outage duration is not known.
+     */
+    pub fn get_outage_duration_text(&self) -> Option<String> {
+        self.get_outage_duration().map(|duration| {
+            let seconds = duration.num_seconds();
+            format!("{}h {}m {}s", seconds / 3600, seconds % 3600 / 60, seconds % 60)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Status;
+
+    use super::*;
+
+    #[test]
+    fn test_get_outage_duration() {
+        let outage_start = Utc::now() - Duration::seconds(3723);
+        let mut monitor_status = MonitorStatus::new("test", &None, Status::Error { message: "Error".to_string() });
+        monitor_status.last_error_time = Some(outage_start + Duration::seconds(3723));
+        let notification = Notification::new(&monitor_status, NotificationType::Error, Some(outage_start));
+        assert_eq!(notification.get_outage_duration(), Some(Duration::seconds(3723)));
+        assert_eq!(notification.get_outage_duration_text(), Some("1h 2m 3s".to_string()));
+        let notification = Notification::new(&monitor_status, NotificationType::Resolved, Some(outage_start));
+        assert_eq!(notification.get_outage_duration(), None);
+        let notification = Notification::new(&monitor_status, NotificationType::Error, None);
+        assert_eq!(notification.get_outage_duration_text(), None);
+    }
+}
@@ -0,0 +1,362 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::common::{ApplicationError, Status};
+
+use super::{Notification, NotificationType};
+
+/**
+ * Header containing the HMAC-SHA256 signature of the payload.
+ */
+const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/**
+ * The json payload posted by the webhook notifier. One payload is posted per notification.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    /// Name of the server.
+    #[serde(rename = "server")]
+    server: String,
+    /// Name of the monitor.
+    #[serde(rename = "name")]
+    name: String,
+    /// Description of the monitor.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "description")]
+    description: Option<String>,
+    /// The status of the monitor. Ok, Unknown, Error or Warn.
+    #[serde(rename = "status")]
+    status: String,
+    /// The status message.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "message")]
+    message: Option<String>,
+    /// The type of notification. Error, Warn or Resolved.
+    #[serde(rename = "notificationType")]
+    notification_type: String,
+    /// The duration of the outage in seconds.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "outageDurationSeconds")]
+    outage_duration_seconds: Option<i64>,
+    /// The last time the monitor was successful.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "lastSuccessfulTime")]
+    last_successful_time: Option<DateTime<Utc>>,
+    /// The last time the monitor encountered an error.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "lastErrorTime")]
+    last_error_time: Option<DateTime<Utc>>,
+    /// The time the notification was sent.
+    #[serde(rename = "notificationTime")]
+    notification_time: DateTime<Utc>,
+}
+
+impl WebhookPayload {
+    /**
+     * Create a new `WebhookPayload` from a `Notification`.
+     *
+     * `notification`: The notification.
+     * `server_name`: The name of the server.
+     *
+     * Returns a new `WebhookPayload`.
+     */
+    fn from_notification(notification: &Notification, server_name: &str) -> WebhookPayload {
+        let monitor_status = &notification.monitor_status;
+        let (status, message) = match &monitor_status.status {
+            Status::Ok => ("Ok", None),
+            Status::Unknown => ("Unknown", None),
+            Status::Error { message } => ("Error", Some(message.clone())),
+            Status::Warn { message } => ("Warn", Some(message.clone())),
+        };
+        let notification_type = match notification.notification_type {
+            NotificationType::Error => "Error",
+            NotificationType::Warn => "Warn",
+            NotificationType::Resolved => "Resolved",
+        };
+        WebhookPayload {
+            server: server_name.to_string(),
+            name: monitor_status.name.clone(),
+            description: monitor_status.description.clone(),
+            status: status.to_string(),
+            message,
+            notification_type: notification_type.to_string(),
+            outage_duration_seconds: notification.get_outage_duration().map(|duration| duration.num_seconds()),
+            last_successful_time: monitor_status.last_successful_time,
+            last_error_time: monitor_status.last_error_time,
+            notification_time: Utc::now(),
+        }
+    }
+}
+
+/**
+ * Notifier posting the notifications as json to a list of urls.
+ */
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    /// The urls to post to.
+    urls: Vec<String>,
+    /// Headers added to the requests.
+    headers: HashMap<String, String>,
+    /// Secret used to sign the payload.
+    secret: Option<String>,
+    /// Number of retries when a request fails.
+    retries: u32,
+    /// Delay before the first retry. Doubled for every following retry.
+    retry_delay: Duration,
+    /// The http client.
+    client: Client,
+}
+
+impl WebhookNotifier {
+    /**
+     * Create a new webhook notifier.
+     *
+     * `urls`: The urls to post to.
+     * `headers`: Headers added to the requests.
+     * `secret`: Secret used to sign the payload with HMAC-SHA256.
+     * `retries`: Number of retries when a request fails.
+     * `retry_delay`: Seconds before the first retry.
+     * `timeout`: The request timeout in seconds.
+     *
+     * Returns a new `WebhookNotifier`.
+     *
+     * throws: `ApplicationError`: If the http client could not be created.
+     */
+    pub fn new(
+        urls: Vec<String>,
+        headers: &Option<HashMap<String, String>>,
+        secret: &Option<String>,
+        retries: u32,
+        retry_delay: u64,
+        timeout: u64,
+    ) -> Result<WebhookNotifier, ApplicationError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(timeout))
+            .build()
+            .map_err(|err| ApplicationError::new(&format!("Error creating webhook client: {err:?}")))?;
+        Ok(WebhookNotifier {
+            urls,
+            headers: headers.clone().unwrap_or_default(),
+            secret: secret.clone(),
+            retries,
+            retry_delay: Duration::from_secs(retry_delay),
+            client,
+        })
+    }
+
+    /**
+     * Post a payload per notification to all urls. All posts are attempted even if one fails.
+     *
+     * `notifications`: The notifications.
+     * `server_name`: The name of the server.
+     *
+     * throws: `ApplicationError`: If any of the posts failed after all retries.
+     */
+    pub async fn notify(&self, notifications: &[Notification], server_name: &str) -> Result<(), ApplicationError> {
+        let mut errors = Vec::new();
+        for notification in notifications {
+            let payload = WebhookPayload::from_notification(notification, server_name);
+            let body = serde_json::to_vec(&payload)
+                .map_err(|err| ApplicationError::new(&format!("Error serializing webhook payload: {err:?}")))?;
+            for url in &self.urls {
+                if let Err(err) = self.post_with_retry(url, &body).await {
+                    errors.push(err.message);
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApplicationError::new(&errors.join(", ")))
+        }
+    }
+
+    /**
+     * Post the body to the url. Retries with a doubling delay until the retries are used up.
+     *
+     * `url`: The url.
+     * `body`: The json body.
+     *
+     * throws: `ApplicationError`: If the last attempt failed.
+     */
+    async fn post_with_retry(&self, url: &str, body: &[u8]) -> Result<(), ApplicationError> {
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            match self.post(url, body).await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < self.retries => {
+                    warn!("Webhook to {} failed, retrying in {:?}: {}", url, delay, err.message);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                },
+                Err(err) => {
+                    return Err(ApplicationError::new(&format!("Webhook to {url} failed after {attempt} retries: {}", err.message)));
+                }
+            }
+        }
+    }
+
+    /**
+     * Post the body to the url once.
+     *
+     * `url`: The url.
+     * `body`: The json body.
+     *
+     * throws: `ApplicationError`: If the request failed or the response status is not a success.
+     */
+    async fn post(&self, url: &str, body: &[u8]) -> Result<(), ApplicationError> {
+        let mut request = self.client.post(url)
+            .header("Content-Type", "application/json")
+            .body(body.to_vec());
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", WebhookNotifier::sign(secret, body)?));
+        }
+        let response = request.send().await
+            .map_err(|err| ApplicationError::new(&format!("Error posting webhook: {err}")))?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ApplicationError::new(&format!("Webhook responded with status {}", response.status())))
+        }
+    }
+
+    /**
+     * Sign the body with HMAC-SHA256.
+     *
+     * `secret`: The secret key.
+     * `body`: The body to sign.
+     *
+     * result: The signature as lowercase hex.
+     *
+     * throws: `ApplicationError`: If the signature could not be created.
+     */
+    fn sign(secret: &str, body: &[u8]) -> Result<String, ApplicationError> {
+        let key = PKey::hmac(secret.as_bytes())
+            .map_err(|err| ApplicationError::new(&format!("Error creating hmac key: {err:?}")))?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)
+            .map_err(|err| ApplicationError::new(&format!("Error creating hmac signer: {err:?}")))?;
+        signer.update(body)
+            .map_err(|err| ApplicationError::new(&format!("Error signing webhook payload: {err:?}")))?;
+        let signature = signer.sign_to_vec()
+            .map_err(|err| ApplicationError::new(&format!("Error signing webhook payload: {err:?}")))?;
+        Ok(signature.iter().map(|byte| format!("{byte:02x}")).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener, sync::mpsc};
+
+    use crate::common::MonitorStatus;
+
+    use super::*;
+
+    /**
+     * Start a local http stand-in. Responds to the requests with the given status codes in order
+     * and sends the received requests on the returned channel.
+     */
+    async fn start_stand_in(status_codes: Vec<u16>) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for status_code in status_codes {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text.lines()
+                            .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|value| value.trim().parse::<usize>().unwrap()))
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + content_length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                sender.send(String::from_utf8_lossy(&request).to_string()).unwrap();
+                let response = format!("HTTP/1.1 {status_code} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    fn get_notification() -> Notification {
+        let monitor_status = MonitorStatus::new("test", &Some("Test monitor".to_string()), Status::Error { message: "Failed".to_string() });
+        Notification::new(&monitor_status, NotificationType::Error, None)
+    }
+
+    #[tokio::test]
+    async fn test_notify() {
+        let (url, mut receiver) = start_stand_in(vec![200]).await;
+        let headers = HashMap::from([("X-Test".to_string(), "value".to_string())]);
+        let notifier = WebhookNotifier::new(vec![url], &Some(headers), &Some("secret".to_string()), 0, 0, 5).unwrap();
+        notifier.notify(&[get_notification()], "server").await.unwrap();
+        let request = receiver.recv().await.unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.1"));
+        let lowercase_request = request.to_lowercase();
+        assert!(lowercase_request.contains("x-test: value"));
+        assert!(lowercase_request.contains("content-type: application/json"));
+        let body = request.split("\r\n\r\n").nth(1).unwrap();
+        let signature = format!("x-signature-256: sha256={}", WebhookNotifier::sign("secret", body.as_bytes()).unwrap());
+        assert!(lowercase_request.contains(&signature));
+        let payload: WebhookPayload = serde_json::from_str(body).unwrap();
+        assert_eq!(payload.server, "server");
+        assert_eq!(payload.name, "test");
+        assert_eq!(payload.description, Some("Test monitor".to_string()));
+        assert_eq!(payload.status, "Error");
+        assert_eq!(payload.message, Some("Failed".to_string()));
+        assert_eq!(payload.notification_type, "Error");
+        assert_eq!(payload.outage_duration_seconds, None);
+    }
+
+    #[test]
+    fn test_payload_resolved() {
+        let mut monitor_status = MonitorStatus::new("test", &None, Status::Ok);
+        let outage_start = Utc::now() - chrono::Duration::seconds(60);
+        monitor_status.last_successful_time = Some(outage_start + chrono::Duration::seconds(60));
+        let notification = Notification::new(&monitor_status, NotificationType::Resolved, Some(outage_start));
+        let payload = WebhookPayload::from_notification(&notification, "server");
+        assert_eq!(payload.status, "Ok");
+        assert_eq!(payload.message, None);
+        assert_eq!(payload.notification_type, "Resolved");
+        assert_eq!(payload.outage_duration_seconds, Some(60));
+    }
+
+    #[tokio::test]
+    async fn test_notify_retry() {
+        let (url, mut receiver) = start_stand_in(vec![500, 503, 200]).await;
+        let notifier = WebhookNotifier::new(vec![url], &None, &None, 2, 0, 5).unwrap();
+        notifier.notify(&[get_notification()], "server").await.unwrap();
+        for _ in 0..3 {
+            let request = receiver.recv().await.unwrap();
+            assert!(!request.to_lowercase().contains("x-signature-256"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_retries_exhausted() {
+        let (url, _receiver) = start_stand_in(vec![500, 500]).await;
+        let notifier = WebhookNotifier::new(vec![url.clone()], &None, &None, 1, 0, 5).unwrap();
+        let result = notifier.notify(&[get_notification()], "server").await;
+        assert_eq!(result.unwrap_err().message, format!("Webhook to {url} failed after 1 retries: Webhook responded with status 500 Internal Server Error"));
+    }
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(WebhookNotifier::sign("key", b"The quick brown fox jumps over the lazy dog").unwrap(), "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+    }
+}
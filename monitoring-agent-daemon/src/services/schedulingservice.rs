@@ -1,11 +1,11 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc};
 
-use log::info;
+use log::{error, info, warn};
 use tokio_cron_scheduler::{Job, JobScheduler};
 
-use crate::{common::{configuration::{MonitoringConfig, ThresholdLevel}, ApplicationError, DatabaseServiceType, MonitorStatusType}, services::jobs::NotificationJob};
-use crate::services::{DbService, jobs::DbCleanupJob};
-use super::monitors::{CertificateMonitor, CommandMonitor, DatabaseMonitor, HttpMonitor, LoadAvgMonitor, MeminfoMonitor, ProcessMonitor, SystemctlMonitor, TcpMonitor};
+use crate::{common::{configuration::{CertificateEndpoint, CertificateSource, HttpAssertions, HttpScenarioStep, MonitoringConfig, ProcessMatchRule, ThresholdLevel}, ApplicationError, DatabaseServiceType, MonitorStatusType, MonitoringConfigType}, services::jobs::NotificationJob};
+use crate::services::{DbService, jobs::{DbCleanupJob, SpoolReplayJob}};
+use super::monitors::{CertificateMonitor, CommandMonitor, CommandOptions, CpuMonitor, DatabaseMonitor, DiskMonitor, HttpMonitor, HttpScenarioMonitor, LoadAvgMonitor, MeminfoMonitor, NetworkMonitor, ProcessMonitor, SystemctlMonitor, TcpMonitor};
 
 /**
  * Scheduling Service.
@@ -14,9 +14,13 @@ use super::monitors::{CertificateMonitor, CommandMonitor, DatabaseMonitor, HttpM
  * 
  * `scheduler`: The job scheduler.
  * `monitoring_config`: The monitoring configuration.
+ * `running_config`: The monitoring configuration shared with the readers that must not wait for the scheduling service.
  * `status`: The status of the monitors.
  * `database_service`: The database service.
  * `server_name`: The server name.
+ * `monitor_jobs`: The scheduled monitor jobs by monitor name.
+ * `cleanup_job`: The scheduled database cleanup job.
+ * `notification_job`: The scheduled notification job.
  * 
  */
 pub struct SchedulingService {
@@ -24,12 +28,42 @@ pub struct SchedulingService {
     scheduler: Option<JobScheduler>,
     /// The monitoring configuration.
     monitoring_config: Arc<MonitoringConfig>,
+    /// The monitoring configuration shared with the readers that must not wait for the scheduling service.
+    running_config: MonitoringConfigType,
     /// The status of the monitors.
     status: MonitorStatusType,
     /// The database service.
     database_service: DatabaseServiceType,
     /// The server name.
     server_name: String,
+    /// The scheduled monitor jobs by monitor name. Used to find the jobs to replace when the configuration is reloaded.
+    monitor_jobs: HashMap<String, (crate::common::Monitor, Job)>,
+    /// The scheduled database cleanup job.
+    cleanup_job: Option<Job>,
+    /// The scheduled notification job. The notification job is kept so a reload can carry over the notified monitors.
+    notification_job: Option<(NotificationJob, Job)>,
+}
+
+/**
+ * Monitor changes.
+ * 
+ * The monitors changed when a new configuration is applied.
+ * 
+ * `added`: Names of monitors that were added.
+ * `removed`: Names of monitors that were removed.
+ * `changed`: Names of monitors that were rescheduled because the configuration changed.
+ * `errors`: Errors removing the previous jobs. The new configuration is applied even if a previous job could not be removed.
+ */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MonitorChanges {
+    /// Names of monitors that were added.
+    pub added: Vec<String>,
+    /// Names of monitors that were removed.
+    pub removed: Vec<String>,
+    /// Names of monitors that were rescheduled.
+    pub changed: Vec<String>,
+    /// Errors removing the previous jobs.
+    pub errors: Vec<String>,
 }
 
 impl SchedulingService {
@@ -43,9 +77,13 @@ impl SchedulingService {
         SchedulingService {
             scheduler: None,
             monitoring_config: monitoring_config.clone(),
+            running_config: Arc::new(std::sync::RwLock::new(monitoring_config.clone())),
             status: status.clone(),
             database_service: database_service.clone(),
             server_name: server_name.to_string(),
+            monitor_jobs: HashMap::new(),
+            cleanup_job: None,
+            notification_job: None,
         }
     }
 
@@ -58,19 +96,345 @@ impl SchedulingService {
      */
     pub async fn start(&mut self, test: bool) -> Result<(), ApplicationError> {       
         /*
-         * Start the scheduling of the monitoring jobs.
-         */
-        let future_scheduling = self.add_jobs();
-        /*
-         * Block the main thread until the scheduling is done. If test is true, the scheduling will be ignored.
+         * Start the scheduling of the monitoring jobs. If test is true, the scheduling will be ignored.
          * This is useful for testing the configuration file and for testing the code.
          */
         if !test {
-            future_scheduling.await?;
+            self.add_jobs().await?;
         }
         Ok(())
     }
 
+    /**
+     * Reload the configuration from file.
+     *
+     * `config_path`: The path to the configuration file.
+     *
+     * result: The monitors that were changed.
+     *
+     * throws: `ApplicationError`: If the configuration could not be read or applied. The running configuration is kept.
+     */
+    pub async fn reload_from_file(&mut self, config_path: &str) -> Result<MonitorChanges, ApplicationError> {
+        let monitoring_config = MonitoringConfig::new(config_path)?;
+        self.reload(&Arc::new(monitoring_config)).await
+    }
+
+    /**
+     * Apply a new configuration to the running scheduler.
+     *
+     * New monitors are scheduled, removed monitors are unscheduled and changed monitors are rescheduled.
+     * Unchanged monitors keep their jobs and status. All the new jobs, including the cleanup and notification
+     * jobs, are created before any previous job is removed. If any of the new jobs fails to be created, the
+     * jobs added so far are removed and the running configuration is kept. Once the new jobs exist the swap is
+     * always completed, and errors removing previous jobs are returned in the changes.
+     *
+     * `monitoring_config`: The new monitoring configuration.
+     *
+     * result: The monitors that were changed.
+     *
+     * throws: `ApplicationError`: If the configuration is invalid or the scheduler is not started.
+     */
+    pub async fn reload(&mut self, monitoring_config: &Arc<MonitoringConfig>) -> Result<MonitorChanges, ApplicationError> {
+        let Some(scheduler) = self.scheduler.clone() else {
+            return Err(ApplicationError::new("Scheduler is not started"));
+        };
+        SchedulingService::validate_monitors(monitoring_config)?;
+        SchedulingService::warn_restart_required(&self.monitoring_config, monitoring_config);
+        /*
+         * Create the jobs for new and changed monitors before touching the running jobs.
+         */
+        let status_snapshot = self.get_status_snapshot()?;
+        let previous_jobs = self.monitor_jobs.clone();
+        let mut changes = MonitorChanges::default();
+        let mut added_jobs: Vec<Job> = Vec::new();
+        for monitor in &monitoring_config.monitors {
+            let previous_monitor = previous_jobs.get(&monitor.name).map(|(previous_monitor, _)| previous_monitor);
+            if previous_monitor == Some(monitor) {
+                continue;
+            }
+            match self.create_and_add_job(monitor, &scheduler).await {
+                Ok(job) => {
+                    added_jobs.push(job);
+                    if previous_monitor.is_some() {
+                        changes.changed.push(monitor.name.clone());
+                    } else {
+                        changes.added.push(monitor.name.clone());
+                    }
+                }
+                Err(err) => {
+                    error!("Error creating job for monitor {}: {err:?}", monitor.name);
+                    self.rollback(&scheduler, added_jobs, previous_jobs, status_snapshot).await;
+                    return Err(err);
+                }
+            }
+        }
+        /*
+         * Create the cleanup and notification jobs if their configuration changed. The previous jobs keep running until
+         * all the new jobs exist.
+         */
+        let previous_config = self.monitoring_config.clone();
+        let previous_cleanup_job = self.cleanup_job.clone();
+        let previous_notification_job = self.notification_job.clone();
+        self.monitoring_config = monitoring_config.clone();
+        let cleanup_changed = previous_config.cleanup_config != monitoring_config.cleanup_config;
+        let notification_changed = previous_config.notification_config != monitoring_config.notification_config;
+        if let Err(err) = self.add_changed_service_jobs(&scheduler, cleanup_changed, notification_changed, &mut added_jobs).await {
+            error!("Error creating service jobs: {err:?}");
+            self.monitoring_config = previous_config;
+            self.cleanup_job = previous_cleanup_job;
+            self.notification_job = previous_notification_job;
+            self.rollback(&scheduler, added_jobs, previous_jobs, status_snapshot).await;
+            return Err(err);
+        }
+        /*
+         * Remove the jobs for removed and changed monitors, and the replaced cleanup and notification jobs. A job that can
+         * not be removed does not stop the swap.
+         */
+        let mut removed_jobs: Vec<Job> = Vec::new();
+        for (name, (monitor, job)) in previous_jobs {
+            let current_monitor = monitoring_config.monitors.iter().find(|current_monitor| current_monitor.name == name);
+            if current_monitor == Some(&monitor) {
+                continue;
+            }
+            removed_jobs.push(job);
+            if current_monitor.is_none() {
+                self.monitor_jobs.remove(&name);
+                if let Err(err) = self.remove_monitor_status(&name) {
+                    changes.errors.push(format!("Could not remove the status of {name}: {}", err.message));
+                }
+                changes.removed.push(name);
+            }
+        }
+        if cleanup_changed {
+            removed_jobs.extend(previous_cleanup_job);
+        }
+        if notification_changed {
+            removed_jobs.extend(previous_notification_job.map(|(_, job)| job));
+        }
+        for job in removed_jobs {
+            if let Err(err) = SchedulingService::remove_job(&scheduler, &job).await {
+                error!("Error removing previous job: {err:?}");
+                changes.errors.push(err.message);
+            }
+        }
+        match self.running_config.write() {
+            Ok(mut running_config) => *running_config = monitoring_config.clone(),
+            Err(err) => changes.errors.push(format!("Could not publish the running configuration: {err:?}")),
+        }
+        info!("Configuration reloaded: {changes:?}");
+        Ok(changes)
+    }
+
+    /**
+     * Create the new cleanup and notification jobs for a reload. The created jobs are added to `added_jobs` so they
+     * can be rolled back.
+     *
+     * `scheduler`: The job scheduler.
+     * `cleanup_changed`: Create a new cleanup job.
+     * `notification_changed`: Create a new notification job.
+     * `added_jobs`: The jobs added by the reload.
+     *
+     * throws: `ApplicationError`: If a job fails to be created or added.
+     */
+    async fn add_changed_service_jobs(&mut self, scheduler: &JobScheduler, cleanup_changed: bool, notification_changed: bool, added_jobs: &mut Vec<Job>) -> Result<(), ApplicationError> {
+        if cleanup_changed {
+            self.cleanup_job = None;
+            self.add_cleanup_job(scheduler).await?;
+            added_jobs.extend(self.cleanup_job.clone());
+        }
+        if notification_changed {
+            self.add_notification_job(scheduler).await?;
+            added_jobs.extend(self.notification_job.clone().map(|(_, job)| job));
+        }
+        Ok(())
+    }
+
+    /**
+     * Get the running monitoring configuration. The configuration is updated when a reload is applied, and can be read
+     * without locking the scheduling service.
+     *
+     * result: The running monitoring configuration.
+     */
+    pub fn get_running_config(&self) -> MonitoringConfigType {
+        self.running_config.clone()
+    }
+
+    /**
+     * Get the configuration of a running monitor.
+     *
+     * `name`: The name of the monitor.
+     *
+     * result: The monitor configuration, or None if no monitor has the name.
+     */
+    pub fn get_monitor(&self, name: &str) -> Option<crate::common::Monitor> {
+        self.monitoring_config.monitors.iter().find(|monitor| monitor.name == name).cloned()
+    }
+
+    /**
+     * Add a monitor to the running configuration and schedule it.
+     *
+     * `monitor`: The monitor configuration.
+     *
+     * result: The monitors that were changed.
+     *
+     * throws: `ApplicationError`: If a monitor with the same name exists or the monitor could not be scheduled.
+     */
+    pub async fn add_monitor(&mut self, monitor: &crate::common::Monitor) -> Result<MonitorChanges, ApplicationError> {
+        if self.get_monitor(&monitor.name).is_some() {
+            return Err(ApplicationError::new(format!("Monitor already exists: {}", monitor.name).as_str()));
+        }
+        let mut monitoring_config = (*self.monitoring_config).clone();
+        monitoring_config.monitors.push(monitor.clone());
+        self.reload(&Arc::new(monitoring_config)).await
+    }
+
+    /**
+     * Replace a monitor in the running configuration and reschedule it.
+     *
+     * `monitor`: The monitor configuration.
+     *
+     * result: The monitors that were changed.
+     *
+     * throws: `ApplicationError`: If no monitor has the name or the monitor could not be scheduled.
+     */
+    pub async fn update_monitor(&mut self, monitor: &crate::common::Monitor) -> Result<MonitorChanges, ApplicationError> {
+        let mut monitoring_config = (*self.monitoring_config).clone();
+        let Some(current_monitor) = monitoring_config.monitors.iter_mut().find(|current_monitor| current_monitor.name == monitor.name) else {
+            return Err(ApplicationError::new(format!("Monitor not found: {}", monitor.name).as_str()));
+        };
+        *current_monitor = monitor.clone();
+        self.reload(&Arc::new(monitoring_config)).await
+    }
+
+    /**
+     * Remove a monitor from the running configuration and unschedule it.
+     *
+     * `name`: The name of the monitor.
+     *
+     * result: The monitors that were changed.
+     *
+     * throws: `ApplicationError`: If no monitor has the name.
+     */
+    pub async fn remove_monitor(&mut self, name: &str) -> Result<MonitorChanges, ApplicationError> {
+        if self.get_monitor(name).is_none() {
+            return Err(ApplicationError::new(format!("Monitor not found: {name}").as_str()));
+        }
+        let mut monitoring_config = (*self.monitoring_config).clone();
+        monitoring_config.monitors.retain(|monitor| monitor.name != name);
+        self.reload(&Arc::new(monitoring_config)).await
+    }
+
+    /**
+     * Validate the monitors in a configuration before it is applied.
+     *
+     * `monitoring_config`: The monitoring configuration.
+     *
+     * throws: `ApplicationError`: If monitor names are not unique.
+     */
+    fn validate_monitors(monitoring_config: &MonitoringConfig) -> Result<(), ApplicationError> {
+        let mut names: Vec<&str> = Vec::new();
+        for monitor in &monitoring_config.monitors {
+            if names.contains(&monitor.name.as_str()) {
+                return Err(ApplicationError::new(format!("Monitor name is not unique: {}", monitor.name).as_str()));
+            }
+            names.push(monitor.name.as_str());
+        }
+        Ok(())
+    }
+
+    /**
+     * Log a warning for configuration changes that are only applied on restart.
+     *
+     * `previous_config`: The running configuration.
+     * `monitoring_config`: The new configuration.
+     */
+    fn warn_restart_required(previous_config: &MonitoringConfig, monitoring_config: &MonitoringConfig) {
+        if previous_config.server != monitoring_config.server {
+            warn!("Server configuration changed. Restart required to apply the change");
+        }
+        if previous_config.database != monitoring_config.database {
+            warn!("Database configuration changed. Restart required to apply the change");
+        }
+        if previous_config.tokio_threads != monitoring_config.tokio_threads || previous_config.tokio_stack_size != monitoring_config.tokio_stack_size {
+            warn!("Tokio configuration changed. Restart required to apply the change");
+        }
+    }
+
+    /**
+     * Undo a partially applied configuration.
+     *
+     * `scheduler`: The job scheduler.
+     * `added_jobs`: The jobs added for the new configuration.
+     * `previous_jobs`: The monitor jobs before the configuration was applied.
+     * `status_snapshot`: The monitor statuses before the configuration was applied.
+     */
+    async fn rollback(&mut self, scheduler: &JobScheduler, added_jobs: Vec<Job>, previous_jobs: HashMap<String, (crate::common::Monitor, Job)>, status_snapshot: HashMap<String, crate::common::MonitorStatus>) {
+        for job in added_jobs {
+            if let Err(err) = SchedulingService::remove_job(scheduler, &job).await {
+                error!("Error removing job during rollback: {err:?}");
+            }
+        }
+        self.monitor_jobs = previous_jobs;
+        match self.status.lock() {
+            Ok(mut status) => {
+                *status = status_snapshot;
+            }
+            Err(err) => {
+                error!("Error restoring monitor status: {err:?}");
+            }
+        }
+    }
+
+    /**
+     * Get a copy of the current monitor statuses.
+     *
+     * result: The monitor statuses.
+     *
+     * throws: `ApplicationError`: If the status lock fails.
+     */
+    fn get_status_snapshot(&self) -> Result<HashMap<String, crate::common::MonitorStatus>, ApplicationError> {
+        match self.status.lock() {
+            Ok(status) => Ok(status.clone()),
+            Err(err) => Err(ApplicationError::new(format!("Could not lock monitor status: {err}").as_str())),
+        }
+    }
+
+    /**
+     * Remove the status of a monitor.
+     *
+     * `name`: The name of the monitor.
+     *
+     * throws: `ApplicationError`: If the status lock fails.
+     */
+    fn remove_monitor_status(&self, name: &str) -> Result<(), ApplicationError> {
+        match self.status.lock() {
+            Ok(mut status) => {
+                status.remove(name);
+                Ok(())
+            },
+            Err(err) => Err(ApplicationError::new(format!("Could not lock monitor status: {err}").as_str())),
+        }
+    }
+
+    /**
+     * Set the failure and success thresholds on the status of a monitor.
+     *
+     * `monitor`: The monitor configuration.
+     *
+     * throws: `ApplicationError`: If the status lock fails.
+     */
+    fn set_monitor_status_thresholds(&self, monitor: &crate::common::Monitor) -> Result<(), ApplicationError> {
+        match self.status.lock() {
+            Ok(mut status) => {
+                if let Some(monitor_status) = status.get_mut(&monitor.name) {
+                    monitor_status.set_thresholds(monitor.failure_threshold.unwrap_or(1), monitor.success_threshold.unwrap_or(1));
+                }
+                Ok(())
+            },
+            Err(err) => Err(ApplicationError::new(format!("Could not lock monitor status: {err}").as_str())),
+        }
+    }
+
     /**
      * Create and add jobs to the scheduler.
      *
@@ -99,28 +463,18 @@ impl SchedulingService {
         for monitor in self.monitoring_config.monitors.clone() {
             self.create_and_add_job(&monitor, &scheduler).await?;
         }
-
         /*
          * Create a cleanup job.
          */
-        let cleanup_config = self.monitoring_config.cleanup_config.clone();
-        if let Some(cleanup_config) = cleanup_config {
-            let max_time_stored_db = cleanup_config.max_time_stored_db;
-            if let Some(max_time_stored_db) = max_time_stored_db {
-                let mut cleanup_job = DbCleanupJob::new(&self.database_service, max_time_stored_db);
-                let job = cleanup_job.get_db_cleanup_job()?;
-                self.add_job(&scheduler, job).await?;
-            }
-        }
+        self.add_cleanup_job(&scheduler).await?;
         /*
          * Create a notification job.
          */
-        let notification_config = self.monitoring_config.notification_config.clone();
-        if let Some(notification_config) = notification_config {
-            let mut notification = NotificationJob::new(&self.status, &notification_config.url, notification_config.recipients, notification_config.from.as_str(), notification_config.reply_to.as_str(), notification_config.resend_after, &notification_config.schedule)?;
-            let job = notification.get_notification_job()?;
-            self.add_job(&scheduler, job).await?;
-        }        
+        self.add_notification_job(&scheduler).await?;
+        /*
+         * Create a spool replay job.
+         */
+        self.add_spool_replay_job(&scheduler).await?;
         /*
          * Start the scheduler.
          */
@@ -129,17 +483,76 @@ impl SchedulingService {
             Ok(()) => {
                 info!("Scheduler started");
                 self.scheduler = Some(scheduler);
+                Ok(())
             }
             Err(err) => {
-                return Err(ApplicationError::new(
+                Err(ApplicationError::new(
                     format!("Could not start scheduler: {err}").as_str(),
-                ));
+                ))
+            }
+        }
+    }
+
+    /**
+     * Create and add the database cleanup job if configured.
+     *
+     * `scheduler`: The job scheduler.
+     *
+     * throws: `ApplicationError`: If the job fails to be added.
+     */
+    async fn add_cleanup_job(&mut self, scheduler: &JobScheduler) -> Result<(), ApplicationError> {
+        let cleanup_config = self.monitoring_config.cleanup_config.clone();
+        if let Some(cleanup_config) = cleanup_config {
+            let max_time_stored_db = cleanup_config.max_time_stored_db;
+            if let Some(max_time_stored_db) = max_time_stored_db {
+                let mut cleanup_job = DbCleanupJob::new(&self.database_service, max_time_stored_db);
+                let job = cleanup_job.get_db_cleanup_job()?;
+                self.cleanup_job = Some(self.add_job(scheduler, job).await?);
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Create and add the spool replay job if the database spool is enabled. The database service
+     * is not replaced when the configuration is reloaded, so the job is only added once.
+     *
+     * `scheduler`: The job scheduler.
+     *
+     * throws: `ApplicationError`: If the job fails to be added.
+     */
+    async fn add_spool_replay_job(&mut self, scheduler: &JobScheduler) -> Result<(), ApplicationError> {
+        let database_config = self.monitoring_config.database.clone();
+        if let Some(database_config) = database_config {
+            if database_config.spool.enabled {
+                let mut spool_replay_job = SpoolReplayJob::new(&self.database_service, &database_config.spool.replay_schedule);
+                let job = spool_replay_job.get_spool_replay_job()?;
+                self.add_job(scheduler, job).await?;
             }
         }
-        loop {
-            info!("Scheduler is awake");
-            tokio::time::sleep(Duration::from_secs(300)).await;
+        Ok(())
+    }
+
+    /**
+     * Create and add the notification job if configured. A replaced notification job hands over the notified monitors.
+     *
+     * `scheduler`: The job scheduler.
+     *
+     * throws: `ApplicationError`: If the job fails to be added.
+     */
+    async fn add_notification_job(&mut self, scheduler: &JobScheduler) -> Result<(), ApplicationError> {
+        let notification_config = self.monitoring_config.notification_config.clone();
+        if let Some(notification_config) = notification_config {
+            let mut notification = NotificationJob::new(&self.status, &notification_config.get_notifiers(), &self.monitoring_config.server.name, notification_config.resend_after, notification_config.minimum_severity, &notification_config.schedule)?;
+            if let Some((previous_notification, _)) = &self.notification_job {
+                notification.keep_notified(previous_notification);
+            }
+            let job = notification.get_notification_job()?;
+            self.notification_job = Some((notification, self.add_job(scheduler, job).await?));
+        } else {
+            self.notification_job = None;
         }
+        Ok(())
     }
 
     /**
@@ -156,18 +569,22 @@ impl SchedulingService {
         &mut self,
         monitor: &crate::common::Monitor,
         scheduler: &JobScheduler,        
-    ) -> Result<(), ApplicationError> {
+    ) -> Result<Job, ApplicationError> {
         let monitor_type = monitor.details.clone();
-        match monitor_type {
-            crate::common::MonitorType::Tcp { host, port, retry } => {
-                self.create_and_schedule_tcp_monitor(host, port, retry, monitor, scheduler).await?
+        let job = match monitor_type {
+            crate::common::MonitorType::Tcp { host, port, retry, warn_latency_ms, error_latency_ms, store_values } => {
+                self.create_and_schedule_tcp_monitor(host, port, retry, warn_latency_ms, error_latency_ms, store_values, monitor, scheduler).await?
             },
             crate::common::MonitorType::Http { url, method, body, headers, use_builtin_root_certs, accept_invalid_certs,
-                tls_info, root_certificate, identity, identity_password, retry} => { 
-                self.create_and_schedule_http_monitor(url, method, body, headers, monitor, use_builtin_root_certs, accept_invalid_certs, tls_info, root_certificate, identity, identity_password, retry, scheduler).await?
+                tls_info, root_certificate, identity, identity_password, retry, warn_latency_ms, error_latency_ms, store_values, assertions} => { 
+                self.create_and_schedule_http_monitor(url, method, body, headers, monitor, use_builtin_root_certs, accept_invalid_certs, tls_info, root_certificate, identity, identity_password, retry, warn_latency_ms, error_latency_ms, store_values, &assertions, scheduler).await?
+            },
+            crate::common::MonitorType::HttpScenario { steps, use_builtin_root_certs, accept_invalid_certs, tls_info, root_certificate, identity, identity_password } => {
+                self.create_and_schedule_http_scenario_monitor(monitor, &steps, use_builtin_root_certs, accept_invalid_certs, tls_info, root_certificate, identity, identity_password, scheduler).await?
             },
-            crate::common::MonitorType::Command { command, args, expected, } => {
-                self.create_and_schedule_command_monitor(monitor, command, args, expected, scheduler).await?
+            crate::common::MonitorType::Command { command, args, expected, mode, store_values, expected_exit_codes, stdout_regex, stderr_regex, environment, working_directory, stdin, user, group, max_output_size } => {
+                let options = CommandOptions { args, expected, mode, store_values, expected_exit_codes, stdout_regex, stderr_regex, environment, working_directory, stdin, user, group, max_output_size };
+                self.create_and_schedule_command_monitor(monitor, command, options, scheduler).await?
             },
             crate::common::MonitorType::LoadAvg { threshold_1min, threshold_5min, threshold_15min, threshold_1min_level, threshold_5min_level, threshold_15min_level, store_values, } => {               
                 self.create_and_schedule_loadavg_monitor(monitor, threshold_1min, threshold_5min, threshold_15min, threshold_1min_level, threshold_5min_level, threshold_15min_level, store_values, scheduler).await?
@@ -182,14 +599,104 @@ impl SchedulingService {
             crate::common::MonitorType::Database {database_config, max_query_time, } => {
                 self.create_and_schedule_database_monitor(monitor, max_query_time, database_config, scheduler).await?
             },
-            crate::common::MonitorType::Process { application_names, pids, regexp, threshold_mem_warn, threshold_mem_error, store_values } => {
-                self.create_and_schedule_process_monitor(monitor, application_names, pids, regexp, threshold_mem_warn, threshold_mem_error, store_values, scheduler).await?
+            crate::common::MonitorType::Process { application_names, pids, regexp, rules, threshold_mem_warn, threshold_mem_error, threshold_cpu_warn, threshold_cpu_error, threshold_io_warn, threshold_io_error, threshold_fd_warn, threshold_fd_error, store_values } => {
+                self.create_and_schedule_process_monitor(monitor, application_names, pids, regexp, &rules, threshold_mem_warn, threshold_mem_error, threshold_cpu_warn, threshold_cpu_error, threshold_io_warn, threshold_io_error, threshold_fd_warn, threshold_fd_error, store_values, scheduler).await?
             },
-            crate::common::MonitorType::Certificate { certificates, threshold_days_warn, threshold_days_error } => {
-                self.create_and_schedule_certificate_monitor(monitor, certificates, threshold_days_warn, threshold_days_error, scheduler).await?
+            crate::common::MonitorType::Certificate { certificates, endpoints, threshold_days_warn, threshold_days_error } => {
+                self.create_and_schedule_certificate_monitor(monitor, certificates, endpoints, threshold_days_warn, threshold_days_error, scheduler).await?
             },
-        }?;   
-        Ok(()) 
+            crate::common::MonitorType::Disk { mounts, error_percentage_used_space, warn_percentage_used_space, error_percentage_used_inodes, warn_percentage_used_inodes, store_values } => {
+                self.create_and_schedule_disk_monitor(monitor, mounts, error_percentage_used_space, warn_percentage_used_space, error_percentage_used_inodes, warn_percentage_used_inodes, store_values, scheduler).await?
+            },
+            crate::common::MonitorType::Cpu { error_percentage_total, warn_percentage_total, error_percentage_iowait, warn_percentage_iowait, per_core, store_values } => {
+                self.create_and_schedule_cpu_monitor(monitor, error_percentage_total, warn_percentage_total, error_percentage_iowait, warn_percentage_iowait, per_core, store_values, scheduler).await?
+            },
+            crate::common::MonitorType::Network { interfaces, error_percentage_saturation, warn_percentage_saturation, error_on_errors, warn_on_drops, store_values } => {
+                self.create_and_schedule_network_monitor(monitor, interfaces, error_percentage_saturation, warn_percentage_saturation, error_on_errors, warn_on_drops, store_values, scheduler).await?
+            },
+        }?;
+        self.set_monitor_status_thresholds(monitor)?;
+        self.monitor_jobs.insert(monitor.name.clone(), (monitor.clone(), job.clone()));
+        Ok(job)
+    }
+
+    /**
+     * Create and schedule a disk monitor.
+     * 
+     * `monitor`: The monitor configuration.
+     * `mounts`: The mount points. If None then the mounted disks are discovered.
+     * `error_percentage_used_space`: The maximum percentage of space used before error.
+     * `warn_percentage_used_space`: The maximum percentage of space used before warning.
+     * `error_percentage_used_inodes`: The maximum percentage of inodes used before error.
+     * `warn_percentage_used_inodes`: The maximum percentage of inodes used before warning.
+     * `store_values`: Store the values.
+     * `scheduler`: The job scheduler.
+     * 
+     * `result`: The result of creating and scheduling the disk monitor.
+     * 
+     * Errors:
+     * - If the disk monitor fails to be created.
+     * - If the job fails to be added.
+     * - If the job fails to be scheduled.
+     */
+    #[allow(clippy::too_many_arguments)]
+    async fn create_and_schedule_disk_monitor(&mut self, monitor: &crate::common::Monitor, mounts: Option<Vec<String>>, error_percentage_used_space: Option<f64>, warn_percentage_used_space: Option<f64>, error_percentage_used_inodes: Option<f64>, warn_percentage_used_inodes: Option<f64>, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let disk_monitor = DiskMonitor::new(&monitor.name, &monitor.description, mounts, error_percentage_used_space, warn_percentage_used_space, error_percentage_used_inodes, warn_percentage_used_inodes, &self.status, &self.database_service.clone(), &monitor.store, store_values);
+        let job = DiskMonitor::get_disk_monitor_job(disk_monitor, monitor.schedule.as_str(), monitor.timeout)?;
+        Ok(self.add_job(scheduler, job).await)
+    }
+
+    /**
+     * Create and schedule a cpu monitor.
+     * 
+     * `monitor`: The monitor configuration.
+     * `error_percentage_total`: The maximum percentage of cpu time used before error.
+     * `warn_percentage_total`: The maximum percentage of cpu time used before warning.
+     * `error_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before error.
+     * `warn_percentage_iowait`: The maximum percentage of cpu time waiting for I/O before warning.
+     * `per_core`: Check the thresholds for each core.
+     * `store_values`: Store the values.
+     * `scheduler`: The job scheduler.
+     * 
+     * `result`: The result of creating and scheduling the cpu monitor.
+     * 
+     * Errors:
+     * - If the cpu monitor fails to be created.
+     * - If the job fails to be added.
+     * - If the job fails to be scheduled.
+     */
+    #[allow(clippy::too_many_arguments)]
+    async fn create_and_schedule_cpu_monitor(&mut self, monitor: &crate::common::Monitor, error_percentage_total: Option<f64>, warn_percentage_total: Option<f64>, error_percentage_iowait: Option<f64>, warn_percentage_iowait: Option<f64>, per_core: bool, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let cpu_monitor = CpuMonitor::new(&monitor.name, &monitor.description, error_percentage_total, warn_percentage_total, error_percentage_iowait, warn_percentage_iowait, per_core, &self.status, &self.database_service.clone(), &monitor.store, store_values);
+        let job = CpuMonitor::get_cpu_monitor_job(cpu_monitor, monitor.schedule.as_str(), monitor.timeout)?;
+        Ok(self.add_job(scheduler, job).await)
+    }
+
+    /**
+     * Create and schedule a network monitor.
+     * 
+     * `monitor`: The monitor configuration.
+     * `interfaces`: The interfaces. If None then all interfaces except loopback are monitored.
+     * `error_percentage_saturation`: The maximum percentage of the link speed used before error.
+     * `warn_percentage_saturation`: The maximum percentage of the link speed used before warning.
+     * `error_on_errors`: Change to error when the errors increase.
+     * `warn_on_drops`: Change to warning when the dropped packets increase.
+     * `store_values`: Store the values.
+     * `scheduler`: The job scheduler.
+     * 
+     * `result`: The result of creating and scheduling the network monitor.
+     * 
+     * Errors:
+     * - If the network monitor fails to be created.
+     * - If the job fails to be added.
+     * - If the job fails to be scheduled.
+     */
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::fn_params_excessive_bools)]
+    async fn create_and_schedule_network_monitor(&mut self, monitor: &crate::common::Monitor, interfaces: Option<Vec<String>>, error_percentage_saturation: Option<f64>, warn_percentage_saturation: Option<f64>, error_on_errors: bool, warn_on_drops: bool, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let network_monitor = NetworkMonitor::new(&monitor.name, &monitor.description, interfaces, error_percentage_saturation, warn_percentage_saturation, error_on_errors, warn_on_drops, &self.status, &self.database_service.clone(), &monitor.store, store_values);
+        let job = NetworkMonitor::get_network_monitor_job(network_monitor, monitor.schedule.as_str(), monitor.timeout)?;
+        Ok(self.add_job(scheduler, job).await)
     }
 
     /**
@@ -197,6 +704,7 @@ impl SchedulingService {
      * 
      * `monitor`: The monitor configuration.
      * `certificates`: The certificates.
+     * `endpoints`: The remote endpoints.
      * `threshold_days_warn`: The threshold days warning.
      * `threshold_days_error`: The threshold days error.
      * `scheduler`: The job scheduler.
@@ -208,18 +716,19 @@ impl SchedulingService {
      * - If the job fails to be added.
      * - If the job fails to be scheduled.
      */
-    async fn create_and_schedule_certificate_monitor(&mut self, monitor: &crate::common::Monitor, certificates: Vec<String>, threshold_days_warn: u32, threshold_days_error: u32, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_certificate_monitor(&mut self, monitor: &crate::common::Monitor, certificates: Vec<CertificateSource>, endpoints: Option<Vec<CertificateEndpoint>>, threshold_days_warn: u32, threshold_days_error: u32, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let certificate_monitor = CertificateMonitor::new(
             &monitor.name,
             &monitor.description,
             &self.status,
-            certificates,
+            certificates.iter().map(CertificateSource::get_file).collect(),
+            endpoints.unwrap_or_default(),
             threshold_days_warn,
             threshold_days_error,
             &self.database_service.clone(),
             &monitor.store,
         );
-        let job = CertificateMonitor::get_certificate_job(certificate_monitor, monitor.schedule.as_str())?;
+        let job = CertificateMonitor::get_certificate_job(certificate_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -230,8 +739,15 @@ impl SchedulingService {
      * `application_names`: The application names.
      * `pids`: The process IDs.
      * `regexp`: The regular expression.
+     * `rules`: The match rules.
      * `threshold_mem_warn`: The threshold memory warning.
      * `threshold_mem_error`: The threshold memory error.
+     * `threshold_cpu_warn`: The threshold cpu warning.
+     * `threshold_cpu_error`: The threshold cpu error.
+     * `threshold_io_warn`: The threshold I/O warning.
+     * `threshold_io_error`: The threshold I/O error.
+     * `threshold_fd_warn`: The threshold file descriptor warning.
+     * `threshold_fd_error`: The threshold file descriptor error.
      * `store_values`: Store the values.
      * `scheduler`: The job scheduler.
      * 
@@ -243,9 +759,9 @@ impl SchedulingService {
      * - If the job fails to be scheduled.
      */
     #[allow(clippy::too_many_arguments)]
-    async fn create_and_schedule_process_monitor(&mut self, monitor: &crate::common::Monitor, application_names: Option<Vec<String>>, pids: Option<Vec<u32>>, regexp: Option<String>, threshold_mem_warn: Option<u64>, threshold_mem_error: Option<u64>, store_values: bool, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
-        let process_monitor = ProcessMonitor::new(&monitor.name, &monitor.description, application_names, pids, regexp, threshold_mem_warn, threshold_mem_error, &self.status, &self.database_service.clone(), &monitor.store, store_values);
-        let job = ProcessMonitor::get_process_monitor_job(process_monitor, monitor.schedule.as_str())?;
+    async fn create_and_schedule_process_monitor(&mut self, monitor: &crate::common::Monitor, application_names: Option<Vec<String>>, pids: Option<Vec<u32>>, regexp: Option<String>, rules: &Option<Vec<ProcessMatchRule>>, threshold_mem_warn: Option<u64>, threshold_mem_error: Option<u64>, threshold_cpu_warn: Option<f64>, threshold_cpu_error: Option<f64>, threshold_io_warn: Option<u64>, threshold_io_error: Option<u64>, threshold_fd_warn: Option<f64>, threshold_fd_error: Option<f64>, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let process_monitor = ProcessMonitor::new(&monitor.name, &monitor.description, application_names, pids, regexp, rules, threshold_mem_warn, threshold_mem_error, threshold_cpu_warn, threshold_cpu_error, threshold_io_warn, threshold_io_error, threshold_fd_warn, threshold_fd_error, &self.status, &self.database_service.clone(), &monitor.store, store_values)?;
+        let job = ProcessMonitor::get_process_monitor_job(process_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -264,7 +780,7 @@ impl SchedulingService {
      * - If the job fails to be added.
      * - If the job fails to be scheduled.
      */
-    async fn create_and_schedule_database_monitor(&mut self, monitor: &crate::common::Monitor, max_query_time: Option<u32>, database_config: Option<crate::common::DatabaseConfig>, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_database_monitor(&mut self, monitor: &crate::common::Monitor, max_query_time: Option<u32>, database_config: Option<crate::common::DatabaseConfig>, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let database_monitor = DatabaseMonitor::new(
             &monitor.name,
             &monitor.description,
@@ -273,7 +789,7 @@ impl SchedulingService {
             &self.get_database_service(&self.database_service, &database_config).await?,
             &monitor.store,
         );
-        let job = DatabaseMonitor::get_database_monitor_job(database_monitor, monitor.schedule.as_str())?;
+        let job = DatabaseMonitor::get_database_monitor_job(database_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -291,9 +807,9 @@ impl SchedulingService {
      * - If the job fails to be added.
      * - If the job fails to be scheduled.
      */
-    async fn create_and_schedule_systemctl_monitor(&mut self, monitor: &crate::common::Monitor, active: Vec<String>, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_systemctl_monitor(&mut self, monitor: &crate::common::Monitor, active: Vec<String>, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let systemctl_monitor = SystemctlMonitor::new(&monitor.name, &monitor.description, &self.status, &self.database_service.clone(), &monitor.store, active);
-        let job = SystemctlMonitor::get_systemctl_monitor_job(systemctl_monitor, monitor.schedule.as_str())?;
+        let job = SystemctlMonitor::get_systemctl_monitor_job(systemctl_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -316,9 +832,9 @@ impl SchedulingService {
      * - If the job fails to be scheduled.
      */
     #[allow(clippy::too_many_arguments)]
-    async fn create_and_schedule_memory_monitor(&mut self, monitor: &crate::common::Monitor, error_percentage_used_mem: Option<f64>, error_percentage_used_swap: Option<f64>, warn_percentage_used_mem: Option<f64>, warn_percentage_used_swap: Option<f64>, store_values: bool, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_memory_monitor(&mut self, monitor: &crate::common::Monitor, error_percentage_used_mem: Option<f64>, error_percentage_used_swap: Option<f64>, warn_percentage_used_mem: Option<f64>, warn_percentage_used_swap: Option<f64>, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let meminfo_monitor = MeminfoMonitor::new(&monitor.name, &monitor.description, error_percentage_used_mem, error_percentage_used_swap, warn_percentage_used_mem, warn_percentage_used_swap, &self.status, &self.database_service.clone(), &monitor.store, store_values);
-        let job = MeminfoMonitor::get_meminfo_monitor_job(meminfo_monitor, monitor.schedule.as_str())?;
+        let job = MeminfoMonitor::get_meminfo_monitor_job(meminfo_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -341,9 +857,9 @@ impl SchedulingService {
      */
     #[allow(clippy::too_many_arguments)]    
     #[allow(clippy::similar_names)]
-    async fn create_and_schedule_loadavg_monitor(&mut self, monitor: &crate::common::Monitor, threshold_1min: Option<f32>, threshold_5min: Option<f32>, threshold_15min: Option<f32>, threshold_1min_level: ThresholdLevel, threshold_5min_level: ThresholdLevel, threshold_15min_level: ThresholdLevel, store_values: bool, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_loadavg_monitor(&mut self, monitor: &crate::common::Monitor, threshold_1min: Option<f32>, threshold_5min: Option<f32>, threshold_15min: Option<f32>, threshold_1min_level: ThresholdLevel, threshold_5min_level: ThresholdLevel, threshold_15min_level: ThresholdLevel, store_values: bool, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let loadavg_monitor = LoadAvgMonitor::new(&monitor.name, &monitor.description, threshold_1min, threshold_5min, threshold_15min, threshold_1min_level, threshold_5min_level, threshold_15min_level, &self.status, &self.database_service.clone(), &monitor.store, store_values);
-        let job = LoadAvgMonitor::get_loadavg_monitor_job(loadavg_monitor, monitor.schedule.as_str())?;
+        let job = LoadAvgMonitor::get_loadavg_monitor_job(loadavg_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -352,8 +868,7 @@ impl SchedulingService {
      * 
      * `monitor`: The monitor configuration.
      * `command`: The command to run.
-     * `args`: The arguments to the command.
-     * `expected`: The expected output of the command.
+     * `options`: The options of the command.
      * `scheduler`: The job scheduler.
      * 
      * `result`: The result of creating and scheduling the command monitor.
@@ -363,9 +878,9 @@ impl SchedulingService {
      * - If the job fails to be added.
      * - If the job fails to be scheduled.
      */
-    async fn create_and_schedule_command_monitor(&mut self, monitor: &crate::common::Monitor, command: String, args: Option<Vec<String>>, expected: Option<String>, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
-        let command_monitor = CommandMonitor::new(&monitor.name, &monitor.description, command.as_str(), args, expected, &self.status, &self.database_service.clone(), &monitor.store);
-        let job = CommandMonitor::get_command_monitor_job(command_monitor, monitor.schedule.as_str())?;
+    async fn create_and_schedule_command_monitor(&mut self, monitor: &crate::common::Monitor, command: String, options: CommandOptions, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let command_monitor = CommandMonitor::new(&monitor.name, &monitor.description, command.as_str(), options, &self.status, &self.database_service.clone(), &monitor.store)?;
+        let job = CommandMonitor::get_command_monitor_job(command_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
 
@@ -383,6 +898,11 @@ impl SchedulingService {
      * `root_certificate`: The root certificate.
      * `identity`: The identity.
      * `identity_password`: The identity password.
+     * `retry`: Number of retries if the request fails.
+     * `warn_latency_ms`: The response time in milliseconds before warning.
+     * `error_latency_ms`: The response time in milliseconds before error.
+     * `store_values`: Store the response times.
+     * `assertions`: The assertions on the response.
      * `scheduler`: The job scheduler.
      * 
      * `result`: The result of creating and scheduling the HTTP monitor.
@@ -393,7 +913,7 @@ impl SchedulingService {
      * - If the job fails to be scheduled.
      */
     #[allow(clippy::too_many_arguments)]
-    async fn create_and_schedule_http_monitor(&mut self, url: String, method: crate::common::HttpMethod, body: Option<String>, headers: Option<HashMap<String, String>>, monitor: &crate::common::Monitor, use_builtin_root_certs: bool, accept_invalid_certs: bool, tls_info: bool, root_certificate: Option<String>, identity: Option<String>, identity_password: Option<String>, retry: Option<u16>, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
+    async fn create_and_schedule_http_monitor(&mut self, url: String, method: crate::common::HttpMethod, body: Option<String>, headers: Option<HashMap<String, String>>, monitor: &crate::common::Monitor, use_builtin_root_certs: bool, accept_invalid_certs: bool, tls_info: bool, root_certificate: Option<String>, identity: Option<String>, identity_password: Option<String>, retry: Option<u16>, warn_latency_ms: Option<u64>, error_latency_ms: Option<u64>, store_values: bool, assertions: &Option<HttpAssertions>, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
         let http_monitor = HttpMonitor::new(
             url.as_str(),
             method,
@@ -408,19 +928,67 @@ impl SchedulingService {
             identity,
             identity_password,
             retry,
+            warn_latency_ms,
+            error_latency_ms,
+            store_values,
+            assertions,
             &self.status,
             &self.database_service.clone(),
             &monitor.store,
         )?;
-        let job = HttpMonitor::get_http_monitor_job(http_monitor, monitor.schedule.as_str())?;
+        let job = HttpMonitor::get_http_monitor_job(http_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
+    /**
+     * Create and schedule a HTTP scenario monitor.
+     * 
+     * `monitor`: The monitor configuration.
+     * `steps`: The requests, run in order.
+     * `use_builtin_root_certs`: Use the builtin root certificates.
+     * `accept_invalid_certs`: Accept invalid certificates.
+     * `tls_info`: Get TLS information.
+     * `root_certificate`: The root certificate.
+     * `identity`: The identity.
+     * `identity_password`: The identity password.
+     * `scheduler`: The job scheduler.
+     * 
+     * `result`: The result of creating and scheduling the HTTP scenario monitor.
+     * 
+     * Errors:
+     * - If the HTTP scenario monitor fails to be created.
+     * - If the job fails to be added.
+     * - If the job fails to be scheduled.
+     */
+    #[allow(clippy::too_many_arguments)]
+    async fn create_and_schedule_http_scenario_monitor(&mut self, monitor: &crate::common::Monitor, steps: &[HttpScenarioStep], use_builtin_root_certs: bool, accept_invalid_certs: bool, tls_info: bool, root_certificate: Option<String>, identity: Option<String>, identity_password: Option<String>, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let http_scenario_monitor = HttpScenarioMonitor::new(
+            &monitor.name,
+            &monitor.description,
+            steps,
+            use_builtin_root_certs,
+            accept_invalid_certs,
+            tls_info,
+            root_certificate,
+            identity,
+            identity_password,
+            &self.status,
+            &self.database_service.clone(),
+            &monitor.store,
+        )?;
+        let job = HttpScenarioMonitor::get_http_scenario_monitor_job(http_scenario_monitor, monitor.schedule.as_str(), monitor.timeout)?;
+        Ok(self.add_job(scheduler, job).await)
+    }
+
     /**
      * Create and schedule a TCP monitor.   
      * 
      * `host`: The host to monitor.
      * `port`: The port to monitor.
+     * `retry`: Number of retries if the connection fails.
+     * `warn_latency_ms`: The response time in milliseconds before warning.
+     * `error_latency_ms`: The response time in milliseconds before error.
+     * `store_values`: Store the response times.
      * `monitor`: The monitor configuration.
      * `scheduler`: The job scheduler.
      * 
@@ -431,9 +999,10 @@ impl SchedulingService {
      * - If the job fails to be added.
      * - If the job fails to be scheduled.
      */
-    async fn create_and_schedule_tcp_monitor(&self, host: String, port: u16, retry: Option<u16>, monitor: &crate::common::Monitor, scheduler: &JobScheduler) -> Result<Result<(), ApplicationError>, ApplicationError> {
-        let tcp_monitor = TcpMonitor::new(host.as_str(), port, retry, &monitor.name, &monitor.description, &self.status.clone(), &self.database_service.clone(), &monitor.store);
-        let job = TcpMonitor::get_tcp_monitor_job(tcp_monitor, monitor.schedule.as_str())?;
+    #[allow(clippy::too_many_arguments)]
+    async fn create_and_schedule_tcp_monitor(&self, host: String, port: u16, retry: Option<u16>, warn_latency_ms: Option<u64>, error_latency_ms: Option<u64>, store_values: bool, monitor: &crate::common::Monitor, scheduler: &JobScheduler) -> Result<Result<Job, ApplicationError>, ApplicationError> {
+        let tcp_monitor = TcpMonitor::new(host.as_str(), port, retry, warn_latency_ms, error_latency_ms, &monitor.name, &monitor.description, &self.status.clone(), &self.database_service.clone(), &monitor.store, store_values);
+        let job = TcpMonitor::get_tcp_monitor_job(tcp_monitor, monitor.schedule.as_str(), monitor.timeout)?;
         Ok(self.add_job(scheduler, job).await)
     }
     
@@ -450,7 +1019,7 @@ impl SchedulingService {
     async fn get_database_service(&self, database_service: &DatabaseServiceType, database_config: &Option<crate::common::DatabaseConfig>) -> Result<DatabaseServiceType, ApplicationError> {
         match database_config {
             Some(database_config) => {
-                let database_service = DbService::new(database_config, &self.server_name).await?;
+                let database_service = DbService::connect(database_config, &self.server_name).await?;
                 Ok(Arc::new(Some(database_service)))
             },
             None => Ok(database_service.clone()),
@@ -467,14 +1036,31 @@ impl SchedulingService {
      *
      * throws: `ApplicationError`: If the job fails to be added.
      */
-    async fn add_job(&self, scheduler: &JobScheduler, job: Job) -> Result<(), ApplicationError> {
-        match scheduler.add(job).await {
-            Ok(_) => Ok(()),
+    async fn add_job(&self, scheduler: &JobScheduler, job: Job) -> Result<Job, ApplicationError> {
+        match scheduler.add(job.clone()).await {
+            Ok(_) => Ok(job),
             Err(err) => Err(ApplicationError::new(
                 format!("Could not add job: {err}").as_str(),
             )),
         }
-    }      
+    }
+
+    /**
+     * Remove a job from the scheduler.
+     *
+     * `scheduler`: The job scheduler.
+     * `job`: The job to remove.
+     *
+     * throws: `ApplicationError`: If the job fails to be removed.
+     */
+    async fn remove_job(scheduler: &JobScheduler, job: &Job) -> Result<(), ApplicationError> {
+        match scheduler.remove(&job.guid()).await {
+            Ok(()) => Ok(()),
+            Err(err) => Err(ApplicationError::new(
+                format!("Could not remove job: {err}").as_str(),
+            )),
+        }
+    }
 
 }
 
@@ -484,7 +1070,7 @@ mod test {
 
     use std::sync::Arc;
 
-    use crate::common::configuration::{DatabaseStoreLevel, ThresholdLevel};
+    use crate::common::configuration::{CommandMode, DatabaseStoreLevel, ThresholdLevel};
 
     use super::*;
 
@@ -604,10 +1190,16 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::Tcp {
                 host: "localhost".to_string(),
                 port: 80,
                 retry: None,
+                warn_latency_ms: None,
+                error_latency_ms: None,
+                store_values: false,
             },
         }, &JobScheduler::new().await.unwrap()).await;
         assert!(res.is_ok())
@@ -622,6 +1214,9 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::Http {
                 url: "http://localhost".to_string(),
                 method: crate::common::HttpMethod::Get,
@@ -633,12 +1228,26 @@ mod test {
                 root_certificate: None,
                 identity: None,
                 identity_password: None,
-                retry: None
+                retry: None,
+                warn_latency_ms: None,
+                error_latency_ms: None,
+                store_values: false,
+                assertions: None,
             },
         }, &JobScheduler::new().await.unwrap()).await;
         assert!(res.is_ok())
     }
 
+    #[tokio::test]
+    async fn test_create_and_add_job_http_scenario_job() {
+        let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitoring_config = Arc::new(MonitoringConfig::new("resources/test/configuration_import_test/test_simple_http_scenario.json").unwrap());
+        let mut scheduling_service = SchedulingService::new("", &monitoring_config, &status, &Arc::new(None));
+        let monitor = monitoring_config.monitors[0].clone();
+        let res = scheduling_service.create_and_add_job(&monitor, &JobScheduler::new().await.unwrap()).await;
+        assert!(res.is_ok())
+    }
+
     #[tokio::test]
     async fn test_create_and_add_job_systemctl_job() {
         let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
@@ -648,6 +1257,9 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::Systemctl { 
                 active: vec!["ssh".to_string()],
             },
@@ -664,10 +1276,24 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::Command {
                 command: "ls".to_string(),
                 args: Some(vec!["-l".to_string()]),
                 expected: Some("total".to_string()),
+                mode: CommandMode::Standard,
+                store_values: false,
+                expected_exit_codes: None,
+                stdout_regex: None,
+                stderr_regex: None,
+                environment: None,
+                working_directory: None,
+                stdin: None,
+                user: None,
+                group: None,
+                max_output_size: 65536,
             },
         }, &JobScheduler::new().await.unwrap()).await;
         assert!(res.is_ok())
@@ -682,6 +1308,9 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::LoadAvg { 
                 threshold_1min: Some(0.0),
                 threshold_5min: Some(0.0),
@@ -704,6 +1333,9 @@ mod test {
             description: None,
             schedule: "* * * * * *".to_string(),
             store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
             details: crate::common::MonitorType::Mem {
                 error_percentage_used_mem: Some(0.0),
                 error_percentage_used_swap: Some(0.0),
@@ -713,7 +1345,265 @@ mod test {
             },
         }, &JobScheduler::new().await.unwrap()).await;
         assert!(res.is_ok())
-    }    
+    }
 
-}
+    #[tokio::test]
+    async fn test_create_and_add_job_disk_job() {
+        let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &Arc::new(MonitoringConfig::new("resources/test/configuration_import_test/test_simple_disk.json").unwrap()), &status, &Arc::new(None));
+        let res = scheduling_service.create_and_add_job(&crate::common::Monitor {
+            name: "test".to_string(),
+            description: None,
+            schedule: "* * * * * *".to_string(),
+            store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
+            details: crate::common::MonitorType::Disk {
+                mounts: None,
+                error_percentage_used_space: Some(90.0),
+                warn_percentage_used_space: Some(80.0),
+                error_percentage_used_inodes: None,
+                warn_percentage_used_inodes: None,
+                store_values: false,
+            },
+        }, &JobScheduler::new().await.unwrap()).await;
+        assert!(res.is_ok())
+    }
+
+    #[tokio::test]
+    async fn test_create_and_add_job_cpu_job() {
+        let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &Arc::new(MonitoringConfig::new("resources/test/configuration_import_test/test_simple_cpu.json").unwrap()), &status, &Arc::new(None));
+        let res = scheduling_service.create_and_add_job(&crate::common::Monitor {
+            name: "test".to_string(),
+            description: None,
+            schedule: "* * * * * *".to_string(),
+            store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
+            details: crate::common::MonitorType::Cpu {
+                error_percentage_total: Some(90.0),
+                warn_percentage_total: Some(80.0),
+                error_percentage_iowait: None,
+                warn_percentage_iowait: None,
+                per_core: false,
+                store_values: false,
+            },
+        }, &JobScheduler::new().await.unwrap()).await;
+        assert!(res.is_ok())
+    }
+
+    #[tokio::test]
+    async fn test_create_and_add_job_network_job() {
+        let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &Arc::new(MonitoringConfig::new("resources/test/configuration_import_test/test_simple_network.json").unwrap()), &status, &Arc::new(None));
+        let res = scheduling_service.create_and_add_job(&crate::common::Monitor {
+            name: "test".to_string(),
+            description: None,
+            schedule: "* * * * * *".to_string(),
+            store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
+            details: crate::common::MonitorType::Network {
+                interfaces: None,
+                error_percentage_saturation: Some(90.0),
+                warn_percentage_saturation: Some(70.0),
+                error_on_errors: true,
+                warn_on_drops: false,
+                store_values: false,
+            },
+        }, &JobScheduler::new().await.unwrap()).await;
+        assert!(res.is_ok())
+    }
+
+    /**
+     * Create a tcp monitor configuration for the reload tests.
+     */
+    fn get_tcp_monitor(name: &str, port: u16) -> crate::common::Monitor {
+        crate::common::Monitor {
+            name: name.to_string(),
+            description: None,
+            schedule: "0 0 0 1 1 *".to_string(),
+            store: DatabaseStoreLevel::None,
+            timeout: None,
+            failure_threshold: None,
+            success_threshold: None,
+            details: crate::common::MonitorType::Tcp {
+                host: "localhost".to_string(),
+                port,
+                retry: None,
+                warn_latency_ms: None,
+                error_latency_ms: None,
+                store_values: false,
+            },
+        }
+    }
+
+    /**
+     * Create a monitoring configuration with the given monitors.
+     */
+    fn get_monitoring_config(monitors: Vec<crate::common::Monitor>) -> Arc<MonitoringConfig> {
+        let mut monitoring_config = MonitoringConfig::new("resources/test/configuration_import_test/test_simple_tcp.json").unwrap();
+        monitoring_config.monitors = monitors;
+        Arc::new(monitoring_config)
+    }
+
+    #[tokio::test]
+    async fn test_reload_not_started() {
+        let status = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("a", 80)]), &status, &Arc::new(None));
+        let res = scheduling_service.reload(&get_monitoring_config(vec![])).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reload_add_remove_change() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("unchanged", 80), get_tcp_monitor("changed", 80), get_tcp_monitor("removed", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        status.lock().unwrap().get_mut("unchanged").unwrap().set_status(&crate::common::Status::Ok);
+
+        let changes = scheduling_service.reload(&get_monitoring_config(vec![get_tcp_monitor("unchanged", 80), get_tcp_monitor("changed", 81), get_tcp_monitor("added", 80)])).await.unwrap();
+        assert_eq!(changes.added, vec!["added".to_string()]);
+        assert_eq!(changes.removed, vec!["removed".to_string()]);
+        assert_eq!(changes.changed, vec!["changed".to_string()]);
+        assert_eq!(scheduling_service.monitor_jobs.len(), 3);
+        assert!(!scheduling_service.monitor_jobs.contains_key("removed"));
+
+        let status = status.lock().unwrap();
+        assert_eq!(status.get("unchanged").unwrap().status, crate::common::Status::Ok);
+        assert_eq!(status.get("changed").unwrap().status, crate::common::Status::Unknown);
+        assert_eq!(status.get("added").unwrap().status, crate::common::Status::Unknown);
+        assert!(status.get("removed").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reload_invalid_schedule_keeps_running_configuration() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitoring_config = get_monitoring_config(vec![get_tcp_monitor("a", 80), get_tcp_monitor("b", 80)]);
+        let mut scheduling_service = SchedulingService::new("", &monitoring_config, &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        status.lock().unwrap().get_mut("b").unwrap().set_status(&crate::common::Status::Ok);
+
+        let mut invalid_monitor = get_tcp_monitor("c", 80);
+        invalid_monitor.schedule = "invalid".to_string();
+        let res = scheduling_service.reload(&get_monitoring_config(vec![get_tcp_monitor("a", 80), get_tcp_monitor("b", 81), invalid_monitor])).await;
+        assert!(res.is_err());
+        assert_eq!(scheduling_service.monitoring_config, monitoring_config);
+        assert_eq!(scheduling_service.monitor_jobs.get("b").unwrap().0, get_tcp_monitor("b", 80));
+        assert!(!scheduling_service.monitor_jobs.contains_key("c"));
+        let status = status.lock().unwrap();
+        assert_eq!(status.len(), 2);
+        assert_eq!(status.get("b").unwrap().status, crate::common::Status::Ok);
+    }
+
+    /**
+     * Get a monitoring config with a notification config.
+     * 
+     * `monitors`: The monitors.
+     * `schedule`: The schedule of the notification job.
+     */
+    fn get_notification_monitoring_config(monitors: Vec<crate::common::Monitor>, schedule: &str) -> Arc<MonitoringConfig> {
+        let mut monitoring_config = (*get_monitoring_config(monitors)).clone();
+        monitoring_config.notification_config = Some(serde_json::from_value(serde_json::json!({ "schedule": schedule })).unwrap());
+        Arc::new(monitoring_config)
+    }
 
+    #[tokio::test]
+    async fn test_reload_replaces_notification_job() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("a", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        assert!(scheduling_service.notification_job.is_none());
+
+        let changes = scheduling_service.reload(&get_notification_monitoring_config(vec![get_tcp_monitor("a", 80)], "0 * * * * *")).await.unwrap();
+        assert!(changes.errors.is_empty());
+        let notification_job = scheduling_service.notification_job.clone().unwrap().1;
+
+        scheduling_service.reload(&get_notification_monitoring_config(vec![get_tcp_monitor("a", 80)], "30 * * * * *")).await.unwrap();
+        assert_ne!(scheduling_service.notification_job.clone().unwrap().1.guid(), notification_job.guid());
+        assert_eq!(scheduling_service.get_running_config().read().unwrap().notification_config, scheduling_service.monitoring_config.notification_config);
+    }
+
+    #[tokio::test]
+    async fn test_reload_invalid_notification_keeps_running_configuration() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let monitoring_config = get_notification_monitoring_config(vec![get_tcp_monitor("a", 80), get_tcp_monitor("b", 80)], "0 * * * * *");
+        let mut scheduling_service = SchedulingService::new("", &monitoring_config, &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        let notification_job = scheduling_service.notification_job.clone().unwrap().1;
+
+        let res = scheduling_service.reload(&get_notification_monitoring_config(vec![get_tcp_monitor("b", 81), get_tcp_monitor("c", 80)], "invalid")).await;
+        assert!(res.is_err());
+        assert_eq!(scheduling_service.monitoring_config, monitoring_config);
+        assert_eq!(*scheduling_service.get_running_config().read().unwrap(), monitoring_config);
+        assert_eq!(scheduling_service.notification_job.clone().unwrap().1.guid(), notification_job.guid());
+        assert!(scheduling_service.monitor_jobs.contains_key("a"));
+        assert_eq!(scheduling_service.monitor_jobs.get("b").unwrap().0, get_tcp_monitor("b", 80));
+        assert!(!scheduling_service.monitor_jobs.contains_key("c"));
+        assert_eq!(status.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_duplicate_names() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("a", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        let res = scheduling_service.reload(&get_monitoring_config(vec![get_tcp_monitor("a", 80), get_tcp_monitor("a", 81)])).await;
+        assert!(res.is_err());
+        assert_eq!(scheduling_service.monitor_jobs.get("a").unwrap().0, get_tcp_monitor("a", 80));
+    }
+
+    #[tokio::test]
+    async fn test_reload_from_file_invalid() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("a", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        let res = scheduling_service.reload_from_file("resources/test/configuration_import_test/test_simple_unknown.json").await;
+        assert!(res.is_err());
+        assert!(scheduling_service.monitor_jobs.contains_key("a"));
+    }
+
+    #[tokio::test]
+    async fn test_add_update_remove_monitor() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![get_tcp_monitor("a", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+
+        let changes = scheduling_service.add_monitor(&get_tcp_monitor("b", 80)).await.unwrap();
+        assert_eq!(changes.added, vec!["b".to_string()]);
+        assert!(scheduling_service.add_monitor(&get_tcp_monitor("b", 81)).await.is_err());
+        assert_eq!(scheduling_service.get_monitor("b"), Some(get_tcp_monitor("b", 80)));
+
+        let changes = scheduling_service.update_monitor(&get_tcp_monitor("b", 81)).await.unwrap();
+        assert_eq!(changes.changed, vec!["b".to_string()]);
+        assert_eq!(scheduling_service.get_monitor("b"), Some(get_tcp_monitor("b", 81)));
+        assert!(scheduling_service.update_monitor(&get_tcp_monitor("c", 81)).await.is_err());
+
+        let changes = scheduling_service.remove_monitor("b").await.unwrap();
+        assert_eq!(changes.removed, vec!["b".to_string()]);
+        assert_eq!(scheduling_service.get_monitor("b"), None);
+        assert!(scheduling_service.remove_monitor("b").await.is_err());
+        assert!(status.lock().unwrap().get("b").is_none());
+        assert!(scheduling_service.get_monitor("a").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_status_thresholds() {
+        let status: MonitorStatusType = std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let mut monitor = get_tcp_monitor("a", 80);
+        monitor.failure_threshold = Some(3);
+        monitor.success_threshold = Some(2);
+        let mut scheduling_service = SchedulingService::new("", &get_monitoring_config(vec![monitor, get_tcp_monitor("b", 80)]), &status, &Arc::new(None));
+        scheduling_service.start(false).await.unwrap();
+        let status_lock = status.lock().unwrap();
+        assert_eq!(status_lock.get("a").unwrap().failure_threshold, 3);
+        assert_eq!(status_lock.get("a").unwrap().success_threshold, 2);
+        assert_eq!(status_lock.get("b").unwrap().failure_threshold, 1);
+        assert_eq!(status_lock.get("b").unwrap().success_threshold, 1);
+    }
+
+}
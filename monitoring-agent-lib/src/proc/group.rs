@@ -101,18 +101,18 @@ mod tests {
     #[test]
     fn test_group() {
         let groups = super::Group::get_groups().unwrap();
-        assert!(groups.len() > 0);
+        assert!(!groups.is_empty());
     }
 
     #[test]
     fn test_group_map() {
         let groups = super::Group::get_groups_map().unwrap();
-        assert!(groups.len() > 0);
+        assert!(!groups.is_empty());
     }  
 
     #[test]
     fn read_groups_file() {
         let groups = super::Group::read_groups("resources/test/test_group").unwrap();
-        assert!(groups.len() > 0);
+        assert!(!groups.is_empty());
     }
 }   
\ No newline at end of file
@@ -101,7 +101,7 @@ impl ProcsLoadavg {
             },
             Err(err) => {
                 error!("Error reading meminfo: {err:?}");
-                return Err(CommonLibError::new(format!("Error reading meminfo: {err:?}").as_str()));
+                Err(CommonLibError::new(format!("Error reading meminfo: {err:?}").as_str()))
             }
         }
     }
@@ -141,7 +141,7 @@ fn handle_loadavg_file(file: File) -> Result<ProcsLoadavg, CommonLibError> {
         },
         Err(err) => {
             error!("Error reading meminfo: {err:?}");
-            return Err(CommonLibError::new(format!("Error reading meminfo: {err:?}").as_str()));
+            Err(CommonLibError::new(format!("Error reading meminfo: {err:?}").as_str()))
         }
     }
 }
@@ -161,9 +161,9 @@ mod test {
         fn test_read_predefined_cpuinfo() {
             let binding = ProcsLoadavg::read_loadavg("resources/test/test_loadavg").unwrap();
             assert_eq!(&binding.loadavg1min.unwrap(), &0.59);
-            assert_eq!(&binding.loadavg5min.clone().unwrap(), &0.63);
-            assert_eq!(&binding.loadavg15min.clone().unwrap(), &0.32);
-            assert_eq!(&binding.current_running_processes.clone().unwrap(), &1);
-            assert_eq!(&binding.total_number_of_processes.clone().unwrap(), &1419);
+            assert_eq!(&binding.loadavg5min.unwrap(), &0.63);
+            assert_eq!(&binding.loadavg15min.unwrap(), &0.32);
+            assert_eq!(&binding.current_running_processes.unwrap(), &1);
+            assert_eq!(&binding.total_number_of_processes.unwrap(), &1419);
         }
 }
\ No newline at end of file
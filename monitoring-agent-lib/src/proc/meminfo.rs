@@ -2,6 +2,7 @@ use std::{collections::HashMap, fs::File, io::{BufRead, BufReader}};
 use std::str::FromStr;
 
 use log::error;
+use serde::{Deserialize, Serialize};
 
 use crate::common::CommonLibError;
 
@@ -9,7 +10,7 @@ use crate::common::CommonLibError;
  * Memory information from /cat/meminfo
  */
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcsMeminfo {
     /// The total memory.
     pub memtotal: Option<u64>,
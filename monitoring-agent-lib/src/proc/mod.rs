@@ -16,6 +16,16 @@ pub mod stat;
 pub mod group;
 /// Structure and methods to read and parse /etc/passwd
 pub mod user;
+/// Structure and methods to read and parse /proc/mounts and get disk usage
+pub mod mounts;
+/// Structure and methods to read and parse /proc/net/dev
+pub mod netdev;
+/// Structure and methods to read and parse /proc/*/stat
+pub mod processstat;
+/// Structure and methods to read and parse /proc/*/io
+pub mod processio;
+/// Open file descriptors and their limit from /proc/*/fd and /proc/*/limits
+pub mod processfd;
 
 pub use crate::proc::cpuinfo::ProcsCpuinfo;
 pub use crate::proc::meminfo::ProcsMeminfo;
@@ -24,6 +34,11 @@ pub use crate::proc::process::ProcsProcess;
 pub use crate::proc::statm::ProcsStatm;
 pub use crate::proc::cmdline::ProcsCmdLine;
 #[allow(clippy::module_name_repetitions)]
-pub use crate::proc::stat::{ ProcStat, ProcCpuStat };
+pub use crate::proc::stat::{ ProcStat, ProcCpuStat, ProcCpuUsage };
 pub use crate::proc::group::Group;
-pub use crate::proc::user::User;
\ No newline at end of file
+pub use crate::proc::user::User;
+pub use crate::proc::mounts::{ProcsMount, ProcsDiskUsage};
+pub use crate::proc::netdev::{ProcsNetDev, ProcsNetDevRate};
+pub use crate::proc::processstat::ProcsProcessStat;
+pub use crate::proc::processio::{ProcsProcessIo, ProcsProcessIoRate};
+pub use crate::proc::processfd::ProcsProcessFd;
\ No newline at end of file
@@ -0,0 +1,414 @@
+use std::{ffi::CString, fs::File, io::{BufRead, BufReader}, mem::MaybeUninit};
+
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+
+use crate::common::CommonLibError;
+
+/**
+ * Filesystem types that do not represent disk storage. These are skipped when mounts are discovered.
+ */
+const PSEUDO_FILESYSTEMS: [&str; 26] = [
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts", "devtmpfs", "efivarfs",
+    "fusectl", "hugetlbfs", "mqueue", "nsfs", "overlay", "proc", "pstore", "ramfs", "rpc_pipefs", "securityfs",
+    "selinuxfs", "squashfs", "sysfs", "tmpfs", "tracefs", "fuse.gvfsd-fuse",
+];
+
+/**
+ * Mount information from /proc/mounts
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsMount {
+    /// The mounted device.
+    pub device: String,
+    /// The mount point.
+    pub mount_point: String,
+    /// The filesystem type.
+    pub fs_type: String,
+    /// The mount options.
+    pub options: Vec<String>,
+}
+
+impl ProcsMount {
+
+    /**
+     * Create a new `ProcsMount`.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsMount;
+     * ProcsMount::new("/dev/sda1", "/", "ext4", vec!["rw".to_string(), "relatime".to_string()]);
+     * ```
+     *
+     * `device`: The mounted device.
+     * `mount_point`: The mount point.
+     * `fs_type`: The filesystem type.
+     * `options`: The mount options.
+     *
+     * Returns a new `ProcsMount`.
+     */
+    #[must_use] pub fn new(device: &str, mount_point: &str, fs_type: &str, options: Vec<String>) -> ProcsMount {
+        ProcsMount {
+            device: device.to_string(),
+            mount_point: mount_point.to_string(),
+            fs_type: fs_type.to_string(),
+            options,
+        }
+    }
+
+    /**
+     * Get the mounts of the system.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsMount;
+     * ProcsMount::get_mounts();
+     * ```
+     *
+     * Returns the mounts or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the mounts file.
+     *  - If there is an error reading a line from the mounts file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_mounts() -> Result<Vec<ProcsMount>, CommonLibError> {
+        let mounts_file = "/proc/mounts";
+        ProcsMount::read_mounts(mounts_file)
+    }
+
+    /**
+     * Get the mounts of the system that represent disk storage.
+     * Pseudo filesystems like proc, sysfs and tmpfs are skipped, and a mount point is only returned once.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsMount;
+     * ProcsMount::get_disk_mounts();
+     * ```
+     *
+     * Returns the mounts or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the mounts file.
+     *  - If there is an error reading a line from the mounts file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_disk_mounts() -> Result<Vec<ProcsMount>, CommonLibError> {
+        Ok(ProcsMount::filter_disk_mounts(ProcsMount::get_mounts()?))
+    }
+
+    /**
+     * Check if the mount represents disk storage.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsMount;
+     * assert!(ProcsMount::new("/dev/sda1", "/", "ext4", vec![]).is_disk());
+     * assert!(!ProcsMount::new("proc", "/proc", "proc", vec![]).is_disk());
+     * ```
+     *
+     * Returns true if the filesystem type is not a pseudo filesystem.
+     */
+    #[must_use] pub fn is_disk(&self) -> bool {
+        !PSEUDO_FILESYSTEMS.contains(&self.fs_type.as_str())
+    }
+
+    /**
+     * Remove pseudo filesystems and duplicate mount points.
+     *
+     * `mounts`: The mounts to filter.
+     *
+     * Returns the filtered mounts.
+     */
+    fn filter_disk_mounts(mounts: Vec<ProcsMount>) -> Vec<ProcsMount> {
+        let mut disk_mounts: Vec<ProcsMount> = Vec::new();
+        for mount in mounts {
+            if mount.is_disk() && !disk_mounts.iter().any(|disk_mount| disk_mount.mount_point == mount.mount_point) {
+                disk_mounts.push(mount);
+            }
+        }
+        disk_mounts
+    }
+
+    /**
+     * Read the mounts file.
+     *
+     * `file`: The file to read.
+     *
+     * Returns the mounts or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the mounts file.
+     *  - If there is an error reading a line from the mounts file.
+     */
+    fn read_mounts(file: &str) -> Result<Vec<ProcsMount>, CommonLibError> {
+        let mounts_file = File::open(file).map_err(|err| {
+            error!("Error reading mounts: {err:?}");
+            CommonLibError::new(format!("Error reading mounts: {err:?}").as_str())
+        })?;
+        let reader = BufReader::new(mounts_file);
+        let mut mounts = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|err| CommonLibError::new(format!("Error reading line: {err:?}").as_str()))?;
+            if let Some(mount) = ProcsMount::parse_line(&line) {
+                mounts.push(mount);
+            }
+        }
+        Ok(mounts)
+    }
+
+    /**
+     * Parse a line from the mounts file.
+     *
+     * `line`: The line to parse.
+     *
+     * Returns the mount or None if the line is not valid.
+     */
+    fn parse_line(line: &str) -> Option<ProcsMount> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 {
+            return None;
+        }
+        Some(ProcsMount::new(
+            &ProcsMount::unescape(parts[0]),
+            &ProcsMount::unescape(parts[1]),
+            parts[2],
+            parts[3].split(',').map(std::string::ToString::to_string).collect(),
+        ))
+    }
+
+    /**
+     * Replace the octal escapes used for space, tab, newline and backslash in the mounts file.
+     *
+     * `value`: The value to unescape.
+     *
+     * Returns the unescaped value.
+     */
+    fn unescape(value: &str) -> String {
+        value.replace("\\040", " ").replace("\\011", "\t").replace("\\012", "\n").replace("\\134", "\\")
+    }
+}
+
+/**
+ * Disk usage of a mounted filesystem from statvfs.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsDiskUsage {
+    /// The mount point.
+    pub mount_point: String,
+    /// The total size in bytes.
+    pub total_bytes: u64,
+    /// The free bytes available to unprivileged users.
+    pub available_bytes: u64,
+    /// The free bytes.
+    pub free_bytes: u64,
+    /// The total number of inodes.
+    pub total_inodes: u64,
+    /// The number of free inodes.
+    pub free_inodes: u64,
+}
+
+impl ProcsDiskUsage {
+
+    /**
+     * Create a new `ProcsDiskUsage`.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsDiskUsage;
+     * ProcsDiskUsage::new("/", 1000, 200, 250, 100, 50);
+     * ```
+     *
+     * `mount_point`: The mount point.
+     * `total_bytes`: The total size in bytes.
+     * `available_bytes`: The free bytes available to unprivileged users.
+     * `free_bytes`: The free bytes.
+     * `total_inodes`: The total number of inodes.
+     * `free_inodes`: The number of free inodes.
+     *
+     * Returns a new `ProcsDiskUsage`.
+     */
+    #[must_use] pub fn new(mount_point: &str, total_bytes: u64, available_bytes: u64, free_bytes: u64, total_inodes: u64, free_inodes: u64) -> ProcsDiskUsage {
+        ProcsDiskUsage {
+            mount_point: mount_point.to_string(),
+            total_bytes,
+            available_bytes,
+            free_bytes,
+            total_inodes,
+            free_inodes,
+        }
+    }
+
+    /**
+     * Get the disk usage of a mount point using statvfs.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsDiskUsage;
+     * ProcsDiskUsage::get_disk_usage("/");
+     * ```
+     *
+     * `mount_point`: The mount point.
+     *
+     * Returns the disk usage or an error.
+     *
+     * # Errors
+     *  - If the mount point contains a nul character.
+     *  - If statvfs fails.
+     */
+    #[allow(clippy::useless_conversion)] // The statvfs fields are 32 bit on some targets.
+    #[tracing::instrument(level = "debug")]
+    pub fn get_disk_usage(mount_point: &str) -> Result<ProcsDiskUsage, CommonLibError> {
+        let path = CString::new(mount_point)
+            .map_err(|err| CommonLibError::new(format!("Invalid mount point {mount_point}: {err:?}").as_str()))?;
+        let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+        /*
+         * Safety: The path is a valid nul terminated string and stat points to memory large enough for statvfs.
+         */
+        let result = unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) };
+        if result != 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(CommonLibError::new(format!("Error getting disk usage for {mount_point}: {err:?}").as_str()));
+        }
+        /*
+         * Safety: statvfs returned success so the struct is initialized.
+         */
+        let stat = unsafe { stat.assume_init() };
+        let fragment_size = u64::from(stat.f_frsize);
+        Ok(ProcsDiskUsage::new(
+            mount_point,
+            u64::from(stat.f_blocks) * fragment_size,
+            u64::from(stat.f_bavail) * fragment_size,
+            u64::from(stat.f_bfree) * fragment_size,
+            u64::from(stat.f_files),
+            u64::from(stat.f_ffree),
+        ))
+    }
+
+    /**
+     * Get the disk usage of the mounted disks. Pseudo filesystems, filesystems without size and
+     * mount points that cannot be read are skipped.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsDiskUsage;
+     * ProcsDiskUsage::get_disks_usage();
+     * ```
+     *
+     * Returns the disk usage or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the mounts file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_disks_usage() -> Result<Vec<ProcsDiskUsage>, CommonLibError> {
+        let mut disks_usage = Vec::new();
+        for mount in ProcsMount::get_disk_mounts()? {
+            match ProcsDiskUsage::get_disk_usage(&mount.mount_point) {
+                Ok(usage) if usage.total_bytes > 0 => disks_usage.push(usage),
+                Ok(_) => {}
+                Err(err) => {
+                    debug!("Skipping mount {}: {}", mount.mount_point, err.message);
+                }
+            }
+        }
+        Ok(disks_usage)
+    }
+
+    /**
+     * Get the percentage of the space used. The space reserved for root is counted as used, same as df.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsDiskUsage;
+     * let usage = ProcsDiskUsage::new("/", 1000, 250, 250, 100, 50);
+     * assert_eq!(usage.get_percent_space_used(), Some(75f64));
+     * ```
+     *
+     * Returns the percentage or None if the size is zero.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use] pub fn get_percent_space_used(&self) -> Option<f64> {
+        let used = self.total_bytes.saturating_sub(self.free_bytes);
+        let usable = used + self.available_bytes;
+        if usable == 0 {
+            return None;
+        }
+        Some((used as f64 / usable as f64) * 100f64)
+    }
+
+    /**
+     * Get the percentage of the inodes used.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::mounts::ProcsDiskUsage;
+     * let usage = ProcsDiskUsage::new("/", 1000, 250, 250, 100, 50);
+     * assert_eq!(usage.get_percent_inodes_used(), Some(50f64));
+     * ```
+     *
+     * Returns the percentage or None if the filesystem has no inodes.
+     */
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use] pub fn get_percent_inodes_used(&self) -> Option<f64> {
+        if self.total_inodes == 0 {
+            return None;
+        }
+        Some(100f64 - ((self.free_inodes as f64 / self.total_inodes as f64) * 100f64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        let mounts = ProcsMount::get_mounts();
+        assert!(mounts.is_ok());
+        assert!(!mounts.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_predefined_mounts() {
+        let mounts = ProcsMount::read_mounts("resources/test/test_mounts").unwrap();
+        assert_eq!(mounts.len(), 7);
+        assert_eq!(mounts[0], ProcsMount::new("sysfs", "/sys", "sysfs", vec!["rw".to_string(), "nosuid".to_string(), "nodev".to_string(), "noexec".to_string(), "relatime".to_string()]));
+        assert_eq!(mounts[2].device, "/dev/nvme0n1p2");
+        assert_eq!(mounts[2].mount_point, "/");
+        assert_eq!(mounts[2].fs_type, "ext4");
+        assert_eq!(mounts[5].mount_point, "/media/usb disk");
+    }
+
+    #[test]
+    fn test_filter_disk_mounts() {
+        let mounts = ProcsMount::read_mounts("resources/test/test_mounts").unwrap();
+        let mounts = ProcsMount::filter_disk_mounts(mounts);
+        let mount_points: Vec<&str> = mounts.iter().map(|mount| mount.mount_point.as_str()).collect();
+        assert_eq!(mount_points, vec!["/", "/boot/efi", "/media/usb disk"]);
+    }
+
+    #[test]
+    fn test_parse_line_invalid() {
+        assert_eq!(ProcsMount::parse_line("invalid line"), None);
+    }
+
+    #[test]
+    fn test_get_disk_usage() {
+        let usage = ProcsDiskUsage::get_disk_usage("/").unwrap();
+        assert_eq!(usage.mount_point, "/");
+        assert!(usage.total_bytes >= usage.free_bytes);
+        assert!(ProcsDiskUsage::get_disk_usage("/non/existing/path").is_err());
+    }
+
+    #[test]
+    fn test_get_disks_usage() {
+        let disks_usage = ProcsDiskUsage::get_disks_usage().unwrap();
+        assert!(disks_usage.iter().all(|usage| usage.total_bytes > 0));
+    }
+
+    #[test]
+    fn test_get_percent_used() {
+        let usage = ProcsDiskUsage::new("/", 0, 0, 0, 0, 0);
+        assert_eq!(usage.get_percent_space_used(), None);
+        assert_eq!(usage.get_percent_inodes_used(), None);
+        let usage = ProcsDiskUsage::new("/", 1000, 200, 250, 100, 75);
+        assert_eq!(usage.get_percent_space_used(), Some(78.94736842105263));
+        assert_eq!(usage.get_percent_inodes_used(), Some(25f64));
+    }
+}
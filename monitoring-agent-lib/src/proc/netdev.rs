@@ -0,0 +1,375 @@
+use std::{fs::File, io::{BufRead, BufReader}};
+use std::str::FromStr;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::common::CommonLibError;
+
+/**
+ * Network interface statistics from /proc/net/dev
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsNetDev {
+    /// The name of the interface.
+    pub interface: String,
+    /// Bytes received.
+    pub rx_bytes: u64,
+    /// Packets received.
+    pub rx_packets: u64,
+    /// Receive errors.
+    pub rx_errors: u64,
+    /// Received packets dropped.
+    pub rx_drop: u64,
+    /// Bytes transmitted.
+    pub tx_bytes: u64,
+    /// Packets transmitted.
+    pub tx_packets: u64,
+    /// Transmit errors.
+    pub tx_errors: u64,
+    /// Transmitted packets dropped.
+    pub tx_drop: u64,
+    /// The link speed in Mbit/s from /sys/class/net. None if the interface does not report a speed.
+    pub speed: Option<u64>,
+}
+
+impl ProcsNetDev {
+
+    /**
+     * Create a new `ProcsNetDev`.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::ProcsNetDev;
+     * ProcsNetDev::new("eth0", 1000, 10, 0, 0, 2000, 20, 0, 0, Some(1000));
+     * ```
+     *
+     * `interface`: The name of the interface.
+     * `rx_bytes`: Bytes received.
+     * `rx_packets`: Packets received.
+     * `rx_errors`: Receive errors.
+     * `rx_drop`: Received packets dropped.
+     * `tx_bytes`: Bytes transmitted.
+     * `tx_packets`: Packets transmitted.
+     * `tx_errors`: Transmit errors.
+     * `tx_drop`: Transmitted packets dropped.
+     * `speed`: The link speed in Mbit/s.
+     *
+     * Returns a new `ProcsNetDev`.
+     */
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(interface: &str,
+                rx_bytes: u64,
+                rx_packets: u64,
+                rx_errors: u64,
+                rx_drop: u64,
+                tx_bytes: u64,
+                tx_packets: u64,
+                tx_errors: u64,
+                tx_drop: u64,
+                speed: Option<u64>) -> ProcsNetDev {
+        ProcsNetDev {
+            interface: interface.to_string(),
+            rx_bytes,
+            rx_packets,
+            rx_errors,
+            rx_drop,
+            tx_bytes,
+            tx_packets,
+            tx_errors,
+            tx_drop,
+            speed,
+        }
+    }
+
+    /**
+     * Get the statistics of the network interfaces. The link speed is read from /sys/class/net.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::ProcsNetDev;
+     * ProcsNetDev::get_netdev();
+     * ```
+     *
+     * Returns the interface statistics or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the net dev file.
+     *  - If there is an error reading a line from the net dev file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_netdev() -> Result<Vec<ProcsNetDev>, CommonLibError> {
+        let netdev_file = "/proc/net/dev";
+        let mut netdevs = ProcsNetDev::read_netdev(netdev_file)?;
+        for netdev in &mut netdevs {
+            netdev.speed = ProcsNetDev::get_link_speed(&netdev.interface);
+        }
+        Ok(netdevs)
+    }
+
+    /**
+     * Get the link speed of an interface.
+     *
+     * `interface`: The name of the interface.
+     *
+     * Returns the link speed in Mbit/s or None if the interface does not report a speed.
+     */
+    fn get_link_speed(interface: &str) -> Option<u64> {
+        std::fs::read_to_string(format!("/sys/class/net/{interface}/speed"))
+            .ok()
+            .and_then(|speed| u64::from_str(speed.trim()).ok())
+            .filter(|speed| *speed > 0)
+    }
+
+    /**
+     * Read the net dev file.
+     *
+     * `file`: The file to read.
+     *
+     * Returns the interface statistics or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the net dev file.
+     *  - If there is an error reading a line from the net dev file.
+     */
+    fn read_netdev(file: &str) -> Result<Vec<ProcsNetDev>, CommonLibError> {
+        let netdev_file = File::open(file).map_err(|err| {
+            error!("Error reading net dev: {err:?}");
+            CommonLibError::new(format!("Error reading net dev: {err:?}").as_str())
+        })?;
+        let reader = BufReader::new(netdev_file);
+        let mut netdevs = Vec::new();
+        for line in reader.lines() {
+            let line = line.map_err(|err| CommonLibError::new(format!("Error reading line: {err:?}").as_str()))?;
+            if let Some(netdev) = ProcsNetDev::parse_line(&line) {
+                netdevs.push(netdev);
+            }
+        }
+        Ok(netdevs)
+    }
+
+    /**
+     * Parse a line from the net dev file. The two header lines have no interface name and are skipped.
+     *
+     * `line`: The line to parse.
+     *
+     * Returns the interface statistics or None if the line is not valid.
+     */
+    fn parse_line(line: &str) -> Option<ProcsNetDev> {
+        let (interface, values) = line.split_once(':')?;
+        let values: Vec<u64> = values.split_whitespace().map(u64::from_str).collect::<Result<Vec<u64>, _>>().ok()?;
+        if values.len() < 16 {
+            return None;
+        }
+        Some(ProcsNetDev::new(
+            interface.trim(),
+            values[0],
+            values[1],
+            values[2],
+            values[3],
+            values[8],
+            values[9],
+            values[10],
+            values[11],
+            None,
+        ))
+    }
+}
+
+/**
+ * Network interface throughput computed from two /proc/net/dev samples.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsNetDevRate {
+    /// The name of the interface.
+    pub interface: String,
+    /// Bytes received per second.
+    pub rx_bytes_per_second: f64,
+    /// Bytes transmitted per second.
+    pub tx_bytes_per_second: f64,
+    /// Receive errors between the samples.
+    pub rx_errors: u64,
+    /// Transmit errors between the samples.
+    pub tx_errors: u64,
+    /// Received packets dropped between the samples.
+    pub rx_drop: u64,
+    /// Transmitted packets dropped between the samples.
+    pub tx_drop: u64,
+    /// The link speed in Mbit/s.
+    pub speed: Option<u64>,
+}
+
+impl ProcsNetDevRate {
+
+    /**
+     * Create a new `ProcsNetDevRate`.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::ProcsNetDevRate;
+     * ProcsNetDevRate::new("eth0", 1000.0, 2000.0, 0, 0, 0, 0, Some(1000));
+     * ```
+     *
+     * `interface`: The name of the interface.
+     * `rx_bytes_per_second`: Bytes received per second.
+     * `tx_bytes_per_second`: Bytes transmitted per second.
+     * `rx_errors`: Receive errors between the samples.
+     * `tx_errors`: Transmit errors between the samples.
+     * `rx_drop`: Received packets dropped between the samples.
+     * `tx_drop`: Transmitted packets dropped between the samples.
+     * `speed`: The link speed in Mbit/s.
+     *
+     * Returns a new `ProcsNetDevRate`.
+     */
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(interface: &str,
+                rx_bytes_per_second: f64,
+                tx_bytes_per_second: f64,
+                rx_errors: u64,
+                tx_errors: u64,
+                rx_drop: u64,
+                tx_drop: u64,
+                speed: Option<u64>) -> ProcsNetDevRate {
+        ProcsNetDevRate {
+            interface: interface.to_string(),
+            rx_bytes_per_second,
+            tx_bytes_per_second,
+            rx_errors,
+            tx_errors,
+            rx_drop,
+            tx_drop,
+            speed,
+        }
+    }
+
+    /**
+     * Compute the throughput of an interface between two samples.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::{ProcsNetDev, ProcsNetDevRate};
+     * let previous = ProcsNetDev::new("eth0", 0, 0, 0, 0, 0, 0, 0, 0, None);
+     * let current = ProcsNetDev::new("eth0", 2000, 10, 1, 0, 4000, 20, 0, 0, None);
+     * let rate = ProcsNetDevRate::from_netdev(&previous, &current, 2.0).unwrap();
+     * assert_eq!(rate.rx_bytes_per_second, 1000.0);
+     * ```
+     *
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * `elapsed_seconds`: The seconds between the samples.
+     *
+     * Returns the throughput or None if no time has passed or the counters were reset.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_netdev(previous: &ProcsNetDev, current: &ProcsNetDev, elapsed_seconds: f64) -> Option<ProcsNetDevRate> {
+        if elapsed_seconds <= 0.0 || current.rx_bytes < previous.rx_bytes || current.tx_bytes < previous.tx_bytes {
+            return None;
+        }
+        Some(ProcsNetDevRate::new(
+            &current.interface,
+            (current.rx_bytes - previous.rx_bytes) as f64 / elapsed_seconds,
+            (current.tx_bytes - previous.tx_bytes) as f64 / elapsed_seconds,
+            current.rx_errors.saturating_sub(previous.rx_errors),
+            current.tx_errors.saturating_sub(previous.tx_errors),
+            current.rx_drop.saturating_sub(previous.rx_drop),
+            current.tx_drop.saturating_sub(previous.tx_drop),
+            current.speed,
+        ))
+    }
+
+    /**
+     * Compute the throughput of all interfaces between two samples. Interfaces are matched by name,
+     * interfaces only found in one of the samples are skipped.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::{ProcsNetDev, ProcsNetDevRate};
+     * let previous = ProcsNetDev::get_netdev().unwrap();
+     * let current = ProcsNetDev::get_netdev().unwrap();
+     * ProcsNetDevRate::from_netdevs(&previous, &current, 1.0);
+     * ```
+     *
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * `elapsed_seconds`: The seconds between the samples.
+     *
+     * Returns the throughput of the interfaces.
+     */
+    #[must_use]
+    pub fn from_netdevs(previous: &[ProcsNetDev], current: &[ProcsNetDev], elapsed_seconds: f64) -> Vec<ProcsNetDevRate> {
+        current.iter().filter_map(|current_netdev| {
+            previous.iter()
+                .find(|previous_netdev| previous_netdev.interface == current_netdev.interface)
+                .and_then(|previous_netdev| ProcsNetDevRate::from_netdev(previous_netdev, current_netdev, elapsed_seconds))
+        }).collect()
+    }
+
+    /**
+     * Get the percentage of the link speed used in the busiest direction.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::netdev::ProcsNetDevRate;
+     * let rate = ProcsNetDevRate::new("eth0", 1_250_000.0, 12_500_000.0, 0, 0, 0, 0, Some(1000));
+     * assert_eq!(rate.get_percent_saturation(), Some(10.0));
+     * ```
+     *
+     * Returns the percentage or None if the link speed is unknown.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_percent_saturation(&self) -> Option<f64> {
+        let speed = self.speed.filter(|speed| *speed > 0)?;
+        let bits_per_second = self.rx_bytes_per_second.max(self.tx_bytes_per_second) * 8.0;
+        Some(bits_per_second * 100.0 / (speed as f64 * 1_000_000.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        let netdevs = ProcsNetDev::get_netdev().unwrap();
+        assert!(netdevs.iter().any(|netdev| netdev.interface == "lo"));
+    }
+
+    #[test]
+    fn test_read_predefined_netdev() {
+        let netdevs = ProcsNetDev::read_netdev("resources/test/test_netdev").unwrap();
+        assert_eq!(netdevs.len(), 3);
+        assert_eq!(netdevs[0], ProcsNetDev::new("lo", 60262802, 6775, 0, 0, 60262802, 6775, 0, 0, None));
+        assert_eq!(netdevs[1], ProcsNetDev::new("eth0", 32659835, 2539, 3, 7, 184275, 2163, 1, 2, None));
+        assert_eq!(netdevs[2].interface, "wlan0");
+        assert_eq!(netdevs[2].rx_bytes, 1215645);
+    }
+
+    #[test]
+    fn test_parse_line_invalid() {
+        assert_eq!(ProcsNetDev::parse_line("Inter-|   Receive                                                |  Transmit"), None);
+        assert_eq!(ProcsNetDev::parse_line("  eth0: 1 2 3"), None);
+    }
+
+    #[test]
+    fn test_from_netdevs() {
+        let previous = vec![
+            ProcsNetDev::new("eth0", 1000, 10, 1, 1, 1000, 10, 0, 0, Some(100)),
+            ProcsNetDev::new("eth1", 1000, 10, 0, 0, 1000, 10, 0, 0, None),
+        ];
+        let current = vec![
+            ProcsNetDev::new("eth0", 11000, 20, 3, 1, 6000, 20, 1, 0, Some(100)),
+            ProcsNetDev::new("eth1", 500, 10, 0, 0, 1000, 10, 0, 0, None),
+            ProcsNetDev::new("eth2", 1000, 10, 0, 0, 1000, 10, 0, 0, None),
+        ];
+        let rates = ProcsNetDevRate::from_netdevs(&previous, &current, 10.0);
+        assert_eq!(rates, vec![ProcsNetDevRate::new("eth0", 1000.0, 500.0, 2, 1, 0, 0, Some(100))]);
+        assert_eq!(ProcsNetDevRate::from_netdevs(&previous, &current, 0.0), vec![]);
+    }
+
+    #[test]
+    fn test_get_percent_saturation() {
+        assert_eq!(ProcsNetDevRate::new("eth0", 6_250_000.0, 0.0, 0, 0, 0, 0, Some(100)).get_percent_saturation(), Some(50.0));
+        assert_eq!(ProcsNetDevRate::new("eth0", 6_250_000.0, 0.0, 0, 0, 0, 0, None).get_percent_saturation(), None);
+    }
+}
@@ -249,8 +249,8 @@ impl ProcsProcess {
      * 
      */
     fn loop_child_paths(paths: ReadDir, processes: &mut Vec<ProcsProcess>, group_names: &HashMap<u32, String>, user_names: &HashMap<u32, String>) -> Result<(), CommonLibError> {
+        let starts_with_number_regexp = Regex::new(r"^[0-9]+$").map_err(|err|CommonLibError::new(format!("Error creating regexp: err: {err:?}").as_str()))?;
         for path in paths {
-            let starts_with_number_regexp = Regex::new(r"^[0-9]+$").map_err(|err|CommonLibError::new(format!("Error creating regexp: err: {err:?}").as_str()))?;
             match &path {
                 Ok(path) => {
                     ProcsProcess::add_child_process(&starts_with_number_regexp, path, processes, group_names, user_names)?;
@@ -521,12 +521,12 @@ mod test {
         println!("{:?}", processes);
         assert!(&processes.is_ok());
         let processes = processes.unwrap().clone();
-        assert_eq!(&processes.get(0).unwrap().pid, &Some(2914));
-        assert_eq!(&processes.get(0).unwrap().parent_pid, &Some(2656));
-        assert_eq!(&processes.get(0).unwrap().name, &Some("code".to_string()));
-        assert_eq!(&processes.get(0).unwrap().umask, &Some("0002".to_string()));
-        assert_eq!(&processes.get(0).unwrap().state, &Some(ProcessState::InterruptableSleep));
-        assert_eq!(&processes.get(0).unwrap().threads, &Some(1));
+        assert_eq!(&processes.first().unwrap().pid, &Some(2914));
+        assert_eq!(&processes.first().unwrap().parent_pid, &Some(2656));
+        assert_eq!(&processes.first().unwrap().name, &Some("code".to_string()));
+        assert_eq!(&processes.first().unwrap().umask, &Some("0002".to_string()));
+        assert_eq!(&processes.first().unwrap().state, &Some(ProcessState::InterruptableSleep));
+        assert_eq!(&processes.first().unwrap().threads, &Some(1));
     }   
 
     #[test]
@@ -552,12 +552,12 @@ mod test {
         println!("{:?}", processes);
         assert!(&processes.is_ok());
         let processes = processes.unwrap().clone();
-        assert_eq!(&processes.get(0).unwrap().pid, &Some(54112));
-        assert_eq!(&processes.get(0).unwrap().parent_pid, &Some(2));
-        assert_eq!(&processes.get(0).unwrap().umask, &Some("0000".to_string()));
-        assert_eq!(&processes.get(0).unwrap().state, &Some(ProcessState::Idle));
-        assert_eq!(&processes.get(0).unwrap().threads, &Some(1));
-        assert_eq!(&processes.get(0).unwrap().groups, &Some(vec![]));
+        assert_eq!(&processes.first().unwrap().pid, &Some(54112));
+        assert_eq!(&processes.first().unwrap().parent_pid, &Some(2));
+        assert_eq!(&processes.first().unwrap().umask, &Some("0000".to_string()));
+        assert_eq!(&processes.first().unwrap().state, &Some(ProcessState::Idle));
+        assert_eq!(&processes.first().unwrap().threads, &Some(1));
+        assert_eq!(&processes.first().unwrap().groups, &Some(vec![]));
     }         
 
     #[test]
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::CommonLibError;
+
+/**
+ * Open file descriptors of a process from /proc/{pid}/fd and the limit from /proc/{pid}/limits
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsProcessFd {
+    /// Number of open file descriptors.
+    pub open: u64,
+    /// Soft limit of open files. None if unlimited.
+    pub soft_limit: Option<u64>,
+    /// Hard limit of open files. None if unlimited.
+    pub hard_limit: Option<u64>,
+}
+
+impl ProcsProcessFd {
+    /**
+     * Create a new `ProcsProcessFd`.
+     *
+     * `open`: Number of open file descriptors.
+     * `soft_limit`: Soft limit of open files. None if unlimited.
+     * `hard_limit`: Hard limit of open files. None if unlimited.
+     *
+     * Returns a new `ProcsProcessFd`.
+     */
+    #[must_use]
+    pub fn new(open: u64, soft_limit: Option<u64>, hard_limit: Option<u64>) -> ProcsProcessFd {
+        ProcsProcessFd {
+            open,
+            soft_limit,
+            hard_limit,
+        }
+    }
+
+    /**
+     * Get the open file descriptors of a process. Reading the file descriptors of a process owned by
+     * another user requires root.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processfd::ProcsProcessFd;
+     * ProcsProcessFd::get_process_fd(1);
+     * ```
+     *
+     * `pid`: The process id.
+     *
+     * Returns the file descriptor usage or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the fd directory.
+     *  - If there is an error reading the limits file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_process_fd(pid: u32) -> Result<ProcsProcessFd, CommonLibError> {
+        let fds = std::fs::read_dir(format!("/proc/{pid}/fd")).map_err(|err| {
+            CommonLibError::new(format!("Error reading process fd directory: {err:?}").as_str())
+        })?;
+        let open = fds.filter(Result::is_ok).count() as u64;
+        let limits = std::fs::read_to_string(format!("/proc/{pid}/limits")).map_err(|err| {
+            CommonLibError::new(format!("Error reading process limits file: {err:?}").as_str())
+        })?;
+        let (soft_limit, hard_limit) = ProcsProcessFd::parse_limits(&limits);
+        Ok(ProcsProcessFd::new(open, soft_limit, hard_limit))
+    }
+
+    /**
+     * Parse the open files limit from the limits file.
+     *
+     * `buffer`: The buffer to parse.
+     *
+     * Returns the soft and hard limit. None if unlimited or missing.
+     */
+    fn parse_limits(buffer: &str) -> (Option<u64>, Option<u64>) {
+        buffer.lines()
+            .find_map(|line| line.strip_prefix("Max open files"))
+            .map_or((None, None), |values| {
+                let mut cols = values.split_whitespace().map(|value| u64::from_str(value).ok());
+                (cols.next().flatten(), cols.next().flatten())
+            })
+    }
+
+    /**
+     * Get the percentage of the soft limit in use.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processfd::ProcsProcessFd;
+     * assert_eq!(ProcsProcessFd::new(256, Some(1024), Some(4096)).get_percent_usage(), Some(25.0));
+     * ```
+     *
+     * Returns the percentage or None if there is no limit.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_percent_usage(&self) -> Option<f64> {
+        let soft_limit = self.soft_limit.filter(|limit| *limit > 0)?;
+        Some(self.open as f64 * 100.0 / soft_limit as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_limits() {
+        let buffer = "Limit                     Soft Limit           Hard Limit           Units     \n\
+                      Max cpu time              unlimited            unlimited            seconds   \n\
+                      Max open files            1024                 524288               files     \n\
+                      Max locked memory         8388608              8388608              bytes     \n";
+        assert_eq!(ProcsProcessFd::parse_limits(buffer), (Some(1024), Some(524288)));
+        assert_eq!(ProcsProcessFd::parse_limits("Max open files            unlimited            unlimited            files"), (None, None));
+        assert_eq!(ProcsProcessFd::parse_limits(""), (None, None));
+    }
+
+    #[test]
+    fn test_get_percent_usage() {
+        assert_eq!(ProcsProcessFd::new(10, Some(100), Some(1000)).get_percent_usage(), Some(10.0));
+        assert_eq!(ProcsProcessFd::new(10, None, None).get_percent_usage(), None);
+    }
+
+    #[test]
+    fn test_get_process_fd() {
+        let fd = ProcsProcessFd::get_process_fd(std::process::id()).unwrap();
+        assert!(fd.open >= 3);
+        assert!(ProcsProcessFd::get_process_fd(0).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::CommonLibError;
+
+/**
+ * Process I/O counters from /proc/{pid}/io
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsProcessIo {
+    /// Bytes read by read system calls, including the page cache.
+    pub rchar: u64,
+    /// Bytes written by write system calls, including the page cache.
+    pub wchar: u64,
+    /// Bytes fetched from the storage layer.
+    pub read_bytes: u64,
+    /// Bytes sent to the storage layer.
+    pub write_bytes: u64,
+}
+
+impl ProcsProcessIo {
+    /**
+     * Create a new `ProcsProcessIo`.
+     *
+     * `rchar`: Bytes read by read system calls, including the page cache.
+     * `wchar`: Bytes written by write system calls, including the page cache.
+     * `read_bytes`: Bytes fetched from the storage layer.
+     * `write_bytes`: Bytes sent to the storage layer.
+     *
+     * Returns a new `ProcsProcessIo`.
+     */
+    #[must_use]
+    pub fn new(rchar: u64, wchar: u64, read_bytes: u64, write_bytes: u64) -> ProcsProcessIo {
+        ProcsProcessIo {
+            rchar,
+            wchar,
+            read_bytes,
+            write_bytes,
+        }
+    }
+
+    /**
+     * Get the I/O counters of a process. Reading the counters of a process owned by another user
+     * requires root.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processio::ProcsProcessIo;
+     * ProcsProcessIo::get_process_io(1);
+     * ```
+     *
+     * `pid`: The process id.
+     *
+     * Returns the I/O counters or an error.
+     *
+     * # Errors
+     *  - If there is an error reading the io file.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_process_io(pid: u32) -> Result<ProcsProcessIo, CommonLibError> {
+        let buffer = std::fs::read_to_string(format!("/proc/{pid}/io")).map_err(|err| {
+            CommonLibError::new(format!("Error reading process io file: {err:?}").as_str())
+        })?;
+        Ok(ProcsProcessIo::parse_io(&buffer))
+    }
+
+    /**
+     * Parse the io file. Missing or invalid counters are set to 0.
+     *
+     * `buffer`: The buffer to parse.
+     *
+     * Returns the I/O counters.
+     */
+    fn parse_io(buffer: &str) -> ProcsProcessIo {
+        let mut io = ProcsProcessIo::new(0, 0, 0, 0);
+        for line in buffer.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = u64::from_str(value.trim()).unwrap_or(0);
+            match key.trim() {
+                "rchar" => io.rchar = value,
+                "wchar" => io.wchar = value,
+                "read_bytes" => io.read_bytes = value,
+                "write_bytes" => io.write_bytes = value,
+                _ => {}
+            }
+        }
+        io
+    }
+}
+
+/**
+ * Process I/O throughput computed from two /proc/{pid}/io samples.
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsProcessIoRate {
+    /// Bytes read from storage per second.
+    pub read_bytes_per_second: f64,
+    /// Bytes written to storage per second.
+    pub write_bytes_per_second: f64,
+}
+
+impl ProcsProcessIoRate {
+    /**
+     * Create a new `ProcsProcessIoRate`.
+     *
+     * `read_bytes_per_second`: Bytes read from storage per second.
+     * `write_bytes_per_second`: Bytes written to storage per second.
+     *
+     * Returns a new `ProcsProcessIoRate`.
+     */
+    #[must_use]
+    pub fn new(read_bytes_per_second: f64, write_bytes_per_second: f64) -> ProcsProcessIoRate {
+        ProcsProcessIoRate {
+            read_bytes_per_second,
+            write_bytes_per_second,
+        }
+    }
+
+    /**
+     * Compute the throughput between two samples.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processio::{ProcsProcessIo, ProcsProcessIoRate};
+     * let previous = ProcsProcessIo::new(0, 0, 1000, 2000);
+     * let current = ProcsProcessIo::new(0, 0, 3000, 2000);
+     * let rate = ProcsProcessIoRate::from_io(&previous, &current, 2.0).unwrap();
+     * assert_eq!(rate.get_total_bytes_per_second(), 1000.0);
+     * ```
+     *
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * `elapsed_seconds`: Seconds between the samples.
+     *
+     * Returns the throughput or None if the counters have been reset or no time has passed.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_io(previous: &ProcsProcessIo, current: &ProcsProcessIo, elapsed_seconds: f64) -> Option<ProcsProcessIoRate> {
+        if elapsed_seconds <= 0.0 || current.read_bytes < previous.read_bytes || current.write_bytes < previous.write_bytes {
+            return None;
+        }
+        Some(ProcsProcessIoRate::new(
+            (current.read_bytes - previous.read_bytes) as f64 / elapsed_seconds,
+            (current.write_bytes - previous.write_bytes) as f64 / elapsed_seconds,
+        ))
+    }
+
+    /**
+     * Get the bytes read and written per second.
+     *
+     * Returns the total throughput.
+     */
+    #[must_use]
+    pub fn get_total_bytes_per_second(&self) -> f64 {
+        self.read_bytes_per_second + self.write_bytes_per_second
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_io() {
+        let buffer = "rchar: 3980\nwchar: 120\nsyscr: 9\nsyscw: 0\nread_bytes: 4096\nwrite_bytes: 8192\ncancelled_write_bytes: 0\n";
+        assert_eq!(ProcsProcessIo::parse_io(buffer), ProcsProcessIo::new(3980, 120, 4096, 8192));
+        assert_eq!(ProcsProcessIo::parse_io("garbage\nread_bytes: x"), ProcsProcessIo::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_from_io() {
+        let previous = ProcsProcessIo::new(0, 0, 1000, 1000);
+        let current = ProcsProcessIo::new(0, 0, 11000, 6000);
+        assert_eq!(ProcsProcessIoRate::from_io(&previous, &current, 10.0), Some(ProcsProcessIoRate::new(1000.0, 500.0)));
+        assert_eq!(ProcsProcessIoRate::from_io(&previous, &current, 0.0), None);
+        assert_eq!(ProcsProcessIoRate::from_io(&current, &previous, 10.0), None);
+    }
+
+    #[test]
+    fn test_get_process_io() {
+        assert!(ProcsProcessIo::get_process_io(std::process::id()).is_ok());
+        assert!(ProcsProcessIo::get_process_io(0).is_err());
+    }
+}
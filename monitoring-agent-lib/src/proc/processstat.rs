@@ -0,0 +1,215 @@
+use std::str::FromStr;
+
+use libc::{sysconf, _SC_CLK_TCK};
+use serde::{Deserialize, Serialize};
+
+use crate::common::CommonLibError;
+
+/**
+ * Process cpu times and scheduling information from /proc/{pid}/stat
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcsProcessStat {
+    /// The process id.
+    pub pid: u32,
+    /// Time scheduled in user mode (clock ticks).
+    pub utime: u64,
+    /// Time scheduled in kernel mode (clock ticks).
+    pub stime: u64,
+    /// Time the process started after system boot (clock ticks).
+    pub start_time: u64,
+    /// The nice value.
+    pub nice: i64,
+    /// Resident set size (pages).
+    pub rss: u64,
+    /// Clock ticks per second.
+    pub clock_ticks: u64,
+}
+
+impl ProcsProcessStat {
+    /**
+     * Create a new `ProcsProcessStat`.
+     *
+     * `pid`: The process id.
+     * `utime`: Time scheduled in user mode (clock ticks).
+     * `stime`: Time scheduled in kernel mode (clock ticks).
+     * `start_time`: Time the process started after system boot (clock ticks).
+     * `nice`: The nice value.
+     * `rss`: Resident set size (pages).
+     * `clock_ticks`: Clock ticks per second.
+     *
+     * Returns a new `ProcsProcessStat`.
+     */
+    #[must_use]
+    pub fn new(pid: u32, utime: u64, stime: u64, start_time: u64, nice: i64, rss: u64, clock_ticks: u64) -> ProcsProcessStat {
+        ProcsProcessStat {
+            pid,
+            utime,
+            stime,
+            start_time,
+            nice,
+            rss,
+            clock_ticks,
+        }
+    }
+
+    /**
+     * Get the cpu times of a process.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processstat::ProcsProcessStat;
+     * ProcsProcessStat::get_process_stat(1);
+     * ```
+     *
+     * `pid`: The process id.
+     *
+     * Returns the process stat or an error.
+     *
+     * # Errors
+     *  - If there is an error getting the clock ticks.
+     *  - If there is an error reading the stat file.
+     *  - If the stat file can not be parsed.
+     */
+    #[tracing::instrument(level = "debug")]
+    pub fn get_process_stat(pid: u32) -> Result<ProcsProcessStat, CommonLibError> {
+        let clock_ticks = ProcsProcessStat::get_clock_ticks()?;
+        let buffer = std::fs::read_to_string(format!("/proc/{pid}/stat")).map_err(|err| {
+            CommonLibError::new(format!("Error reading process stat file: {err:?}").as_str())
+        })?;
+        ProcsProcessStat::parse_stat(&buffer, clock_ticks)
+            .ok_or_else(|| CommonLibError::new(format!("Error parsing process stat file for pid {pid}").as_str()))
+    }
+
+    /**
+     * Get the number of clock ticks per second.
+     *
+     * Returns the clock ticks or an error.
+     *
+     * # Errors
+     * - If there is an error getting the clock ticks.
+     */
+    fn get_clock_ticks() -> Result<u64, CommonLibError> {
+        let sysconf_clock_ticks = unsafe { sysconf(_SC_CLK_TCK) };
+        sysconf_clock_ticks
+            .try_into()
+            .map_err(|err| CommonLibError::new(format!("Error getting clock ticks: {err:?}").as_str()))
+    }
+
+    /**
+     * Parse the stat file. The process name is in parentheses and may contain spaces and parentheses,
+     * so the fields are read after the last closing parenthesis.
+     *
+     * `buffer`: The buffer to parse.
+     * `clock_ticks`: Clock ticks per second.
+     *
+     * Returns the process stat or None if the buffer is not valid.
+     */
+    fn parse_stat(buffer: &str, clock_ticks: u64) -> Option<ProcsProcessStat> {
+        let (pid, _) = buffer.split_once(" (")?;
+        let (_, fields) = buffer.rsplit_once(')')?;
+        let cols = fields.split_whitespace().collect::<Vec<&str>>();
+        if cols.len() < 22 {
+            return None;
+        }
+        Some(ProcsProcessStat::new(
+            u32::from_str(pid.trim()).ok()?,
+            u64::from_str(cols[11]).ok()?,
+            u64::from_str(cols[12]).ok()?,
+            u64::from_str(cols[19]).ok()?,
+            i64::from_str(cols[16]).ok()?,
+            u64::from_str(cols[21]).ok()?,
+            clock_ticks,
+        ))
+    }
+
+    /**
+     * Get the total cpu time used by the process in seconds.
+     *
+     * Returns the cpu time in seconds.
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_cpu_seconds(&self) -> f64 {
+        if self.clock_ticks == 0 {
+            return 0.0;
+        }
+        (self.utime + self.stime) as f64 / self.clock_ticks as f64
+    }
+
+    /**
+     * Get the cpu usage of a process between two samples. A process using one core fully is 100%.
+     *
+     * ```
+     * use monitoring_agent_lib::proc::processstat::ProcsProcessStat;
+     * let previous = ProcsProcessStat::new(1, 100, 50, 10, 0, 1000, 100);
+     * let current = ProcsProcessStat::new(1, 200, 100, 10, 0, 1000, 100);
+     * assert_eq!(ProcsProcessStat::get_cpu_percent(&previous, &current, 2.0), Some(75.0));
+     * ```
+     *
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * `elapsed_seconds`: Seconds between the samples.
+     *
+     * Returns the cpu percentage or None if the samples are not from the same process.
+     */
+    #[must_use]
+    pub fn get_cpu_percent(previous: &ProcsProcessStat, current: &ProcsProcessStat, elapsed_seconds: f64) -> Option<f64> {
+        if elapsed_seconds <= 0.0 || previous.pid != current.pid || previous.start_time != current.start_time {
+            return None;
+        }
+        let cpu_seconds = current.get_cpu_seconds() - previous.get_cpu_seconds();
+        if cpu_seconds < 0.0 {
+            return None;
+        }
+        Some(cpu_seconds * 100.0 / elapsed_seconds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let buffer = "7139 (cat) R 7133 7139 7133 0 -1 4194304 80 0 0 0 12 7 0 0 20 -5 1 0 861264 2703360 285 18446744073709551615 0 0 0 0 0";
+        let stat = ProcsProcessStat::parse_stat(buffer, 100).unwrap();
+        assert_eq!(stat, ProcsProcessStat::new(7139, 12, 7, 861264, -5, 285, 100));
+    }
+
+    #[test]
+    fn test_parse_stat_name_with_parentheses() {
+        let buffer = "42 (my (odd) name) S 1 42 42 0 -1 4194304 80 0 0 0 300 100 0 0 20 0 1 0 500 2703360 10 18446744073709551615";
+        let stat = ProcsProcessStat::parse_stat(buffer, 100).unwrap();
+        assert_eq!(stat.pid, 42);
+        assert_eq!(stat.utime, 300);
+        assert_eq!(stat.stime, 100);
+        assert_eq!(stat.start_time, 500);
+        assert_eq!(stat.get_cpu_seconds(), 4.0);
+    }
+
+    #[test]
+    fn test_parse_stat_invalid() {
+        assert_eq!(ProcsProcessStat::parse_stat("", 100), None);
+        assert_eq!(ProcsProcessStat::parse_stat("1 (init) S 0 1", 100), None);
+    }
+
+    #[test]
+    fn test_get_cpu_percent() {
+        let previous = ProcsProcessStat::new(1, 100, 50, 10, 0, 1000, 100);
+        let current = ProcsProcessStat::new(1, 300, 150, 10, 0, 1000, 100);
+        assert_eq!(ProcsProcessStat::get_cpu_percent(&previous, &current, 2.0), Some(150.0));
+        assert_eq!(ProcsProcessStat::get_cpu_percent(&previous, &current, 0.0), None);
+        let restarted = ProcsProcessStat::new(1, 300, 150, 20, 0, 1000, 100);
+        assert_eq!(ProcsProcessStat::get_cpu_percent(&previous, &restarted, 2.0), None);
+    }
+
+    #[test]
+    fn test_get_process_stat() {
+        let stat = ProcsProcessStat::get_process_stat(std::process::id()).unwrap();
+        assert_eq!(stat.pid, std::process::id());
+        assert!(stat.clock_ticks > 0);
+        assert!(stat.rss > 0);
+        assert!(ProcsProcessStat::get_process_stat(0).is_err());
+    }
+}
@@ -245,6 +245,19 @@ impl ProcCpuStat {
         }        
     }
 
+    /**
+     * Get the sum of all the time counters.
+     * 
+     * Returns the total time.
+     * 
+     */
+    fn get_total(&self) -> u64 {
+        [self.user, self.nice, self.system, self.idle, self.iowait, self.irq, self.softirq, self.steal]
+            .iter()
+            .map(|value| value.unwrap_or(0))
+            .sum()
+    }
+
     /**
      * Read the cpu statistics from the line.
      * 
@@ -271,6 +284,160 @@ impl ProcCpuStat {
 }
 
 
+/**
+ * CPU utilisation computed from two /proc/stat samples.
+ * 
+ * The values are the percentage of the time between the samples.
+ * 
+ * `name`: The name of the CPU. Example cpu or cpu0. cpu is the total of all CPUs.
+ * `user`: Percentage of time spent in user mode, including low priority (nice).
+ * `system`: Percentage of time spent in system mode, including interrupts and softirqs.
+ * `iowait`: Percentage of time spent waiting for I/O to complete.
+ * `steal`: Percentage of time spent in other OS instances when running in a virtualized environment.
+ * `idle`: Percentage of time spent in the idle task.
+ * `total`: Percentage of time not spent idle or waiting for I/O.
+ * 
+ */
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcCpuUsage {
+    /// The name of the CPU. Example cpu or cpu0. cpu is the total of all CPUs.
+    pub name: Option<String>,
+    /// Percentage of time spent in user mode, including low priority (nice).
+    pub user: f64,
+    /// Percentage of time spent in system mode, including interrupts and softirqs.
+    pub system: f64,
+    /// Percentage of time spent waiting for I/O to complete.
+    pub iowait: f64,
+    /// Percentage of time spent in other OS instances when running in a virtualized environment.
+    pub steal: f64,
+    /// Percentage of time spent in the idle task.
+    pub idle: f64,
+    /// Percentage of time not spent idle or waiting for I/O.
+    pub total: f64,
+}
+
+impl ProcCpuUsage {
+
+    /**
+     * Create a new `ProcCpuUsage`.
+     * 
+     * ```
+     * use monitoring_agent_lib::proc::stat::ProcCpuUsage;
+     * ProcCpuUsage::new(Some("cpu"), 10.0, 5.0, 1.0, 0.0, 84.0, 15.0);
+     * ```
+     * 
+     * `name`: The name of the CPU.
+     * `user`: Percentage of time spent in user mode.
+     * `system`: Percentage of time spent in system mode.
+     * `iowait`: Percentage of time spent waiting for I/O to complete.
+     * `steal`: Percentage of time stolen by other OS instances.
+     * `idle`: Percentage of time spent idle.
+     * `total`: Percentage of time not spent idle or waiting for I/O.
+     * 
+     * Returns a new `ProcCpuUsage`.
+     * 
+     */
+    #[must_use]
+    pub fn new(name: Option<&str>, user: f64, system: f64, iowait: f64, steal: f64, idle: f64, total: f64) -> Self {
+        ProcCpuUsage {
+            name: name.map(std::string::ToString::to_string),
+            user,
+            system,
+            iowait,
+            steal,
+            idle,
+            total,
+        }
+    }
+
+    /**
+     * Compute the CPU utilisation between two samples of a CPU.
+     * 
+     * ```
+     * use monitoring_agent_lib::proc::stat::{ProcCpuStat, ProcCpuUsage};
+     * let previous = ProcCpuStat::new(Some("cpu"), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0));
+     * let current = ProcCpuStat::new(Some("cpu"), Some(50), Some(0), Some(25), Some(25), Some(0), Some(0), Some(0), Some(0));
+     * let usage = ProcCpuUsage::from_cpu_stats(&previous, &current).unwrap();
+     * assert_eq!(usage.total, 75.0);
+     * ```
+     * 
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * 
+     * Returns the CPU utilisation or None if no time has passed between the samples or the counters were reset.
+     * 
+     */
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_cpu_stats(previous: &ProcCpuStat, current: &ProcCpuStat) -> Option<ProcCpuUsage> {
+        let delta = |previous: Option<u64>, current: Option<u64>| current.unwrap_or(0).saturating_sub(previous.unwrap_or(0));
+        if previous.get_total() > current.get_total() {
+            return None;
+        }
+        let user = delta(previous.user, current.user) + delta(previous.nice, current.nice);
+        let system = delta(previous.system, current.system) + delta(previous.irq, current.irq) + delta(previous.softirq, current.softirq);
+        let iowait = delta(previous.iowait, current.iowait);
+        let steal = delta(previous.steal, current.steal);
+        let idle = delta(previous.idle, current.idle);
+        let total = user + system + iowait + steal + idle;
+        if total == 0 {
+            return None;
+        }
+        let percent = |value: u64| value as f64 * 100.0 / total as f64;
+        Some(ProcCpuUsage::new(
+            current.name.as_deref(),
+            percent(user),
+            percent(system),
+            percent(iowait),
+            percent(steal),
+            percent(idle),
+            percent(total - idle - iowait),
+        ))
+    }
+
+    /**
+     * Compute the CPU utilisation of all CPUs between two /proc/stat samples. CPUs are matched by name, 
+     * CPUs only found in one of the samples are skipped.
+     * 
+     * ```
+     * use monitoring_agent_lib::proc::stat::{ProcStat, ProcCpuUsage};
+     * let previous = ProcStat::get_stat().unwrap();
+     * let current = ProcStat::get_stat().unwrap();
+     * ProcCpuUsage::from_stats(&previous, &current);
+     * ```
+     * 
+     * `previous`: The previous sample.
+     * `current`: The current sample.
+     * 
+     * Returns the CPU utilisation of the CPUs with time passed between the samples.
+     * 
+     */
+    #[must_use]
+    pub fn from_stats(previous: &ProcStat, current: &ProcStat) -> Vec<ProcCpuUsage> {
+        let (Some(previous_cpus), Some(current_cpus)) = (previous.cpus.as_ref(), current.cpus.as_ref()) else {
+            return Vec::new();
+        };
+        current_cpus.iter().filter_map(|current_cpu| {
+            previous_cpus.iter()
+                .find(|previous_cpu| previous_cpu.name == current_cpu.name)
+                .and_then(|previous_cpu| ProcCpuUsage::from_cpu_stats(previous_cpu, current_cpu))
+        }).collect()
+    }
+
+    /**
+     * Is this the total of all CPUs.
+     * 
+     * Returns true if the name is cpu.
+     * 
+     */
+    #[must_use]
+    pub fn is_total(&self) -> bool {
+        self.name.as_deref() == Some("cpu")
+    }
+}
+
+
 #[cfg(test)]
 mod test {
 
@@ -287,14 +454,14 @@ mod test {
         let proc_stat = ProcStat::read_stat("resources/test/test_stat").unwrap();
         assert_eq!(proc_stat.clone().cpus.unwrap().len(), 17);
         assert_eq!(proc_stat.clone().cpus.unwrap()[0].name.clone().unwrap(), "cpu");
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].user.clone().unwrap(), 728050);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].nice.clone().unwrap(), 301008);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].system.clone().unwrap(), 1228186);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].idle.clone().unwrap(), 43365149);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].iowait.clone().unwrap(), 613178);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].irq.clone().unwrap(), 0);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].softirq.clone().unwrap(), 21734);
-        assert_eq!(proc_stat.clone().cpus.unwrap()[0].steal.clone().unwrap(), 0);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].user.unwrap(), 728050);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].nice.unwrap(), 301008);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].system.unwrap(), 1228186);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].idle.unwrap(), 43365149);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].iowait.unwrap(), 613178);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].irq.unwrap(), 0);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].softirq.unwrap(), 21734);
+        assert_eq!(proc_stat.clone().cpus.unwrap()[0].steal.unwrap(), 0);
         assert_eq!(proc_stat.clone().intr.unwrap(), 793571364);
         assert_eq!(proc_stat.clone().ctxt.unwrap(), 1526901585);
         assert_eq!(proc_stat.clone().btime.unwrap(), 1724165385);
@@ -303,4 +470,49 @@ mod test {
         assert_eq!(proc_stat.clone().procs_blocked.unwrap(), 0);
     }
 
+    #[test]
+    fn test_cpu_usage_from_cpu_stats() {
+        let previous = ProcCpuStat::new(Some("cpu0"), Some(100), Some(100), Some(100), Some(500), Some(100), Some(50), Some(50), Some(0));
+        let current = ProcCpuStat::new(Some("cpu0"), Some(150), Some(150), Some(150), Some(1050), Some(200), Some(100), Some(100), Some(100));
+        let usage = ProcCpuUsage::from_cpu_stats(&previous, &current).unwrap();
+        assert_eq!(usage.name, Some("cpu0".to_string()));
+        assert_eq!(usage.user, 10.0);
+        assert_eq!(usage.system, 15.0);
+        assert_eq!(usage.iowait, 10.0);
+        assert_eq!(usage.steal, 10.0);
+        assert_eq!(usage.idle, 55.0);
+        assert_eq!(usage.total, 35.0);
+    }
+
+    #[test]
+    fn test_cpu_usage_no_time_passed() {
+        let previous = ProcCpuStat::new(Some("cpu"), Some(100), Some(100), Some(100), Some(500), Some(100), Some(50), Some(50), Some(0));
+        assert_eq!(ProcCpuUsage::from_cpu_stats(&previous, &previous), None);
+    }
+
+    #[test]
+    fn test_cpu_usage_counter_reset() {
+        let previous = ProcCpuStat::new(Some("cpu"), Some(100), Some(100), Some(100), Some(500), Some(100), Some(50), Some(50), Some(0));
+        let current = ProcCpuStat::new(Some("cpu"), Some(10), Some(10), Some(10), Some(50), Some(10), Some(5), Some(5), Some(0));
+        assert_eq!(ProcCpuUsage::from_cpu_stats(&previous, &current), None);
+    }
+
+    #[test]
+    fn test_cpu_usage_from_stats() {
+        let previous = ProcStat::new(Some(vec![
+            ProcCpuStat::new(Some("cpu"), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0)),
+            ProcCpuStat::new(Some("cpu0"), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0), Some(0)),
+        ]), None, None, None, None, None, None);
+        let current = ProcStat::new(Some(vec![
+            ProcCpuStat::new(Some("cpu"), Some(20), Some(0), Some(0), Some(80), Some(0), Some(0), Some(0), Some(0)),
+            ProcCpuStat::new(Some("cpu0"), Some(20), Some(0), Some(0), Some(80), Some(0), Some(0), Some(0), Some(0)),
+            ProcCpuStat::new(Some("cpu1"), Some(20), Some(0), Some(0), Some(80), Some(0), Some(0), Some(0), Some(0)),
+        ]), None, None, None, None, None, None);
+        let usage = ProcCpuUsage::from_stats(&previous, &current);
+        assert_eq!(usage.len(), 2);
+        assert!(usage[0].is_total());
+        assert!(!usage[1].is_total());
+        assert_eq!(usage[1].total, 20.0);
+    }
+
 }
\ No newline at end of file
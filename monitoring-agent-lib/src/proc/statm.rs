@@ -4,6 +4,7 @@ use std::{
 };
 
 use libc::{sysconf, _SC_PAGE_SIZE};
+use serde::{Deserialize, Serialize};
 
 use crate::common::CommonLibError;
 
@@ -13,7 +14,7 @@ use crate::common::CommonLibError;
  * TODO: Add more detailed text.
  */
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcsStatm {
     /// Total program size (pages)
     pub size: Option<u32>,
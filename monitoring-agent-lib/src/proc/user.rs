@@ -98,18 +98,18 @@ mod tests {
     #[test]
     fn test_users() {
         let users = super::User::get_users().unwrap();
-        assert!(users.len() > 0);
+        assert!(!users.is_empty());
     }
 
     #[test]
     fn test_users_map() {
         let users = super::User::get_users_map().unwrap();
-        assert!(users.len() > 0);
+        assert!(!users.is_empty());
     }  
 
     #[test]
     fn read_users_file() {
         let users = super::User::read_users("resources/test/test_passwd").unwrap();
-        assert!(users.len() > 0);
+        assert!(!users.is_empty());
     }
 }   
\ No newline at end of file